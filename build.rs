@@ -0,0 +1,47 @@
+//! Embed the NML2 core type definitions into the binary, such that `nmlcc`
+//! does not depend on the location of `ext/`. If the submodule has not been
+//! checked out, nothing is embedded and core types must be given via
+//! `--include-dir`. Tests relying on the core types are gated on the
+//! `core_types` cfg set here.
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const CORE: &str = "ext/NeuroML2/NeuroML2CoreTypes";
+
+fn main() -> std::io::Result<()> {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed={}", CORE);
+    println!("cargo:rustc-check-cfg=cfg(core_types)");
+
+    let root = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap()).join(CORE);
+    let mut files = Vec::new();
+    if let Ok(dir) = fs::read_dir(&root) {
+        for entry in dir {
+            let path = entry?.path();
+            if path.extension().map(|e| e == "xml").unwrap_or(false) {
+                files.push(path);
+            }
+        }
+    } else {
+        println!(
+            "cargo:warning=No core types found in {}, run bootstrap.sh to embed them.",
+            CORE
+        );
+    }
+    files.sort();
+    if !files.is_empty() {
+        println!("cargo:rustc-cfg=core_types");
+    }
+
+    let mut out = String::from("pub const CORE_TYPES: &[(&str, &str)] = &[\n");
+    for file in &files {
+        let name = file.file_name().unwrap().to_string_lossy();
+        out.push_str(&format!("    ({:?}, include_str!({:?})),\n", name, file));
+        println!("cargo:rerun-if-changed={}", file.display());
+    }
+    out.push_str("];\n");
+
+    let dst = Path::new(&env::var("OUT_DIR").unwrap()).join("core_types.rs");
+    fs::write(dst, out)
+}
@@ -1,50 +1,340 @@
 use crate::{
+    cell,
     error::Error,
     expr::Quantity,
     lems::file::LemsFile,
+    morphology::Morphology,
+    network,
     neuroml::process_files,
     neuroml::raw::{
         BiophysicalProperties, BiophysicalPropertiesBody, ChannelDensity, ChannelDensityNernst,
         ExtracellularProperties, InitMembPotential, IntracellularProperties,
-        IntracellularPropertiesBody, MembraneProperties, MembranePropertiesBody, Resistivity,
-        Species, SpecificCapacitance,
+        IntracellularPropertiesBody, MembraneProperties, MembranePropertiesBody,
+        PoissonFiringSynapse, PulseGenerator, PulseGeneratorDL, RampGenerator, RampGeneratorDL,
+        Resistivity, SineGenerator, SineGeneratorDL, Species, SpecificCapacitance, SpikeArray,
+        SpikeArrayBody, SpikeGenerator, SpikeGeneratorPoisson, SpikeThresh, TimedSynapticInput,
+        TimedSynapticInputBody, TransientPoissonFiringSynapse,
     },
+    sweep::Overrides,
     xml, Result,
 };
 
+use roxmltree::Node;
+
 use std::collections::HashMap as Map;
 use std::fs::write;
 use std::path::PathBuf;
-use tracing::info;
+use tracing::{info, warn};
 
-pub fn export(lems: &LemsFile, nml: &[String], cell: &Option<&str>, pfx: &str) -> Result<()> {
+pub fn export(
+    lems: &LemsFile,
+    nml: &[String],
+    cell: &Option<&str>,
+    detector: &Option<&str>,
+    pfx: &str,
+    ovr: &Overrides,
+) -> Result<()> {
     std::fs::create_dir_all(&pfx)?;
-    process_files(nml, |_, node| {
-        if node.tag_name().name() != "cell" {
-            return Ok(());
+    let mut stimuli = stimuli(lems, nml)?;
+    for cell::Cell {
+        id,
+        morphology,
+        mut properties,
+    } in cell::cells(&lems.include_dir, nml)?
+    {
+        if let Some(cell) = cell {
+            if id != *cell {
+                continue;
+            }
+        }
+        let mut result = Vec::new();
+        if let Some(prop) = &mut properties {
+            ovr.apply_properties(&id, prop);
+            result.append(&mut acc(prop, lems)?);
+        }
+        ovr.apply_decor(lems, &mut result)?;
+        if let Some(loc) = detector {
+            relocate_detectors(&mut result, loc);
         }
+        for (name, mut stimuli) in instances(&mut stimuli, &id) {
+            let mut decor = result.clone();
+            decor.append(&mut stimuli);
+            let mut file = PathBuf::from(pfx);
+            file.push(&name);
+            file.set_extension("acc");
+            info!("Writing ACC to {:?}", &file);
+            if let Some(mrf) = &morphology {
+                write(&file, cable_cell(mrf, &decor))?;
+            } else {
+                warn!("Cell {} has no morphology, writing decor only", id);
+                write(&file, decor.to_sexp())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Move all threshold detectors to `locset`
+pub fn relocate_detectors(decor: &mut [Decor], locset: &str) {
+    for d in decor.iter_mut() {
+        if let Decor::Place(l, Placeable::ThresholdDetector(_), _) = d {
+            *l = locset.to_string();
+        }
+    }
+}
+
+/// Stimuli placed by network inputs, by cell id and targetted instance, ie
+/// population and index
+pub type Stimuli = Map<String, Map<(String, i64), Vec<Decor>>>;
+
+/// Variants of cell `id` to write as (name, stimuli), taking its entries from
+/// `stimuli`. Inputs on a single instance go to the cell itself, named `id`.
+/// Otherwise, each stimulated instance becomes `<id>_<population>_<index>`,
+/// next to the unstimulated cell.
+pub fn instances(stimuli: &mut Stimuli, id: &str) -> Vec<(String, Vec<Decor>)> {
+    let mut result = stimuli
+        .remove(id)
+        .unwrap_or_default()
+        .into_iter()
+        .collect::<Vec<_>>();
+    result.sort_by(|a, b| a.0.cmp(&b.0));
+    match result.len() {
+        0 => vec![(id.to_string(), Vec::new())],
+        1 => vec![(id.to_string(), result.remove(0).1)],
+        _ => std::iter::once((id.to_string(), Vec::new()))
+            .chain(
+                result
+                    .into_iter()
+                    .map(|((pop, ix), d)| (format!("{}_{}_{}", id, pop, ix), d)),
+            )
+            .collect(),
+    }
+}
+
+/// Collect stimuli placed by network inputs, see `Stimuli`. Connections from
+/// spike sources, eg a population of `spikeArray`s, place the projection's
+/// synapse driven by the source's schedule.
+pub fn stimuli(lems: &LemsFile, nml: &[String]) -> Result<Stimuli> {
+    let mut sources = Map::new();
+    let mut schedules = Map::new();
+    let mut spikes = Vec::new();
+    process_files(&lems.include_dir, nml, |_, node| {
         if let Some(id) = node.attribute("id") {
-            if let Some(cell) = cell {
-                if id != *cell {
-                    return Ok(());
+            if let Some(items) = placeables(lems, node)? {
+                sources.insert(id.to_string(), items);
+            } else if let Some(schedule) = schedule(lems, node)? {
+                schedules.insert(id.to_string(), schedule);
+            } else if lems.derived_from(node.tag_name().name(), "baseSpikeSource") {
+                spikes.push(id.to_string());
+            }
+        }
+        Ok(())
+    })?;
+
+    let mut result: Stimuli = Map::new();
+    for input in network::inputs(&lems.include_dir, nml)? {
+        let connection;
+        let items = if let Some((synapse, delay)) = &input.synapse {
+            if let Some(schedule) = schedules.get(&input.source) {
+                let delay = lems.normalise_quantity(delay)?.value;
+                connection = [Placeable::Synapse(
+                    synapse.clone(),
+                    1.0,
+                    schedule.delayed(delay),
+                )];
+                &connection[..]
+            } else {
+                if spikes.contains(&input.source) {
+                    warn!(
+                        "Connection {} uses spike source {}, which has no Arbor counterpart. Skipping.",
+                        input.label, input.source
+                    );
                 }
+                // Otherwise, this connects two cells and is no input
+                continue;
             }
+        } else if let Some(items) = sources.get(&input.source) {
+            items
+        } else if spikes.contains(&input.source) || schedules.contains_key(&input.source) {
+            warn!(
+                "Input {} uses spike source {}, which needs a synapse; use a projection instead. Skipping.",
+                input.label, input.source
+            );
+            continue;
+        } else {
+            warn!(
+                "Input {} uses unsupported or unknown source {}, skipping",
+                input.label, input.source
+            );
+            continue;
+        };
+        for (ix, item) in items.iter().enumerate() {
+            let label = if items.len() == 1 {
+                input.label.clone()
+            } else {
+                format!("{}_{}", input.label, ix)
+            };
+            let item = match item {
+                Placeable::Synapse(m, _, s) => {
+                    Placeable::Synapse(m.clone(), input.weight, s.clone())
+                }
+                p => p.clone(),
+            };
+            result
+                .entry(input.cell.clone())
+                .or_default()
+                .entry((input.population.clone(), input.index))
+                .or_default()
+                .push(Decor::Place(input.locset(), item, label));
+        }
+    }
+    Ok(result)
+}
+
+/// Arbor counterparts of an input component, if any; compound inputs map to
+/// multiple items.
+fn placeables(lems: &LemsFile, node: &Node) -> Result<Option<Vec<Placeable>>> {
+    let norm = |v: &Quantity| -> Result<f64> { Ok(lems.normalise_quantity(v)?.value) };
+    let pulse =
+        |d, t, a| -> Result<Placeable> { Ok(Placeable::IClamp(norm(d)?, norm(t)?, norm(a)?)) };
+    let sine = |d, t, a, p, ph: &Quantity| -> Result<Placeable> {
+        let (d, t, a, p) = (norm(d)?, norm(t)?, norm(a)?, norm(p)?);
+        // NML measures phase from the onset, Arbor from t=0
+        let phase = ph.value - 2.0 * std::f64::consts::PI * d / p;
+        Ok(Placeable::Clamp(
+            vec![(d, a), (d + t, a), (d + t, 0.0)],
+            1.0 / p,
+            phase,
+        ))
+    };
+    let ramp = |d, t, a, b, c| -> Result<Placeable> {
+        let (d, t, a, b, c) = (norm(d)?, norm(t)?, norm(a)?, norm(b)?, norm(c)?);
+        Ok(Placeable::Clamp(
+            vec![(0.0, c), (d, c), (d, a), (d + t, b), (d + t, c)],
+            0.0,
+            0.0,
+        ))
+    };
+    let poisson =
+        |r, start, stop| -> Result<Schedule> { Ok(Schedule::Poisson(start, stop, norm(r)?)) };
+
+    let result = match node.tag_name().name() {
+        "pulseGenerator" => {
+            let p: PulseGenerator = xml::XML::from_node(node)?;
+            vec![pulse(&p.delay, &p.duration, &p.amplitude)?]
+        }
+        "pulseGeneratorDL" => {
+            let p: PulseGeneratorDL = xml::XML::from_node(node)?;
+            vec![pulse(&p.delay, &p.duration, &p.amplitude)?]
+        }
+        "sineGenerator" => {
+            let p: SineGenerator = xml::XML::from_node(node)?;
+            vec![sine(
+                &p.delay,
+                &p.duration,
+                &p.amplitude,
+                &p.period,
+                &p.phase,
+            )?]
+        }
+        "sineGeneratorDL" => {
+            let p: SineGeneratorDL = xml::XML::from_node(node)?;
+            vec![sine(
+                &p.delay,
+                &p.duration,
+                &p.amplitude,
+                &p.period,
+                &p.phase,
+            )?]
+        }
+        "rampGenerator" => {
+            let p: RampGenerator = xml::XML::from_node(node)?;
+            vec![ramp(
+                &p.delay,
+                &p.duration,
+                &p.startAmplitude,
+                &p.finishAmplitude,
+                &p.baselineAmplitude,
+            )?]
+        }
+        "rampGeneratorDL" => {
+            let p: RampGeneratorDL = xml::XML::from_node(node)?;
+            vec![ramp(
+                &p.delay,
+                &p.duration,
+                &p.startAmplitude,
+                &p.finishAmplitude,
+                &p.baselineAmplitude,
+            )?]
+        }
+        "compoundInput" | "compoundInputDL" => {
             let mut result = Vec::new();
-            for bpp in node.descendants() {
-                if bpp.tag_name().name() != "biophysicalProperties" {
-                    continue;
+            for child in node.children().filter(|n| n.is_element()) {
+                if let Some(mut items) = placeables(lems, &child)? {
+                    result.append(&mut items);
                 }
-                let prop: BiophysicalProperties = xml::XML::from_node(&bpp);
-                result.append(&mut acc(&prop, lems)?);
             }
-            let mut file = PathBuf::from(pfx);
-            file.push(id);
-            file.set_extension("acc");
-            info!("Writing ACC to {:?}", &file);
-            write(&file, result.to_sexp())?;
+            result
         }
-        Ok(())
-    })
+        "poissonFiringSynapse" => {
+            let p: PoissonFiringSynapse = xml::XML::from_node(node)?;
+            vec![Placeable::Synapse(
+                p.synapse,
+                1.0,
+                poisson(&p.averageRate, 0.0, None)?,
+            )]
+        }
+        "transientPoissonFiringSynapse" => {
+            let p: TransientPoissonFiringSynapse = xml::XML::from_node(node)?;
+            let (d, t) = (norm(&p.delay)?, norm(&p.duration)?);
+            vec![Placeable::Synapse(
+                p.synapse,
+                1.0,
+                poisson(&p.averageRate, d, Some(d + t))?,
+            )]
+        }
+        "timedSynapticInput" => {
+            let p: TimedSynapticInput = xml::XML::from_node(node)?;
+            let mut ts = Vec::new();
+            for it in &p.body {
+                if let TimedSynapticInputBody::spike(s) = it {
+                    ts.push(norm(&s.time)?);
+                }
+            }
+            vec![Placeable::Synapse(p.synapse, 1.0, Schedule::Explicit(ts))]
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(result))
+}
+
+/// Arbor event schedule of a spike source, if it has a counterpart
+fn schedule(lems: &LemsFile, node: &Node) -> Result<Option<Schedule>> {
+    let norm = |v: &Quantity| -> Result<f64> { Ok(lems.normalise_quantity(v)?.value) };
+    let result = match node.tag_name().name() {
+        "spikeArray" => {
+            let p: SpikeArray = xml::XML::from_node(node)?;
+            let mut ts = Vec::new();
+            for it in &p.body {
+                if let SpikeArrayBody::spike(s) = it {
+                    ts.push(norm(&s.time)?);
+                }
+            }
+            Schedule::Explicit(ts)
+        }
+        "spikeGenerator" => {
+            // First spike after one period
+            let p: SpikeGenerator = xml::XML::from_node(node)?;
+            let dt = norm(&p.period)?;
+            Schedule::Regular(dt, dt)
+        }
+        "spikeGeneratorPoisson" => {
+            let p: SpikeGeneratorPoisson = xml::XML::from_node(node)?;
+            Schedule::Poisson(0.0, None, norm(&p.averageRate)?)
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(result))
 }
 
 fn acc_unimplemented(f: &str) -> Error {
@@ -57,24 +347,21 @@ pub trait Sexp {
     fn to_sexp(&self) -> String;
 }
 
+#[derive(Clone)]
 pub enum Paintable {
-    Xi(String, String),
-    Xo(String, String),
-    Ra(String),
-    Vm(String),
-    Cm(String),
-    Er(String, String),
+    Xi(String, Quantity),
+    Xo(String, Quantity),
+    Ra(Quantity),
+    Vm(Quantity),
+    Cm(Quantity),
+    Er(String, Quantity),
     Em(String, String),
-    Mech(String, Map<String, String>),
+    Mech(String, Map<String, Quantity>),
 }
 
 impl Paintable {
     fn normalise(&self, lems: &LemsFile) -> Result<Self> {
-        let norm = |v: &str| -> Result<String> {
-            let q = Quantity::parse(v)?;
-            let u = lems.normalise_quantity(&q)?;
-            Ok(format!("{}", u.value))
-        };
+        let norm = |v: &Quantity| lems.normalise_quantity(v);
         let r = match self {
             Paintable::Xi(i, v) => Paintable::Xi(i.clone(), norm(v)?),
             Paintable::Xo(i, v) => Paintable::Xo(i.clone(), norm(v)?),
@@ -82,7 +369,7 @@ impl Paintable {
             Paintable::Vm(v) => Paintable::Vm(norm(v)?),
             Paintable::Cm(v) => Paintable::Cm(norm(v)?),
             Paintable::Er(i, v) => Paintable::Er(i.clone(), norm(v)?),
-            Paintable::Em(i, m) => Paintable::Em(i.clone(), norm(m)?),
+            Paintable::Em(i, m) => Paintable::Em(i.clone(), m.clone()),
             Paintable::Mech(m, ps) => {
                 let mut ps = ps.clone();
                 for v in ps.values_mut() {
@@ -98,20 +385,24 @@ impl Paintable {
 impl Sexp for Paintable {
     fn to_sexp(&self) -> String {
         match self {
-            Paintable::Xi(i, v) => format!("(ion-internal-concentration \"{}\" {})", i, v),
-            Paintable::Xo(i, v) => format!("(ion-external-concentration \"{}\" {})", i, v),
-            Paintable::Er(i, v) => format!("(ion-reversal-potential \"{}\" {})", i, v),
+            Paintable::Xi(i, v) => {
+                format!("(ion-internal-concentration \"{}\" {})", i, v.value)
+            }
+            Paintable::Xo(i, v) => {
+                format!("(ion-external-concentration \"{}\" {})", i, v.value)
+            }
+            Paintable::Er(i, v) => format!("(ion-reversal-potential \"{}\" {})", i, v.value),
             Paintable::Em(i, v) => format!(
                 "(ion-reversal-potential-method \"{}\" (mechanism \"{}/{}\"))",
                 i, v, i
             ),
-            Paintable::Ra(v) => format!("(axial-resistivity {})", v),
-            Paintable::Vm(v) => format!("(membrane-potential {})", v),
-            Paintable::Cm(v) => format!("(membrane-capacitance {})", v),
+            Paintable::Ra(v) => format!("(axial-resistivity {})", v.value),
+            Paintable::Vm(v) => format!("(membrane-potential {})", v.value),
+            Paintable::Cm(v) => format!("(membrane-capacitance {})", v.value),
             Paintable::Mech(m, gs) => {
                 let mut result = format!("(density (mechanism \"{}\"", m);
                 for (k, v) in gs.iter() {
-                    let x = format!(" (\"{}\" {})", k, v);
+                    let x = format!(" (\"{}\" {})", k, v.value);
                     result.push_str(&x);
                 }
                 result.push(')');
@@ -122,9 +413,100 @@ impl Sexp for Paintable {
     }
 }
 
+/// Event schedule driving a synapse. This is not part of ACC, but must be
+/// set up by the simulation script, see `Schedule::to_py`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Schedule {
+    /// Fixed spike times
+    Explicit(Vec<f64>),
+    /// Start, optional stop, and frequency
+    Poisson(f64, Option<f64>, f64),
+    /// Start and interval
+    Regular(f64, f64),
+}
+
+impl Schedule {
+    /// Construct as Arbor Python object
+    pub fn to_py(&self) -> String {
+        match self {
+            Schedule::Explicit(ts) => format!(
+                "A.explicit_schedule([{}])",
+                ts.iter()
+                    .map(|t| t.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Schedule::Poisson(t0, Some(t1), f) => {
+                format!("A.poisson_schedule({}, {}, tstop={})", t0, f, t1)
+            }
+            Schedule::Poisson(t0, None, f) => format!("A.poisson_schedule({}, {})", t0, f),
+            Schedule::Regular(t0, dt) => format!("A.regular_schedule({}, {})", t0, dt),
+        }
+    }
+
+    /// Shift all events by `dt`, eg a connection's delay
+    pub fn delayed(&self, dt: f64) -> Self {
+        match self {
+            Schedule::Explicit(ts) => Schedule::Explicit(ts.iter().map(|t| t + dt).collect()),
+            Schedule::Poisson(t0, t1, f) => Schedule::Poisson(t0 + dt, t1.map(|t| t + dt), *f),
+            Schedule::Regular(t0, t) => Schedule::Regular(t0 + dt, *t),
+        }
+    }
+}
+
+/// Items placed on a locset
+#[derive(Clone)]
+pub enum Placeable {
+    /// Current clamp with delay, duration, and amplitude
+    IClamp(f64, f64, f64),
+    /// Current clamp with a piecewise linear envelope of (time, amplitude)
+    /// pairs, frequency, and phase
+    Clamp(Vec<(f64, f64)>, f64, f64),
+    /// Synapse mechanism driven by events of a given weight
+    Synapse(String, f64, Schedule),
+    /// Spike detector with threshold
+    ThresholdDetector(Quantity),
+}
+
+impl Placeable {
+    fn normalise(&self, lems: &LemsFile) -> Result<Self> {
+        let r = match self {
+            Placeable::ThresholdDetector(v) => {
+                Placeable::ThresholdDetector(lems.normalise_quantity(v)?)
+            }
+            p => p.clone(),
+        };
+        Ok(r)
+    }
+}
+
+impl Sexp for Placeable {
+    fn to_sexp(&self) -> String {
+        match self {
+            Placeable::IClamp(d, t, a) => {
+                format!("(current-clamp (envelope-pulse {} {} {}) 0 0)", d, t, a)
+            }
+            Placeable::Clamp(env, f, p) => format!(
+                "(current-clamp (envelope {}) {} {})",
+                env.iter()
+                    .map(|(t, a)| format!("({} {})", t, a))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                f,
+                p
+            ),
+            Placeable::Synapse(m, _, _) => format!("(synapse (mechanism \"{}\"))", m),
+            Placeable::ThresholdDetector(v) => format!("(threshold-detector {})", v.value),
+        }
+    }
+}
+
+#[derive(Clone)]
 pub enum Decor {
     Default(Paintable),
     Paint(String, Paintable),
+    /// Locset, item, and label
+    Place(String, Placeable, String),
 }
 
 impl Decor {
@@ -144,6 +526,7 @@ impl Decor {
         match self {
             Decor::Default(p) => Ok(Decor::Default(p.normalise(lems)?)),
             Decor::Paint(r, p) => Ok(Decor::Paint(r.clone(), p.normalise(lems)?)),
+            Decor::Place(l, p, n) => Ok(Decor::Place(l.clone(), p.normalise(lems)?, n.clone())),
         }
     }
 }
@@ -153,6 +536,7 @@ impl Sexp for Decor {
         match self {
             Decor::Default(i) => format!("(default {})", i.to_sexp()),
             Decor::Paint(r, i) => format!("(paint (region \"{}\") {})", r, i.to_sexp()),
+            Decor::Place(l, i, n) => format!("(place {} {} \"{}\")", l, i.to_sexp(), n),
         }
     }
 }
@@ -174,6 +558,27 @@ impl Sexp for Vec<Decor> {
     }
 }
 
+/// Self-contained cable cell: morphology, label dictionary, and decor
+pub fn cable_cell(mrf: &Morphology, decor: &[Decor]) -> String {
+    let mut result = String::from(
+        "(arbor-component
+  (meta-data (version \"0.1-dev\"))
+  (cable-cell
+    ",
+    );
+    result.push_str(&mrf.to_sexp());
+    result.push_str("\n    (label-dict");
+    for (k, v) in mrf.labels() {
+        result += &format!("\n      (region-def \"{}\" {})", k, v);
+    }
+    result.push_str(")\n    (decor");
+    for it in decor {
+        result += &format!("\n      {}", it.to_sexp());
+    }
+    result.push_str(")))\n");
+    result
+}
+
 pub fn acc(prop: &BiophysicalProperties, lems: &LemsFile) -> Result<Vec<Decor>> {
     use BiophysicalPropertiesBody::*;
     let mut decor = Vec::new();
@@ -215,11 +620,11 @@ fn membrane(membrane: &MembraneProperties) -> Result<Vec<Decor>> {
                 if ion != "non_specific" {
                     result.push(Decor::new(
                         segmentGroup,
-                        Paintable::Er(ion.to_string(), erev.to_string()),
+                        Paintable::Er(ion.to_string(), erev.clone()),
                         false,
                     ));
                 } else {
-                    gs.insert(String::from("e"), erev.to_string());
+                    gs.insert(String::from("e"), erev.clone());
                 }
                 result.push(Decor::new(
                     segmentGroup,
@@ -253,13 +658,31 @@ fn membrane(membrane: &MembraneProperties) -> Result<Vec<Decor>> {
                     true,
                 ));
             }
-            spikeThresh(_) => {}
+            spikeThresh(SpikeThresh {
+                value,
+                segmentGroup,
+            }) => {
+                // Default to the proximal end of the cell/group
+                let (locset, label) = if segmentGroup.is_empty() || segmentGroup == "all" {
+                    (String::from("(root)"), String::from("detector"))
+                } else {
+                    (
+                        format!("(proximal (region \"{}\"))", segmentGroup),
+                        format!("detector_{}", segmentGroup),
+                    )
+                };
+                result.push(Decor::Place(
+                    locset,
+                    Placeable::ThresholdDetector(value.clone()),
+                    label,
+                ));
+            }
             specificCapacitance(SpecificCapacitance {
                 value,
                 segmentGroup,
             }) => result.push(Decor::new(
                 segmentGroup,
-                Paintable::Cm(value.to_string()),
+                Paintable::Cm(value.clone()),
                 false,
             )),
             initMembPotential(InitMembPotential {
@@ -267,7 +690,7 @@ fn membrane(membrane: &MembraneProperties) -> Result<Vec<Decor>> {
                 segmentGroup,
             }) => result.push(Decor::new(
                 segmentGroup,
-                Paintable::Vm(value.to_string()),
+                Paintable::Vm(value.clone()),
                 false,
             )),
             channelPopulation(_)
@@ -299,12 +722,12 @@ fn intra(intra: &IntracellularProperties) -> Result<Vec<Decor>> {
                 let ion = ion.as_deref().unwrap();
                 result.push(Decor::new(
                     segmentGroup,
-                    Paintable::Xi(ion.to_string(), initialConcentration.to_string()),
+                    Paintable::Xi(ion.to_string(), initialConcentration.clone()),
                     false,
                 ));
                 result.push(Decor::new(
                     segmentGroup,
-                    Paintable::Xo(ion.to_string(), initialExtConcentration.to_string()),
+                    Paintable::Xo(ion.to_string(), initialExtConcentration.clone()),
                     false,
                 ));
             }
@@ -314,7 +737,7 @@ fn intra(intra: &IntracellularProperties) -> Result<Vec<Decor>> {
             }) => {
                 result.push(Decor::new(
                     segmentGroup,
-                    Paintable::Ra(value.to_string()),
+                    Paintable::Ra(value.clone()),
                     false,
                 ));
             }
@@ -328,3 +751,122 @@ fn extra(_: &ExtracellularProperties) -> Result<Vec<Decor>> {
     info!("Not handling extracellular settings, if required please file an issue.");
     Ok(Vec::new())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    #[cfg_attr(
+        not(core_types),
+        ignore = "needs the NML2 core types, run bootstrap.sh"
+    )]
+    fn test_stimuli_per_instance() {
+        let lems = crate::fixture::core_types();
+        let dir = crate::fixture::Scratch::new("acc-stimuli");
+        let file = dir.write(
+            "net.nml",
+            r#"<neuroml id="net">
+  <pulseGenerator id="ic" delay="10ms" duration="20ms" amplitude="1nA"/>
+  <network id="net">
+    <population id="pop" component="cell" size="2"/>
+    <inputList id="ics" population="pop" component="ic">
+      <input id="0" target="../pop[0]" destination="synapses"/>
+      <input id="1" target="../pop[1]" destination="synapses" segmentId="1"/>
+    </inputList>
+  </network>
+</neuroml>"#,
+        );
+        let mut stimuli = stimuli(&lems, &[file]).unwrap();
+        let placed = instances(&mut stimuli, "cell")
+            .into_iter()
+            .map(|(name, decor)| (name, decor.iter().map(|d| d.to_sexp()).collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+        let clamp = |loc: &str, label: &str| {
+            format!(
+                "(place {} (current-clamp (envelope-pulse 10 20 1) 0 0) \"{}\")",
+                loc, label
+            )
+        };
+        assert_eq!(
+            placed,
+            vec![
+                (String::from("cell"), vec![]),
+                (
+                    String::from("cell_pop_0"),
+                    vec![clamp("(on-components 0.5 (region \"0\"))", "ics_0")]
+                ),
+                (
+                    String::from("cell_pop_1"),
+                    vec![clamp("(on-components 0.5 (region \"1\"))", "ics_1")]
+                ),
+            ]
+        );
+        assert!(stimuli.is_empty());
+    }
+
+    #[test]
+    #[cfg_attr(
+        not(core_types),
+        ignore = "needs the NML2 core types, run bootstrap.sh"
+    )]
+    fn test_spike_sources() {
+        let lems = crate::fixture::core_types();
+        let dir = crate::fixture::Scratch::new("acc-spikes");
+        let file = dir.write(
+            "net.nml",
+            r#"<neuroml id="net">
+  <spikeArray id="spikes">
+    <spike id="0" time="5ms"/>
+    <spike id="1" time="15ms"/>
+  </spikeArray>
+  <spikeGeneratorPoisson id="poisson" averageRate="0.5per_ms"/>
+  <network id="net">
+    <population id="pop" component="cell" size="2"/>
+    <population id="pre" component="spikes" size="1"/>
+    <population id="bg" component="poisson" size="1"/>
+    <projection id="prj" presynapticPopulation="pre" postsynapticPopulation="pop" synapse="ampa">
+      <connectionWD id="0" preCellId="../pre[0]" postCellId="../pop[1]" postSegmentId="2" postFractionAlong="0.25" weight="2" delay="1ms"/>
+    </projection>
+    <projection id="noise" presynapticPopulation="bg" postsynapticPopulation="pop" synapse="gaba">
+      <connection id="0" preCellId="../bg/0/poisson" postCellId="../pop/1/cell"/>
+    </projection>
+    <projection id="rec" presynapticPopulation="pop" postsynapticPopulation="pop" synapse="ampa">
+      <connection id="0" preCellId="../pop[0]" postCellId="../pop[1]"/>
+    </projection>
+  </network>
+</neuroml>"#,
+        );
+        let stimuli = stimuli(&lems, &[file]).unwrap();
+        let placed = stimuli["cell"][&(String::from("pop"), 1)]
+            .iter()
+            .map(|d| match d {
+                Decor::Place(l, Placeable::Synapse(m, w, s), n) => {
+                    (l.as_str(), m.as_str(), *w, s.clone(), n.as_str())
+                }
+                _ => panic!("Expected a synapse"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            placed,
+            vec![
+                (
+                    "(on-components 0.25 (region \"2\"))",
+                    "ampa",
+                    2.0,
+                    Schedule::Explicit(vec![6.0, 16.0]),
+                    "prj_0"
+                ),
+                (
+                    "(on-components 0.5 (region \"0\"))",
+                    "gaba",
+                    1.0,
+                    Schedule::Poisson(0.0, None, 0.5),
+                    "noise_0"
+                ),
+            ]
+        );
+        assert_eq!(stimuli.len(), 1);
+        assert_eq!(stimuli["cell"].len(), 1);
+    }
+}
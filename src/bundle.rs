@@ -1,60 +1,332 @@
 use std::collections::HashMap as Map;
 use std::collections::HashSet as Set;
 use std::fs::{create_dir_all, write};
-use tracing::info;
+use tracing::{info, warn};
 
-use crate::acc::Paintable;
+use crate::acc::{Paintable, Placeable};
 use crate::{
     acc::{self, Sexp},
+    cell,
     error::{Error, Result},
     expr::Expr,
     expr::Quantity,
     instance::{Collapsed, Context, Instance},
     lems::file::LemsFile,
+    network,
     neuroml::process_files,
     neuroml::raw::{
-        BiophysicalProperties, BiophysicalPropertiesBody, ChannelDensity, MembranePropertiesBody,
-        PulseGenerator,
+        BiophysicalPropertiesBody, Cell, CellBody, ChannelDensity, ChannelDensityNernst,
+        MembranePropertiesBody, Morphology, NeuroMLDocument, NeuroMLDocumentBody, PulseGenerator,
     },
     nmodl,
+    simulation::Simulation,
+    sweep::{Overrides, Sweep},
     variable::{VarKind, Variable},
-    xml::XML,
+    xml::{to_nml2_string, XML},
 };
 
-pub fn export(lems: &LemsFile, nml: &[String], bundle: &str, use_super_mechs: bool) -> Result<()> {
-    export_template(lems, nml, bundle)?;
+pub fn export(
+    lems: &LemsFile,
+    nml: &[String],
+    bundle: &str,
+    use_super_mechs: bool,
+    detector: &Option<&str>,
+    set: &Overrides,
+    sweep: &Sweep,
+) -> Result<()> {
+    if use_super_mechs && !sweep.is_empty() {
+        return Err(Error::Nml {
+            what: String::from("Sweeps need a shared catalogue, cannot use super mechanisms"),
+        });
+    }
+
+    export_template(lems, nml, bundle, "here")?;
+    export_morphologies(lems, nml, bundle)?;
 
-    // We always export these to keep synapse etc alive
-    nmodl::export(lems, nml, &None, "-*", &format!("{}/cat", bundle))?;
+    // We always export these to keep synapse etc alive; swept parameters
+    // remain tweakable, so all points can share the catalogue.
+    let cat = format!("{}/cat", bundle);
+    nmodl::export(lems, nml, &None, "-*", &cat, &set.with(&sweep.retained()))?;
 
     if use_super_mechs {
-        export_with_super_mechanisms(lems, nml, bundle)?;
+        export_with_super_mechanisms(lems, nml, bundle, detector, set)?;
     } else {
-        acc::export(lems, nml, &None, &format!("{}/acc", bundle))?;
+        // Parameters of channels are already set in the catalogue
+        let set = set.densities();
+        acc::export(lems, nml, &None, detector, &format!("{}/acc", bundle), &set)?;
+        sweep.visit(&set, bundle, |ovr, dir| {
+            export_template(lems, nml, dir, "here.parent.parent")?;
+            acc::export(lems, nml, &None, detector, &format!("{}/acc", dir), ovr)
+        })?;
+    }
+    Ok(())
+}
+
+/// Write the morphology of each cell to `mrf/<id>.nml` under `bundle`, as a
+/// standalone NML2 file for use outside of Arbor.
+fn export_morphologies(lems: &LemsFile, nml: &[String], bundle: &str) -> Result<()> {
+    create_dir_all(format!("{}/mrf", bundle))?;
+    for (id, mrf) in cell::morphologies(&lems.include_dir, nml)? {
+        if let Some(mrf) = mrf {
+            write(format!("{}/mrf/{}.nml", bundle, id), mk_mrf(&id, &mrf))?;
+        }
     }
     Ok(())
 }
 
-fn mk_main_py(lems: &LemsFile, id: &str, stim: &[PulseGenerator]) -> Result<String> {
-    let norm = |v: &str| -> Result<String> {
-        let q = Quantity::parse(v)?;
-        let u = lems.normalise_quantity(&q)?;
+/// NML2 document holding cell `id` with its morphology `mrf` inline
+fn mk_mrf(id: &str, mrf: &Morphology) -> String {
+    let cell = Cell {
+        morphology: None,
+        biophysicalProperties: None,
+        metaid: None,
+        id: id.to_string(),
+        neuroLexId: None,
+        body: vec![CellBody::morphology(mrf.clone())],
+    };
+    to_nml2_string(&NeuroMLDocument {
+        metaid: None,
+        id: id.to_string(),
+        neuroLexId: None,
+        body: vec![NeuroMLDocumentBody::cell(cell)],
+    })
+}
+
+/// Write traces recorded for `probes` to disk, see `mk_main_py`
+const OUTPUT_PY: &str = "
+# Write traces like jnml: tab separated, time followed by one column per
+# quantity, all in SI units divided by the column's scale, if given
+traces = iter(sim.traces)
+for fn, locs in probes:
+    cols = [next(traces) for _ in locs]
+    path = here / fn
+    path.parent.mkdir(parents=True, exist_ok=True)
+    with open(path, 'w') as fd:
+        for ix, t in enumerate(cols[0].time):
+            row = [t*time_to_si] + [col.value[ix]*f for col, (_, f) in zip(cols, locs)]
+            fd.write('\\t'.join(str(x) for x in row) + '\\n')
+";
+
+/// Unit of the time axis of Arbor's traces
+const TIME_UNIT: &str = "ms";
+
+/// Ion species unknown to Arbor with valence and initial internal and external
+/// concentrations
+struct Ion {
+    name: String,
+    valence: Option<i64>,
+    xi: f64,
+    xo: f64,
+}
+
+/// Species Arbor does not define by default, eg the second calcium pool `ca2`
+/// of `cell2CaPools`. These must be declared globally before use.
+fn extra_ions(decor: &[acc::Decor]) -> Vec<Ion> {
+    let mut result: Vec<Ion> = Vec::new();
+    for d in decor {
+        let (p, first) = match d {
+            acc::Decor::Default(p) => (p, true),
+            acc::Decor::Paint(_, p) => (p, false),
+            _ => continue,
+        };
+        let name = match p {
+            Paintable::Xi(i, _)
+            | Paintable::Xo(i, _)
+            | Paintable::Er(i, _)
+            | Paintable::Em(i, _) => i,
+            _ => continue,
+        };
+        if matches!(name.as_str(), "na" | "k" | "ca") {
+            continue;
+        }
+        let ix = if let Some(ix) = result.iter().position(|i| &i.name == name) {
+            ix
+        } else {
+            let valence = match name.trim_end_matches(|c: char| c.is_ascii_digit()) {
+                "ca" => Some(2),
+                "na" | "k" => Some(1),
+                "cl" => Some(-1),
+                _ => None,
+            };
+            result.push(Ion {
+                name: name.clone(),
+                valence,
+                xi: 0.0,
+                xo: 0.0,
+            });
+            result.len() - 1
+        };
+        // Prefer defaults, the ACC file sets the actual values anyhow
+        match p {
+            Paintable::Xi(_, v) if first || result[ix].xi == 0.0 => result[ix].xi = v.value,
+            Paintable::Xo(_, v) if first || result[ix].xo == 0.0 => result[ix].xo = v.value,
+            _ => {}
+        }
+    }
+    result
+}
+
+/// Probe for a LEMS quantity on the cell `id`, given as the location and the
+/// unit Arbor reports values in. Accepts membrane potentials as `pop[i]/v`,
+/// `pop/i/cell/v`, and `pop/i/cell/segment/v`, where `pops` maps population to
+/// cell ids.
+fn probe_location(
+    quantity: &str,
+    id: &str,
+    pops: &Map<String, String>,
+) -> Option<(String, &'static str)> {
+    let path = quantity.split('/').collect::<Vec<_>>();
+    let (pop, seg) = match path.as_slice() {
+        [pop, "v"] => (pop.split('[').next()?, "0"),
+        [pop, _, _, "v"] => (*pop, "0"),
+        [pop, _, _, seg, "v"] => (*pop, *seg),
+        _ => return None,
+    };
+    if pops.get(pop).map(|c| c == id).unwrap_or(false) {
+        Some((format!("(on-components 0.5 (region \"{}\"))", seg), "mV"))
+    } else {
+        None
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn mk_main_py(
+    lems: &LemsFile,
+    id: &str,
+    name: &str,
+    stim: &[PulseGenerator],
+    events: &[acc::Decor],
+    sim: Option<&Simulation>,
+    pops: &Map<String, String>,
+    ions: &[Ion],
+    cat: &str,
+) -> Result<String> {
+    let norm = |v: &Quantity| -> Result<String> {
+        let u = lems.normalise_quantity(v)?;
         Ok(format!("{}", u.value))
     };
 
-    let ics = stim
+    let (length, step) = if let Some(sim) = sim {
+        (norm(&sim.length)?, norm(&sim.step)?)
+    } else {
+        (String::from("100"), String::from("0.0025"))
+    };
+
+    // Probes and output files derived from the LEMS OutputFiles
+    let mut probes = Vec::new();
+    for w in sim.iter().flat_map(|s| s.writers.iter()) {
+        let mut locs = Vec::new();
+        for r in &w.records {
+            if let Some((loc, unit)) = probe_location(&r.quantity, id, pops) {
+                // Arbor's values to SI units, divided by the scale, if given
+                let mut factor = lems.si_value(&Quantity {
+                    value: 1.0,
+                    unit: Some(unit.to_string()),
+                })?;
+                if let Some(scale) = &r.scale {
+                    factor /= lems.si_value(scale)?;
+                }
+                locs.push(format!("('{}', {})", loc, factor));
+            } else {
+                warn!(
+                    "Cannot record {} in {} for cell {}, skipping",
+                    r.quantity, w.name, id
+                );
+            }
+        }
+        if !locs.is_empty() {
+            probes.push(format!("    ('{}', [{}]),", w.name, locs.join(", ")));
+        }
+    }
+    let output = if probes.is_empty() {
+        String::new()
+    } else {
+        String::from(OUTPUT_PY)
+    };
+    let probes = if probes.is_empty() {
+        String::from(
+            "# Add probes here (example below)
+# sim.probe('voltage', '<FIXME>', frequency=10)
+",
+        )
+    } else {
+        format!(
+            "# Probes as (file, [(locset, factor to output unit)]) from the LEMS OutputFiles
+probes = [
+{}
+]
+time_to_si = {}
+
+for _, locs in probes:
+    for loc, _ in locs:
+        sim.probe('voltage', loc, frequency=1/{})
+",
+            probes.join("\n"),
+            lems.si_value(&Quantity {
+                value: 1.0,
+                unit: Some(TIME_UNIT.to_string()),
+            })?,
+            step
+        )
+    };
+    // Synapses placed in the ACC file are driven by event generators
+    let events = events
+        .iter()
+        .filter_map(|d| match d {
+            acc::Decor::Place(_, Placeable::Synapse(_, w, s), l) => Some(format!(
+                "sim.event_generator(A.event_generator('{}', {}, {}))\n",
+                l,
+                w,
+                s.to_py()
+            )),
+            _ => None,
+        })
+        .collect::<String>();
+    let events = if events.is_empty() {
+        events
+    } else {
+        format!("\n# Event sources for synapses\n{}", events)
+    };
+
+    let ions = ions
         .iter()
-        .map(|p| {
-            Ok(format!(
-                "# decor.place('<FIXME>', A.iclamp({}, {}, {}), '{}')",
-                norm(&p.delay)?,
-                norm(&p.duration)?,
-                norm(&p.amplitude)?,
-                p.id
-            ))
+        .map(|i| {
+            let valence = i
+                .valence
+                .map(|v| v.to_string())
+                .unwrap_or_else(|| String::from("<FIXME>"));
+            format!(
+                "sim.properties.set_ion('{}', valence={}, int_con={}, ext_con={}, rev_pot=0)\n",
+                i.name, valence, i.xi, i.xo
+            )
         })
-        .collect::<Result<Vec<_>>>()?
-        .join("\n");
+        .collect::<String>();
+    let ions = if ions.is_empty() {
+        ions
+    } else {
+        format!("\n# Ion species beyond Arbor's defaults\n{}", ions)
+    };
+
+    let ics = if stim.is_empty() {
+        String::from("# Stimuli are placed in the ACC file")
+    } else {
+        format!(
+            "# Place your stimuli by adding these to acc/{}.acc (fill in a locset)\n{}",
+            name,
+            stim.iter()
+                .map(|p| {
+                    Ok(format!(
+                        "#   (place <FIXME> (current-clamp (envelope-pulse {} {} {}) 0 0) \"{}\")",
+                        norm(&p.delay)?,
+                        norm(&p.duration)?,
+                        norm(&p.amplitude)?,
+                        p.id
+                    ))
+                })
+                .collect::<Result<Vec<_>>>()?
+                .join("\n")
+        )
+    };
 
     Ok(format!(
         "#!/usr/bin/env python3
@@ -67,97 +339,120 @@ from pathlib import Path
 
 here = Path(__file__).parent
 
-def nml_load_cell():
-    nml = A.neuroml(here / 'mrf' / '{id}.nml').cell_morphology(\"{id}\", allow_spherical_root=True)
-    lbl = A.label_dict()
-    lbl.append(nml.segments())
-    lbl.append(nml.named_segments())
-    lbl.append(nml.groups())
-    lbl['all'] = '(all)'
-    dec = A.load_component(here / 'acc' / '{id}.acc').component
-    return nml.morphology, lbl, dec
+def load_cell():
+    return A.load_component(here / 'acc' / '{name}.acc').component
 
 def mk_cat():
-    sp.run('arbor-build-catalogue local cat', shell=True, check=True)
+    sp.run('arbor-build-catalogue local cat', shell=True, check=True, cwd={cat})
     res = A.default_catalogue()
-    cat = A.load_catalogue(here / 'local-catalogue.so')
+    cat = A.load_catalogue({cat} / 'local-catalogue.so')
     res.extend(cat, '')
     return res
 
-morph, labels, decor = nml_load_cell()
-
-# Place your stimuli here (add a locset and uncomment)
 {ics}
 
-cell = A.cable_cell(morph, labels, decor)
+cell = load_cell()
 sim  = A.single_cell_model(cell)
 
 sim.properties.catalogue = mk_cat()
-
-# Add probes here (example below)
-# sim.probe('voltage', '<FIXME>', frequency=10)
-
+{ions}
+{probes}{events}
 # Now run the simulation
-sim.run(100, 0.0025)
-",
-        id = id,
-        ics = ics
+sim.run({length}, {step})
+{output}",
+        name = name,
+        ics = ics,
+        ions = ions,
+        probes = probes,
+        events = events,
+        output = output,
+        length = length,
+        step = step,
+        cat = cat,
     ))
 }
 
-fn mk_mrf(id: &str, mrf: &str) -> String {
-    format!(
-        r#"<?xml version="1.0" encoding="UTF-8"?>
-
-<neuroml xmlns="http://www.neuroml.org/schema/neuroml2"
-         xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"
-         xsi:schemaLocation="http://www.neuroml.org/schema/neuroml2  https://raw.githubusercontent.com/NeuroML/NeuroML2/master/Schemas/NeuroML2/NeuroML_v2beta3.xsd"
-    id="{}">
-    <cell id="{}">
-        {}
-    </cell>
-</neuroml>
-"#,
-        id, id, mrf
-    )
-}
-
-fn export_template(lems: &LemsFile, nml: &[String], bundle: &str) -> Result<()> {
+/// Write a `main.<cell>.py` per cell to `bundle`, loading the catalogue from
+/// the directory given by the Python expression `cat`, eg `here`.
+fn export_template(lems: &LemsFile, nml: &[String], bundle: &str, cat: &str) -> Result<()> {
     create_dir_all(&bundle)?;
-    create_dir_all(&format!("{}/mrf", bundle))?;
 
+    let sim = Simulation::from_files(&lems.include_dir, nml)?;
+    let placed = network::inputs(&lems.include_dir, nml)?
+        .into_iter()
+        .map(|i| i.source)
+        .collect::<Set<_>>();
+
+    let mut stimuli = acc::stimuli(lems, nml)?;
     let mut ics = Vec::new();
-    let mut ids = Vec::new();
-    process_files(nml, |_, node| {
-        // TODO This is clunky and too restrictive
-        if node.tag_name().name() == "pulseGenerator" {
-            let ic: PulseGenerator = XML::from_node(node);
-            ics.push(ic);
+    let mut nets = Map::new();
+    process_files(&lems.include_dir, nml, |_, node| {
+        if node.tag_name().name() == "network" {
+            if let Some(id) = node.attribute("id") {
+                let pops = node
+                    .children()
+                    .filter(|n| n.tag_name().name() == "population")
+                    .filter_map(|n| Some((n.attribute("id")?, n.attribute("component")?)))
+                    .map(|(p, c)| (p.to_string(), c.to_string()))
+                    .collect::<Map<_, _>>();
+                nets.insert(id.to_string(), pops);
+            }
         }
 
-        let doc = node.document().input_text();
-        for mrf in node.descendants() {
-            if node.tag_name().name() == "cell" {
-                let id = node.attribute("id").ok_or(Error::Nml {
-                    what: String::from("Cell has no id"),
-                })?;
-                ids.push(id.to_string());
-                if mrf.tag_name().name() == "morphology" {
-                    write(
-                        format!("{}/mrf/{}.nml", bundle, id),
-                        mk_mrf(id, &doc[mrf.range()]),
-                    )?;
-                }
+        // Generators not used by any input need to be placed by hand
+        if node.tag_name().name() == "pulseGenerator" {
+            let ic: PulseGenerator = XML::from_node(node)?;
+            if !placed.contains(&ic.id) {
+                ics.push(ic);
             }
         }
+
         Ok(())
     })?;
 
+    let mut ids = Vec::new();
+    let mut ions = Map::new();
+    for cell in cell::cells(&lems.include_dir, nml)? {
+        if let Some(prop) = &cell.properties {
+            ions.insert(cell.id.clone(), extra_ions(&acc::acc(prop, lems)?));
+        }
+        ids.push(cell.id);
+    }
+
+    // Restrict to what the simulation targets, either a cell or a network
+    let mut pops = Map::new();
+    if let Some(sim) = &sim {
+        if let Some(ps) = nets.get(&sim.target) {
+            pops = ps.clone();
+            ids.retain(|id| pops.values().any(|c| c == id));
+        } else {
+            ids.retain(|id| id == &sim.target);
+        }
+        if ids.is_empty() {
+            warn!(
+                "Simulation {} targets {}, but no matching cells were found",
+                sim.id, sim.target
+            );
+        }
+    }
+
     for id in &ids {
-        write(
-            &format!("{}/main.{}.py", bundle, id),
-            mk_main_py(lems, id, &ics)?,
-        )?;
+        for (name, events) in acc::instances(&mut stimuli, id) {
+            write(
+                &format!("{}/main.{}.py", bundle, name),
+                mk_main_py(
+                    lems,
+                    id,
+                    &name,
+                    &ics,
+                    &events,
+                    sim.as_ref(),
+                    &pops,
+                    ions.get(id).map(|v| v.as_slice()).unwrap_or_default(),
+                    cat,
+                )?,
+            )?;
+        }
     }
     Ok(())
 }
@@ -165,96 +460,118 @@ fn export_template(lems: &LemsFile, nml: &[String], bundle: &str) -> Result<()>
 struct Assign {
     m: String,
     g: Quantity,
-    e: Quantity,
+    /// Reversal potential, `None` if computed by the Nernst equation
+    e: Option<Quantity>,
 }
 
 impl Assign {
-    fn new(m: &str, g: &str, e: &str) -> Result<Self> {
-        let g = Quantity::parse(g)?;
-        let e = Quantity::parse(e)?;
-        Ok(Self {
+    fn new(m: &str, g: &Quantity, e: Option<&Quantity>) -> Self {
+        Self {
             m: m.to_string(),
-            g,
-            e,
-        })
+            g: g.clone(),
+            e: e.cloned(),
+        }
     }
 }
 
-pub fn export_with_super_mechanisms(lems: &LemsFile, nml: &[String], bundle: &str) -> Result<()> {
+pub fn export_with_super_mechanisms(
+    lems: &LemsFile,
+    nml: &[String],
+    bundle: &str,
+    detector: &Option<&str>,
+    set: &Overrides,
+) -> Result<()> {
     use BiophysicalPropertiesBody::*;
     use MembranePropertiesBody::*;
     let mut sms: Map<(String, String), Vec<Assign>> = Map::new();
-    process_files(nml, |_, node| {
-        if node.tag_name().name() == "cell" {
-            let id = node.attribute("id").ok_or(Error::Nml {
-                what: "Cell without id".to_string(),
-            })?;
-            let mut ass = Vec::new();
-            for bpp in node.descendants() {
-                if bpp.tag_name().name() != "biophysicalProperties" {
-                    continue;
-                }
-                let prop: BiophysicalProperties = XML::from_node(&bpp);
-                ass.append(&mut acc::acc(&prop, lems)?);
-                for item in &prop.body {
-                    if let membraneProperties(membrane) = item {
-                        for item in &membrane.body {
-                            if let channelDensity(ChannelDensity {
+    let mut stimuli = acc::stimuli(lems, nml)?;
+    create_dir_all(format!("{}/acc", bundle))?;
+    create_dir_all(format!("{}/cat", bundle))?;
+    for cell::Cell {
+        id,
+        morphology,
+        mut properties,
+    } in cell::cells(&lems.include_dir, nml)?
+    {
+        let mut ass = Vec::new();
+        if let Some(prop) = &mut properties {
+            set.apply_properties(&id, prop);
+            ass.append(&mut acc::acc(prop, lems)?);
+            for item in &prop.body {
+                if let membraneProperties(membrane) = item {
+                    for item in &membrane.body {
+                        let (ion_channel, cond_density, erev, segment_group) = match item {
+                            channelDensity(ChannelDensity {
                                 ionChannel,
                                 condDensity,
                                 erev,
                                 segmentGroup,
                                 ..
-                            }) = item
-                            {
-                                let a = Assign::new(
-                                    ionChannel,
-                                    condDensity.as_deref().unwrap(),
-                                    erev.as_str(),
-                                )?;
-                                let region = if segmentGroup.is_empty() {
-                                    "all"
-                                } else {
-                                    segmentGroup
-                                }
-                                .to_string();
-                                sms.entry((id.to_string(), region)).or_default().push(a);
-                            }
+                            }) => (ionChannel, condDensity, Some(erev), segmentGroup),
+                            channelDensityNernst(ChannelDensityNernst {
+                                ionChannel,
+                                condDensity,
+                                segmentGroup,
+                                ..
+                            }) => (ionChannel, condDensity, None, segmentGroup),
+                            _ => continue,
+                        };
+                        let g = cond_density.as_ref().ok_or(Error::Nml {
+                            what: format!("ChannelDensity on {} without condDensity", ion_channel),
+                        })?;
+                        let a = Assign::new(ion_channel, g, erev);
+                        let region = if segment_group.is_empty() {
+                            "all"
+                        } else {
+                            segment_group
                         }
+                        .to_string();
+                        sms.entry((id.to_string(), region)).or_default().push(a);
                     }
                 }
             }
+        }
 
-            let path = format!("{}/acc/{}.acc", bundle, id);
-
-            let mut seen = Set::new();
-            let mut ass_sm = Vec::new();
-            for d in ass.into_iter() {
-                match d {
-                    acc::Decor::Paint(r, Paintable::Mech(_, _)) => {
-                        if !seen.contains(&r) {
-                            ass_sm.push(acc::Decor::Paint(
-                                r.to_string(),
-                                Paintable::Mech(format!("{}_{}", id, r), Map::new()),
-                            ));
-                            seen.insert(r.to_string());
-                        }
+        let mut seen = Set::new();
+        let mut ass_sm = Vec::new();
+        for d in ass.into_iter() {
+            match d {
+                acc::Decor::Paint(r, Paintable::Mech(_, _)) => {
+                    if !seen.contains(&r) {
+                        ass_sm.push(acc::Decor::Paint(
+                            r.to_string(),
+                            Paintable::Mech(format!("{}_{}", id, r), Map::new()),
+                        ));
+                        seen.insert(r.to_string());
                     }
-                    _ => ass_sm.push(d),
                 }
+                _ => ass_sm.push(d),
             }
+        }
 
+        if let Some(loc) = detector {
+            acc::relocate_detectors(&mut ass_sm, loc);
+        }
+        for (name, mut stimuli) in acc::instances(&mut stimuli, &id) {
+            let mut decor = ass_sm.clone();
+            decor.append(&mut stimuli);
+            let path = format!("{}/acc/{}.acc", bundle, name);
             info!("Writing Super Mechanism ACC to {:?}", &path);
-            write(&path, ass_sm.to_sexp())?;
+            if let Some(mrf) = &morphology {
+                write(&path, acc::cable_cell(mrf, &decor))?;
+            } else {
+                warn!("Cell {} has no morphology, writing decor only", id);
+                write(&path, decor.to_sexp())?;
+            }
         }
-        Ok(())
-    })?;
+    }
 
     let mut instances = Vec::new();
-    process_files(nml, |_, node| {
+    process_files(&lems.include_dir, nml, |_, node| {
         let tag = node.tag_name().name();
         if lems.derived_from(tag, "baseIonChannel") {
-            let instance = Instance::new(lems, node)?;
+            let mut instance = Instance::new(lems, node)?;
+            set.apply_instance(lems, &mut instance)?;
             instances.push(instance);
         }
         Ok(())
@@ -272,6 +589,9 @@ pub fn export_with_super_mechanisms(lems: &LemsFile, nml: &[String], bundle: &st
                     let g = lems.normalise_quantity(g)?;
                     inst.parameters.insert(format!("{}_conductance", m), g);
                     if ion.is_empty() {
+                        let e = e.as_ref().ok_or(Error::Nml {
+                            what: format!("Non-specific channel {} needs a reversal potential", m),
+                        })?;
                         let e = lems.normalise_quantity(e)?;
                         inst.parameters.insert(String::from("e"), e);
                         inst.component_type.parameters.push(String::from("e"));
@@ -282,6 +602,23 @@ pub fn export_with_super_mechanisms(lems: &LemsFile, nml: &[String], bundle: &st
             }
         }
 
+        // Concentrations of both calcium pools, as in nmodl::to_nmodl. These
+        // are read by concentration dependent channels, eg KCa, whether or not
+        // the region also has a calcium channel. Only `ca2` channels define
+        // the second pool themselves.
+        let mut pools = vec![("caConc", "cai")];
+        if !ions.contains_key("ca2") {
+            pools.push(("caConc2", "ca2i"));
+        }
+        for (name, xi) in pools {
+            coll.variables.push(Variable {
+                name: name.to_string(),
+                exposure: None,
+                dimension: String::from("concentration"),
+                kind: VarKind::Derived(Vec::new(), Some(Expr::parse(xi)?)),
+            });
+        }
+
         // Add iX
         for (ion, mechs) in &ions {
             if !ion.is_empty() {
@@ -323,7 +660,7 @@ pub fn export_with_super_mechanisms(lems: &LemsFile, nml: &[String], bundle: &st
         }
 
         // Simplify with none to keep and export
-        coll = coll.simplify("-*");
+        coll = coll.simplify("-*")?;
         let nmodl = nmodl::mk_nmodl(&coll)?;
 
         let path = format!("{}/cat/{}-{}.mod", bundle, id, reg);
@@ -336,3 +673,170 @@ pub fn export_with_super_mechanisms(lems: &LemsFile, nml: &[String], bundle: &st
 
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::simulation::{DataWriter, Record};
+
+    #[test]
+    fn test_probe_location() {
+        let pops = Map::from([
+            (String::from("pop"), String::from("cell")),
+            (String::from("other"), String::from("cell2")),
+        ]);
+        let probe = |q: &str| probe_location(q, "cell", &pops);
+        let soma = Some((String::from("(on-components 0.5 (region \"0\"))"), "mV"));
+        assert_eq!(probe("pop[0]/v"), soma);
+        assert_eq!(probe("pop/0/cell/v"), soma);
+        assert_eq!(
+            probe("pop/1/cell/3/v"),
+            Some((String::from("(on-components 0.5 (region \"3\"))"), "mV"))
+        );
+        // Other cells, unknown populations, and quantities we cannot record
+        assert_eq!(probe("other[0]/v"), None);
+        assert_eq!(probe("nope[0]/v"), None);
+        assert_eq!(probe("pop[0]/caConc"), None);
+        assert_eq!(
+            probe("pop/0/cell/biophys/membraneProperties/na/na/m/q"),
+            None
+        );
+    }
+
+    #[test]
+    #[cfg_attr(
+        not(core_types),
+        ignore = "needs the NML2 core types, run bootstrap.sh"
+    )]
+    fn test_probe_units() {
+        let lems = crate::fixture::core_types();
+        let record = |quantity: &str, scale: Option<&str>| Record {
+            id: quantity.to_string(),
+            quantity: quantity.to_string(),
+            scale: scale.map(|s| Quantity::parse(s).unwrap()),
+        };
+        let sim = Simulation {
+            id: String::from("sim"),
+            target: String::from("net"),
+            length: Quantity::parse("10ms").unwrap(),
+            step: Quantity::parse("0.025ms").unwrap(),
+            writers: vec![DataWriter {
+                id: String::from("of"),
+                name: String::from("v.dat"),
+                records: vec![
+                    record("pop[0]/v", None),
+                    record("pop/0/cell/2/v", Some("1mV")),
+                    record("pop[0]/caConc", None),
+                ],
+            }],
+            displays: Vec::new(),
+        };
+        let pops = Map::from([(String::from("pop"), String::from("cell"))]);
+        let py = mk_main_py(
+            &lems,
+            "cell",
+            "cell",
+            &[],
+            &[],
+            Some(&sim),
+            &pops,
+            &[],
+            "here",
+        )
+        .unwrap();
+        // Arbor records mV and ms, jnml writes V and s unless scaled
+        assert!(py.contains(
+            "    ('v.dat', [('(on-components 0.5 (region \"0\"))', 0.001), ('(on-components 0.5 (region \"2\"))', 1)]),\n"
+        ));
+        assert!(py.contains("time_to_si = 0.001\n"));
+    }
+
+    #[test]
+    #[cfg_attr(
+        not(core_types),
+        ignore = "needs the NML2 core types, run bootstrap.sh"
+    )]
+    fn test_super_mechanisms_calcium() {
+        let mut lems = crate::fixture::core_types();
+        let dir = crate::fixture::Scratch::new("super-mechanisms-calcium");
+        let nml = dir.write(
+            "cells.nml",
+            r#"<neuroml id="cells">
+  <ComponentType name="kca_rate" extends="baseVoltageConcDepRate">
+    <Constant name="TIME_SCALE" dimension="time" value="1 ms"/>
+    <Constant name="CONC_SCALE" dimension="concentration" value="1 mM"/>
+    <Dynamics>
+      <DerivedVariable name="r" exposure="r" dimension="per_time" value="caConc / CONC_SCALE / TIME_SCALE"/>
+    </Dynamics>
+  </ComponentType>
+  <ComponentType name="kca2_rate" extends="baseVoltageDepRate">
+    <Requirement name="caConc2" dimension="concentration"/>
+    <Constant name="TIME_SCALE" dimension="time" value="1 ms"/>
+    <Constant name="CONC_SCALE" dimension="concentration" value="1 mM"/>
+    <Dynamics>
+      <DerivedVariable name="r" exposure="r" dimension="per_time" value="caConc2 / CONC_SCALE / TIME_SCALE"/>
+    </Dynamics>
+  </ComponentType>
+  <ionChannelHH id="cal" conductance="10pS" species="ca">
+    <gateHHrates id="m" instances="1">
+      <forwardRate type="HHExpRate" rate="1per_ms" midpoint="-20mV" scale="10mV"/>
+      <reverseRate type="HHExpRate" rate="1per_ms" midpoint="-20mV" scale="-10mV"/>
+    </gateHHrates>
+  </ionChannelHH>
+  <ionChannelHH id="kca" conductance="10pS" species="k">
+    <gateHHrates id="z" instances="1">
+      <forwardRate type="kca_rate"/>
+      <reverseRate type="HHExpRate" rate="1per_ms" midpoint="-20mV" scale="-10mV"/>
+    </gateHHrates>
+  </ionChannelHH>
+  <ionChannelHH id="kca2" conductance="10pS" species="k">
+    <gateHHrates id="z" instances="1">
+      <forwardRate type="kca2_rate"/>
+      <reverseRate type="HHExpRate" rate="1per_ms" midpoint="-20mV" scale="-10mV"/>
+    </gateHHrates>
+  </ionChannelHH>
+  <cell id="one">
+    <biophysicalProperties id="bpp1">
+      <membraneProperties>
+        <channelDensityNernst id="caL" ionChannel="cal" condDensity="1 mS_per_cm2" ion="ca"/>
+        <channelDensity id="kCa" ionChannel="kca" condDensity="2 mS_per_cm2" erev="-80mV" ion="k"/>
+      </membraneProperties>
+    </biophysicalProperties>
+  </cell>
+  <cell2CaPools id="two">
+    <biophysicalProperties2CaPools id="bpp2">
+      <membraneProperties2CaPools>
+        <channelDensityNernstCa2 id="caL" ionChannel="cal" condDensity="1 mS_per_cm2" ion="ca2"/>
+        <channelDensity id="kCa2" ionChannel="kca2" condDensity="2 mS_per_cm2" erev="-80mV" ion="k"/>
+      </membraneProperties2CaPools>
+    </biophysicalProperties2CaPools>
+  </cell2CaPools>
+</neuroml>"#,
+        );
+        let nml = [nml];
+        crate::neuroml::process_files(&[], &nml, |_, node| {
+            if node.tag_name().name() == "ComponentType" {
+                let ct: crate::lems::raw::ComponentType = XML::from_node(node)?;
+                lems.add_component_type(&ct)?;
+            }
+            Ok(())
+        })
+        .unwrap();
+        let bundle = dir.path().join("bundle");
+        let bundle = bundle.to_str().unwrap();
+        export_with_super_mechanisms(&lems, &nml, bundle, &None, &Overrides::default()).unwrap();
+
+        // KCa reads the pool the Ca channel writes
+        let one = std::fs::read_to_string(format!("{}/cat/one-all.mod", bundle)).unwrap();
+        assert!(one.contains("  USEION ca READ eca, cai WRITE ica\n"));
+        assert!(one.contains("  kca_gates_z_forwardRate_r = 1000000 * cai\n"));
+        assert!(!one.contains("USEION ca2"));
+        assert!(!one.contains("caConc"));
+
+        // The second pool is only requested when read
+        let two = std::fs::read_to_string(format!("{}/cat/two-all.mod", bundle)).unwrap();
+        assert!(two.contains("  USEION ca2 READ ca2i\n"));
+        assert!(two.contains("  kca2_gates_z_forwardRate_r = 1000000 * ca2i\n"));
+        assert!(!two.contains("caConc"));
+    }
+}
@@ -0,0 +1,406 @@
+use std::collections::HashMap as Map;
+use std::path::Path;
+
+use roxmltree::Node;
+use tracing::warn;
+
+use crate::{
+    error::{Error, Result},
+    morphology::Morphology,
+    neuroml::{
+        process_files,
+        raw::{
+            BiophysicalProperties, BiophysicalProperties2CaPools,
+            BiophysicalProperties2CaPoolsBody, BiophysicalPropertiesBody, ChannelDensityNernst,
+            ChannelDensityNernstBody, ChannelDensityNernstCa2, ChannelDensityNernstCa2Body,
+            IntracellularProperties, IntracellularProperties2CaPools,
+            IntracellularProperties2CaPoolsBody, IntracellularPropertiesBody, MembraneProperties,
+            MembraneProperties2CaPools, MembraneProperties2CaPoolsBody, MembranePropertiesBody,
+            Morphology as RawMorphology,
+        },
+    },
+    xml::{req_attr, XML},
+};
+
+/// A `<cell>` or `<cell2CaPools>` with its morphology and biophysical
+/// properties. These are either given inline or by id, referencing top-level
+/// elements from any of the input files and their includes, eg the common
+/// pattern of a separate morphology file. Properties with two calcium pools are
+/// lowered to plain ones, see `lower`.
+#[derive(Debug, Clone)]
+pub struct Cell {
+    pub id: String,
+    pub morphology: Option<Morphology>,
+    pub properties: Option<BiophysicalProperties>,
+}
+
+/// Inline definition or reference by id
+pub enum Part<T> {
+    Inline(T),
+    Ref(String),
+    Missing,
+}
+
+impl<T: Clone> Part<T> {
+    fn resolve(self, known: &Map<String, T>, cell: &str, what: &str) -> Result<Option<T>> {
+        match self {
+            Part::Inline(t) => Ok(Some(t)),
+            Part::Ref(id) => known.get(&id).cloned().map(Some).ok_or_else(|| Error::Nml {
+                what: format!("Cell {} references unknown {} {}", cell, what, id),
+            }),
+            Part::Missing => Ok(None),
+        }
+    }
+}
+
+/// Tags of the cell types we know how to export
+pub fn is_cell(tag: &str) -> bool {
+    matches!(tag, "cell" | "cell2CaPools")
+}
+
+/// Find `tag` on `cell`, either as child element, optionally under one of
+/// `alts` tags, or as attribute
+pub fn part<T, F>(cell: &Node, id: &str, tag: &str, alts: &[&str], parse: F) -> Result<Part<T>>
+where
+    F: Fn(&Node) -> Result<T>,
+{
+    let child = cell.children().find(|n| {
+        let t = n.tag_name().name();
+        t == tag || alts.contains(&t)
+    });
+    match (child, cell.attribute(tag)) {
+        (Some(node), r) => {
+            if r.is_some() {
+                warn!(
+                    "Cell {} has both inline and referenced {}, using the inline one",
+                    id, tag
+                );
+            }
+            Ok(Part::Inline(parse(&node)?))
+        }
+        (None, Some(r)) => Ok(Part::Ref(r.to_string())),
+        (None, None) => Ok(Part::Missing),
+    }
+}
+
+/// All cells in `nml` and its includes, found via `paths`, in order of
+/// appearance. Cells with invalid morphologies are reported and skipped, such
+/// that they do not prevent exporting the others.
+pub fn cells(paths: &[String], nml: &[String]) -> Result<Vec<Cell>> {
+    Ok(valid(scan(
+        paths,
+        nml,
+        Morphology::from_node,
+        properties_from_node,
+    )?)
+    .into_iter()
+    .map(|(id, morphology, properties)| Cell {
+        id,
+        morphology,
+        properties,
+    })
+    .collect())
+}
+
+/// Unprocessed morphologies of all cells in `nml` and its includes by cell id
+pub fn morphologies(
+    paths: &[String],
+    nml: &[String],
+) -> Result<Vec<(String, Option<RawMorphology>)>> {
+    Ok(valid(scan(paths, nml, XML::from_node, |_| Ok(()))?)
+        .into_iter()
+        .map(|(id, m, _)| (id, m))
+        .collect())
+}
+
+/// Morphology as read by `scan`, or the reason it could not be
+type Parsed<M> = std::result::Result<M, String>;
+
+/// Drop cells with morphologies that failed to parse, reporting why
+#[allow(clippy::type_complexity)]
+fn valid<M, P>(
+    cells: Vec<(String, Option<Parsed<M>>, Option<P>)>,
+) -> Vec<(String, Option<M>, Option<P>)> {
+    let mut result = Vec::new();
+    for (id, m, p) in cells {
+        match m.transpose() {
+            Ok(m) => result.push((id, m, p)),
+            Err(e) => warn!("Skipping cell {}, its morphology is invalid: {}", id, e),
+        }
+    }
+    result
+}
+
+/// Collect cells, reading morphologies via `morph` and biophysical properties
+/// via `prop`, and resolve references. Morphologies are kept even if invalid,
+/// as these are only of concern to the cells using them.
+#[allow(clippy::type_complexity)]
+fn scan<M, P, F, G>(
+    paths: &[String],
+    nml: &[String],
+    morph: F,
+    prop: G,
+) -> Result<Vec<(String, Option<Parsed<M>>, Option<P>)>>
+where
+    M: Clone,
+    P: Clone,
+    F: Fn(&Node) -> Result<M>,
+    G: Fn(&Node) -> Result<P>,
+{
+    let mut morphologies = Map::new();
+    let mut properties = Map::new();
+    let mut cells = Vec::new();
+    process_files(paths, nml, |file, node| {
+        let top = node
+            .parent_element()
+            .map(|p| p.tag_name().name() == "neuroml")
+            .unwrap_or(false);
+        let id = || -> Result<String> { req_attr(node, "id") };
+        let morph = |node: &Node| -> Result<Parsed<M>> {
+            Ok(morph(node).map_err(|e| e.in_file(Path::new(file), "").to_string()))
+        };
+        match node.tag_name().name() {
+            "morphology" if top => {
+                morphologies.insert(id()?, morph(node)?);
+            }
+            "biophysicalProperties" | "biophysicalProperties2CaPools" if top => {
+                properties.insert(id()?, prop(node)?);
+            }
+            t if is_cell(t) => {
+                let id = node.attribute("id").ok_or(Error::Nml {
+                    what: String::from("Cell has no id"),
+                })?;
+                let mrf = part(node, id, "morphology", &[], morph)?;
+                let bpp = part(
+                    node,
+                    id,
+                    "biophysicalProperties",
+                    &["biophysicalProperties2CaPools"],
+                    &prop,
+                )?;
+                cells.push((id.to_string(), mrf, bpp));
+            }
+            _ => {}
+        }
+        Ok(())
+    })?;
+
+    let mut result = Vec::new();
+    for (id, mrf, bpp) in cells {
+        let m = mrf.resolve(&morphologies, &id, "morphology")?;
+        let p = bpp.resolve(&properties, &id, "biophysicalProperties")?;
+        result.push((id, m, p));
+    }
+    Ok(result)
+}
+
+/// Read either kind of biophysical properties
+fn properties_from_node(node: &Node) -> Result<BiophysicalProperties> {
+    if node.tag_name().name() == "biophysicalProperties2CaPools" {
+        let bpp: BiophysicalProperties2CaPools = XML::from_node(node)?;
+        Ok(lower(bpp))
+    } else {
+        XML::from_node(node)
+    }
+}
+
+/// Rewrite properties with a second calcium pool in terms of the plain ones; a
+/// `channelDensityNernstCa2` is a `channelDensityNernst` on the second pool's
+/// ion, usually `ca2`.
+fn lower(bpp: BiophysicalProperties2CaPools) -> BiophysicalProperties {
+    use BiophysicalProperties2CaPoolsBody as B2;
+    use BiophysicalPropertiesBody as B;
+    let body = bpp
+        .body
+        .into_iter()
+        .map(|item| match item {
+            B2::membraneProperties2CaPools(m) => B::membraneProperties(lower_membrane(m)),
+            B2::intracellularProperties2CaPools(i) => B::intracellularProperties(lower_intra(i)),
+            B2::extracellularProperties(e) => B::extracellularProperties(e),
+            B2::notes(n) => B::notes(n),
+            B2::property(p) => B::property(p),
+            B2::annotation(a) => B::annotation(a),
+        })
+        .collect();
+    BiophysicalProperties {
+        metaid: bpp.metaid,
+        id: bpp.id,
+        neuroLexId: bpp.neuroLexId,
+        body,
+    }
+}
+
+fn lower_membrane(m: MembraneProperties2CaPools) -> MembraneProperties {
+    use MembraneProperties2CaPoolsBody as M2;
+    use MembranePropertiesBody as M;
+    let body = m
+        .body
+        .into_iter()
+        .map(|item| match item {
+            M2::channelDensityNernstCa2(ChannelDensityNernstCa2 {
+                ionChannel,
+                condDensity,
+                segmentGroup,
+                segment,
+                ion,
+                id,
+                neuroLexId,
+                body,
+            }) => M::channelDensityNernst(ChannelDensityNernst {
+                ionChannel,
+                condDensity,
+                segmentGroup,
+                segment,
+                ion,
+                id,
+                neuroLexId,
+                body: body
+                    .into_iter()
+                    .map(|ChannelDensityNernstCa2Body::variableParameter(p)| {
+                        ChannelDensityNernstBody::variableParameter(p)
+                    })
+                    .collect(),
+            }),
+            M2::channelPopulation(x) => M::channelPopulation(x),
+            M2::channelDensity(x) => M::channelDensity(x),
+            M2::channelDensityVShift(x) => M::channelDensityVShift(x),
+            M2::channelDensityNernst(x) => M::channelDensityNernst(x),
+            M2::channelDensityGHK(x) => M::channelDensityGHK(x),
+            M2::channelDensityGHK2(x) => M::channelDensityGHK2(x),
+            M2::channelDensityNonUniform(x) => M::channelDensityNonUniform(x),
+            M2::channelDensityNonUniformNernst(x) => M::channelDensityNonUniformNernst(x),
+            M2::channelDensityNonUniformGHK(x) => M::channelDensityNonUniformGHK(x),
+            M2::spikeThresh(x) => M::spikeThresh(x),
+            M2::specificCapacitance(x) => M::specificCapacitance(x),
+            M2::initMembPotential(x) => M::initMembPotential(x),
+        })
+        .collect();
+    MembraneProperties { body }
+}
+
+fn lower_intra(i: IntracellularProperties2CaPools) -> IntracellularProperties {
+    use IntracellularProperties2CaPoolsBody as I2;
+    use IntracellularPropertiesBody as I;
+    let body = i
+        .body
+        .into_iter()
+        .map(|item| match item {
+            I2::species(s) => I::species(s),
+            I2::resistivity(r) => I::resistivity(r),
+        })
+        .collect();
+    IntracellularProperties { body }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixture::Scratch;
+
+    #[test]
+    fn test_invalid_morphology() {
+        let dir = Scratch::new("invalid-morphology");
+        let nml = dir.write(
+            "cells.nml",
+            r#"<neuroml id="cells">
+  <morphology id="orphan">
+    <segment id="0"><proximal x="0" y="0" z="0" diameter="1"/><distal x="1" y="0" z="0" diameter="1"/></segment>
+    <segment id="1"><parent segment="7"/><distal x="2" y="0" z="0" diameter="1"/></segment>
+  </morphology>
+  <morphology id="unused">
+    <segment id="0"><proximal x="0" y="0" z="0" diameter="1"/><distal x="1" y="0" z="0" diameter="1"/></segment>
+    <segmentGroup id="soma"><member segment="3"/></segmentGroup>
+  </morphology>
+  <morphology id="fine">
+    <segment id="0"><proximal x="0" y="0" z="0" diameter="1"/><distal x="1" y="0" z="0" diameter="1"/></segment>
+  </morphology>
+  <cell id="bad" morphology="orphan"/>
+  <cell id="good" morphology="fine"/>
+  <cell id="inline">
+    <morphology id="m">
+      <segment id="0"><proximal x="0" y="0" z="0" diameter="1"/><distal x="1" y="0" z="0" diameter="1"/></segment>
+      <segmentGroup id="soma"><member segment="3"/></segmentGroup>
+    </morphology>
+  </cell>
+</neuroml>"#,
+        );
+        let ids = cells(&[], std::slice::from_ref(&nml))
+            .unwrap()
+            .into_iter()
+            .map(|c| c.id)
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec![String::from("good")]);
+        // Unprocessed morphologies are not checked
+        let ids = morphologies(&[], &[nml])
+            .unwrap()
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["bad", "good", "inline"]);
+    }
+
+    #[test]
+    fn test_lower() {
+        let dir = Scratch::new("lower-ca-pools");
+        let nml = dir.write(
+            "cells.nml",
+            r#"<neuroml id="cells">
+  <cell2CaPools id="two">
+    <biophysicalProperties2CaPools id="bpp">
+      <membraneProperties2CaPools>
+        <channelDensity id="leak" ionChannel="pas" condDensity="1 mS_per_cm2" erev="-70mV" ion="non_specific"/>
+        <channelDensityNernst id="caL" ionChannel="cal" condDensity="2 mS_per_cm2" segmentGroup="soma" ion="ca"/>
+        <channelDensityNernstCa2 id="caH" ionChannel="cah" condDensity="3 mS_per_cm2" segmentGroup="dend" ion="ca2"/>
+        <specificCapacitance value="1 uF_per_cm2"/>
+      </membraneProperties2CaPools>
+      <intracellularProperties2CaPools>
+        <resistivity value="100 ohm_cm"/>
+      </intracellularProperties2CaPools>
+    </biophysicalProperties2CaPools>
+  </cell2CaPools>
+</neuroml>"#,
+        );
+        let cells = cells(&[], &[nml]).unwrap();
+        assert_eq!(cells.len(), 1);
+        let bpp = cells[0].properties.as_ref().unwrap();
+        assert_eq!(bpp.id, "bpp");
+        let mut membrane = None;
+        let mut intra = None;
+        for item in &bpp.body {
+            match item {
+                BiophysicalPropertiesBody::membraneProperties(m) => membrane = Some(m),
+                BiophysicalPropertiesBody::intracellularProperties(i) => intra = Some(i),
+                _ => {}
+            }
+        }
+        let densities = membrane
+            .unwrap()
+            .body
+            .iter()
+            .filter_map(|item| match item {
+                MembranePropertiesBody::channelDensityNernst(d) => Some((
+                    d.ionChannel.as_str(),
+                    d.ion.as_str(),
+                    d.segmentGroup.as_str(),
+                )),
+                MembranePropertiesBody::channelDensity(d) => {
+                    Some((d.ionChannel.as_str(), d.ion.as_str(), "leak"))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            densities,
+            vec![
+                ("pas", "non_specific", "leak"),
+                ("cal", "ca", "soma"),
+                ("cah", "ca2", "dend")
+            ]
+        );
+        assert_eq!(membrane.unwrap().body.len(), 4);
+        assert!(matches!(
+            intra.unwrap().body.as_slice(),
+            [IntracellularPropertiesBody::resistivity(_)]
+        ));
+    }
+}
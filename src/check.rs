@@ -0,0 +1,805 @@
+use std::collections::{HashMap as Map, HashSet as Set};
+
+use roxmltree::Node;
+
+use crate::{
+    cell::{is_cell, part, Part},
+    error::{Error, Location, Result},
+    lems::file::LemsFile,
+    morphology::Issue,
+    network::cell_ref,
+    neuroml::{
+        process_files,
+        raw::{
+            ContinuousProjectionBody, ElectricalProjectionBody, Morphology as RawMorphology,
+            MorphologyBody, Network, NetworkBody,
+        },
+    },
+    xml::XML,
+};
+
+fn nml2_error<T: Into<String>>(what: T) -> Error {
+    Error::Nml { what: what.into() }
+}
+
+/// Elements allowed below `<neuroml>` that are not components
+const NOT_COMPONENTS: &[&str] = &[
+    "include",
+    "notes",
+    "annotation",
+    "property",
+    "ComponentType",
+];
+
+/// Position as `file:row`, the full source line is not needed here
+fn short(loc: &Location) -> String {
+    format!("{}:{}", loc.file.as_deref().unwrap_or_default(), loc.row)
+}
+
+/// Problem and where it was found
+#[derive(Debug, Clone, PartialEq)]
+struct Finding {
+    loc: Location,
+    issue: Issue,
+}
+
+/// Reference to a top-level component that must derive from `base`, if
+/// given; resolved once all files have been read.
+#[derive(Debug, Clone, PartialEq)]
+struct Ref {
+    loc: Location,
+    from: String,
+    what: &'static str,
+    id: String,
+    base: Option<&'static str>,
+}
+
+/// Element of biophysical properties applied to a segment group, eg
+/// `specificCapacitance`, possibly placing an ion channel.
+#[derive(Debug, Clone, PartialEq)]
+struct Placement {
+    loc: Location,
+    tag: String,
+    id: Option<String>,
+    channel: Option<String>,
+    ion: Option<String>,
+    group: Option<String>,
+}
+
+impl Placement {
+    fn name(&self) -> String {
+        match &self.id {
+            Some(id) => format!("{} {}", self.tag, id),
+            None => self.tag.clone(),
+        }
+    }
+}
+
+/// Everything in biophysical properties mentioning a channel or group
+fn placements(file: &str, node: &Node) -> Vec<Placement> {
+    node.descendants()
+        .filter(|n| n.has_attribute("ionChannel") || n.has_attribute("segmentGroup"))
+        .map(|n| Placement {
+            loc: Location::of(file, &n),
+            tag: n.tag_name().name().to_string(),
+            id: n.attribute("id").map(String::from),
+            channel: n.attribute("ionChannel").map(String::from),
+            ion: n.attribute("ion").map(String::from),
+            group: n.attribute("segmentGroup").map(String::from),
+        })
+        .collect()
+}
+
+/// Ids of all segment groups in `node`
+fn groups(node: &Node) -> Result<Set<String>> {
+    let raw: RawMorphology = XML::from_node(node)?;
+    Ok(raw
+        .body
+        .iter()
+        .filter_map(|it| match it {
+            MorphologyBody::segmentGroup(g) => Some(g.id.clone()),
+            _ => None,
+        })
+        .collect())
+}
+
+struct CellParts {
+    id: String,
+    loc: Location,
+    morphology: Part<Set<String>>,
+    properties: Part<Vec<Placement>>,
+}
+
+/// Model as seen by the checker, collected in a single pass over all files
+#[derive(Default)]
+struct Model {
+    /// Top-level components: id -> (type, location)
+    components: Map<String, (String, Location)>,
+    /// Ion channel id -> species
+    species: Map<String, Option<String>>,
+    morphologies: Map<String, Set<String>>,
+    properties: Map<String, Vec<Placement>>,
+    cells: Vec<CellParts>,
+    refs: Vec<Ref>,
+    findings: Vec<Finding>,
+}
+
+impl Model {
+    fn error(&mut self, loc: &Location, what: String) {
+        self.findings.push(Finding {
+            loc: loc.clone(),
+            issue: Issue::error(what),
+        });
+    }
+
+    fn warning(&mut self, loc: &Location, what: String) {
+        self.findings.push(Finding {
+            loc: loc.clone(),
+            issue: Issue::warning(what),
+        });
+    }
+
+    fn visit(&mut self, lems: &LemsFile, file: &str, node: &Node) -> Result<()> {
+        if !node.is_element() {
+            return Ok(());
+        }
+        let loc = Location::of(file, node);
+        let tag = node.tag_name().name();
+        let top = node
+            .parent_element()
+            .map(|p| p.tag_name().name() == "neuroml")
+            .unwrap_or(false);
+        let in_lems = node
+            .ancestors()
+            .any(|n| n.tag_name().name() == "ComponentType");
+
+        if let Some(ty) = node.attribute("type") {
+            if !in_lems && !lems.types.contains_key(ty) {
+                self.error(&loc, format!("Unknown component type {} on {}", ty, tag));
+            }
+        }
+        if !in_lems && tag != "neuroml" {
+            self.duplicate_children(file, node);
+        }
+        if !top || NOT_COMPONENTS.contains(&tag) {
+            return self.visit_nested(file, node);
+        }
+
+        if !tag.eq_ignore_ascii_case("component") && !lems.types.contains_key(tag) {
+            self.error(&loc, format!("Unknown component type {}", tag));
+        }
+        let ty = node.attribute("type").unwrap_or(tag);
+        let id = if let Some(id) = node.attribute("id") {
+            id
+        } else {
+            self.warning(&loc, format!("Top-level {} has no id", tag));
+            return self.visit_nested(file, node);
+        };
+        if let Some((_, first)) = self.components.get(id) {
+            let what = format!("Duplicate id {}, first defined at {}", id, short(first));
+            self.error(&loc, what);
+        } else {
+            self.components
+                .insert(id.to_string(), (ty.to_string(), loc.clone()));
+        }
+        if lems.derived_from(ty, "baseIonChannel") {
+            self.species
+                .insert(id.to_string(), node.attribute("species").map(String::from));
+        }
+        match tag {
+            "morphology" => {
+                self.morphologies.insert(id.to_string(), groups(node)?);
+            }
+            "biophysicalProperties" | "biophysicalProperties2CaPools" => {
+                self.properties
+                    .insert(id.to_string(), placements(file, node));
+            }
+            _ => {}
+        }
+        self.visit_nested(file, node)
+    }
+
+    /// Cells and networks may be nested in other elements, eg a `<cell>`
+    /// in a `<component>`.
+    fn visit_nested(&mut self, file: &str, node: &Node) -> Result<()> {
+        let tag = node.tag_name().name();
+        if is_cell(tag) {
+            let id = node.attribute("id").unwrap_or_default();
+            let morphology = part(node, id, "morphology", &[], groups)?;
+            let properties = part(
+                node,
+                id,
+                "biophysicalProperties",
+                &["biophysicalProperties2CaPools"],
+                |n| Ok(placements(file, n)),
+            )?;
+            self.cells.push(CellParts {
+                id: id.to_string(),
+                loc: Location::of(file, node),
+                morphology,
+                properties,
+            });
+            Ok(())
+        } else if tag == "network" {
+            self.network(file, node)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Report children of `node` sharing both tag and id
+    fn duplicate_children(&mut self, file: &str, node: &Node) {
+        let mut seen = Set::new();
+        for child in node.children().filter(|n| n.is_element()) {
+            if let Some(id) = child.attribute("id") {
+                let tag = child.tag_name().name();
+                if !seen.insert((tag, id)) {
+                    let what = format!("Duplicate {} id {} in {}", tag, id, node.tag_name().name());
+                    self.error(&Location::of(file, &child), what);
+                }
+            }
+        }
+    }
+
+    /// Check populations used in `node` and queue references to components
+    fn network(&mut self, file: &str, node: &Node) -> Result<()> {
+        use NetworkBody::*;
+        let net: Network = XML::from_node(node)?;
+        let pops = net
+            .body
+            .iter()
+            .filter_map(|it| match it {
+                population(p) => Some(p.id.as_str()),
+                _ => None,
+            })
+            .collect::<Set<_>>();
+        let syn = Some("baseSynapse");
+        // Each child element becomes one item of the body
+        let locs = node
+            .children()
+            .filter(|n| n.is_element())
+            .map(|n| Location::of(file, &n));
+        for (item, loc) in net.body.iter().zip(locs) {
+            let (from, used, refs) = match item {
+                population(p) => (
+                    format!("Population {}", p.id),
+                    vec![],
+                    vec![("component", &p.component, None)],
+                ),
+                projection(p) => (
+                    format!("Projection {}", p.id),
+                    vec![
+                        p.presynapticPopulation.clone(),
+                        p.postsynapticPopulation.clone(),
+                    ],
+                    vec![("synapse", &p.synapse, syn)],
+                ),
+                electricalProjection(p) => {
+                    use ElectricalProjectionBody::*;
+                    let refs = p
+                        .body
+                        .iter()
+                        .map(|c| match c {
+                            electricalConnection(c) => ("synapse", &c.synapse, syn),
+                            electricalConnectionInstance(c) => ("synapse", &c.synapse, syn),
+                            electricalConnectionInstanceW(c) => ("synapse", &c.synapse, syn),
+                        })
+                        .collect();
+                    (
+                        format!("Projection {}", p.id),
+                        vec![
+                            p.presynapticPopulation.clone(),
+                            p.postsynapticPopulation.clone(),
+                        ],
+                        refs,
+                    )
+                }
+                continuousProjection(p) => {
+                    use ContinuousProjectionBody::*;
+                    let refs = p
+                        .body
+                        .iter()
+                        .flat_map(|c| match c {
+                            continuousConnection(c) => [&c.preComponent, &c.postComponent],
+                            continuousConnectionInstance(c) => [&c.preComponent, &c.postComponent],
+                            continuousConnectionInstanceW(c) => [&c.preComponent, &c.postComponent],
+                        })
+                        .map(|c| ("synapse", c, syn))
+                        .collect();
+                    (
+                        format!("Projection {}", p.id),
+                        vec![
+                            p.presynapticPopulation.clone(),
+                            p.postsynapticPopulation.clone(),
+                        ],
+                        refs,
+                    )
+                }
+                inputList(i) => (
+                    format!("Input list {}", i.id),
+                    vec![i.population.clone()],
+                    vec![("input", &i.component, None)],
+                ),
+                explicitInput(e) => {
+                    let used = match cell_ref(&e.target) {
+                        Ok((pop, _)) => vec![pop],
+                        Err(err) => {
+                            self.error(&loc, err.to_string());
+                            vec![]
+                        }
+                    };
+                    (
+                        format!("Explicit input {}", e.input),
+                        used,
+                        vec![("input", &e.input, None)],
+                    )
+                }
+                _ => continue,
+            };
+            for pop in used {
+                if !pops.contains(pop.as_str()) {
+                    let what = format!(
+                        "{} references unknown population {} in network {}",
+                        from, pop, net.id
+                    );
+                    self.error(&loc, what);
+                }
+            }
+            for (what, id, base) in refs {
+                self.refs.push(Ref {
+                    loc: loc.clone(),
+                    from: from.clone(),
+                    what,
+                    id: id.clone(),
+                    base,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Match references against what was collected
+    fn resolve(&mut self, lems: &LemsFile) {
+        let cells = std::mem::take(&mut self.cells);
+        for cell in &cells {
+            let groups = match &cell.morphology {
+                Part::Inline(g) => Some(g.clone()),
+                Part::Ref(m) => {
+                    let g = self.morphologies.get(m).cloned();
+                    if g.is_none() {
+                        let what = format!("Cell {} references unknown morphology {}", cell.id, m);
+                        self.error(&cell.loc, what);
+                    }
+                    g
+                }
+                Part::Missing => None,
+            };
+            let placements = match &cell.properties {
+                Part::Inline(p) => p.clone(),
+                Part::Ref(b) => {
+                    let p = self.properties.get(b).cloned();
+                    if p.is_none() {
+                        let what = format!(
+                            "Cell {} references unknown biophysicalProperties {}",
+                            cell.id, b
+                        );
+                        self.error(&cell.loc, what);
+                    }
+                    p.unwrap_or_default()
+                }
+                Part::Missing => Vec::new(),
+            };
+            for p in &placements {
+                self.placement(&cell.id, groups.as_ref(), p);
+            }
+        }
+
+        for r in std::mem::take(&mut self.refs) {
+            match self.components.get(&r.id).cloned() {
+                None => {
+                    let what = format!("{} references unknown {} {}", r.from, r.what, r.id);
+                    self.error(&r.loc, what);
+                }
+                Some((ty, _)) => {
+                    if let Some(base) = r.base {
+                        if !lems.derived_from(&ty, base) {
+                            let what = format!(
+                                "{} references {} {} of type {}, which is not a {}",
+                                r.from, r.what, r.id, ty, base
+                            );
+                            self.error(&r.loc, what);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Check segment group, channel, and ion of `p` on `cell`
+    fn placement(&mut self, cell: &str, groups: Option<&Set<String>>, p: &Placement) {
+        let name = p.name();
+        if let (Some(groups), Some(group)) = (groups, &p.group) {
+            if !group.is_empty() && group != "all" && !groups.contains(group) {
+                let what = format!(
+                    "Cell {}: {} uses segment group {} not in the morphology",
+                    cell, name, group
+                );
+                self.error(&p.loc, what);
+            }
+        }
+        let channel = if let Some(channel) = &p.channel {
+            channel
+        } else {
+            return;
+        };
+        self.refs.push(Ref {
+            loc: p.loc.clone(),
+            from: format!("Cell {}: {}", cell, name),
+            what: "ionChannel",
+            id: channel.clone(),
+            base: Some("baseIonChannel"),
+        });
+        if let (Some(species), Some(ion)) = (self.species.get(channel).cloned(), &p.ion) {
+            let expected = species.as_deref().unwrap_or("non_specific");
+            if ion != expected {
+                let what = format!(
+                    "Cell {}: {} has ion {}, but channel {} has species {}",
+                    cell, name, ion, channel, expected
+                );
+                if species.is_some() {
+                    self.error(&p.loc, what);
+                } else {
+                    self.warning(&p.loc, what);
+                }
+            }
+        }
+    }
+}
+
+/// Check the model in `nml` as a whole, returning all findings by location
+fn findings(lems: &LemsFile, nml: &[String]) -> Result<Vec<Finding>> {
+    let mut model = Model::default();
+    process_files(&lems.include_dir, nml, |file, node| {
+        model.visit(lems, file, node)
+    })?;
+    model.resolve(lems);
+    model.findings.sort_by(|a, b| a.loc.cmp(&b.loc));
+    Ok(model.findings)
+}
+
+/// Check the model in `nml` as a whole, printing all findings; fails if any
+/// of them is an error.
+pub fn report(lems: &LemsFile, nml: &[String]) -> Result<()> {
+    let mut errors = 0;
+    for Finding {
+        loc,
+        issue: Issue { error, what },
+    } in &findings(lems, nml)?
+    {
+        if *error {
+            errors += 1;
+        }
+        println!(
+            "{}: {}: {}",
+            short(loc),
+            if *error { "error" } else { "warning" },
+            what
+        );
+    }
+    if errors > 0 {
+        Err(nml2_error(format!("Found {} error(s) in model", errors)))
+    } else {
+        println!("No errors found");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixture::{core_types, Scratch};
+
+    /// Findings for `files`, the first being the model, as (file, row, col,
+    /// kind, what); files are named relative to the scratch directory, also
+    /// in messages.
+    fn check(name: &str, files: &[(&str, &str)]) -> Vec<(String, u32, u32, &'static str, String)> {
+        let dir = Scratch::new(name);
+        let paths = files
+            .iter()
+            .map(|(file, content)| dir.write(file, content))
+            .collect::<Vec<_>>();
+        let prefix = format!("{}/", dir.path().to_str().unwrap());
+        findings(&core_types(), &paths[..1])
+            .unwrap()
+            .into_iter()
+            .map(|Finding { loc, issue }| {
+                let file = loc.file.unwrap_or_default();
+                (
+                    file.strip_prefix(&prefix).unwrap_or(&file).to_string(),
+                    loc.row,
+                    loc.col,
+                    if issue.error { "error" } else { "warning" },
+                    issue.what.replace(&prefix, ""),
+                )
+            })
+            .collect()
+    }
+
+    fn finding(
+        file: &str,
+        row: u32,
+        col: u32,
+        kind: &'static str,
+        what: &str,
+    ) -> (String, u32, u32, &'static str, String) {
+        (file.to_string(), row, col, kind, what.to_string())
+    }
+
+    const CHANNELS: &str = r#"<neuroml id="channels">
+  <ionChannelHH id="na" species="na" conductance="10pS"/>
+  <ionChannelPassive id="leak" conductance="10pS"/>
+</neuroml>"#;
+
+    const MORPHOLOGY: &str = r#"<neuroml id="morphology">
+  <morphology id="m">
+    <segment id="0"><proximal x="0" y="0" z="0" diameter="1"/><distal x="1" y="0" z="0" diameter="1"/></segment>
+    <segmentGroup id="soma"><member segment="0"/></segmentGroup>
+  </morphology>
+</neuroml>"#;
+
+    #[test]
+    #[cfg_attr(
+        not(core_types),
+        ignore = "needs the NML2 core types, run bootstrap.sh"
+    )]
+    fn test_complete() {
+        // Parts referenced across included files
+        let cell = r#"<neuroml id="cell">
+  <include href="channels.nml"/>
+  <include href="morphology.nml"/>
+  <biophysicalProperties id="b">
+    <membraneProperties>
+      <channelDensity id="d0" ionChannel="na" ion="na" segmentGroup="soma" condDensity="1mS_per_cm2" erev="50mV"/>
+      <channelDensity id="d1" ionChannel="leak" ion="non_specific" condDensity="1mS_per_cm2" erev="-70mV"/>
+    </membraneProperties>
+  </biophysicalProperties>
+  <cell id="c" morphology="m" biophysicalProperties="b"/>
+  <network id="net">
+    <population id="pop" component="c" size="1"/>
+  </network>
+</neuroml>"#;
+        let files = [
+            ("cell.nml", cell),
+            ("channels.nml", CHANNELS),
+            ("morphology.nml", MORPHOLOGY),
+        ];
+        assert_eq!(check("check-complete", &files), vec![]);
+    }
+
+    #[test]
+    #[cfg_attr(
+        not(core_types),
+        ignore = "needs the NML2 core types, run bootstrap.sh"
+    )]
+    fn test_cells() {
+        let cells = r#"<neuroml id="cells">
+  <include href="channels.nml"/>
+  <include href="morphology.nml"/>
+  <cell id="c" morphology="m">
+    <biophysicalProperties id="b">
+      <membraneProperties>
+        <channelDensity id="d0" ionChannel="kdr" ion="k" condDensity="1mS_per_cm2" erev="-77mV"/>
+        <channelDensity id="d1" ionChannel="na" ion="na" segmentGroup="dend" condDensity="1mS_per_cm2" erev="50mV"/>
+        <channelDensity id="d2" ionChannel="na" ion="k" condDensity="1mS_per_cm2" erev="50mV"/>
+        <channelDensity id="d3" ionChannel="leak" ion="na" condDensity="1mS_per_cm2" erev="-70mV"/>
+        <specificCapacitance segmentGroup="axon" value="1uF_per_cm2"/>
+      </membraneProperties>
+    </biophysicalProperties>
+  </cell>
+  <cell id="d" morphology="n" biophysicalProperties="q"/>
+</neuroml>"#;
+        let files = [
+            ("cells.nml", cells),
+            ("channels.nml", CHANNELS),
+            ("morphology.nml", MORPHOLOGY),
+        ];
+        assert_eq!(
+            check("check-cells", &files),
+            vec![
+                finding(
+                    "cells.nml",
+                    7,
+                    9,
+                    "error",
+                    "Cell c: channelDensity d0 references unknown ionChannel kdr"
+                ),
+                finding(
+                    "cells.nml",
+                    8,
+                    9,
+                    "error",
+                    "Cell c: channelDensity d1 uses segment group dend not in the morphology"
+                ),
+                finding(
+                    "cells.nml",
+                    9,
+                    9,
+                    "error",
+                    "Cell c: channelDensity d2 has ion k, but channel na has species na"
+                ),
+                finding(
+                    "cells.nml",
+                    10,
+                    9,
+                    "warning",
+                    "Cell c: channelDensity d3 has ion na, but channel leak has species non_specific"
+                ),
+                finding(
+                    "cells.nml",
+                    11,
+                    9,
+                    "error",
+                    "Cell c: specificCapacitance uses segment group axon not in the morphology"
+                ),
+                finding(
+                    "cells.nml",
+                    15,
+                    3,
+                    "error",
+                    "Cell d references unknown morphology n"
+                ),
+                finding(
+                    "cells.nml",
+                    15,
+                    3,
+                    "error",
+                    "Cell d references unknown biophysicalProperties q"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg_attr(
+        not(core_types),
+        ignore = "needs the NML2 core types, run bootstrap.sh"
+    )]
+    fn test_network() {
+        let net = r#"<neuroml id="net">
+  <include href="channels.nml"/>
+  <cell id="c"/>
+  <network id="net">
+    <population id="pop" component="c" size="1"/>
+    <population id="ghosts" component="ghost" size="1"/>
+    <projection id="prj" presynapticPopulation="pop" postsynapticPopulation="other" synapse="syn"/>
+    <projection id="chn" presynapticPopulation="pop" postsynapticPopulation="pop" synapse="na"/>
+    <explicitInput target="pop[0" input="ic"/>
+  </network>
+</neuroml>"#;
+        let files = [("net.nml", net), ("channels.nml", CHANNELS)];
+        assert_eq!(
+            check("check-network", &files),
+            vec![
+                finding(
+                    "net.nml",
+                    6,
+                    5,
+                    "error",
+                    "Population ghosts references unknown component ghost"
+                ),
+                finding(
+                    "net.nml",
+                    7,
+                    5,
+                    "error",
+                    "Projection prj references unknown population other in network net"
+                ),
+                finding(
+                    "net.nml",
+                    7,
+                    5,
+                    "error",
+                    "Projection prj references unknown synapse syn"
+                ),
+                finding(
+                    "net.nml",
+                    8,
+                    5,
+                    "error",
+                    "Projection chn references synapse na of type ionChannelHH, which is not a baseSynapse"
+                ),
+                finding(
+                    "net.nml",
+                    9,
+                    5,
+                    "error",
+                    "NML2 error: Cannot parse cell reference \"pop[0\""
+                ),
+                finding(
+                    "net.nml",
+                    9,
+                    5,
+                    "error",
+                    "Explicit input ic references unknown input ic"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg_attr(
+        not(core_types),
+        ignore = "needs the NML2 core types, run bootstrap.sh"
+    )]
+    fn test_duplicates() {
+        let model = r#"<neuroml id="model">
+  <include href="channels.nml"/>
+  <ionChannelPassive id="leak" conductance="10pS"/>
+  <cell id="c">
+    <morphology id="m">
+      <segment id="0"><proximal x="0" y="0" z="0" diameter="1"/><distal x="1" y="0" z="0" diameter="1"/></segment>
+      <segment id="0"><proximal x="0" y="0" z="0" diameter="1"/><distal x="1" y="0" z="0" diameter="1"/></segment>
+    </morphology>
+  </cell>
+</neuroml>"#;
+        let files = [("model.nml", model), ("channels.nml", CHANNELS)];
+        assert_eq!(
+            check("check-duplicates", &files),
+            vec![
+                finding(
+                    "channels.nml",
+                    3,
+                    3,
+                    "error",
+                    "Duplicate id leak, first defined at model.nml:3"
+                ),
+                finding(
+                    "model.nml",
+                    7,
+                    7,
+                    "error",
+                    "Duplicate segment id 0 in morphology"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg_attr(
+        not(core_types),
+        ignore = "needs the NML2 core types, run bootstrap.sh"
+    )]
+    fn test_unknown_types() {
+        let model = r#"<neuroml id="model">
+  <fancyChannel id="f"/>
+  <component id="g" type="fancyChannel"/>
+  <ionChannelPassive conductance="10pS"/>
+</neuroml>"#;
+        assert_eq!(
+            check("check-types", &[("model.nml", model)]),
+            vec![
+                finding(
+                    "model.nml",
+                    2,
+                    3,
+                    "error",
+                    "Unknown component type fancyChannel"
+                ),
+                finding(
+                    "model.nml",
+                    3,
+                    3,
+                    "error",
+                    "Unknown component type fancyChannel on component"
+                ),
+                finding(
+                    "model.nml",
+                    4,
+                    3,
+                    "warning",
+                    "Top-level ionChannelPassive has no id"
+                ),
+            ]
+        );
+    }
+}
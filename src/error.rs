@@ -1,3 +1,6 @@
+use roxmltree::{Node, TextPos};
+use std::fmt;
+use std::path::Path;
 use thiserror::Error;
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -26,4 +29,104 @@ pub enum Error {
     Lems { what: String },
     #[error("Parse error: {}", .what)]
     Parse { what: String },
+    #[error("Evaluation error: {}", .what)]
+    Eval { what: String },
+    #[error("Include error: {}", .what)]
+    Include { what: String },
+    #[error("{}\n{}", .source, .loc)]
+    Located { source: Box<Error>, loc: Location },
+}
+
+impl Error {
+    /// Attach the position of `node` unless we already know a (more precise)
+    /// location.
+    pub fn at(self, node: &Node) -> Self {
+        match self {
+            Error::Located { .. } => self,
+            e => {
+                let doc = node.document();
+                let pos = doc.text_pos_at(node.range().start);
+                Error::Located {
+                    source: Box::new(e),
+                    loc: Location::new(None, pos, doc.input_text()),
+                }
+            }
+        }
+    }
+
+    /// Record the file an error originated from. XML parser errors come with
+    /// their own position, all others without one just get the path.
+    pub fn in_file(self, file: &Path, text: &str) -> Self {
+        let file = Some(file.to_string_lossy().to_string());
+        match self {
+            Error::Located { source, mut loc } => {
+                loc.file = loc.file.or(file);
+                Error::Located { source, loc }
+            }
+            Error::Xml { source } => {
+                let loc = Location::new(file, source.pos(), text);
+                Error::Located {
+                    source: Box::new(Error::Xml { source }),
+                    loc,
+                }
+            }
+            e => e,
+        }
+    }
+}
+
+/// Position in an XML source document
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Location {
+    pub file: Option<String>,
+    pub row: u32,
+    pub col: u32,
+    /// Source line at `row`
+    pub line: String,
+}
+
+impl Location {
+    /// Position of `node` in `file`
+    pub fn of(file: &str, node: &Node) -> Self {
+        let doc = node.document();
+        let pos = doc.text_pos_at(node.range().start);
+        Location::new(Some(file.to_string()), pos, doc.input_text())
+    }
+
+    fn new(file: Option<String>, pos: TextPos, text: &str) -> Self {
+        let line = text
+            .lines()
+            .nth((pos.row as usize).saturating_sub(1))
+            .unwrap_or_default()
+            .trim_end()
+            .to_string();
+        Location {
+            file,
+            row: pos.row,
+            col: pos.col,
+            line,
+        }
+    }
+}
+
+/// Render rustc-style, eg
+///   --> example/nml-hh-cell.nml:12:9
+///    |
+/// 12 |         <channelDensity id="naChans" ... />
+///    |         ^
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let row = self.row.to_string();
+        let pad = " ".repeat(row.len());
+        let file = self.file.as_deref().unwrap_or("<unknown>");
+        writeln!(f, "{}--> {}:{}:{}", pad, file, self.row, self.col)?;
+        writeln!(f, "{} |", pad)?;
+        writeln!(f, "{} | {}", row, self.line)?;
+        write!(
+            f,
+            "{} | {}^",
+            pad,
+            " ".repeat((self.col as usize).saturating_sub(1))
+        )
+    }
 }
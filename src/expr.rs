@@ -1,9 +1,15 @@
+use std::collections::HashMap as Map;
+
 use crate::error::{Error, Result};
 
 fn parse_error<T: Into<String>>(what: T) -> Error {
     Error::Parse { what: what.into() }
 }
 
+fn eval_error<T: Into<String>>(what: T) -> Error {
+    Error::Eval { what: what.into() }
+}
+
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
 pub struct Quantity {
     pub value: f64,
@@ -20,6 +26,31 @@ impl Quantity {
     }
 }
 
+/// Physical quantity as given in NML2 attributes, eg `-65 mV`
+pub type Nml2Quantity = Quantity;
+
+impl std::str::FromStr for Quantity {
+    type Err = Error;
+
+    /// Like `parse`, but the whole input must be consumed
+    fn from_str(input: &str) -> Result<Self> {
+        match parse::quantity(input.trim()) {
+            Ok(("", result)) => Ok(result),
+            _ => Err(parse_error(format!("Not a quantity: {:?}", input))),
+        }
+    }
+}
+
+impl std::fmt::Display for Quantity {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if let Some(u) = &self.unit {
+            write!(f, "{}{}", self.value, u)
+        } else {
+            write!(f, "{}", self.value)
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
 pub enum Expr {
     // Simple Types
@@ -110,6 +141,28 @@ impl Expr {
         }
         old
     }
+
+    /// Numerical value given the variable bindings in `env`. Powers are
+    /// evaluated right to left, ie `a^b^c = a^(b^c)`.
+    pub fn eval(&self, env: &Map<String, f64>) -> Result<f64> {
+        match self {
+            Expr::F64(x) => Ok(*x),
+            Expr::Var(v) => env
+                .get(v)
+                .copied()
+                .ok_or_else(|| eval_error(format!("Unbound variable: {}", v))),
+            Expr::Add(xs) => xs.iter().map(|x| x.eval(env)).sum(),
+            Expr::Mul(xs) => xs.iter().map(|x| x.eval(env)).product(),
+            Expr::Pow(xs) => match xs.split_last() {
+                Some((x, bs)) => bs
+                    .iter()
+                    .rev()
+                    .try_fold(x.eval(env)?, |e, b| Ok(b.eval(env)?.powf(e))),
+                None => Ok(1.0),
+            },
+            Expr::Exp(x) => Ok(x.eval(env)?.exp()),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
@@ -169,6 +222,26 @@ impl Boolean {
         self.clone()
     }
 
+    /// Truth value given the variable bindings in `env`, see `Expr::eval`.
+    pub fn eval(&self, env: &Map<String, f64>) -> Result<bool> {
+        match self {
+            Boolean::Cmp(o, l, r) => {
+                let l = l.eval(env)?;
+                let r = r.eval(env)?;
+                Ok(match o {
+                    Cmp::Eq => l == r,
+                    Cmp::Ne => l != r,
+                    Cmp::Le => l <= r,
+                    Cmp::Ge => l >= r,
+                    Cmp::Lt => l < r,
+                    Cmp::Gt => l > r,
+                })
+            }
+            Boolean::Op(Op::And, l, r) => Ok(l.eval(env)? && r.eval(env)?),
+            Boolean::Op(Op::Or, l, r) => Ok(l.eval(env)? || r.eval(env)?),
+        }
+    }
+
     pub fn map(&self, f: &impl Fn(&Expr) -> Expr) -> Boolean {
         match self {
             Boolean::Cmp(o, l, r) => {
@@ -667,6 +740,22 @@ mod test {
         assert_eq!(Expr::parse("exp(1 - 1)").unwrap(), Expr::F64(1.0));
     }
 
+    #[test]
+    fn test_eval() {
+        let env = [(String::from("x"), 2.0), (String::from("y"), 3.0)]
+            .into_iter()
+            .collect::<Map<_, _>>();
+        let eval = |s: &str| Expr::parse(s).unwrap().eval(&env).unwrap();
+        assert_eq!(eval("x*y + 1"), 7.0);
+        assert_eq!(eval("x/y"), 2.0 / 3.0);
+        assert_eq!(eval("x^y^x"), 512.0);
+        assert_eq!(eval("exp(x - x*y/3)"), 1.0);
+        assert!(Expr::parse("z + x").unwrap().eval(&env).is_err());
+        let test = |s: &str| Boolean::parse(s).unwrap().eval(&env).unwrap();
+        assert!(test("x .lt. y .and. y .gt. 2"));
+        assert!(!test("x .eq. y .or. x .neq. 2"));
+    }
+
     #[test]
     fn test_path() {
         use Path::*;
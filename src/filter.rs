@@ -0,0 +1,198 @@
+use std::fmt;
+
+use crate::error::{Error, Result};
+
+fn parse_error<T: Into<String>>(what: T) -> Error {
+    Error::Parse { what: what.into() }
+}
+
+/// What a selector is matched against
+#[derive(Debug, Clone, PartialEq)]
+enum Pattern {
+    /// Glob on the flattened name, eg `gates_m_*`
+    Name(String),
+    /// Globs on the segments of the NML2 path, eg `gates[m]/*/rate`; unless
+    /// anchored by a leading `/`, any suffix of the path may match.
+    Path(Vec<String>),
+}
+
+/// A single `+pattern` or `-pattern`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Selector {
+    pub keep: bool,
+    pattern: Pattern,
+    text: String,
+}
+
+impl Selector {
+    fn parse(input: &str) -> Result<Self> {
+        let keep = match input.chars().next() {
+            Some('+') => true,
+            Some('-') => false,
+            _ => {
+                return Err(parse_error(format!(
+                    "Parameter selector must start with + or -, got {:?}",
+                    input
+                )))
+            }
+        };
+        let pat = &input[1..];
+        if pat.is_empty() {
+            return Err(parse_error(format!("Empty parameter selector {:?}", input)));
+        }
+        let pattern = if pat.contains('/') {
+            let mut segments = Vec::new();
+            let pat = if let Some(pat) = pat.strip_prefix('/') {
+                pat
+            } else {
+                segments.push(String::from("**"));
+                pat
+            };
+            for s in pat.split('/') {
+                if s.is_empty() {
+                    return Err(parse_error(format!(
+                        "Empty path segment in parameter selector {:?}",
+                        input
+                    )));
+                }
+                segments.push(s.to_string());
+            }
+            Pattern::Path(segments)
+        } else {
+            Pattern::Name(pat.to_string())
+        };
+        Ok(Selector {
+            keep,
+            pattern,
+            text: input.to_string(),
+        })
+    }
+
+    /// Does this select the parameter with flattened `name` and NML2 `path`?
+    pub fn matches(&self, name: &str, path: Option<&str>) -> bool {
+        match &self.pattern {
+            Pattern::Name(p) => glob(p, name),
+            Pattern::Path(ps) => {
+                if let Some(path) = path {
+                    let segs = path.split('/').collect::<Vec<_>>();
+                    segments(ps, &segs)
+                } else {
+                    false
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for Selector {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+/// Parameter selection, a comma separated list of selectors. Each parameter
+/// is decided by the last selector matching it; unmatched parameters are not
+/// retained.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Filter(pub Vec<Selector>);
+
+impl Filter {
+    pub fn parse(input: &str) -> Result<Self> {
+        Ok(Filter(
+            input
+                .split(',')
+                .map(|s| s.trim())
+                .filter(|s| !s.is_empty())
+                .map(Selector::parse)
+                .collect::<Result<_>>()?,
+        ))
+    }
+
+    /// Selector deciding about the parameter `name` at `path`, if any
+    pub fn decide(&self, name: &str, path: Option<&str>) -> Option<&Selector> {
+        self.0.iter().rev().find(|s| s.matches(name, path))
+    }
+}
+
+/// Match `text` against `pattern`, where `*` matches any sequence and `?` any
+/// single character. On a mismatch, only the last `*` is retried, absorbing
+/// one more character, so this takes at most `O(|pattern| * |text|)` steps.
+fn glob(pattern: &str, text: &str) -> bool {
+    let p = pattern.chars().collect::<Vec<_>>();
+    let t = text.chars().collect::<Vec<_>>();
+    let (mut pi, mut ti) = (0, 0);
+    // Pattern position after the last `*` and where its match ends in text
+    let mut star = None;
+    while ti < t.len() {
+        if pi < p.len() && p[pi] == '*' {
+            pi += 1;
+            star = Some((pi, ti));
+        } else if pi < p.len() && (p[pi] == '?' || p[pi] == t[ti]) {
+            pi += 1;
+            ti += 1;
+        } else if let Some((sp, st)) = star {
+            pi = sp;
+            ti = st + 1;
+            star = Some((sp, ti));
+        } else {
+            return false;
+        }
+    }
+    p[pi..].iter().all(|c| *c == '*')
+}
+
+/// Match path segments, `**` matches any number of segments
+fn segments(pattern: &[String], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((p, rest)) if p == "**" => (0..=path.len()).any(|ix| segments(rest, &path[ix..])),
+        Some((p, rest)) => !path.is_empty() && glob(p, path[0]) && segments(rest, &path[1..]),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_filter() {
+        let path = "naChan/gates[m]/forwardRate/rate";
+        let name = "gates_m_forwardRate_rate";
+        let keep = |f: &str| {
+            Filter::parse(f)
+                .unwrap()
+                .decide(name, Some(path))
+                .map(|s| s.keep)
+        };
+        assert_eq!(keep("+*"), Some(true));
+        assert_eq!(keep("+gates_*_rate"), Some(true));
+        assert_eq!(keep("+gates[m]/*/rate"), Some(true));
+        assert_eq!(keep("+gates[h]/*/rate"), None);
+        assert_eq!(keep("+*/rate"), Some(true));
+        assert_eq!(keep("+/*/rate"), None);
+        assert_eq!(keep("+/naChan/**/rate"), Some(true));
+        assert_eq!(keep("+gates[*]/**"), Some(true));
+        assert_eq!(keep("+*,-gates[m]/forwardRate/*"), Some(false));
+        assert_eq!(keep("-gates[m]/forwardRate/*,+*"), Some(true));
+        assert!(Filter::parse("*").is_err());
+        assert!(Filter::parse("+a//b").is_err());
+        assert_eq!(Filter::parse("").unwrap(), Filter::default());
+    }
+
+    #[test]
+    fn test_glob() {
+        assert!(glob("", ""));
+        assert!(glob("*", ""));
+        assert!(!glob("?", ""));
+        assert!(glob("gates_?_*", "gates_m_tau"));
+        assert!(!glob("gates_?_*", "gates_mh_tau"));
+        assert!(glob("*_rate", "gates_m_forwardRate_rate"));
+        assert!(glob("a*b*c", "aXbYbZc"));
+        assert!(!glob("a*b*c", "aXbYbZ"));
+        assert!(glob("**x", "abx"));
+        // Linear per star, this would not finish if backtracking
+        let text = "a".repeat(200);
+        assert!(!glob(&format!("{}b", "*a".repeat(20)), &text));
+        assert!(glob(&"*a".repeat(20), &text));
+    }
+}
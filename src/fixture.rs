@@ -0,0 +1,45 @@
+//! Shared setup for tests
+
+use std::path::{Path, PathBuf};
+
+use crate::lems::file::LemsFile;
+
+/// The NML2 core types. These are only available in bootstrapped working
+/// copies, so tests using them must be gated on the `core_types` cfg, see
+/// `build.rs`.
+pub fn core_types() -> LemsFile {
+    LemsFile::from(&[], &[String::from("NeuroML2CoreTypes.xml")]).unwrap()
+}
+
+/// Temporary directory for input files, removed when dropped
+pub struct Scratch(PathBuf);
+
+impl Scratch {
+    /// Fresh directory, `name` must be unique across tests as these run in
+    /// parallel.
+    pub fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("nmlcc-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        Scratch(dir)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+
+    /// Write `content` to `file` relative to this directory, creating
+    /// intermediate directories, and return the full path.
+    pub fn write(&self, file: &str, content: &str) -> String {
+        let path = self.0.join(file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, content).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+}
+
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
@@ -0,0 +1,143 @@
+use std::fs::{create_dir_all, write};
+use std::path::PathBuf;
+use tracing::{info, warn};
+
+use crate::{
+    error::{Error, Result},
+    instance::{Collapsed, Instance},
+    lems::file::LemsFile,
+    neuroml::process_files,
+    nmodl::collapse,
+};
+
+/// Sampling of an interval `from:to:step`, both ends included; a single
+/// value is sampled once.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Range {
+    pub from: f64,
+    pub to: f64,
+    pub step: f64,
+}
+
+impl Range {
+    pub fn values(&self) -> Vec<f64> {
+        let n = ((self.to - self.from) / self.step + 1e-9).floor() as usize;
+        (0..=n)
+            .map(|ix| self.from + ix as f64 * self.step)
+            .collect()
+    }
+}
+
+impl std::str::FromStr for Range {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self> {
+        let err = || Error::Parse {
+            what: format!("Expected value or from:to:step, got {:?}", input),
+        };
+        let xs = input
+            .split(':')
+            .map(|x| x.trim().parse::<f64>().map_err(|_| err()))
+            .collect::<Result<Vec<_>>>()?;
+        match xs[..] {
+            [x] => Ok(Range {
+                from: x,
+                to: x,
+                step: 1.0,
+            }),
+            [from, to, step] if step > 0.0 && from <= to => Ok(Range { from, to, step }),
+            _ => Err(err()),
+        }
+    }
+}
+
+/// Columns to tabulate per gate: rates, steady state, and time constant of HH
+/// gates; forward and reverse rates of each transition of kinetic schemes.
+fn columns(instance: &Instance, coll: &Collapsed) -> Vec<(String, String)> {
+    let mut result = Vec::new();
+    for gate in instance.children.get("gates").into_iter().flatten() {
+        let id = gate.id.as_deref().unwrap_or_default();
+        let pfx = format!("gates_{}", id);
+        for q in ["alpha", "beta", "inf", "tau"] {
+            result.push((format!("{}_{}", id, q), format!("{}_{}", pfx, q)));
+        }
+        for tr in gate.children.get("transitions").into_iter().flatten() {
+            let tid = tr.id.as_deref().unwrap_or_default();
+            for r in ["rf", "rr"] {
+                let var = format!("{}_transitions_{}_{}", pfx, tid, r);
+                result.push((format!("{}_{}_{}", id, tid, r), var));
+            }
+        }
+    }
+    // Not every gate or transition defines all of these
+    result.retain(|(_, var)| coll.variables.iter().any(|v| &v.name == var));
+    result
+}
+
+/// Tabulate the gates of `instance` over membrane potential `v` in mV and
+/// internal calcium `cai` in mM as CSV.
+pub fn to_csv(instance: &Instance, v: &Range, cai: &Range, temperature: f64) -> Result<String> {
+    let coll = collapse(instance, "-*")?;
+    let columns = columns(instance, &coll);
+    let mut result = String::from("v,cai");
+    for (c, _) in &columns {
+        result.push(',');
+        result.push_str(c);
+    }
+    result.push('\n');
+    let mut base = coll.bindings();
+    base.insert(String::from("temperature"), temperature);
+    for c in cai.values() {
+        for u in v.values() {
+            let mut env = base.clone();
+            env.insert(String::from("v"), u);
+            env.insert(String::from("cai"), c);
+            env.insert(String::from("ca2i"), c);
+            result.push_str(&format!("{},{}", u, c));
+            for (_, var) in &columns {
+                result.push_str(&format!(",{}", coll.value(var, &mut env)?));
+            }
+            result.push('\n');
+        }
+    }
+    Ok(result)
+}
+
+pub fn export(
+    lems: &LemsFile,
+    nml: &[String],
+    channel: &Option<&str>,
+    v: &Range,
+    cai: &Range,
+    temperature: f64,
+    dir: &str,
+) -> Result<()> {
+    process_files(&lems.include_dir, nml, |_, node| {
+        let ty = node
+            .attribute("type")
+            .unwrap_or_else(|| node.tag_name().name());
+        if !lems.derived_from(ty, "baseIonChannel") {
+            return Ok(());
+        }
+        let instance = Instance::new(lems, node)?;
+        let id = instance.id.as_deref().ok_or(Error::Nml {
+            what: String::from("Channel must have an id"),
+        })?;
+        if let Some(channel) = channel {
+            if id != *channel {
+                return Ok(());
+            }
+        }
+        if !instance.children.contains_key("gates") {
+            warn!("Channel {} has no gates, skipping", id);
+            return Ok(());
+        }
+        create_dir_all(dir)?;
+        let mut path = PathBuf::from(dir);
+        path.push(id);
+        path.set_extension("csv");
+        info!("Writing gate curves for '{}' to {:?}", id, &path);
+        write(&path, to_csv(&instance, v, cai, temperature)?)?;
+        Ok(())
+    })
+}
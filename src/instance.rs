@@ -2,11 +2,12 @@ use std::collections::HashMap as Map;
 use std::collections::HashSet as Set;
 
 use roxmltree::Node;
-use tracing::{info, trace};
+use tracing::{info, trace, warn};
 
 use crate::{
     error::Error,
     expr::{Boolean, Expr, Match, Quantity},
+    filter::{Filter, Selector},
     lems,
     variable::{SelectBy, VarKind, Variable},
     Result,
@@ -16,12 +17,17 @@ fn nml2_error<T: Into<String>>(what: T) -> Error {
     Error::Nml { what: what.into() }
 }
 
+fn eval_error<T: Into<String>>(what: T) -> Error {
+    Error::Eval { what: what.into() }
+}
+
 /// Kinetic scheme from components
 /// This does not hold any real data, just links and prefixes. The surrounding
 /// component needs to held a set of components with prefix `node` each exposing
 /// a state variable with name `state`. These are states (NB. overloaded term)
 /// of the scheme. Similarly, we need to find components prefixed with `edge`
-/// each exposing `forward` and `backward` transition rates.
+/// each exposing `forward` and `backward` transition rates and linking to their
+/// source and target states via the attributes `src` and `tgt`.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Kinetic {
     /// Identifier
@@ -32,7 +38,11 @@ pub struct Kinetic {
     pub edge: Match,
     /// State variable name exposed by all nodes
     pub state: String,
-    /// Backward rates exposed by all edges
+    /// Attribute naming the source node on each edge
+    pub src: String,
+    /// Attribute naming the target node on each edge
+    pub tgt: String,
+    /// Forward rates exposed by all edges
     pub rfwd: String,
     /// Backward rates exposed by all edges
     pub rbwd: String,
@@ -45,27 +55,78 @@ impl Kinetic {
             node: Match::parse(&format!("{}[*]", ks.nodes))?,
             edge: Match::parse(&format!("{}[*]", ks.edges))?,
             state: ks.stateVariable.to_string(),
+            src: ks.edgeSource.to_string(),
+            tgt: ks.edgeTarget.to_string(),
             rfwd: ks.forwardRate.to_string(),
             rbwd: ks.reverseRate.to_string(),
         })
     }
 
-    fn add_prefix(&self, ctx: &Context) -> Self {
-        let pfx = ctx.keys();
-        let name = ctx.add_prefix(&self.name);
-        let node = self.node.add_prefix(&pfx);
-        let edge = self.edge.add_prefix(&pfx);
-        let state = self.state.to_string();
-        let rfwd = self.rfwd.to_string();
-        let rbwd = self.rbwd.to_string();
-        Kinetic {
-            name,
-            node,
-            edge,
-            state,
-            rfwd,
-            rbwd,
+    /// Resolve nodes and edges against the instance `inst` owning this scheme
+    /// and produce transitions (from, to, forward, backward) named in the
+    /// same way as the flattened variables under `ctx`.
+    fn transitions(
+        &self,
+        inst: &Instance,
+        ctx: &Context,
+    ) -> Result<Vec<(String, String, String, String)>> {
+        let name = |pfx: &[String], var: &str| {
+            let mut pfx = pfx.to_vec();
+            pfx.push(var.to_string());
+            ctx.add_prefix(&pfx.join("_"))
+        };
+        let nodes = inst.select(&self.node)?;
+        for (pfx, node) in &nodes {
+            if !node.component_type.exposures.contains_key(&self.state) {
+                return Err(nml2_error(format!(
+                    "Kinetic scheme {}: state {} does not expose {}",
+                    self.name,
+                    pfx.join("/"),
+                    self.state
+                )));
+            }
         }
+        let state_of = |edge: &[String], id: &str| -> Result<String> {
+            nodes
+                .iter()
+                .find(|(_, n)| n.id.as_deref() == Some(id))
+                .map(|(pfx, _)| name(pfx, &self.state))
+                .ok_or_else(|| {
+                    nml2_error(format!(
+                        "Kinetic scheme {}: transition {} refers to unknown state {}",
+                        self.name,
+                        edge.join("/"),
+                        id
+                    ))
+                })
+        };
+        let mut result = Vec::new();
+        for (pfx, edge) in inst.select(&self.edge)? {
+            let link = |attr: &str| {
+                edge.attributes.get(attr).ok_or_else(|| {
+                    nml2_error(format!(
+                        "Kinetic scheme {}: transition {} lacks attribute {}",
+                        self.name,
+                        pfx.join("/"),
+                        attr
+                    ))
+                })
+            };
+            let from = state_of(&pfx, link(&self.src)?)?;
+            let to = state_of(&pfx, link(&self.tgt)?)?;
+            for rate in [&self.rfwd, &self.rbwd] {
+                if !edge.component_type.exposures.contains_key(rate) {
+                    return Err(nml2_error(format!(
+                        "Kinetic scheme {}: transition {} does not expose {}",
+                        self.name,
+                        pfx.join("/"),
+                        rate
+                    )));
+                }
+            }
+            result.push((from, to, name(&pfx, &self.rfwd), name(&pfx, &self.rbwd)));
+        }
+        Ok(result)
     }
 }
 
@@ -85,7 +146,7 @@ impl Instance {
         let node = xml
             .attribute("type")
             .unwrap_or_else(|| xml.tag_name().name());
-        let component_type = lems.compose_component_type(node)?;
+        let component_type = lems.compose_component_type(node).map_err(|e| e.at(xml))?;
 
         let mut attributes = Map::new();
         let mut parameters = Map::new();
@@ -94,7 +155,8 @@ impl Instance {
             let key = attr.name().to_string();
             let val = attr.value();
             if component_type.parameters.contains(&key) {
-                parameters.insert(key, lems.normalise_quantity(&Quantity::parse(val)?)?);
+                let q = Quantity::parse(val).and_then(|q| lems.normalise_quantity(&q));
+                parameters.insert(key, q.map_err(|e| e.at(xml))?);
             } else if component_type.attributes.contains(&key)
                 || component_type.links.contains_key(&key)
             {
@@ -102,9 +164,10 @@ impl Instance {
             } else if "id" == key || "type" == key {
             } else {
                 return Err(nml2_error(format!(
-                    "Unknown key/value pair in Instance: {:?} => {:?} in node: {:?}",
-                    key, val, node
-                )));
+                    "Unknown key/value pair in Instance of type {}: {} = {:?}",
+                    node, key, val
+                ))
+                .at(xml));
             }
         }
         let id = xml.attribute("id").map(|s| s.to_string());
@@ -115,8 +178,10 @@ impl Instance {
             if component_type.child.contains_key(nm) {
                 child.insert(nm.to_string(), Instance::new(lems, &node)?);
             } else {
+                // Children may be given by tag, or tagged by the collection and typed
+                let ty = node.attribute("type").unwrap_or(nm);
                 for (n, t) in &component_type.children {
-                    if lems.derived_from(nm, t) {
+                    if lems.derived_from(ty, t) {
                         children
                             .entry(n.to_string())
                             .or_insert_with(Vec::new)
@@ -134,6 +199,50 @@ impl Instance {
             attributes,
         })
     }
+
+    /// Find all sub-instances matching `path`, relative to this instance. Each
+    /// match comes with the list of prefixes used to name its members when
+    /// collapsing, ie `child` or `children` followed by the child's `id`.
+    pub fn select(&self, path: &Match) -> Result<Vec<(Vec<String>, &Instance)>> {
+        use crate::expr::Path;
+        let mut result = vec![(Vec::new(), self)];
+        for p in &path.0 {
+            let (nm, wh) = match p {
+                Path::Fixed(nm) => (nm, None),
+                Path::When(nm, wh) => (nm, Some(wh)),
+            };
+            let mut next = Vec::new();
+            for (pfx, inst) in &result {
+                if let Some(child) = inst.child.get(nm) {
+                    let mut pfx = pfx.clone();
+                    pfx.push(child.id.as_deref().unwrap_or(nm).to_string());
+                    next.push((pfx, child));
+                } else if let Some(children) = inst.children.get(nm) {
+                    for child in children {
+                        let id = child.id.as_deref().ok_or_else(|| {
+                            nml2_error(format!("Element of {} must have an id", nm))
+                        })?;
+                        if matches!(wh, Some(w) if w != "*" && w != id) {
+                            continue;
+                        }
+                        let mut pfx = pfx.clone();
+                        pfx.push(nm.to_string());
+                        pfx.push(id.to_string());
+                        next.push((pfx, child));
+                    }
+                } else if !inst.component_type.child.contains_key(nm)
+                    && !inst.component_type.children.contains_key(nm)
+                {
+                    return Err(nml2_error(format!(
+                        "Could not resolve {} in path {:?} of {}",
+                        nm, path, self.component_type.name
+                    )));
+                }
+            }
+            result = next;
+        }
+        Ok(result)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -145,8 +254,46 @@ pub struct Collapsed {
     pub parameters: Map<String, Option<Quantity>>,
     pub attributes: Map<String, Option<String>>,
     pub events: Vec<(String, Expr)>,
-    pub kinetic: Vec<Kinetic>,
     pub transitions: Vec<(String, String, String, String)>,
+    pub names: Names,
+}
+
+/// Flattened names and the NML2 paths they were derived from. Flattening
+/// joins path elements with `_`, so distinct paths may end up with the same
+/// name if ids contain `_`; we refuse those.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Names(Map<String, String>);
+
+impl Names {
+    /// Record `name` as derived from `path`
+    fn insert(&mut self, name: String, path: String) -> Result<()> {
+        match self.0.get(&name) {
+            Some(old) if *old != path => Err(nml2_error(format!(
+                "Name {} is used for both {} and {}",
+                name, old, path
+            ))),
+            _ => {
+                self.0.insert(name, path);
+                Ok(())
+            }
+        }
+    }
+
+    fn merge(&mut self, other: &Names) -> Result<()> {
+        for (name, path) in &other.0 {
+            self.insert(name.clone(), path.clone())?;
+        }
+        Ok(())
+    }
+
+    /// NML2 path `name` was derived from
+    pub fn path(&self, name: &str) -> Option<&str> {
+        self.0.get(name).map(|s| s.as_str())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.0.iter()
+    }
 }
 
 impl Collapsed {
@@ -159,8 +306,8 @@ impl Collapsed {
             parameters: Map::new(),
             attributes: Map::new(),
             events: Vec::new(),
-            kinetic: Vec::new(),
             transitions: Vec::new(),
+            names: Names::default(),
         }
     }
 
@@ -169,103 +316,13 @@ impl Collapsed {
     }
 
     pub fn from_instance_with_name(inst: &Instance, use_name: bool) -> Result<Self> {
-        use crate::expr::Path;
-        let mut coll = Self::from_instance_(inst, &Context::new(), None, use_name)?;
-        for ks in &coll.kinetic {
-            let Match(ps) = &ks.edge;
-            let mut ix = 0;
-            let mut nodes = vec![(vec![], inst.clone())];
-            'a: loop {
-                if ix >= ps.len() {
-                    break;
-                }
-                let p = &ps[ix];
-                ix += 1;
-                match &p {
-                    Path::Fixed(s) => {
-                        for (pfx, node) in &nodes {
-                            if let Some(x) = node.child.get(s) {
-                                let mut pfx = pfx.clone();
-                                pfx.push(s.clone());
-                                nodes = vec![(pfx, x.clone())];
-                                continue 'a;
-                            }
-                        }
-                        if ix < ps.len() {
-                            if let Path::Fixed(q) = &ps[ix] {
-                                ix += 1;
-                                for (pfx, node) in &nodes {
-                                    if let Some(xs) = node.children.get(s) {
-                                        let mut pfx = pfx.clone();
-                                        pfx.push(s.clone());
-                                        nodes = xs
-                                            .iter()
-                                            .filter(|x| x.id == Some(q.to_string()))
-                                            .cloned()
-                                            .map(|x| {
-                                                let mut pfx = pfx.clone();
-                                                pfx.push(x.id.as_deref().unwrap().to_string());
-                                                (pfx, x)
-                                            })
-                                            .collect();
-                                        continue 'a;
-                                    }
-                                }
-                            }
-                        }
-                        panic!("Impossible path {:?}", ks.edge);
-                    }
-                    Path::When(s, m) if m == "*" => {
-                        for (pfx, node) in &nodes {
-                            if let Some(xs) = node.children.get(s) {
-                                nodes = xs
-                                    .iter()
-                                    .map(|x| {
-                                        let mut pfx = pfx.clone();
-                                        pfx.push(s.to_string());
-                                        pfx.push(x.id.as_deref().unwrap().to_string());
-                                        (pfx, x.clone())
-                                    })
-                                    .collect();
-                                continue 'a;
-                            }
-                        }
-                    }
-                    _ => unimplemented!(),
-                }
-            }
-            for (pfx, node) in nodes {
-                // TODO This is a horrible hack and must be replaced by proper lookup
-                let mut qfx = Vec::new();
-                let Match(ref qs) = &ks.node;
-                for q in qs {
-                    match q {
-                        Path::Fixed(s) => qfx.push(s.clone()),
-                        Path::When(s, _) => qfx.push(s.clone()),
-                    }
-                }
-                let qfx = qfx.join("_");
-                // TODO End of horrible hack
-                let pfx = pfx.join("_");
-                coll.transitions.push((
-                    format!(
-                        "{}_{}_{}",
-                        qfx,
-                        node.attributes.get("from").unwrap(),
-                        ks.state
-                    ),
-                    format!(
-                        "{}_{}_{}",
-                        qfx,
-                        node.attributes.get("to").unwrap(),
-                        ks.state
-                    ),
-                    format!("{}_{}", pfx, ks.rfwd),
-                    format!("{}_{}", pfx, ks.rbwd),
-                ));
-            }
-        }
-        Ok(coll)
+        Self::from_instance_(inst, &Context::new(), None, use_name, true)
+    }
+
+    /// Collapse `inst` but keep `Select` variables as they are, see
+    /// `resolve_selects`.
+    pub fn from_instance_unresolved(inst: &Instance) -> Result<Self> {
+        Self::from_instance_(inst, &Context::new(), None, false, false)
     }
 
     fn from_instance_(
@@ -273,6 +330,7 @@ impl Collapsed {
         ctx: &Context,
         name: Option<String>,
         add_name: bool,
+        resolve: bool,
     ) -> Result<Self> {
         let mut result = Collapsed::new(&inst.id);
         let ct = &inst.component_type;
@@ -287,8 +345,11 @@ impl Collapsed {
         } else {
             ""
         };
+        // Single children are found by their name, all others by id
+        let segment = name.as_deref().or(inst.id.as_deref()).unwrap_or(nm);
         ctx.enter(
             nm,
+            segment,
             &ct.exposures
                 .keys()
                 .chain(ct.parameters.iter())
@@ -323,7 +384,19 @@ impl Collapsed {
             .iter()
             .map(|k| (ctx.add_prefix(k), inst.attributes.get(k).cloned()))
             .collect();
-        result.kinetic = ct.kinetic.iter().map(|k| k.add_prefix(&ctx)).collect();
+        for ks in &ct.kinetic {
+            result.transitions.append(&mut ks.transitions(inst, &ctx)?);
+        }
+        for k in ct
+            .exposures
+            .keys()
+            .chain(ct.parameters.iter())
+            .chain(ct.attributes.iter())
+            .chain(ct.constants.keys())
+            .chain(ct.variables.iter().map(|v| &v.name))
+        {
+            result.names.insert(ctx.add_prefix(k), ctx.path(k))?;
+        }
 
         for v in &ct.variables {
             let name = ctx.add_prefix(&v.name);
@@ -340,21 +413,57 @@ impl Collapsed {
 
         // Merge children by prefixing w/ children and id
         for (n, cs) in &inst.children {
-            ctx.enter(n, &Vec::new());
+            ctx.enter_collection(n);
             for inst in cs {
-                result.add(inst, &ctx, None)?;
+                result.add_(inst, &ctx, None, resolve)?;
             }
             ctx.exit();
         }
         // Merge child by prefixing w{} child
         for (n, inst) in &inst.child {
-            result.add(inst, &ctx, Some(n.to_string()))?;
+            result.add_(inst, &ctx, Some(n.to_string()), resolve)?;
         }
 
-        // concretise reductions/selects by converting Select/Product/Sum into DerivedVariables
-        for v in result.variables.iter_mut() {
+        if resolve {
+            result.resolve_selects()?;
+        }
+        ctx.exit();
+        Ok(result)
+    }
+
+    pub fn add(&mut self, inst: &Instance, ctx: &Context, name: Option<String>) -> Result<()> {
+        self.add_(inst, ctx, name, true)
+    }
+
+    fn add_(
+        &mut self,
+        inst: &Instance,
+        ctx: &Context,
+        name: Option<String>,
+        resolve: bool,
+    ) -> Result<()> {
+        let other = Self::from_instance_(inst, ctx, name, true, resolve)?;
+        self.names.merge(&other.names)?;
+        self.parameters
+            .extend(other.parameters.iter().map(|(a, b)| (a.clone(), b.clone())));
+        self.attributes
+            .extend(other.attributes.iter().map(|(a, b)| (a.clone(), b.clone())));
+        self.constants
+            .extend(other.constants.iter().map(|(a, b)| (a.clone(), b.clone())));
+        self.exposures
+            .extend(other.exposures.iter().map(|(a, b)| (a.clone(), b.clone())));
+        self.variables.extend(other.variables.iter().cloned());
+        self.transitions.extend(other.transitions.iter().cloned());
+        self.events.extend(other.events.iter().cloned());
+        Ok(())
+    }
+
+    /// Concretise reductions/selects by converting Select/Product/Sum into
+    /// DerivedVariables over the matching exposures.
+    fn resolve_selects(&mut self) -> Result<()> {
+        for v in self.variables.iter_mut() {
             if let VarKind::Select(by, ps) = &v.kind {
-                let ks = result.exposures.keys().cloned().collect::<Vec<_>>();
+                let ks = self.exposures.keys().cloned().collect::<Vec<_>>();
                 let ms = ps
                     .on_path(&ks)
                     .iter()
@@ -367,7 +476,7 @@ impl Collapsed {
                         } else {
                             return Err(nml2_error(format!(
                                 "Required field is not found for {:?} in {:?}",
-                                ps, result.exposures
+                                ps, self.exposures
                             )));
                         }
                     }
@@ -389,65 +498,49 @@ impl Collapsed {
                 v.kind = VarKind::Derived(Vec::new(), Some(kind));
             }
         }
-        ctx.exit();
-        Ok(result)
-    }
-
-    pub fn add(&mut self, inst: &Instance, ctx: &Context, name: Option<String>) -> Result<()> {
-        let other = Self::from_instance_(inst, ctx, name, true)?;
-        self.parameters
-            .extend(other.parameters.iter().map(|(a, b)| (a.clone(), b.clone())));
-        self.attributes
-            .extend(other.attributes.iter().map(|(a, b)| (a.clone(), b.clone())));
-        self.constants
-            .extend(other.constants.iter().map(|(a, b)| (a.clone(), b.clone())));
-        self.exposures
-            .extend(other.exposures.iter().map(|(a, b)| (a.clone(), b.clone())));
-        self.variables.extend(other.variables.iter().cloned());
-        self.kinetic.extend(other.kinetic.iter().cloned());
-        self.events.extend(other.events.iter().cloned());
         Ok(())
     }
 
-    pub fn simplify(&self, filter: &str) -> Self {
-        // Remove parameters we do not need
-        let mut retain = Set::new();
-        for f in filter.split(',') {
-            if let Some(f) = f.strip_prefix('+') {
-                if f.ends_with('*') {
-                    let keep = self
-                        .parameters
-                        .keys()
-                        .filter(|p| p.starts_with(&f[..f.len() - 1]))
-                        .map(|p| p.to_string())
-                        .collect::<Set<_>>();
-                    retain = retain.union(&keep).cloned().collect();
-                } else {
-                    retain.insert(f.to_string());
-                }
-            } else if let Some(f) = f.strip_prefix('-') {
-                if f.ends_with('*') {
-                    let keep = self
-                        .parameters
-                        .keys()
-                        .filter(|p| p.starts_with(&f[..f.len() - 1]))
-                        .map(|p| p.to_string())
-                        .collect::<Set<_>>();
-                    retain = retain.difference(&keep).cloned().collect();
-                } else {
-                    retain.remove(&f.to_string());
-                }
-            } else {
-                panic!("Unknown filter kind: {}", f);
+    /// Decide for each parameter whether it stays tweakable and by which
+    /// selector of `filter`; parameters without a value are always kept.
+    pub fn select_parameters<'a>(
+        &self,
+        filter: &'a Filter,
+    ) -> Vec<(String, bool, Option<&'a Selector>)> {
+        for s in &filter.0 {
+            if !self
+                .parameters
+                .keys()
+                .any(|p| s.matches(p, self.names.path(p)))
+            {
+                warn!(
+                    "Parameter selector {} matches nothing in {}",
+                    s,
+                    self.name.as_deref().unwrap_or("Unknown")
+                );
             }
         }
+        let mut result = self
+            .parameters
+            .iter()
+            .map(|(p, v)| {
+                let sel = filter.decide(p, self.names.path(p));
+                let keep = v.is_none() || sel.map(|s| s.keep).unwrap_or_default();
+                (p.clone(), keep, sel)
+            })
+            .collect::<Vec<_>>();
+        result.sort_by(|a, b| a.0.cmp(&b.0));
+        result
+    }
 
-        retain.extend(
-            self.parameters
-                .iter()
-                .filter(|t| t.1.is_none())
-                .map(|t| t.0.clone()),
-        );
+    pub fn simplify(&self, filter: &str) -> Result<Self> {
+        // Remove parameters we do not need
+        let retain = self
+            .select_parameters(&Filter::parse(filter)?)
+            .into_iter()
+            .filter(|(_, keep, _)| *keep)
+            .map(|(p, _, _)| p)
+            .collect::<Set<_>>();
         trace!("Retaining parameters {:?}", retain);
 
         // Constant propagation
@@ -514,20 +607,135 @@ impl Collapsed {
             .into_iter()
             .filter(|p| retain.contains(&p.0))
             .collect();
-        prv
+        Ok(prv)
+    }
+
+    /// Bindings of all parameters with a value and all constants, the
+    /// starting point for evaluating variables numerically.
+    pub fn bindings(&self) -> Map<String, f64> {
+        self.parameters
+            .iter()
+            .filter_map(|(k, v)| v.as_ref().map(|q| (k.to_string(), q.value)))
+            .chain(self.constants.iter().map(|(k, q)| (k.to_string(), q.value)))
+            .collect()
+    }
+
+    /// Value of `expr`, computing derived variables it refers to from their
+    /// dependencies. Everything else, eg state variables and `v`, must be
+    /// bound in `env`; derived values are memoised there.
+    pub fn eval(&self, expr: &Expr, env: &mut Map<String, f64>) -> Result<f64> {
+        let mut deps = Vec::new();
+        expr.fold(&mut deps, &|e, acc: &mut Vec<String>| {
+            if let Expr::Var(v) = e {
+                acc.push(v.to_string());
+            }
+        });
+        for dep in deps {
+            self.value(&dep, env)?;
+        }
+        expr.eval(env)
+    }
+
+    /// Value of variable `name`, see `eval`.
+    pub fn value(&self, name: &str, env: &mut Map<String, f64>) -> Result<f64> {
+        self.value_(name, env, &mut Vec::new())
+    }
+
+    fn value_(
+        &self,
+        name: &str,
+        env: &mut Map<String, f64>,
+        stack: &mut Vec<String>,
+    ) -> Result<f64> {
+        if let Some(x) = env.get(name) {
+            return Ok(*x);
+        }
+        if stack.iter().any(|s| s == name) {
+            return Err(eval_error(format!("Cyclic definition of {}", name)));
+        }
+        let var = self
+            .variables
+            .iter()
+            .find(|v| v.name == name)
+            .ok_or_else(|| eval_error(format!("Unbound variable: {}", name)))?;
+        let (cs, df) = match &var.kind {
+            VarKind::Derived(cs, df) => (cs, df),
+            _ => {
+                return Err(eval_error(format!(
+                    "Variable {} is not a derived variable",
+                    name
+                )))
+            }
+        };
+        let mut deps = Vec::new();
+        let add = |e: &Expr, acc: &mut Vec<String>| {
+            if let Expr::Var(v) = e {
+                acc.push(v.to_string());
+            }
+        };
+        for (c, e) in cs {
+            c.fold(&mut deps, &add);
+            e.fold(&mut deps, &add);
+        }
+        if let Some(e) = df {
+            e.fold(&mut deps, &add);
+        }
+        stack.push(name.to_string());
+        for dep in deps {
+            self.value_(&dep, env, stack)?;
+        }
+        stack.pop();
+        let mut result = None;
+        for (c, e) in cs {
+            if c.eval(env)? {
+                result = Some(e.eval(env)?);
+                break;
+            }
+        }
+        let result = match (result, df) {
+            (Some(x), _) => x,
+            (None, Some(e)) => e.eval(env)?,
+            (None, None) => return Err(eval_error(format!("No case of {} applies", name))),
+        };
+        env.insert(name.to_string(), result);
+        Ok(result)
     }
 }
 
+/// Scope of local symbols, named by `prefix` when flattening and by `segment`
+/// in NML2 paths. Collections of children are scopes without symbols, the
+/// following scope is the element of the collection.
+#[derive(Debug, Clone)]
+struct Scope {
+    prefix: String,
+    segment: String,
+    vars: Vec<String>,
+    collection: bool,
+}
+
 /// Stacked contexts of local symbols
 #[derive(Debug, Clone)]
-pub struct Context(Vec<(String, Vec<String>)>);
+pub struct Context(Vec<Scope>);
 
 impl Context {
     pub fn new() -> Self {
         Context(Vec::new())
     }
-    fn enter(&mut self, name: &str, vars: &[String]) {
-        self.0.push((name.to_string(), vars.to_vec()));
+    fn enter(&mut self, prefix: &str, segment: &str, vars: &[String]) {
+        self.0.push(Scope {
+            prefix: prefix.to_string(),
+            segment: segment.to_string(),
+            vars: vars.to_vec(),
+            collection: false,
+        });
+    }
+    fn enter_collection(&mut self, name: &str) {
+        self.0.push(Scope {
+            prefix: name.to_string(),
+            segment: name.to_string(),
+            vars: Vec::new(),
+            collection: true,
+        });
     }
     fn exit(&mut self) {
         self.0.pop();
@@ -535,25 +743,49 @@ impl Context {
     fn keys(&self) -> Vec<String> {
         self.0
             .iter()
-            .filter(|k| !k.0.is_empty())
-            .map(|t| t.0.clone())
+            .filter(|s| !s.prefix.is_empty())
+            .map(|s| s.prefix.clone())
             .collect()
     }
 
+    /// Flattened name of `name` in the current scope; the mangling scheme
+    /// used throughout, joining prefixes with `_`.
     fn add_prefix(&self, name: &str) -> String {
         let mut ks = self.keys();
         ks.push(name.to_string());
         ks.join("_")
     }
 
+    /// NML2 path of `name` in the current scope, eg
+    /// `naChan/gates[m]/forwardRate/rate`
+    fn path(&self, name: &str) -> String {
+        let mut result = Vec::new();
+        let mut collection = None;
+        for scope in &self.0 {
+            if scope.collection {
+                collection = Some(&scope.segment);
+                continue;
+            }
+            let segment = match collection.take() {
+                Some(c) => format!("{}[{}]", c, scope.segment),
+                None => scope.segment.clone(),
+            };
+            if !segment.is_empty() {
+                result.push(segment);
+            }
+        }
+        result.push(name.to_string());
+        result.join("/")
+    }
+
     fn rename(&self, name: &str) -> String {
         let name = name.to_string();
         let mut it = false;
         let mut pfx: Vec<&str> = vec![&name];
-        for (p, vs) in self.0.iter().rev() {
-            it |= vs.contains(&name);
+        for Scope { prefix, vars, .. } in self.0.iter().rev() {
+            it |= vars.contains(&name);
             if it {
-                pfx.push(p);
+                pfx.push(prefix);
             }
         }
         if !it {
@@ -812,3 +1044,253 @@ fn lems_dynamics(
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Collapse component `id` from NML2 document `xml`
+    fn collapse(lems: &lems::file::LemsFile, xml: &str, id: &str) -> Result<Collapsed> {
+        let doc = roxmltree::Document::parse(xml).unwrap();
+        let node = doc
+            .descendants()
+            .find(|n| n.attribute("id") == Some(id))
+            .unwrap();
+        Collapsed::from_instance(&Instance::new(lems, &node)?)
+    }
+
+    fn transition(from: &str, to: &str, fwd: &str, bwd: &str) -> (String, String, String, String) {
+        (from.into(), to.into(), fwd.into(), bwd.into())
+    }
+
+    const KS: &str = r#"<neuroml>
+  <ionChannelKS id="kv" species="k" conductance="10pS">
+    <gateKS id="g" instances="1">
+      <q10Settings type="q10Fixed" fixedQ10="3"/>
+      <closedState id="c"/>
+      <openState id="o"/>
+      <vHalfTransition id="t1" from="c" to="o" vHalf="-20mV" z="2" gamma="0.5" tau="1ms" tauMin="0.1ms"/>
+      <tauInfTransition id="t2" from="o" to="c">
+        <steadyState type="HHSigmoidVariable" rate="1" midpoint="-30mV" scale="5mV"/>
+        <timeCourse id="tc" type="fixedTimeCourse" tau="2ms"/>
+      </tauInfTransition>
+    </gateKS>
+  </ionChannelKS>
+</neuroml>"#;
+
+    #[test]
+    #[cfg_attr(
+        not(core_types),
+        ignore = "needs the NML2 core types, run bootstrap.sh"
+    )]
+    fn test_kinetic_forward_reverse() {
+        let lems = crate::fixture::core_types();
+        let xml = std::fs::read_to_string("example/nml-simple-ks.nml").unwrap();
+        let coll = collapse(&lems, &xml, "k_fwd_rev").unwrap();
+        let mut expected = Vec::new();
+        for g in ["n", "m"] {
+            let state = |s: &str| format!("gates_{}_states_{}_occupancy", g, s);
+            let rate = |t: &str, r: &str| format!("gates_{}_transitions_{}_{}", g, t, r);
+            for t in ["ft", "rt"] {
+                expected.push(transition(
+                    &state("c1"),
+                    &state("o1"),
+                    &rate(t, "rf"),
+                    &rate(t, "rr"),
+                ));
+            }
+        }
+        assert_eq!(coll.transitions, expected);
+        // Transitions refer to flattened variables
+        for (from, to, fwd, bwd) in &coll.transitions {
+            for name in [from, to, fwd, bwd] {
+                assert!(coll.variables.iter().any(|v| &v.name == name), "{}", name);
+            }
+        }
+        assert_eq!(
+            coll.names.path("gates_m_states_o1_occupancy"),
+            Some("k_fwd_rev/gates[m]/states[o1]/occupancy")
+        );
+        assert_eq!(
+            coll.names.path("gates_n_transitions_rt_rr"),
+            Some("k_fwd_rev/gates[n]/transitions[rt]/rr")
+        );
+    }
+
+    #[test]
+    #[cfg_attr(
+        not(core_types),
+        ignore = "needs the NML2 core types, run bootstrap.sh"
+    )]
+    fn test_kinetic_vhalf_tauinf_q10() {
+        let lems = crate::fixture::core_types();
+        let coll = collapse(&lems, &KS.replace(r#" id="tc""#, ""), "kv").unwrap();
+        assert_eq!(
+            coll.transitions,
+            vec![
+                transition(
+                    "gates_g_states_c_occupancy",
+                    "gates_g_states_o_occupancy",
+                    "gates_g_transitions_t1_rf",
+                    "gates_g_transitions_t1_rr"
+                ),
+                transition(
+                    "gates_g_states_o_occupancy",
+                    "gates_g_states_c_occupancy",
+                    "gates_g_transitions_t2_rf",
+                    "gates_g_transitions_t2_rr"
+                ),
+            ]
+        );
+        // The rates are scaled by the gate's q10Settings
+        let scale = coll
+            .variables
+            .iter()
+            .find(|v| v.name == "gates_g_rateScale")
+            .unwrap();
+        assert!(
+            matches!(&scale.kind, VarKind::Derived(cs, Some(e)) if cs.is_empty() && e.print_to_string().contains("q10")),
+            "{:?}",
+            scale.kind
+        );
+    }
+
+    #[test]
+    #[cfg_attr(
+        not(core_types),
+        ignore = "needs the NML2 core types, run bootstrap.sh"
+    )]
+    fn test_select_prefix() {
+        let lems = crate::fixture::core_types();
+        let doc = roxmltree::Document::parse(KS).unwrap();
+        let node = doc
+            .descendants()
+            .find(|n| n.attribute("id") == Some("kv"))
+            .unwrap();
+        let inst = Instance::new(&lems, &node).unwrap();
+        let coll = Collapsed::from_instance_unresolved(&inst).unwrap();
+        let select = |path: &str| {
+            inst.select(&Match::parse(path).unwrap())
+                .unwrap()
+                .into_iter()
+                .map(|(pfx, _)| pfx.join("_"))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            select("gates[*]/states[*]"),
+            vec!["gates_g_states_c", "gates_g_states_o"]
+        );
+        // Single children are named by id if given, by their name otherwise,
+        // in line with the flattened variables
+        for (path, var) in [
+            ("gates[g]/transitions[t2]/timeCourse", "t"),
+            ("gates[g]/transitions[t2]/steadyState", "x"),
+        ] {
+            let pfx = select(path);
+            assert_eq!(pfx.len(), 1);
+            let name = format!("{}_{}", pfx[0], var);
+            assert!(coll.variables.iter().any(|v| v.name == name), "{}", name);
+        }
+        assert_eq!(
+            select("gates[g]/transitions[t2]/timeCourse"),
+            vec!["gates_g_transitions_t2_tc"]
+        );
+        assert_eq!(
+            select("gates[g]/transitions[t2]/steadyState"),
+            vec!["gates_g_transitions_t2_steadyState"]
+        );
+        assert!(select("gates[nope]/states[*]").is_empty());
+        assert!(inst.select(&Match::parse("nope[*]").unwrap()).is_err());
+    }
+
+    #[test]
+    #[cfg_attr(
+        not(core_types),
+        ignore = "needs the NML2 core types, run bootstrap.sh"
+    )]
+    fn test_kinetic_errors() {
+        let mut lems = crate::fixture::core_types();
+        // Gate whose scheme refers to state and rate exposures we can vary
+        let ct = r#"<Lems>
+  <ComponentType name="brokenGate" extends="baseGate">
+    <Children name="states" type="KSState"/>
+    <Children name="transitions" type="KSTransition"/>
+    <Dynamics>
+      <KineticScheme name="ks" nodes="states" stateVariable="nope" edges="transitions" edgeSource="from" edgeTarget="to" forwardRate="rf" reverseRate="nope"/>
+    </Dynamics>
+  </ComponentType>
+</Lems>"#;
+        let doc = roxmltree::Document::parse(ct).unwrap();
+        let node = doc
+            .descendants()
+            .find(|n| n.has_tag_name("ComponentType"))
+            .unwrap();
+        lems.add_component_type(&crate::xml::XML::from_node(&node).unwrap())
+            .unwrap();
+        let error = |lems: &lems::file::LemsFile, xml: &str| match collapse(lems, xml, "g") {
+            Err(Error::Nml { what }) => what,
+            r => panic!("Expected an NML2 error, got {:?}", r),
+        };
+        let gate = |ty: &str, transition: &str| {
+            format!(
+                r#"<neuroml>
+  <{} id="g" instances="1">
+    <closedState id="c"/>
+    <openState id="o"/>
+    {}
+  </{}>
+</neuroml>"#,
+                ty, transition, ty
+            )
+        };
+        let vhalf = |from: &str, to: &str| {
+            format!(
+                r#"<vHalfTransition id="t" {} {} vHalf="-20mV" z="2" gamma="0.5" tau="1ms" tauMin="0.1ms"/>"#,
+                from, to
+            )
+        };
+        let ok = vhalf(r#"from="c""#, r#"to="o""#);
+        assert_eq!(
+            collapse(&lems, &gate("gateKS", &ok), "g")
+                .unwrap()
+                .transitions
+                .len(),
+            1
+        );
+        assert_eq!(
+            error(&lems, &gate("gateKS", &vhalf(r#"from="c""#, r#"to="x""#))),
+            "Kinetic scheme ks: transition transitions/t refers to unknown state x"
+        );
+        assert_eq!(
+            error(&lems, &gate("gateKS", &vhalf(r#"from="c""#, ""))),
+            "Kinetic scheme ks: transition transitions/t lacks attribute to"
+        );
+        assert_eq!(
+            error(&lems, &gate("brokenGate", &ok)),
+            "Kinetic scheme ks: state states/c does not expose nope"
+        );
+        let mut rates = lems.types["brokenGate"].clone();
+        rates.kinetic[0].state = String::from("occupancy");
+        lems.types.insert(String::from("brokenGate"), rates);
+        assert_eq!(
+            error(&lems, &gate("brokenGate", &ok)),
+            "Kinetic scheme ks: transition transitions/t does not expose nope"
+        );
+    }
+
+    #[test]
+    fn test_name_collision() {
+        let mut names = Names::default();
+        let name = String::from("parts_a_sub_p");
+        assert!(names
+            .insert(name.clone(), String::from("t/parts[a]/sub/p"))
+            .is_ok());
+        assert!(names
+            .insert(name.clone(), String::from("t/parts[a]/sub/p"))
+            .is_ok());
+        assert!(names
+            .insert(name.clone(), String::from("t/parts[a_sub]/p"))
+            .is_err());
+        assert_eq!(names.path(&name), Some("t/parts[a]/sub/p"));
+    }
+}
@@ -0,0 +1,355 @@
+use std::collections::HashMap as Map;
+use std::fmt::{self, Write};
+
+use crate::{
+    error::{Error, Result},
+    expr::{Match, Path, Quantity},
+    instance::{Collapsed, ComponentType, Instance, Kinetic},
+    lems::file::LemsFile,
+    neuroml::process_files,
+    nmodl::fix_known_types,
+    variable::{SelectBy, VarKind, Variable},
+};
+
+/// Minimal JSON document; objects keep their insertion order.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Num(f64),
+    Str(String),
+    Arr(Vec<Json>),
+    Obj(Vec<(String, Json)>),
+}
+
+impl Json {
+    fn str(s: &str) -> Self {
+        Json::Str(s.to_string())
+    }
+
+    fn obj(kvs: Vec<(&str, Json)>) -> Self {
+        Json::Obj(kvs.into_iter().map(|(k, v)| (k.to_string(), v)).collect())
+    }
+
+    /// Object from a map, sorted by key to make output diffable
+    fn map<T: ToJson>(map: &Map<String, T>) -> Self {
+        let mut kvs = map
+            .iter()
+            .map(|(k, v)| (k.clone(), v.to_json()))
+            .collect::<Vec<_>>();
+        kvs.sort_by(|a, b| a.0.cmp(&b.0));
+        Json::Obj(kvs)
+    }
+
+    fn arr<T: ToJson>(xs: &[T]) -> Self {
+        Json::Arr(xs.iter().map(|x| x.to_json()).collect())
+    }
+
+    fn write(&self, out: &mut String, depth: usize) -> fmt::Result {
+        let pad = |n| "  ".repeat(n);
+        match self {
+            Json::Null => out.push_str("null"),
+            Json::Num(x) if x.is_finite() => write!(out, "{}", x)?,
+            Json::Num(_) => out.push_str("null"),
+            Json::Str(s) => {
+                out.push('"');
+                for c in s.chars() {
+                    match c {
+                        '"' => out.push_str("\\\""),
+                        '\\' => out.push_str("\\\\"),
+                        '\n' => out.push_str("\\n"),
+                        '\t' => out.push_str("\\t"),
+                        c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32)?,
+                        c => out.push(c),
+                    }
+                }
+                out.push('"');
+            }
+            Json::Arr(xs) if xs.is_empty() => out.push_str("[]"),
+            Json::Arr(xs) => {
+                out.push_str("[\n");
+                for (ix, x) in xs.iter().enumerate() {
+                    out.push_str(&pad(depth + 1));
+                    x.write(out, depth + 1)?;
+                    out.push_str(if ix + 1 < xs.len() { ",\n" } else { "\n" });
+                }
+                out.push_str(&pad(depth));
+                out.push(']');
+            }
+            Json::Obj(kvs) if kvs.is_empty() => out.push_str("{}"),
+            Json::Obj(kvs) => {
+                out.push_str("{\n");
+                for (ix, (k, v)) in kvs.iter().enumerate() {
+                    out.push_str(&pad(depth + 1));
+                    Json::Str(k.clone()).write(out, depth + 1)?;
+                    out.push_str(": ");
+                    v.write(out, depth + 1)?;
+                    out.push_str(if ix + 1 < kvs.len() { ",\n" } else { "\n" });
+                }
+                out.push_str(&pad(depth));
+                out.push('}');
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut out = String::new();
+        self.write(&mut out, 0)?;
+        f.write_str(&out)
+    }
+}
+
+pub trait ToJson {
+    fn to_json(&self) -> Json;
+}
+
+impl ToJson for String {
+    fn to_json(&self) -> Json {
+        Json::str(self)
+    }
+}
+
+impl<T: ToJson> ToJson for Option<T> {
+    fn to_json(&self) -> Json {
+        self.as_ref().map(|x| x.to_json()).unwrap_or(Json::Null)
+    }
+}
+
+impl ToJson for Quantity {
+    fn to_json(&self) -> Json {
+        Json::obj(vec![
+            ("value", Json::Num(self.value)),
+            ("unit", self.unit.to_json()),
+        ])
+    }
+}
+
+impl ToJson for Match {
+    fn to_json(&self) -> Json {
+        let path = self
+            .0
+            .iter()
+            .map(|p| match p {
+                Path::Fixed(f) => f.clone(),
+                Path::When(f, w) => format!("{}[{}]", f, w),
+            })
+            .collect::<Vec<_>>();
+        Json::Str(path.join("/"))
+    }
+}
+
+impl ToJson for Variable {
+    fn to_json(&self) -> Json {
+        let expr = |e: &Option<crate::expr::Expr>| {
+            e.as_ref()
+                .map(|e| Json::Str(e.print_to_string()))
+                .unwrap_or(Json::Null)
+        };
+        let mut kvs = vec![
+            ("name", Json::str(&self.name)),
+            ("exposure", self.exposure.to_json()),
+            ("dimension", Json::str(&self.dimension)),
+        ];
+        match &self.kind {
+            VarKind::State(init, deriv) => {
+                kvs.push(("kind", Json::str("state")));
+                kvs.push(("init", expr(init)));
+                kvs.push(("deriv", expr(deriv)));
+            }
+            VarKind::Derived(cases, default) => {
+                kvs.push(("kind", Json::str("derived")));
+                let cases = cases
+                    .iter()
+                    .map(|(c, e)| {
+                        Json::obj(vec![
+                            ("when", Json::Str(c.print_to_string())),
+                            ("value", Json::Str(e.print_to_string())),
+                        ])
+                    })
+                    .collect();
+                kvs.push(("cases", Json::Arr(cases)));
+                kvs.push(("default", expr(default)));
+            }
+            VarKind::Select(by, path) => {
+                kvs.push(("kind", Json::str("select")));
+                let by = match by {
+                    SelectBy::Get => "get",
+                    SelectBy::Sum => "sum",
+                    SelectBy::Product => "product",
+                };
+                kvs.push(("by", Json::str(by)));
+                kvs.push(("path", path.to_json()));
+            }
+        }
+        Json::obj(kvs)
+    }
+}
+
+fn events(events: &[(String, crate::expr::Expr)]) -> Json {
+    Json::Arr(
+        events
+            .iter()
+            .map(|(v, e)| {
+                Json::obj(vec![
+                    ("variable", Json::str(v)),
+                    ("value", Json::Str(e.print_to_string())),
+                ])
+            })
+            .collect(),
+    )
+}
+
+impl ToJson for Kinetic {
+    fn to_json(&self) -> Json {
+        Json::obj(vec![
+            ("name", Json::str(&self.name)),
+            ("node", self.node.to_json()),
+            ("edge", self.edge.to_json()),
+            ("state", Json::str(&self.state)),
+            ("src", Json::str(&self.src)),
+            ("tgt", Json::str(&self.tgt)),
+            ("forward", Json::str(&self.rfwd)),
+            ("backward", Json::str(&self.rbwd)),
+        ])
+    }
+}
+
+impl ToJson for ComponentType {
+    fn to_json(&self) -> Json {
+        Json::obj(vec![
+            ("name", Json::str(&self.name)),
+            ("base", self.base.to_json()),
+            ("child", Json::map(&self.child)),
+            ("children", Json::map(&self.children)),
+            ("exposures", Json::map(&self.exposures)),
+            ("parameters", Json::arr(&self.parameters)),
+            ("attributes", Json::arr(&self.attributes)),
+            ("constants", Json::map(&self.constants)),
+            ("links", Json::map(&self.links)),
+            ("variables", Json::arr(&self.variables)),
+            ("events", events(&self.events)),
+            ("kinetic", Json::arr(&self.kinetic)),
+        ])
+    }
+}
+
+impl ToJson for Vec<Instance> {
+    fn to_json(&self) -> Json {
+        Json::arr(self)
+    }
+}
+
+/// Instances name their type only, the composed type is dumped separately.
+impl ToJson for Instance {
+    fn to_json(&self) -> Json {
+        Json::obj(vec![
+            ("type", Json::str(&self.component_type.name)),
+            ("id", self.id.to_json()),
+            ("parameters", Json::map(&self.parameters)),
+            ("attributes", Json::map(&self.attributes)),
+            ("child", Json::map(&self.child)),
+            ("children", Json::map(&self.children)),
+        ])
+    }
+}
+
+impl ToJson for Collapsed {
+    fn to_json(&self) -> Json {
+        let transitions = self
+            .transitions
+            .iter()
+            .map(|(from, to, rf, rr)| {
+                Json::obj(vec![
+                    ("from", Json::str(from)),
+                    ("to", Json::str(to)),
+                    ("forward", Json::str(rf)),
+                    ("backward", Json::str(rr)),
+                ])
+            })
+            .collect();
+        Json::obj(vec![
+            ("name", self.name.to_json()),
+            ("exposures", Json::map(&self.exposures)),
+            ("parameters", Json::map(&self.parameters)),
+            ("attributes", Json::map(&self.attributes)),
+            ("constants", Json::map(&self.constants)),
+            ("variables", Json::arr(&self.variables)),
+            ("events", events(&self.events)),
+            ("transitions", Json::Arr(transitions)),
+            (
+                "names",
+                Json::map(
+                    &self
+                        .names
+                        .iter()
+                        .map(|(k, v)| (k.clone(), v.clone()))
+                        .collect(),
+                ),
+            ),
+        ])
+    }
+}
+
+/// All stages of lowering `instance` to NMODL: the composed type, the
+/// instance tree after fixing up known types, and the collapsed form before
+/// and after resolving selects, and after simplifying with `filter`.
+pub fn stages(instance: &Instance, filter: &str) -> Result<Json> {
+    let (instance, filter) = fix_known_types(instance, filter)?;
+    let unresolved = Collapsed::from_instance_unresolved(&instance)?;
+    let resolved = Collapsed::from_instance(&instance)?;
+    let simplified = resolved.simplify(&filter)?;
+    Ok(Json::obj(vec![
+        ("filter", Json::Str(filter)),
+        ("component_type", instance.component_type.to_json()),
+        ("instance", instance.to_json()),
+        ("unresolved", unresolved.to_json()),
+        ("resolved", resolved.to_json()),
+        ("simplified", simplified.to_json()),
+    ]))
+}
+
+/// Print the stages of the first component with `id` in `nml` as JSON
+pub fn export(lems: &LemsFile, nml: &[String], id: &str, filter: &str) -> Result<()> {
+    let mut result = None;
+    process_files(&lems.include_dir, nml, |_, node| {
+        if result.is_some() || node.attribute("id") != Some(id) {
+            return Ok(());
+        }
+        let ty = node
+            .attribute("type")
+            .unwrap_or_else(|| node.tag_name().name());
+        if lems.types.contains_key(ty) {
+            result = Some(stages(&Instance::new(lems, node)?, filter)?);
+        }
+        Ok(())
+    })?;
+    let json = result.ok_or_else(|| Error::Nml {
+        what: format!("No component with id {}", id),
+    })?;
+    println!("{}", json);
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_json() {
+        let json = Json::obj(vec![
+            ("s", Json::str("a\"b\\c\n")),
+            (
+                "xs",
+                Json::Arr(vec![Json::Num(1.5), Json::Num(f64::NAN), Json::Num(-2.0)]),
+            ),
+            ("empty", Json::Obj(Vec::new())),
+            ("null", Json::Null),
+        ]);
+        assert_eq!(
+            json.to_string(),
+            "{\n  \"s\": \"a\\\"b\\\\c\\n\",\n  \"xs\": [\n    1.5,\n    null,\n    -2\n  ],\n  \"empty\": {},\n  \"null\": null\n}"
+        );
+    }
+}
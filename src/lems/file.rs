@@ -74,12 +74,16 @@ pub struct LemsFile {
     pub dimensions: Map<String, Dimension>,
     /// dimension -> unit
     pub blessed_units: Map<String, Unit>,
+    /// Search paths for includes, also used for NML2 files
+    pub include_dir: Vec<String>,
 }
 
 impl LemsFile {
     /// Pull LEMS from file
     pub fn from(dn: &[String], file: &[String]) -> Result<Self> {
-        Self::from_raw(&Lems::from_file(dn, file)?)
+        let mut result = Self::from_raw(&Lems::from_file(dn, file)?)?;
+        result.include_dir = dn.to_vec();
+        Ok(result)
     }
 
     /// Ingest raw LEMS and munge into a digestible form
@@ -137,6 +141,7 @@ impl LemsFile {
             units,
             dimensions,
             blessed_units,
+            include_dir: Vec::new(),
         };
         for ct in &raw.component_types {
             result.add_component_type(ct)?;
@@ -167,6 +172,25 @@ impl LemsFile {
         normalise_quantity(quantity, &self.units, &self.blessed_units)
     }
 
+    /// Value of `quantity` in SI units, eg 0.001 for 1mV
+    pub fn si_value(&self, quantity: &Quantity) -> Result<f64> {
+        if let Some(u) = quantity.unit.as_deref() {
+            let v = self.units.get(u).ok_or_else(|| {
+                unit_error(format!(
+                    "Failed to find unit {} for quantity {:?}",
+                    u, quantity
+                ))
+            })?;
+            let e: i32 = v
+                .power
+                .try_into()
+                .map_err(|_| unit_error(format!("Couldn't convert {} to i32", v.power)))?;
+            Ok(quantity.value * v.scale * f64::powi(10.0, e) + v.offset)
+        } else {
+            Ok(quantity.value)
+        }
+    }
+
     /// Check if type `d` derives from type `b`.
     pub fn derived_from(&self, d: &str, b: &str) -> bool {
         let mut d = d;
@@ -1,12 +1,17 @@
 pub mod file;
 pub mod raw;
 
-use std::path::PathBuf;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 
 use roxmltree::Document;
 use tracing::trace;
 
-use crate::{error::Error, xml, Result};
+use crate::{
+    error::Error,
+    resolve::{not_found, Resolver, Trail},
+    xml, Result,
+};
 
 fn lems_error<T: Into<String>>(what: T) -> Error {
     Error::Lems { what: what.into() }
@@ -19,54 +24,109 @@ pub struct Lems {
     pub dimensions: Vec<raw::Dimension>,
 }
 
+/// Pseudo directory holding the embedded core types
+const BUILTIN: &str = "<built-in>";
+
+/// NML2 core types embedded at build time, see `build.rs`
+mod builtin {
+    include!(concat!(env!("OUT_DIR"), "/core_types.rs"));
+}
+
+/// On-disk location of the core types in a bootstrapped working copy
+pub const CORE_DIR: &str = "ext/NeuroML2/NeuroML2CoreTypes";
+
+/// Were core types embedded at build time?
+pub fn has_builtin_core_types() -> bool {
+    !builtin::CORE_TYPES.is_empty()
+}
+
+/// Is `file`, found for the LEMS `<Include file="name">`, one of the core
+/// type files, which have been loaded already? That is the case if it is the
+/// file the core types are read from via `resolver`, or a copy of an embedded
+/// one. Other files of the same name, eg a model's own `Cells.xml`, are not.
+/// If nothing was found, `name` is skipped if it refers to an embedded file.
+pub fn is_core_type_file(resolver: &Resolver, name: &str, file: Option<&Path>) -> bool {
+    let base = Path::new(name).file_name().and_then(|n| n.to_str());
+    let builtin = builtin::CORE_TYPES.iter().find(|(n, _)| Some(*n) == base);
+    match file {
+        Some(file) => {
+            resolver.resolve(name, None).ok().as_deref() == Some(file)
+                || builtin
+                    .map(|(_, xml)| std::fs::read_to_string(file).ok().as_deref() == Some(*xml))
+                    .unwrap_or(false)
+        }
+        None => builtin.is_some(),
+    }
+}
+
+/// Locate `name` via `resolver`, relative to `base` if given, then fall back
+/// to the embedded core types. Returns the location used for diagnostics and
+/// the text.
+fn find_file(resolver: &Resolver, name: &str, base: Option<&Path>) -> Result<(PathBuf, String)> {
+    match resolver.resolve(name, base) {
+        Ok(file) => {
+            trace!("Reading LEMS file {:?}", file);
+            let xml = std::fs::read_to_string(&file)?;
+            Ok((file, xml))
+        }
+        Err(_) => {
+            if let Some((n, xml)) = builtin::CORE_TYPES.iter().find(|(n, _)| *n == name) {
+                trace!("Using built-in LEMS file {}", n);
+                return Ok((PathBuf::from(format!("{}/{}", BUILTIN, n)), xml.to_string()));
+            }
+            let mut tried = resolver.candidates(name, base);
+            tried.push(PathBuf::from(BUILTIN).join(name));
+            Err(not_found(name, &tried))
+        }
+    }
+}
+
 impl Lems {
+    /// Read LEMS files `names` and all their includes. Files are searched in
+    /// `paths` first, such that external definitions override the built-in
+    /// core types.
     pub fn from_file(paths: &[String], names: &[String]) -> Result<Self> {
         let mut result = Lems {
             component_types: Vec::new(),
             units: Vec::new(),
             dimensions: Vec::new(),
         };
-        let mut todo = names.iter().map(|s| s.to_string()).collect::<Vec<_>>();
-        let mut done = vec![];
-        while let Some(name) = todo.pop() {
-            if done.contains(&name) {
+        let resolver = Resolver::new(paths);
+        let mut todo = names
+            .iter()
+            .map(|s| (s.to_string(), None, Trail::default()))
+            .collect::<Vec<_>>();
+        let mut done = HashSet::new();
+        while let Some((name, base, trail)) = todo.pop() {
+            let (file, xml) = find_file(&resolver, &name, base.as_deref())?;
+            let trail = trail.push(&file)?;
+            if !done.insert(file.clone()) {
                 continue;
             }
-            let mut ok = false;
-            for path in paths {
-                let mut file = PathBuf::from(path);
-                file.push(&name);
-                trace!("Reading LEMS file {:?}", file);
-                match std::fs::read_to_string(file) {
-                    Ok(xml) => {
-                        ok = true;
-                        let doc = Document::parse(&xml)?;
-                        let root = doc.root_element();
-                        let raw: raw::Lems = match root.tag_name().name() {
-                            "Lems" => Ok(xml::XML::from_node(&doc.root_element())),
-                            t => Err(lems_error(format!("Unknown doc kind {}", t))),
-                        }?;
-                        for item in raw.body {
-                            match item {
-                                raw::LemsBody::Include(inc) => todo.push(inc.file.to_string()),
-                                raw::LemsBody::ComponentType(ct) => result.component_types.push(ct),
-                                raw::LemsBody::Unit(un) => result.units.push(un),
-                                raw::LemsBody::Dimension(dm) => result.dimensions.push(dm),
-                                _ => {}
-                            }
-                        }
+            let doc = Document::parse(&xml).map_err(|e| Error::from(e).in_file(&file, &xml))?;
+            let root = doc.root_element();
+            let raw: raw::Lems = match root.tag_name().name() {
+                "Lems" => xml::XML::from_node(&root).map_err(|e| e.in_file(&file, &xml)),
+                t => Err(lems_error(format!("Unknown doc kind {}", t))
+                    .at(&root)
+                    .in_file(&file, &xml)),
+            }?;
+            // Built-in files have no directory to be relative to
+            let base = file
+                .parent()
+                .filter(|p| !p.starts_with(BUILTIN))
+                .map(|p| p.to_path_buf());
+            for item in raw.body {
+                match item {
+                    raw::LemsBody::Include(inc) => {
+                        todo.push((inc.file.to_string(), base.clone(), trail.clone()))
                     }
-                    Err(e) => trace!("{:?}", e),
+                    raw::LemsBody::ComponentType(ct) => result.component_types.push(ct),
+                    raw::LemsBody::Unit(un) => result.units.push(un),
+                    raw::LemsBody::Dimension(dm) => result.dimensions.push(dm),
+                    _ => {}
                 }
             }
-            if ok {
-                done.push(name.to_string());
-            } else {
-                return Err(lems_error(format!(
-                    "Could not find LEMS file {} in paths {:?}",
-                    name, paths
-                )));
-            }
         }
         Ok(result)
     }
@@ -5,7 +5,9 @@
 
 use roxmltree::Node;
 
-use crate::xml::XML;
+use crate::error::Result;
+use crate::xml::{close_tag, def_attr, open_tag, opt_attr, req_attr, unexpected_tag, write_attr, XML};
+use crate::xml::{inner_xml, write_inner};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Child {
@@ -15,15 +17,23 @@ pub struct Child {
 }
 
 impl XML for Child {
-    fn from_node(node: &Node) -> Self {
-        let name = node.attribute("name").map(|s| s.to_string()).unwrap();
-        let r#type = node.attribute("type").map(|s| s.to_string()).unwrap();
-        let description = node.attribute("description").map(|s| s.to_string());
-        Child {
+    fn from_node(node: &Node) -> Result<Self> {
+        let name = req_attr::<String>(node, "name")?;
+        let r#type = req_attr::<String>(node, "type")?;
+        let description = opt_attr::<String>(node, "description")?;
+        Ok(Child {
             name,
             r#type,
             description,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "name", &self.name);
+        write_attr(out, "type", &self.r#type);
+        if let Some(x) = &self.description { write_attr(out, "description", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -32,25 +42,47 @@ pub enum OnStartBody {
     StateAssignment(StateAssignment),
 }
 
+#[allow(dead_code)]
+impl OnStartBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            OnStartBody::StateAssignment(x) => x.to_xml("StateAssignment", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct OnStart {
     pub body: Vec<OnStartBody>
 }
 
 impl XML for OnStart {
-    fn from_node(node: &Node) -> Self {
+    fn from_node(node: &Node) -> Result<Self> {
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "StateAssignment" => body.push(OnStartBody::StateAssignment(StateAssignment::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of OnStart.", t)
+                "StateAssignment" => body.push(OnStartBody::StateAssignment(StateAssignment::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "OnStart")),
             };
         }
-        OnStart {
+        Ok(OnStart {
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -63,15 +95,23 @@ pub struct ComponentReference {
 }
 
 impl XML for ComponentReference {
-    fn from_node(node: &Node) -> Self {
-        let name = node.attribute("name").map(|s| s.to_string()).unwrap();
-        let r#type = node.attribute("type").map(|s| s.to_string()).unwrap();
-        let local = node.attribute("local").map(|s| s.to_string());
-        ComponentReference {
+    fn from_node(node: &Node) -> Result<Self> {
+        let name = req_attr::<String>(node, "name")?;
+        let r#type = req_attr::<String>(node, "type")?;
+        let local = opt_attr::<String>(node, "local")?;
+        Ok(ComponentReference {
             name,
             r#type,
             local,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "name", &self.name);
+        write_attr(out, "type", &self.r#type);
+        if let Some(x) = &self.local { write_attr(out, "local", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -81,11 +121,17 @@ pub struct EventOut {
 }
 
 impl XML for EventOut {
-    fn from_node(node: &Node) -> Self {
-        let port = node.attribute("port").map(|s| s.to_string()).unwrap();
-        EventOut {
+    fn from_node(node: &Node) -> Result<Self> {
+        let port = req_attr::<String>(node, "port")?;
+        Ok(EventOut {
             port,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "port", &self.port);
+        out.push_str("/>\n");
     }
 }
 
@@ -98,17 +144,26 @@ pub struct DerivedParameter {
 }
 
 impl XML for DerivedParameter {
-    fn from_node(node: &Node) -> Self {
-        let name = node.attribute("name").map(|s| s.to_string()).unwrap();
-        let dimension = node.attribute("dimension").or(Some("none")).map(|s| s.to_string()).unwrap();
-        let value = node.attribute("value").map(|s| s.to_string()).unwrap();
-        let description = node.attribute("description").map(|s| s.to_string());
-        DerivedParameter {
+    fn from_node(node: &Node) -> Result<Self> {
+        let name = req_attr::<String>(node, "name")?;
+        let dimension = def_attr::<String>(node, "dimension", "none")?;
+        let value = req_attr::<String>(node, "value")?;
+        let description = opt_attr::<String>(node, "description")?;
+        Ok(DerivedParameter {
             name,
             dimension,
             value,
             description,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "name", &self.name);
+        write_attr(out, "dimension", &self.dimension);
+        write_attr(out, "value", &self.value);
+        if let Some(x) = &self.description { write_attr(out, "description", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -125,33 +180,63 @@ pub enum DynamicsBody {
     KineticScheme(KineticScheme),
 }
 
+#[allow(dead_code)]
+impl DynamicsBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            DynamicsBody::StateVariable(x) => x.to_xml("StateVariable", depth, out),
+            DynamicsBody::DerivedVariable(x) => x.to_xml("DerivedVariable", depth, out),
+            DynamicsBody::ConditionalDerivedVariable(x) => x.to_xml("ConditionalDerivedVariable", depth, out),
+            DynamicsBody::TimeDerivative(x) => x.to_xml("TimeDerivative", depth, out),
+            DynamicsBody::OnStart(x) => x.to_xml("OnStart", depth, out),
+            DynamicsBody::OnEvent(x) => x.to_xml("OnEvent", depth, out),
+            DynamicsBody::OnCondition(x) => x.to_xml("OnCondition", depth, out),
+            DynamicsBody::Regime(x) => x.to_xml("Regime", depth, out),
+            DynamicsBody::KineticScheme(x) => x.to_xml("KineticScheme", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Dynamics {
     pub body: Vec<DynamicsBody>
 }
 
 impl XML for Dynamics {
-    fn from_node(node: &Node) -> Self {
+    fn from_node(node: &Node) -> Result<Self> {
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "StateVariable" => body.push(DynamicsBody::StateVariable(StateVariable::from_node(&child))),
-                "DerivedVariable" => body.push(DynamicsBody::DerivedVariable(DerivedVariable::from_node(&child))),
-                "ConditionalDerivedVariable" => body.push(DynamicsBody::ConditionalDerivedVariable(ConditionalDerivedVariable::from_node(&child))),
-                "TimeDerivative" => body.push(DynamicsBody::TimeDerivative(TimeDerivative::from_node(&child))),
-                "OnStart" => body.push(DynamicsBody::OnStart(OnStart::from_node(&child))),
-                "OnEvent" => body.push(DynamicsBody::OnEvent(OnEvent::from_node(&child))),
-                "OnCondition" => body.push(DynamicsBody::OnCondition(OnCondition::from_node(&child))),
-                "Regime" => body.push(DynamicsBody::Regime(Regime::from_node(&child))),
-                "KineticScheme" => body.push(DynamicsBody::KineticScheme(KineticScheme::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of Dynamics.", t)
+                "StateVariable" => body.push(DynamicsBody::StateVariable(StateVariable::from_node(&child)?)),
+                "DerivedVariable" => body.push(DynamicsBody::DerivedVariable(DerivedVariable::from_node(&child)?)),
+                "ConditionalDerivedVariable" => body.push(DynamicsBody::ConditionalDerivedVariable(ConditionalDerivedVariable::from_node(&child)?)),
+                "TimeDerivative" => body.push(DynamicsBody::TimeDerivative(TimeDerivative::from_node(&child)?)),
+                "OnStart" => body.push(DynamicsBody::OnStart(OnStart::from_node(&child)?)),
+                "OnEvent" => body.push(DynamicsBody::OnEvent(OnEvent::from_node(&child)?)),
+                "OnCondition" => body.push(DynamicsBody::OnCondition(OnCondition::from_node(&child)?)),
+                "Regime" => body.push(DynamicsBody::Regime(Regime::from_node(&child)?)),
+                "KineticScheme" => body.push(DynamicsBody::KineticScheme(KineticScheme::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "Dynamics")),
             };
         }
-        Dynamics {
+        Ok(Dynamics {
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -181,6 +266,35 @@ pub enum ComponentTypeBody {
     Simulation(Simulation),
 }
 
+#[allow(dead_code)]
+impl ComponentTypeBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            ComponentTypeBody::Property(x) => x.to_xml("Property", depth, out),
+            ComponentTypeBody::Parameter(x) => x.to_xml("Parameter", depth, out),
+            ComponentTypeBody::DerivedParameter(x) => x.to_xml("DerivedParameter", depth, out),
+            ComponentTypeBody::IndexParameter(x) => x.to_xml("IndexParameter", depth, out),
+            ComponentTypeBody::Constant(x) => x.to_xml("Constant", depth, out),
+            ComponentTypeBody::Child(x) => x.to_xml("Child", depth, out),
+            ComponentTypeBody::Children(x) => x.to_xml("Children", depth, out),
+            ComponentTypeBody::Fixed(x) => x.to_xml("Fixed", depth, out),
+            ComponentTypeBody::Link(x) => x.to_xml("Link", depth, out),
+            ComponentTypeBody::ComponentReference(x) => x.to_xml("ComponentReference", depth, out),
+            ComponentTypeBody::Attachments(x) => x.to_xml("Attachments", depth, out),
+            ComponentTypeBody::EventPort(x) => x.to_xml("EventPort", depth, out),
+            ComponentTypeBody::Exposure(x) => x.to_xml("Exposure", depth, out),
+            ComponentTypeBody::Requirement(x) => x.to_xml("Requirement", depth, out),
+            ComponentTypeBody::ComponentRequirement(x) => x.to_xml("ComponentRequirement", depth, out),
+            ComponentTypeBody::InstanceRequirement(x) => x.to_xml("InstanceRequirement", depth, out),
+            ComponentTypeBody::Path(x) => x.to_xml("Path", depth, out),
+            ComponentTypeBody::Text(x) => x.to_xml("Text", depth, out),
+            ComponentTypeBody::Dynamics(x) => x.to_xml("Dynamics", depth, out),
+            ComponentTypeBody::Structure(x) => x.to_xml("Structure", depth, out),
+            ComponentTypeBody::Simulation(x) => x.to_xml("Simulation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ComponentType {
     pub name: String,
@@ -190,45 +304,61 @@ pub struct ComponentType {
 }
 
 impl XML for ComponentType {
-    fn from_node(node: &Node) -> Self {
-        let name = node.attribute("name").map(|s| s.to_string()).unwrap();
-        let extends = node.attribute("extends").map(|s| s.to_string());
-        let description = node.attribute("description").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let name = req_attr::<String>(node, "name")?;
+        let extends = opt_attr::<String>(node, "extends")?;
+        let description = opt_attr::<String>(node, "description")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "Property" => body.push(ComponentTypeBody::Property(Property::from_node(&child))),
-                "Parameter" => body.push(ComponentTypeBody::Parameter(Parameter::from_node(&child))),
-                "DerivedParameter" => body.push(ComponentTypeBody::DerivedParameter(DerivedParameter::from_node(&child))),
-                "IndexParameter" => body.push(ComponentTypeBody::IndexParameter(IndexParameter::from_node(&child))),
-                "Constant" => body.push(ComponentTypeBody::Constant(Constant::from_node(&child))),
-                "Child" => body.push(ComponentTypeBody::Child(Child::from_node(&child))),
-                "Children" => body.push(ComponentTypeBody::Children(Children::from_node(&child))),
-                "Fixed" => body.push(ComponentTypeBody::Fixed(Fixed::from_node(&child))),
-                "Link" => body.push(ComponentTypeBody::Link(Link::from_node(&child))),
-                "ComponentReference" => body.push(ComponentTypeBody::ComponentReference(ComponentReference::from_node(&child))),
-                "Attachments" => body.push(ComponentTypeBody::Attachments(Attachments::from_node(&child))),
-                "EventPort" => body.push(ComponentTypeBody::EventPort(EventPort::from_node(&child))),
-                "Exposure" => body.push(ComponentTypeBody::Exposure(Exposure::from_node(&child))),
-                "Requirement" => body.push(ComponentTypeBody::Requirement(Requirement::from_node(&child))),
-                "ComponentRequirement" => body.push(ComponentTypeBody::ComponentRequirement(ComponentRequirement::from_node(&child))),
-                "InstanceRequirement" => body.push(ComponentTypeBody::InstanceRequirement(InstanceRequirement::from_node(&child))),
-                "Path" => body.push(ComponentTypeBody::Path(Path::from_node(&child))),
-                "Text" => body.push(ComponentTypeBody::Text(Text::from_node(&child))),
-                "Dynamics" => body.push(ComponentTypeBody::Dynamics(Dynamics::from_node(&child))),
-                "Structure" => body.push(ComponentTypeBody::Structure(Structure::from_node(&child))),
-                "Simulation" => body.push(ComponentTypeBody::Simulation(Simulation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of ComponentType.", t)
+                "Property" => body.push(ComponentTypeBody::Property(Property::from_node(&child)?)),
+                "Parameter" => body.push(ComponentTypeBody::Parameter(Parameter::from_node(&child)?)),
+                "DerivedParameter" => body.push(ComponentTypeBody::DerivedParameter(DerivedParameter::from_node(&child)?)),
+                "IndexParameter" => body.push(ComponentTypeBody::IndexParameter(IndexParameter::from_node(&child)?)),
+                "Constant" => body.push(ComponentTypeBody::Constant(Constant::from_node(&child)?)),
+                "Child" => body.push(ComponentTypeBody::Child(Child::from_node(&child)?)),
+                "Children" => body.push(ComponentTypeBody::Children(Children::from_node(&child)?)),
+                "Fixed" => body.push(ComponentTypeBody::Fixed(Fixed::from_node(&child)?)),
+                "Link" => body.push(ComponentTypeBody::Link(Link::from_node(&child)?)),
+                "ComponentReference" => body.push(ComponentTypeBody::ComponentReference(ComponentReference::from_node(&child)?)),
+                "Attachments" => body.push(ComponentTypeBody::Attachments(Attachments::from_node(&child)?)),
+                "EventPort" => body.push(ComponentTypeBody::EventPort(EventPort::from_node(&child)?)),
+                "Exposure" => body.push(ComponentTypeBody::Exposure(Exposure::from_node(&child)?)),
+                "Requirement" => body.push(ComponentTypeBody::Requirement(Requirement::from_node(&child)?)),
+                "ComponentRequirement" => body.push(ComponentTypeBody::ComponentRequirement(ComponentRequirement::from_node(&child)?)),
+                "InstanceRequirement" => body.push(ComponentTypeBody::InstanceRequirement(InstanceRequirement::from_node(&child)?)),
+                "Path" => body.push(ComponentTypeBody::Path(Path::from_node(&child)?)),
+                "Text" => body.push(ComponentTypeBody::Text(Text::from_node(&child)?)),
+                "Dynamics" => body.push(ComponentTypeBody::Dynamics(Dynamics::from_node(&child)?)),
+                "Structure" => body.push(ComponentTypeBody::Structure(Structure::from_node(&child)?)),
+                "Simulation" => body.push(ComponentTypeBody::Simulation(Simulation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "ComponentType")),
             };
         }
-        ComponentType {
+        Ok(ComponentType {
             name,
             extends,
             description,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "name", &self.name);
+        if let Some(x) = &self.extends { write_attr(out, "extends", x); }
+        if let Some(x) = &self.description { write_attr(out, "description", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -240,13 +370,20 @@ pub struct Case {
 }
 
 impl XML for Case {
-    fn from_node(node: &Node) -> Self {
-        let condition = node.attribute("condition").map(|s| s.to_string());
-        let value = node.attribute("value").map(|s| s.to_string()).unwrap();
-        Case {
+    fn from_node(node: &Node) -> Result<Self> {
+        let condition = opt_attr::<String>(node, "condition")?;
+        let value = req_attr::<String>(node, "value")?;
+        Ok(Case {
             condition,
             value,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if let Some(x) = &self.condition { write_attr(out, "condition", x); }
+        write_attr(out, "value", &self.value);
+        out.push_str("/>\n");
     }
 }
 
@@ -255,6 +392,15 @@ pub enum EventConnectionBody {
     Assign(Assign),
 }
 
+#[allow(dead_code)]
+impl EventConnectionBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            EventConnectionBody::Assign(x) => x.to_xml("Assign", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct EventConnection {
     pub from: String,
@@ -268,25 +414,25 @@ pub struct EventConnection {
 }
 
 impl XML for EventConnection {
-    fn from_node(node: &Node) -> Self {
-        let from = node.attribute("from").map(|s| s.to_string()).unwrap();
-        let to = node.attribute("to").map(|s| s.to_string()).unwrap();
-        let sourcePort = node.attribute("sourcePort").map(|s| s.to_string());
-        let targetPort = node.attribute("targetPort").map(|s| s.to_string());
-        let receiver = node.attribute("receiver").map(|s| s.to_string());
-        let receiverContainer = node.attribute("receiverContainer").map(|s| s.to_string());
-        let delay = node.attribute("delay").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let from = req_attr::<String>(node, "from")?;
+        let to = req_attr::<String>(node, "to")?;
+        let sourcePort = opt_attr::<String>(node, "sourcePort")?;
+        let targetPort = opt_attr::<String>(node, "targetPort")?;
+        let receiver = opt_attr::<String>(node, "receiver")?;
+        let receiverContainer = opt_attr::<String>(node, "receiverContainer")?;
+        let delay = opt_attr::<String>(node, "delay")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "Assign" => body.push(EventConnectionBody::Assign(Assign::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of EventConnection.", t)
+                "Assign" => body.push(EventConnectionBody::Assign(Assign::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "EventConnection")),
             };
         }
-        EventConnection {
+        Ok(EventConnection {
             from,
             to,
             sourcePort,
@@ -295,6 +441,26 @@ impl XML for EventConnection {
             receiverContainer,
             delay,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "from", &self.from);
+        write_attr(out, "to", &self.to);
+        if let Some(x) = &self.sourcePort { write_attr(out, "sourcePort", x); }
+        if let Some(x) = &self.targetPort { write_attr(out, "targetPort", x); }
+        if let Some(x) = &self.receiver { write_attr(out, "receiver", x); }
+        if let Some(x) = &self.receiverContainer { write_attr(out, "receiverContainer", x); }
+        if let Some(x) = &self.delay { write_attr(out, "delay", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -310,6 +476,21 @@ pub enum LemsBody {
     Component(Component),
 }
 
+#[allow(dead_code)]
+impl LemsBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            LemsBody::Target(x) => x.to_xml("Target", depth, out),
+            LemsBody::Include(x) => x.to_xml("Include", depth, out),
+            LemsBody::Dimension(x) => x.to_xml("Dimension", depth, out),
+            LemsBody::Unit(x) => x.to_xml("Unit", depth, out),
+            LemsBody::Constant(x) => x.to_xml("Constant", depth, out),
+            LemsBody::ComponentType(x) => x.to_xml("ComponentType", depth, out),
+            LemsBody::Component(x) => x.to_xml("Component", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Lems {
     pub description: Option<String>,
@@ -317,27 +498,41 @@ pub struct Lems {
 }
 
 impl XML for Lems {
-    fn from_node(node: &Node) -> Self {
-        let description = node.attribute("description").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let description = opt_attr::<String>(node, "description")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "Target" => body.push(LemsBody::Target(Target::from_node(&child))),
-                "Include" => body.push(LemsBody::Include(Include::from_node(&child))),
-                "Dimension" => body.push(LemsBody::Dimension(Dimension::from_node(&child))),
-                "Unit" => body.push(LemsBody::Unit(Unit::from_node(&child))),
-                "Constant" => body.push(LemsBody::Constant(Constant::from_node(&child))),
-                "ComponentType" => body.push(LemsBody::ComponentType(ComponentType::from_node(&child))),
-                "Component" => body.push(LemsBody::Component(Component::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of Lems.", t)
+                "Target" => body.push(LemsBody::Target(Target::from_node(&child)?)),
+                "Include" => body.push(LemsBody::Include(Include::from_node(&child)?)),
+                "Dimension" => body.push(LemsBody::Dimension(Dimension::from_node(&child)?)),
+                "Unit" => body.push(LemsBody::Unit(Unit::from_node(&child)?)),
+                "Constant" => body.push(LemsBody::Constant(Constant::from_node(&child)?)),
+                "ComponentType" => body.push(LemsBody::ComponentType(ComponentType::from_node(&child)?)),
+                "Component" => body.push(LemsBody::Component(Component::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "Lems")),
             };
         }
-        Lems {
+        Ok(Lems {
             description,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if let Some(x) = &self.description { write_attr(out, "description", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -352,30 +547,57 @@ pub enum SimulationBody {
     EventWriter(EventWriter),
 }
 
+#[allow(dead_code)]
+impl SimulationBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            SimulationBody::DataDisplay(x) => x.to_xml("DataDisplay", depth, out),
+            SimulationBody::Record(x) => x.to_xml("Record", depth, out),
+            SimulationBody::EventRecord(x) => x.to_xml("EventRecord", depth, out),
+            SimulationBody::Run(x) => x.to_xml("Run", depth, out),
+            SimulationBody::DataWriter(x) => x.to_xml("DataWriter", depth, out),
+            SimulationBody::EventWriter(x) => x.to_xml("EventWriter", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Simulation {
     pub body: Vec<SimulationBody>
 }
 
 impl XML for Simulation {
-    fn from_node(node: &Node) -> Self {
+    fn from_node(node: &Node) -> Result<Self> {
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "DataDisplay" => body.push(SimulationBody::DataDisplay(DataDisplay::from_node(&child))),
-                "Record" => body.push(SimulationBody::Record(Record::from_node(&child))),
-                "EventRecord" => body.push(SimulationBody::EventRecord(EventRecord::from_node(&child))),
-                "Run" => body.push(SimulationBody::Run(Run::from_node(&child))),
-                "DataWriter" => body.push(SimulationBody::DataWriter(DataWriter::from_node(&child))),
-                "EventWriter" => body.push(SimulationBody::EventWriter(EventWriter::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of Simulation.", t)
+                "DataDisplay" => body.push(SimulationBody::DataDisplay(DataDisplay::from_node(&child)?)),
+                "Record" => body.push(SimulationBody::Record(Record::from_node(&child)?)),
+                "EventRecord" => body.push(SimulationBody::EventRecord(EventRecord::from_node(&child)?)),
+                "Run" => body.push(SimulationBody::Run(Run::from_node(&child)?)),
+                "DataWriter" => body.push(SimulationBody::DataWriter(DataWriter::from_node(&child)?)),
+                "EventWriter" => body.push(SimulationBody::EventWriter(EventWriter::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "Simulation")),
             };
         }
-        Simulation {
+        Ok(Simulation {
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -387,6 +609,17 @@ pub enum OnConditionBody {
     Transition(Transition),
 }
 
+#[allow(dead_code)]
+impl OnConditionBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            OnConditionBody::StateAssignment(x) => x.to_xml("StateAssignment", depth, out),
+            OnConditionBody::EventOut(x) => x.to_xml("EventOut", depth, out),
+            OnConditionBody::Transition(x) => x.to_xml("Transition", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct OnCondition {
     pub test: String,
@@ -394,23 +627,37 @@ pub struct OnCondition {
 }
 
 impl XML for OnCondition {
-    fn from_node(node: &Node) -> Self {
-        let test = node.attribute("test").map(|s| s.to_string()).unwrap();
+    fn from_node(node: &Node) -> Result<Self> {
+        let test = req_attr::<String>(node, "test")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "StateAssignment" => body.push(OnConditionBody::StateAssignment(StateAssignment::from_node(&child))),
-                "EventOut" => body.push(OnConditionBody::EventOut(EventOut::from_node(&child))),
-                "Transition" => body.push(OnConditionBody::Transition(Transition::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of OnCondition.", t)
+                "StateAssignment" => body.push(OnConditionBody::StateAssignment(StateAssignment::from_node(&child)?)),
+                "EventOut" => body.push(OnConditionBody::EventOut(EventOut::from_node(&child)?)),
+                "Transition" => body.push(OnConditionBody::Transition(Transition::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "OnCondition")),
             };
         }
-        OnCondition {
+        Ok(OnCondition {
             test,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "test", &self.test);
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -420,25 +667,47 @@ pub enum OnEntryBody {
     StateAssignment(StateAssignment),
 }
 
+#[allow(dead_code)]
+impl OnEntryBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            OnEntryBody::StateAssignment(x) => x.to_xml("StateAssignment", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct OnEntry {
     pub body: Vec<OnEntryBody>
 }
 
 impl XML for OnEntry {
-    fn from_node(node: &Node) -> Self {
+    fn from_node(node: &Node) -> Result<Self> {
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "StateAssignment" => body.push(OnEntryBody::StateAssignment(StateAssignment::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of OnEntry.", t)
+                "StateAssignment" => body.push(OnEntryBody::StateAssignment(StateAssignment::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "OnEntry")),
             };
         }
-        OnEntry {
+        Ok(OnEntry {
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -450,13 +719,20 @@ pub struct Text {
 }
 
 impl XML for Text {
-    fn from_node(node: &Node) -> Self {
-        let name = node.attribute("name").map(|s| s.to_string()).unwrap();
-        let description = node.attribute("description").map(|s| s.to_string());
-        Text {
+    fn from_node(node: &Node) -> Result<Self> {
+        let name = req_attr::<String>(node, "name")?;
+        let description = opt_attr::<String>(node, "description")?;
+        Ok(Text {
             name,
             description,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "name", &self.name);
+        if let Some(x) = &self.description { write_attr(out, "description", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -469,17 +745,26 @@ pub struct StateVariable {
 }
 
 impl XML for StateVariable {
-    fn from_node(node: &Node) -> Self {
-        let name = node.attribute("name").map(|s| s.to_string()).unwrap();
-        let dimension = node.attribute("dimension").or(Some("none")).map(|s| s.to_string()).unwrap();
-        let exposure = node.attribute("exposure").map(|s| s.to_string());
-        let description = node.attribute("description").map(|s| s.to_string());
-        StateVariable {
+    fn from_node(node: &Node) -> Result<Self> {
+        let name = req_attr::<String>(node, "name")?;
+        let dimension = def_attr::<String>(node, "dimension", "none")?;
+        let exposure = opt_attr::<String>(node, "exposure")?;
+        let description = opt_attr::<String>(node, "description")?;
+        Ok(StateVariable {
             name,
             dimension,
             exposure,
             description,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "name", &self.name);
+        write_attr(out, "dimension", &self.dimension);
+        if let Some(x) = &self.exposure { write_attr(out, "exposure", x); }
+        if let Some(x) = &self.description { write_attr(out, "description", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -491,15 +776,23 @@ pub struct NamedDimensionalType {
 }
 
 impl XML for NamedDimensionalType {
-    fn from_node(node: &Node) -> Self {
-        let name = node.attribute("name").map(|s| s.to_string()).unwrap();
-        let dimension = node.attribute("dimension").or(Some("none")).map(|s| s.to_string()).unwrap();
-        let description = node.attribute("description").map(|s| s.to_string());
-        NamedDimensionalType {
+    fn from_node(node: &Node) -> Result<Self> {
+        let name = req_attr::<String>(node, "name")?;
+        let dimension = def_attr::<String>(node, "dimension", "none")?;
+        let description = opt_attr::<String>(node, "description")?;
+        Ok(NamedDimensionalType {
             name,
             dimension,
             description,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "name", &self.name);
+        write_attr(out, "dimension", &self.dimension);
+        if let Some(x) = &self.description { write_attr(out, "description", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -510,13 +803,20 @@ pub struct Path {
 }
 
 impl XML for Path {
-    fn from_node(node: &Node) -> Self {
-        let name = node.attribute("name").map(|s| s.to_string()).unwrap();
-        let description = node.attribute("description").map(|s| s.to_string());
-        Path {
+    fn from_node(node: &Node) -> Result<Self> {
+        let name = req_attr::<String>(node, "name")?;
+        let description = opt_attr::<String>(node, "description")?;
+        Ok(Path {
             name,
             description,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "name", &self.name);
+        if let Some(x) = &self.description { write_attr(out, "description", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -528,15 +828,23 @@ pub struct Attachments {
 }
 
 impl XML for Attachments {
-    fn from_node(node: &Node) -> Self {
-        let name = node.attribute("name").map(|s| s.to_string()).unwrap();
-        let r#type = node.attribute("type").map(|s| s.to_string()).unwrap();
-        let description = node.attribute("description").map(|s| s.to_string());
-        Attachments {
+    fn from_node(node: &Node) -> Result<Self> {
+        let name = req_attr::<String>(node, "name")?;
+        let r#type = req_attr::<String>(node, "type")?;
+        let description = opt_attr::<String>(node, "description")?;
+        Ok(Attachments {
             name,
             r#type,
             description,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "name", &self.name);
+        write_attr(out, "type", &self.r#type);
+        if let Some(x) = &self.description { write_attr(out, "description", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -550,30 +858,57 @@ pub enum StructureBody {
     EventConnection(EventConnection),
 }
 
+#[allow(dead_code)]
+impl StructureBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            StructureBody::ChildInstance(x) => x.to_xml("ChildInstance", depth, out),
+            StructureBody::MultiInstantiate(x) => x.to_xml("MultiInstantiate", depth, out),
+            StructureBody::ForEach(x) => x.to_xml("ForEach", depth, out),
+            StructureBody::With(x) => x.to_xml("With", depth, out),
+            StructureBody::Tunnel(x) => x.to_xml("Tunnel", depth, out),
+            StructureBody::EventConnection(x) => x.to_xml("EventConnection", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Structure {
     pub body: Vec<StructureBody>
 }
 
 impl XML for Structure {
-    fn from_node(node: &Node) -> Self {
+    fn from_node(node: &Node) -> Result<Self> {
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "ChildInstance" => body.push(StructureBody::ChildInstance(ChildInstance::from_node(&child))),
-                "MultiInstantiate" => body.push(StructureBody::MultiInstantiate(MultiInstantiate::from_node(&child))),
-                "ForEach" => body.push(StructureBody::ForEach(ForEach::from_node(&child))),
-                "With" => body.push(StructureBody::With(With::from_node(&child))),
-                "Tunnel" => body.push(StructureBody::Tunnel(Tunnel::from_node(&child))),
-                "EventConnection" => body.push(StructureBody::EventConnection(EventConnection::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of Structure.", t)
+                "ChildInstance" => body.push(StructureBody::ChildInstance(ChildInstance::from_node(&child)?)),
+                "MultiInstantiate" => body.push(StructureBody::MultiInstantiate(MultiInstantiate::from_node(&child)?)),
+                "ForEach" => body.push(StructureBody::ForEach(ForEach::from_node(&child)?)),
+                "With" => body.push(StructureBody::With(With::from_node(&child)?)),
+                "Tunnel" => body.push(StructureBody::Tunnel(Tunnel::from_node(&child)?)),
+                "EventConnection" => body.push(StructureBody::EventConnection(EventConnection::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "Structure")),
             };
         }
-        Structure {
+        Ok(Structure {
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -585,13 +920,20 @@ pub struct DataDisplay {
 }
 
 impl XML for DataDisplay {
-    fn from_node(node: &Node) -> Self {
-        let title = node.attribute("title").map(|s| s.to_string()).unwrap();
-        let dataRegion = node.attribute("dataRegion").map(|s| s.to_string()).unwrap();
-        DataDisplay {
+    fn from_node(node: &Node) -> Result<Self> {
+        let title = req_attr::<String>(node, "title")?;
+        let dataRegion = req_attr::<String>(node, "dataRegion")?;
+        Ok(DataDisplay {
             title,
             dataRegion,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "title", &self.title);
+        write_attr(out, "dataRegion", &self.dataRegion);
+        out.push_str("/>\n");
     }
 }
 
@@ -600,6 +942,15 @@ pub enum ConditionalDerivedVariableBody {
     Case(Case),
 }
 
+#[allow(dead_code)]
+impl ConditionalDerivedVariableBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            ConditionalDerivedVariableBody::Case(x) => x.to_xml("Case", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ConditionalDerivedVariable {
     pub name: String,
@@ -609,25 +960,41 @@ pub struct ConditionalDerivedVariable {
 }
 
 impl XML for ConditionalDerivedVariable {
-    fn from_node(node: &Node) -> Self {
-        let name = node.attribute("name").map(|s| s.to_string()).unwrap();
-        let dimension = node.attribute("dimension").or(Some("none")).map(|s| s.to_string()).unwrap();
-        let exposure = node.attribute("exposure").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let name = req_attr::<String>(node, "name")?;
+        let dimension = def_attr::<String>(node, "dimension", "none")?;
+        let exposure = opt_attr::<String>(node, "exposure")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "Case" => body.push(ConditionalDerivedVariableBody::Case(Case::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of ConditionalDerivedVariable.", t)
+                "Case" => body.push(ConditionalDerivedVariableBody::Case(Case::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "ConditionalDerivedVariable")),
             };
         }
-        ConditionalDerivedVariable {
+        Ok(ConditionalDerivedVariable {
             name,
             dimension,
             exposure,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "name", &self.name);
+        write_attr(out, "dimension", &self.dimension);
+        if let Some(x) = &self.exposure { write_attr(out, "exposure", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -638,11 +1005,17 @@ pub struct IndexParameter {
 }
 
 impl XML for IndexParameter {
-    fn from_node(node: &Node) -> Self {
-        let name = node.attribute("name").map(|s| s.to_string()).unwrap();
-        IndexParameter {
+    fn from_node(node: &Node) -> Result<Self> {
+        let name = req_attr::<String>(node, "name")?;
+        Ok(IndexParameter {
             name,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "name", &self.name);
+        out.push_str("/>\n");
     }
 }
 
@@ -652,11 +1025,17 @@ pub struct ChildInstance {
 }
 
 impl XML for ChildInstance {
-    fn from_node(node: &Node) -> Self {
-        let component = node.attribute("component").map(|s| s.to_string()).unwrap();
-        ChildInstance {
+    fn from_node(node: &Node) -> Result<Self> {
+        let component = req_attr::<String>(node, "component")?;
+        Ok(ChildInstance {
             component,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "component", &self.component);
+        out.push_str("/>\n");
     }
 }
 
@@ -667,13 +1046,20 @@ pub struct TimeDerivative {
 }
 
 impl XML for TimeDerivative {
-    fn from_node(node: &Node) -> Self {
-        let variable = node.attribute("variable").map(|s| s.to_string()).unwrap();
-        let value = node.attribute("value").map(|s| s.to_string()).unwrap();
-        TimeDerivative {
+    fn from_node(node: &Node) -> Result<Self> {
+        let variable = req_attr::<String>(node, "variable")?;
+        let value = req_attr::<String>(node, "value")?;
+        Ok(TimeDerivative {
             variable,
             value,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "variable", &self.variable);
+        write_attr(out, "value", &self.value);
+        out.push_str("/>\n");
     }
 }
 
@@ -687,19 +1073,29 @@ pub struct Unit {
 }
 
 impl XML for Unit {
-    fn from_node(node: &Node) -> Self {
-        let symbol = node.attribute("symbol").map(|s| s.to_string()).unwrap();
-        let dimension = node.attribute("dimension").map(|s| s.to_string()).unwrap();
-        let power = node.attribute("power").or(Some("0")).map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let scale = node.attribute("scale").or(Some("1")).map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let offset = node.attribute("offset").or(Some("0")).map(|s| s.parse::<f64>().unwrap()).unwrap();
-        Unit {
+    fn from_node(node: &Node) -> Result<Self> {
+        let symbol = req_attr::<String>(node, "symbol")?;
+        let dimension = req_attr::<String>(node, "dimension")?;
+        let power = def_attr::<i64>(node, "power", "0")?;
+        let scale = def_attr::<f64>(node, "scale", "1")?;
+        let offset = def_attr::<f64>(node, "offset", "0")?;
+        Ok(Unit {
             symbol,
             dimension,
             power,
             scale,
             offset,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "symbol", &self.symbol);
+        write_attr(out, "dimension", &self.dimension);
+        write_attr(out, "power", &self.power);
+        write_attr(out, "scale", &self.scale);
+        write_attr(out, "offset", &self.offset);
+        out.push_str("/>\n");
     }
 }
 
@@ -711,15 +1107,23 @@ pub struct Requirement {
 }
 
 impl XML for Requirement {
-    fn from_node(node: &Node) -> Self {
-        let name = node.attribute("name").map(|s| s.to_string()).unwrap();
-        let dimension = node.attribute("dimension").or(Some("none")).map(|s| s.to_string()).unwrap();
-        let description = node.attribute("description").map(|s| s.to_string());
-        Requirement {
+    fn from_node(node: &Node) -> Result<Self> {
+        let name = req_attr::<String>(node, "name")?;
+        let dimension = def_attr::<String>(node, "dimension", "none")?;
+        let description = opt_attr::<String>(node, "description")?;
+        Ok(Requirement {
             name,
             dimension,
             description,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "name", &self.name);
+        write_attr(out, "dimension", &self.dimension);
+        if let Some(x) = &self.description { write_attr(out, "description", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -731,15 +1135,23 @@ pub struct EventWriter {
 }
 
 impl XML for EventWriter {
-    fn from_node(node: &Node) -> Self {
-        let path = node.attribute("path").map(|s| s.to_string()).unwrap();
-        let fileName = node.attribute("fileName").map(|s| s.to_string()).unwrap();
-        let format = node.attribute("format").map(|s| s.to_string()).unwrap();
-        EventWriter {
+    fn from_node(node: &Node) -> Result<Self> {
+        let path = req_attr::<String>(node, "path")?;
+        let fileName = req_attr::<String>(node, "fileName")?;
+        let format = req_attr::<String>(node, "format")?;
+        Ok(EventWriter {
             path,
             fileName,
             format,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "path", &self.path);
+        write_attr(out, "fileName", &self.fileName);
+        write_attr(out, "format", &self.format);
+        out.push_str("/>\n");
     }
 }
 
@@ -751,15 +1163,23 @@ pub struct Fixed {
 }
 
 impl XML for Fixed {
-    fn from_node(node: &Node) -> Self {
-        let parameter = node.attribute("parameter").map(|s| s.to_string()).unwrap();
-        let value = node.attribute("value").map(|s| s.to_string()).unwrap();
-        let description = node.attribute("description").map(|s| s.to_string());
-        Fixed {
+    fn from_node(node: &Node) -> Result<Self> {
+        let parameter = req_attr::<String>(node, "parameter")?;
+        let value = req_attr::<String>(node, "value")?;
+        let description = opt_attr::<String>(node, "description")?;
+        Ok(Fixed {
             parameter,
             value,
             description,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "parameter", &self.parameter);
+        write_attr(out, "value", &self.value);
+        if let Some(x) = &self.description { write_attr(out, "description", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -771,15 +1191,23 @@ pub struct Target {
 }
 
 impl XML for Target {
-    fn from_node(node: &Node) -> Self {
-        let component = node.attribute("component").map(|s| s.to_string()).unwrap();
-        let reportFile = node.attribute("reportFile").map(|s| s.to_string());
-        let timesFile = node.attribute("timesFile").map(|s| s.to_string());
-        Target {
+    fn from_node(node: &Node) -> Result<Self> {
+        let component = req_attr::<String>(node, "component")?;
+        let reportFile = opt_attr::<String>(node, "reportFile")?;
+        let timesFile = opt_attr::<String>(node, "timesFile")?;
+        Ok(Target {
             component,
             reportFile,
             timesFile,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "component", &self.component);
+        if let Some(x) = &self.reportFile { write_attr(out, "reportFile", x); }
+        if let Some(x) = &self.timesFile { write_attr(out, "timesFile", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -793,19 +1221,29 @@ pub struct Children {
 }
 
 impl XML for Children {
-    fn from_node(node: &Node) -> Self {
-        let name = node.attribute("name").map(|s| s.to_string()).unwrap();
-        let r#type = node.attribute("type").map(|s| s.to_string());
-        let min = node.attribute("min").map(|s| s.parse::<i64>().unwrap());
-        let max = node.attribute("max").map(|s| s.parse::<i64>().unwrap());
-        let description = node.attribute("description").map(|s| s.to_string());
-        Children {
+    fn from_node(node: &Node) -> Result<Self> {
+        let name = req_attr::<String>(node, "name")?;
+        let r#type = opt_attr::<String>(node, "type")?;
+        let min = opt_attr::<i64>(node, "min")?;
+        let max = opt_attr::<i64>(node, "max")?;
+        let description = opt_attr::<String>(node, "description")?;
+        Ok(Children {
             name,
             r#type,
             min,
             max,
             description,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "name", &self.name);
+        if let Some(x) = &self.r#type { write_attr(out, "type", x); }
+        if let Some(x) = &self.min { write_attr(out, "min", x); }
+        if let Some(x) = &self.max { write_attr(out, "max", x); }
+        if let Some(x) = &self.description { write_attr(out, "description", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -818,17 +1256,26 @@ pub struct Property {
 }
 
 impl XML for Property {
-    fn from_node(node: &Node) -> Self {
-        let name = node.attribute("name").map(|s| s.to_string()).unwrap();
-        let dimension = node.attribute("dimension").or(Some("none")).map(|s| s.to_string()).unwrap();
-        let description = node.attribute("description").map(|s| s.to_string());
-        let defaultValue = node.attribute("defaultValue").map(|s| s.parse::<f64>().unwrap());
-        Property {
+    fn from_node(node: &Node) -> Result<Self> {
+        let name = req_attr::<String>(node, "name")?;
+        let dimension = def_attr::<String>(node, "dimension", "none")?;
+        let description = opt_attr::<String>(node, "description")?;
+        let defaultValue = opt_attr::<f64>(node, "defaultValue")?;
+        Ok(Property {
             name,
             dimension,
             description,
             defaultValue,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "name", &self.name);
+        write_attr(out, "dimension", &self.dimension);
+        if let Some(x) = &self.description { write_attr(out, "description", x); }
+        if let Some(x) = &self.defaultValue { write_attr(out, "defaultValue", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -840,15 +1287,23 @@ pub struct Parameter {
 }
 
 impl XML for Parameter {
-    fn from_node(node: &Node) -> Self {
-        let name = node.attribute("name").map(|s| s.to_string()).unwrap();
-        let dimension = node.attribute("dimension").or(Some("none")).map(|s| s.to_string()).unwrap();
-        let description = node.attribute("description").map(|s| s.to_string());
-        Parameter {
+    fn from_node(node: &Node) -> Result<Self> {
+        let name = req_attr::<String>(node, "name")?;
+        let dimension = def_attr::<String>(node, "dimension", "none")?;
+        let description = opt_attr::<String>(node, "description")?;
+        Ok(Parameter {
             name,
             dimension,
             description,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "name", &self.name);
+        write_attr(out, "dimension", &self.dimension);
+        if let Some(x) = &self.description { write_attr(out, "description", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -864,15 +1319,15 @@ pub struct Dimension {
 }
 
 impl XML for Dimension {
-    fn from_node(node: &Node) -> Self {
-        let name = node.attribute("name").map(|s| s.to_string()).unwrap();
-        let m = node.attribute("m").or(Some("0")).map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let l = node.attribute("l").or(Some("0")).map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let t = node.attribute("t").or(Some("0")).map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let i = node.attribute("i").or(Some("0")).map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let k = node.attribute("k").or(Some("0")).map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let n = node.attribute("n").or(Some("0")).map(|s| s.parse::<i64>().unwrap()).unwrap();
-        Dimension {
+    fn from_node(node: &Node) -> Result<Self> {
+        let name = req_attr::<String>(node, "name")?;
+        let m = def_attr::<i64>(node, "m", "0")?;
+        let l = def_attr::<i64>(node, "l", "0")?;
+        let t = def_attr::<i64>(node, "t", "0")?;
+        let i = def_attr::<i64>(node, "i", "0")?;
+        let k = def_attr::<i64>(node, "k", "0")?;
+        let n = def_attr::<i64>(node, "n", "0")?;
+        Ok(Dimension {
             name,
             m,
             l,
@@ -880,7 +1335,19 @@ impl XML for Dimension {
             i,
             k,
             n,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "name", &self.name);
+        write_attr(out, "m", &self.m);
+        write_attr(out, "l", &self.l);
+        write_attr(out, "t", &self.t);
+        write_attr(out, "i", &self.i);
+        write_attr(out, "k", &self.k);
+        write_attr(out, "n", &self.n);
+        out.push_str("/>\n");
     }
 }
 
@@ -897,16 +1364,16 @@ pub struct KineticScheme {
 }
 
 impl XML for KineticScheme {
-    fn from_node(node: &Node) -> Self {
-        let name = node.attribute("name").map(|s| s.to_string()).unwrap();
-        let nodes = node.attribute("nodes").map(|s| s.to_string()).unwrap();
-        let stateVariable = node.attribute("stateVariable").map(|s| s.to_string()).unwrap();
-        let edges = node.attribute("edges").map(|s| s.to_string()).unwrap();
-        let edgeSource = node.attribute("edgeSource").map(|s| s.to_string()).unwrap();
-        let edgeTarget = node.attribute("edgeTarget").map(|s| s.to_string()).unwrap();
-        let forwardRate = node.attribute("forwardRate").map(|s| s.to_string()).unwrap();
-        let reverseRate = node.attribute("reverseRate").map(|s| s.to_string()).unwrap();
-        KineticScheme {
+    fn from_node(node: &Node) -> Result<Self> {
+        let name = req_attr::<String>(node, "name")?;
+        let nodes = req_attr::<String>(node, "nodes")?;
+        let stateVariable = req_attr::<String>(node, "stateVariable")?;
+        let edges = req_attr::<String>(node, "edges")?;
+        let edgeSource = req_attr::<String>(node, "edgeSource")?;
+        let edgeTarget = req_attr::<String>(node, "edgeTarget")?;
+        let forwardRate = req_attr::<String>(node, "forwardRate")?;
+        let reverseRate = req_attr::<String>(node, "reverseRate")?;
+        Ok(KineticScheme {
             name,
             nodes,
             stateVariable,
@@ -915,7 +1382,20 @@ impl XML for KineticScheme {
             edgeTarget,
             forwardRate,
             reverseRate,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "name", &self.name);
+        write_attr(out, "nodes", &self.nodes);
+        write_attr(out, "stateVariable", &self.stateVariable);
+        write_attr(out, "edges", &self.edges);
+        write_attr(out, "edgeSource", &self.edgeSource);
+        write_attr(out, "edgeTarget", &self.edgeTarget);
+        write_attr(out, "forwardRate", &self.forwardRate);
+        write_attr(out, "reverseRate", &self.reverseRate);
+        out.push_str("/>\n");
     }
 }
 
@@ -925,6 +1405,16 @@ pub enum OnEventBody {
     EventOut(EventOut),
 }
 
+#[allow(dead_code)]
+impl OnEventBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            OnEventBody::StateAssignment(x) => x.to_xml("StateAssignment", depth, out),
+            OnEventBody::EventOut(x) => x.to_xml("EventOut", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct OnEvent {
     pub port: String,
@@ -932,22 +1422,36 @@ pub struct OnEvent {
 }
 
 impl XML for OnEvent {
-    fn from_node(node: &Node) -> Self {
-        let port = node.attribute("port").map(|s| s.to_string()).unwrap();
+    fn from_node(node: &Node) -> Result<Self> {
+        let port = req_attr::<String>(node, "port")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "StateAssignment" => body.push(OnEventBody::StateAssignment(StateAssignment::from_node(&child))),
-                "EventOut" => body.push(OnEventBody::EventOut(EventOut::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of OnEvent.", t)
+                "StateAssignment" => body.push(OnEventBody::StateAssignment(StateAssignment::from_node(&child)?)),
+                "EventOut" => body.push(OnEventBody::EventOut(EventOut::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "OnEvent")),
             };
         }
-        OnEvent {
+        Ok(OnEvent {
             port,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "port", &self.port);
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -959,13 +1463,20 @@ pub struct Assign {
 }
 
 impl XML for Assign {
-    fn from_node(node: &Node) -> Self {
-        let property = node.attribute("property").map(|s| s.to_string()).unwrap();
-        let value = node.attribute("value").map(|s| s.to_string()).unwrap();
-        Assign {
+    fn from_node(node: &Node) -> Result<Self> {
+        let property = req_attr::<String>(node, "property")?;
+        let value = req_attr::<String>(node, "value")?;
+        Ok(Assign {
             property,
             value,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "property", &self.property);
+        write_attr(out, "value", &self.value);
+        out.push_str("/>\n");
     }
 }
 
@@ -975,11 +1486,17 @@ pub struct Transition {
 }
 
 impl XML for Transition {
-    fn from_node(node: &Node) -> Self {
-        let regime = node.attribute("regime").map(|s| s.to_string()).unwrap();
-        Transition {
+    fn from_node(node: &Node) -> Result<Self> {
+        let regime = req_attr::<String>(node, "regime")?;
+        Ok(Transition {
             regime,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "regime", &self.regime);
+        out.push_str("/>\n");
     }
 }
 
@@ -990,13 +1507,20 @@ pub struct DataWriter {
 }
 
 impl XML for DataWriter {
-    fn from_node(node: &Node) -> Self {
-        let path = node.attribute("path").map(|s| s.to_string()).unwrap();
-        let fileName = node.attribute("fileName").map(|s| s.to_string()).unwrap();
-        DataWriter {
+    fn from_node(node: &Node) -> Result<Self> {
+        let path = req_attr::<String>(node, "path")?;
+        let fileName = req_attr::<String>(node, "fileName")?;
+        Ok(DataWriter {
             path,
             fileName,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "path", &self.path);
+        write_attr(out, "fileName", &self.fileName);
+        out.push_str("/>\n");
     }
 }
 
@@ -1007,13 +1531,20 @@ pub struct InstanceRequirement {
 }
 
 impl XML for InstanceRequirement {
-    fn from_node(node: &Node) -> Self {
-        let name = node.attribute("name").map(|s| s.to_string()).unwrap();
-        let r#type = node.attribute("type").map(|s| s.to_string()).unwrap();
-        InstanceRequirement {
+    fn from_node(node: &Node) -> Result<Self> {
+        let name = req_attr::<String>(node, "name")?;
+        let r#type = req_attr::<String>(node, "type")?;
+        Ok(InstanceRequirement {
             name,
             r#type,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "name", &self.name);
+        write_attr(out, "type", &self.r#type);
+        out.push_str("/>\n");
     }
 }
 
@@ -1025,26 +1556,42 @@ pub struct EventPort {
 }
 
 impl XML for EventPort {
-    fn from_node(node: &Node) -> Self {
-        let name = node.attribute("name").map(|s| s.to_string()).unwrap();
-        let direction = node.attribute("direction").map(|s| s.to_string()).unwrap();
-        let description = node.attribute("description").map(|s| s.to_string());
-        EventPort {
+    fn from_node(node: &Node) -> Result<Self> {
+        let name = req_attr::<String>(node, "name")?;
+        let direction = req_attr::<String>(node, "direction")?;
+        let description = opt_attr::<String>(node, "description")?;
+        Ok(EventPort {
             name,
             direction,
             description,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "name", &self.name);
+        write_attr(out, "direction", &self.direction);
+        if let Some(x) = &self.description { write_attr(out, "description", x); }
+        out.push_str("/>\n");
     }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Component {
+    pub body: String,
 }
 
 impl XML for Component {
-    fn from_node(node: &Node) -> Self {
-        Component {
-        }
+    fn from_node(node: &Node) -> Result<Self> {
+        let body = inner_xml(node);
+        Ok(Component {
+            body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_inner(out, tag, &self.body);
     }
 }
 
@@ -1055,13 +1602,20 @@ pub struct StateAssignment {
 }
 
 impl XML for StateAssignment {
-    fn from_node(node: &Node) -> Self {
-        let variable = node.attribute("variable").map(|s| s.to_string()).unwrap();
-        let value = node.attribute("value").map(|s| s.to_string()).unwrap();
-        StateAssignment {
+    fn from_node(node: &Node) -> Result<Self> {
+        let variable = req_attr::<String>(node, "variable")?;
+        let value = req_attr::<String>(node, "value")?;
+        Ok(StateAssignment {
             variable,
             value,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "variable", &self.variable);
+        write_attr(out, "value", &self.value);
+        out.push_str("/>\n");
     }
 }
 
@@ -1078,16 +1632,16 @@ pub struct DerivedVariable {
 }
 
 impl XML for DerivedVariable {
-    fn from_node(node: &Node) -> Self {
-        let name = node.attribute("name").map(|s| s.to_string()).unwrap();
-        let dimension = node.attribute("dimension").or(Some("none")).map(|s| s.to_string()).unwrap();
-        let exposure = node.attribute("exposure").map(|s| s.to_string());
-        let description = node.attribute("description").map(|s| s.to_string());
-        let select = node.attribute("select").map(|s| s.to_string());
-        let value = node.attribute("value").map(|s| s.to_string());
-        let reduce = node.attribute("reduce").map(|s| s.to_string());
-        let required = node.attribute("required").map(|s| s.to_string());
-        DerivedVariable {
+    fn from_node(node: &Node) -> Result<Self> {
+        let name = req_attr::<String>(node, "name")?;
+        let dimension = def_attr::<String>(node, "dimension", "none")?;
+        let exposure = opt_attr::<String>(node, "exposure")?;
+        let description = opt_attr::<String>(node, "description")?;
+        let select = opt_attr::<String>(node, "select")?;
+        let value = opt_attr::<String>(node, "value")?;
+        let reduce = opt_attr::<String>(node, "reduce")?;
+        let required = opt_attr::<String>(node, "required")?;
+        Ok(DerivedVariable {
             name,
             dimension,
             exposure,
@@ -1096,7 +1650,20 @@ impl XML for DerivedVariable {
             value,
             reduce,
             required,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "name", &self.name);
+        write_attr(out, "dimension", &self.dimension);
+        if let Some(x) = &self.exposure { write_attr(out, "exposure", x); }
+        if let Some(x) = &self.description { write_attr(out, "description", x); }
+        if let Some(x) = &self.select { write_attr(out, "select", x); }
+        if let Some(x) = &self.value { write_attr(out, "value", x); }
+        if let Some(x) = &self.reduce { write_attr(out, "reduce", x); }
+        if let Some(x) = &self.required { write_attr(out, "required", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -1107,13 +1674,20 @@ pub struct MultiInstantiate {
 }
 
 impl XML for MultiInstantiate {
-    fn from_node(node: &Node) -> Self {
-        let component = node.attribute("component").map(|s| s.to_string()).unwrap();
-        let number = node.attribute("number").map(|s| s.to_string()).unwrap();
-        MultiInstantiate {
+    fn from_node(node: &Node) -> Result<Self> {
+        let component = req_attr::<String>(node, "component")?;
+        let number = req_attr::<String>(node, "number")?;
+        Ok(MultiInstantiate {
             component,
             number,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "component", &self.component);
+        write_attr(out, "number", &self.number);
+        out.push_str("/>\n");
     }
 }
 
@@ -1125,15 +1699,23 @@ pub struct Link {
 }
 
 impl XML for Link {
-    fn from_node(node: &Node) -> Self {
-        let name = node.attribute("name").map(|s| s.to_string()).unwrap();
-        let r#type = node.attribute("type").map(|s| s.to_string()).unwrap();
-        let description = node.attribute("description").map(|s| s.to_string());
-        Link {
+    fn from_node(node: &Node) -> Result<Self> {
+        let name = req_attr::<String>(node, "name")?;
+        let r#type = req_attr::<String>(node, "type")?;
+        let description = opt_attr::<String>(node, "description")?;
+        Ok(Link {
             name,
             r#type,
             description,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "name", &self.name);
+        write_attr(out, "type", &self.r#type);
+        if let Some(x) = &self.description { write_attr(out, "description", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -1146,17 +1728,26 @@ pub struct With {
 }
 
 impl XML for With {
-    fn from_node(node: &Node) -> Self {
-        let instance = node.attribute("instance").map(|s| s.to_string());
-        let list = node.attribute("list").map(|s| s.to_string());
-        let index = node.attribute("index").map(|s| s.to_string());
-        let r#as = node.attribute("as").map(|s| s.to_string()).unwrap();
-        With {
+    fn from_node(node: &Node) -> Result<Self> {
+        let instance = opt_attr::<String>(node, "instance")?;
+        let list = opt_attr::<String>(node, "list")?;
+        let index = opt_attr::<String>(node, "index")?;
+        let r#as = req_attr::<String>(node, "as")?;
+        Ok(With {
             instance,
             list,
             index,
             r#as,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if let Some(x) = &self.instance { write_attr(out, "instance", x); }
+        if let Some(x) = &self.list { write_attr(out, "list", x); }
+        if let Some(x) = &self.index { write_attr(out, "index", x); }
+        write_attr(out, "as", &self.r#as);
+        out.push_str("/>\n");
     }
 }
 
@@ -1169,17 +1760,26 @@ pub struct Record {
 }
 
 impl XML for Record {
-    fn from_node(node: &Node) -> Self {
-        let quantity = node.attribute("quantity").map(|s| s.to_string()).unwrap();
-        let timeScale = node.attribute("timeScale").map(|s| s.to_string());
-        let scale = node.attribute("scale").map(|s| s.to_string());
-        let color = node.attribute("color").map(|s| s.to_string());
-        Record {
+    fn from_node(node: &Node) -> Result<Self> {
+        let quantity = req_attr::<String>(node, "quantity")?;
+        let timeScale = opt_attr::<String>(node, "timeScale")?;
+        let scale = opt_attr::<String>(node, "scale")?;
+        let color = opt_attr::<String>(node, "color")?;
+        Ok(Record {
             quantity,
             timeScale,
             scale,
             color,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "quantity", &self.quantity);
+        if let Some(x) = &self.timeScale { write_attr(out, "timeScale", x); }
+        if let Some(x) = &self.scale { write_attr(out, "scale", x); }
+        if let Some(x) = &self.color { write_attr(out, "color", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -1188,6 +1788,15 @@ pub enum TunnelBody {
     Assign(Assign),
 }
 
+#[allow(dead_code)]
+impl TunnelBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            TunnelBody::Assign(x) => x.to_xml("Assign", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Tunnel {
     pub name: String,
@@ -1199,29 +1808,47 @@ pub struct Tunnel {
 }
 
 impl XML for Tunnel {
-    fn from_node(node: &Node) -> Self {
-        let name = node.attribute("name").map(|s| s.to_string()).unwrap();
-        let endA = node.attribute("endA").map(|s| s.to_string()).unwrap();
-        let endB = node.attribute("endB").map(|s| s.to_string()).unwrap();
-        let componentA = node.attribute("componentA").map(|s| s.to_string()).unwrap();
-        let componentB = node.attribute("componentB").map(|s| s.to_string()).unwrap();
+    fn from_node(node: &Node) -> Result<Self> {
+        let name = req_attr::<String>(node, "name")?;
+        let endA = req_attr::<String>(node, "endA")?;
+        let endB = req_attr::<String>(node, "endB")?;
+        let componentA = req_attr::<String>(node, "componentA")?;
+        let componentB = req_attr::<String>(node, "componentB")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "Assign" => body.push(TunnelBody::Assign(Assign::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of Tunnel.", t)
+                "Assign" => body.push(TunnelBody::Assign(Assign::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "Tunnel")),
             };
         }
-        Tunnel {
+        Ok(Tunnel {
             name,
             endA,
             endB,
             componentA,
             componentB,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "name", &self.name);
+        write_attr(out, "endA", &self.endA);
+        write_attr(out, "endB", &self.endB);
+        write_attr(out, "componentA", &self.componentA);
+        write_attr(out, "componentB", &self.componentB);
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -1232,11 +1859,17 @@ pub struct Include {
 }
 
 impl XML for Include {
-    fn from_node(node: &Node) -> Self {
-        let file = node.attribute("file").map(|s| s.to_string()).unwrap();
-        Include {
+    fn from_node(node: &Node) -> Result<Self> {
+        let file = req_attr::<String>(node, "file")?;
+        Ok(Include {
             file,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "file", &self.file);
+        out.push_str("/>\n");
     }
 }
 
@@ -1249,17 +1882,26 @@ pub struct Constant {
 }
 
 impl XML for Constant {
-    fn from_node(node: &Node) -> Self {
-        let name = node.attribute("name").map(|s| s.to_string()).unwrap();
-        let dimension = node.attribute("dimension").or(Some("none")).map(|s| s.to_string()).unwrap();
-        let value = node.attribute("value").map(|s| s.to_string()).unwrap();
-        let description = node.attribute("description").map(|s| s.to_string());
-        Constant {
+    fn from_node(node: &Node) -> Result<Self> {
+        let name = req_attr::<String>(node, "name")?;
+        let dimension = def_attr::<String>(node, "dimension", "none")?;
+        let value = req_attr::<String>(node, "value")?;
+        let description = opt_attr::<String>(node, "description")?;
+        Ok(Constant {
             name,
             dimension,
             value,
             description,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "name", &self.name);
+        write_attr(out, "dimension", &self.dimension);
+        write_attr(out, "value", &self.value);
+        if let Some(x) = &self.description { write_attr(out, "description", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -1270,13 +1912,20 @@ pub struct EventRecord {
 }
 
 impl XML for EventRecord {
-    fn from_node(node: &Node) -> Self {
-        let quantity = node.attribute("quantity").map(|s| s.to_string()).unwrap();
-        let eventPort = node.attribute("eventPort").map(|s| s.to_string()).unwrap();
-        EventRecord {
+    fn from_node(node: &Node) -> Result<Self> {
+        let quantity = req_attr::<String>(node, "quantity")?;
+        let eventPort = req_attr::<String>(node, "eventPort")?;
+        Ok(EventRecord {
             quantity,
             eventPort,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "quantity", &self.quantity);
+        write_attr(out, "eventPort", &self.eventPort);
+        out.push_str("/>\n");
     }
 }
 
@@ -1287,6 +1936,17 @@ pub enum RegimeBody {
     OnCondition(OnCondition),
 }
 
+#[allow(dead_code)]
+impl RegimeBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            RegimeBody::TimeDerivative(x) => x.to_xml("TimeDerivative", depth, out),
+            RegimeBody::OnEntry(x) => x.to_xml("OnEntry", depth, out),
+            RegimeBody::OnCondition(x) => x.to_xml("OnCondition", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Regime {
     pub name: String,
@@ -1295,25 +1955,40 @@ pub struct Regime {
 }
 
 impl XML for Regime {
-    fn from_node(node: &Node) -> Self {
-        let name = node.attribute("name").map(|s| s.to_string()).unwrap();
-        let initial = node.attribute("initial").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let name = req_attr::<String>(node, "name")?;
+        let initial = opt_attr::<String>(node, "initial")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "TimeDerivative" => body.push(RegimeBody::TimeDerivative(TimeDerivative::from_node(&child))),
-                "OnEntry" => body.push(RegimeBody::OnEntry(OnEntry::from_node(&child))),
-                "OnCondition" => body.push(RegimeBody::OnCondition(OnCondition::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of Regime.", t)
+                "TimeDerivative" => body.push(RegimeBody::TimeDerivative(TimeDerivative::from_node(&child)?)),
+                "OnEntry" => body.push(RegimeBody::OnEntry(OnEntry::from_node(&child)?)),
+                "OnCondition" => body.push(RegimeBody::OnCondition(OnCondition::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "Regime")),
             };
         }
-        Regime {
+        Ok(Regime {
             name,
             initial,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "name", &self.name);
+        if let Some(x) = &self.initial { write_attr(out, "initial", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -1323,6 +1998,15 @@ pub enum ForEachBody {
     MultiInstantiate(MultiInstantiate),
 }
 
+#[allow(dead_code)]
+impl ForEachBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            ForEachBody::MultiInstantiate(x) => x.to_xml("MultiInstantiate", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ForEach {
     pub instances: String,
@@ -1331,23 +2015,38 @@ pub struct ForEach {
 }
 
 impl XML for ForEach {
-    fn from_node(node: &Node) -> Self {
-        let instances = node.attribute("instances").map(|s| s.to_string()).unwrap();
-        let r#as = node.attribute("as").map(|s| s.to_string()).unwrap();
+    fn from_node(node: &Node) -> Result<Self> {
+        let instances = req_attr::<String>(node, "instances")?;
+        let r#as = req_attr::<String>(node, "as")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "MultiInstantiate" => body.push(ForEachBody::MultiInstantiate(MultiInstantiate::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of ForEach.", t)
+                "MultiInstantiate" => body.push(ForEachBody::MultiInstantiate(MultiInstantiate::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "ForEach")),
             };
         }
-        ForEach {
+        Ok(ForEach {
             instances,
             r#as,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "instances", &self.instances);
+        write_attr(out, "as", &self.r#as);
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -1360,15 +2059,23 @@ pub struct Exposure {
 }
 
 impl XML for Exposure {
-    fn from_node(node: &Node) -> Self {
-        let name = node.attribute("name").map(|s| s.to_string()).unwrap();
-        let dimension = node.attribute("dimension").or(Some("none")).map(|s| s.to_string()).unwrap();
-        let description = node.attribute("description").map(|s| s.to_string());
-        Exposure {
+    fn from_node(node: &Node) -> Result<Self> {
+        let name = req_attr::<String>(node, "name")?;
+        let dimension = def_attr::<String>(node, "dimension", "none")?;
+        let description = opt_attr::<String>(node, "description")?;
+        Ok(Exposure {
             name,
             dimension,
             description,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "name", &self.name);
+        write_attr(out, "dimension", &self.dimension);
+        if let Some(x) = &self.description { write_attr(out, "description", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -1381,17 +2088,26 @@ pub struct Run {
 }
 
 impl XML for Run {
-    fn from_node(node: &Node) -> Self {
-        let component = node.attribute("component").map(|s| s.to_string()).unwrap();
-        let variable = node.attribute("variable").map(|s| s.to_string()).unwrap();
-        let increment = node.attribute("increment").map(|s| s.to_string()).unwrap();
-        let total = node.attribute("total").map(|s| s.to_string()).unwrap();
-        Run {
+    fn from_node(node: &Node) -> Result<Self> {
+        let component = req_attr::<String>(node, "component")?;
+        let variable = req_attr::<String>(node, "variable")?;
+        let increment = req_attr::<String>(node, "increment")?;
+        let total = req_attr::<String>(node, "total")?;
+        Ok(Run {
             component,
             variable,
             increment,
             total,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "component", &self.component);
+        write_attr(out, "variable", &self.variable);
+        write_attr(out, "increment", &self.increment);
+        write_attr(out, "total", &self.total);
+        out.push_str("/>\n");
     }
 }
 
@@ -1401,10 +2117,16 @@ pub struct ComponentRequirement {
 }
 
 impl XML for ComponentRequirement {
-    fn from_node(node: &Node) -> Self {
-        let name = node.attribute("name").map(|s| s.to_string()).unwrap();
-        ComponentRequirement {
+    fn from_node(node: &Node) -> Result<Self> {
+        let name = req_attr::<String>(node, "name")?;
+        Ok(ComponentRequirement {
             name,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "name", &self.name);
+        out.push_str("/>\n");
     }
 }
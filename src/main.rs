@@ -5,14 +5,28 @@ use lems::file::LemsFile;
 
 mod acc;
 mod bundle;
+mod cell;
+mod check;
 mod error;
 mod expr;
+mod filter;
+#[cfg(test)]
+mod fixture;
+mod gates;
 mod instance;
+mod ir;
 mod lems;
+mod morphology;
 mod network;
 mod neuroml;
 mod nmodl;
+mod reference;
+mod resolve;
+mod simulation;
+mod swc;
+mod sweep;
 mod variable;
+mod vclamp;
 mod xml;
 
 use error::Result;
@@ -21,8 +35,9 @@ use error::Result;
 #[clap(name = "nmlcc")]
 #[clap(version = "0.2.0", author = "t.hater@fz-juelich.de")]
 struct Cli {
-    /// Path to NMLCoreTypes
-    #[clap(short, long, default_value = "ext/NeuroML2/NeuroML2CoreTypes")]
+    /// Path to NMLCoreTypes, searched before the built-in definitions; if
+    /// none were built in, defaults to ext/NeuroML2/NeuroML2CoreTypes
+    #[clap(short, long)]
     include_dir: Vec<String>,
     /// Toplevel CoreType definition file
     #[clap(short, long, default_value = "NeuroML2CoreTypes.xml")]
@@ -42,16 +57,44 @@ enum Cmd {
         #[clap(short, long)]
         r#type: Option<String>,
         /// Parameters to be retained/removed from NMODL; prefix with `-` to
-        /// remove or `+` to retain, `*` matches all suffices, cannot be given
-        /// as infix/prefix; eg --parameter='-*,+foo_*,-foo_bar_*' will retain
-        /// only those starting with `foo_`, unless followed by `bar_`. NOTE:
-        /// Must be given in order of specificity and follow our internal naming
-        /// scheme (sorry, but this is for fine-tuning).
+        /// remove or `+` to retain. Selectors match either the flattened name,
+        /// eg `gates_m_*`, or, if containing `/`, the NML2 path, eg
+        /// `gates[m]/*/rate`; `*` and `?` glob anywhere, `**` matches any
+        /// number of path segments and a leading `/` anchors the path at the
+        /// mechanism; regular expressions are not supported. The last
+        /// matching selector wins, eg
+        /// --parameter='-*,+foo_*,-foo_bar_*' will retain only those starting
+        /// with `foo_`, unless followed by `bar_`.
         #[clap(short, long, default_value = "+*")]
         parameter: String,
         /// Write mechanisms under this prefix
         #[clap(short, long, default_value = ".")]
         dir: String,
+        /// Print which parameters would be kept or inlined, and why, instead
+        /// of writing NMODL
+        #[clap(long)]
+        dry_run: bool,
+        /// Override a parameter before export, given as path=value; the path
+        /// is either an NML2 parameter path like those listed in the NMODL
+        /// header, eg `naChan/gates[m]/forwardRate/midpoint=-30mV`, or a
+        /// channel density's `condDensity` or `erev`, eg
+        /// `naChans/condDensity=100mS_per_cm2`; prefix the cell, eg
+        /// `hhcell/naChans/condDensity`, if several cells use that id
+        #[clap(long)]
+        set: Vec<sweep::Assignment>,
+    },
+    /// Dump the intermediate representation of a component as JSON: its
+    /// composed type, the instance tree, and the collapsed form before and
+    /// after resolving selects and simplification
+    Ir {
+        /// NeuroML2 compliant XML file
+        nml: Vec<String>,
+        /// Id of the component to dump
+        #[clap(long)]
+        id: String,
+        /// Parameters to be retained/removed when simplifying, see nmodl
+        #[clap(short, long, default_value = "+*")]
+        parameter: String,
     },
     /// Export to Arbor Cable Cell format (.acc)
     Acc {
@@ -60,33 +103,201 @@ enum Cmd {
         /// Cell id to extract, if not given will visit _all_ cells.
         #[clap(short, long)]
         cell: Option<String>,
+        /// Place threshold detectors from spikeThresh on this locset instead
+        /// of the proximal end of their segment group, eg '(location 0 0.5)'
+        #[clap(long)]
+        detector: Option<String>,
+        /// Write ouput under this prefix
+        #[clap(short, long, default_value = ".")]
+        dir: String,
+        /// Override a parameter before export, given as path=value; the path
+        /// is either an NML2 parameter path like those listed in the NMODL
+        /// header, eg `naChan/gates[m]/forwardRate/midpoint=-30mV`, or a
+        /// channel density's `condDensity` or `erev`, eg
+        /// `naChans/condDensity=100mS_per_cm2`; prefix the cell, eg
+        /// `hhcell/naChans/condDensity`, if several cells use that id
+        #[clap(long)]
+        set: Vec<sweep::Assignment>,
+        /// Sweep a parameter over a list of values, given as path=v0,v1,...
+        /// with paths as for --set; writes one variant per point of the grid
+        /// spanned by all sweeps to sweep/<n>, see sweep/index.csv
+        #[clap(long)]
+        sweep: Vec<sweep::Axis>,
+    },
+    /// Tabulate rates, steady states, and time constants of ion channel gates as CSV
+    Gates {
+        /// NeuroML2 compliant XML file
+        nml: Vec<String>,
+        /// Channel id to tabulate, if not given will visit _all_ channels.
+        #[clap(short, long)]
+        channel: Option<String>,
+        /// Membrane potential in mV, given as from:to:step
+        #[clap(short, long, default_value = "-100:50:1")]
+        voltage: gates::Range,
+        /// Internal calcium concentration in mM, either a single value or
+        /// from:to:step
+        #[clap(long, default_value = "5e-5")]
+        calcium: gates::Range,
+        /// Temperature in K, defaults to 6.3C
+        #[clap(short, long, default_value = "279.45")]
+        temperature: f64,
+        /// Write ouput under this prefix
+        #[clap(short, long, default_value = ".")]
+        dir: String,
+    },
+    /// Simulate ion channels in a single compartment under current clamp,
+    /// writing voltage and state traces to trace.csv
+    Simulate {
+        /// NeuroML2 compliant XML file
+        nml: Vec<String>,
+        /// Channel to insert, given as id or id=count; if not given, one of
+        /// _each_ channel is inserted.
+        #[clap(short, long)]
+        channel: Vec<String>,
+        /// Simplify channels like the NMODL export, using this parameter
+        /// filter (see nmodl); if not given, simulate the plain flattened form
+        #[clap(short, long)]
+        parameter: Option<String>,
+        /// Membrane capacitance in nF
+        #[clap(long, default_value = "0.01")]
+        capacitance: f64,
+        /// Current clamp given as delay:duration:amplitude in ms and nA
+        #[clap(long)]
+        clamp: Option<reference::Clamp>,
+        /// Fix a value seen by all channels, eg ena=50 or cai=5e-5
+        #[clap(short, long)]
+        bind: Vec<reference::Binding>,
+        /// Initial membrane potential in mV
+        #[clap(long, default_value = "-65")]
+        v0: f64,
+        /// Simulation time in ms
+        #[clap(short, long, default_value = "100")]
+        t_final: f64,
+        /// Time step in ms
+        #[clap(long, default_value = "0.025")]
+        dt: f64,
+        /// Integration method, one of euler, cnexp, rk4
+        #[clap(short, long, default_value = "cnexp")]
+        method: reference::Method,
+        /// Write ouput under this prefix
+        #[clap(short, long, default_value = ".")]
+        dir: String,
+    },
+    /// Run voltage clamp steps on ion channels, writing I-V curves,
+    /// activation/inactivation fits, and current traces as CSV
+    Vclamp {
+        /// NeuroML2 compliant XML file
+        nml: Vec<String>,
+        /// Channel id to clamp, if not given will visit _all_ channels.
+        #[clap(short, long)]
+        channel: Option<String>,
+        /// Holding potential in mV
+        #[clap(long, default_value = "-80")]
+        holding: f64,
+        /// Time at the holding potential before each step in ms
+        #[clap(long, default_value = "10")]
+        hold: f64,
+        /// Step potentials in mV, given as from:to:step
+        #[clap(short, long, default_value = "-100:50:10")]
+        steps: gates::Range,
+        /// Duration of each step in ms
+        #[clap(long, default_value = "50")]
+        duration: f64,
+        /// Test potential in mV following each step, measures inactivation
+        #[clap(long, default_value = "0")]
+        test: f64,
+        /// Duration of the test pulse in ms
+        #[clap(long, default_value = "20")]
+        test_duration: f64,
+        /// Simplify channels like the NMODL export, using this parameter
+        /// filter (see nmodl); if not given, simulate the plain flattened form
+        #[clap(short, long)]
+        parameter: Option<String>,
+        /// Fix a value seen by the channel, eg ena=50 or cai=5e-5
+        #[clap(short, long)]
+        bind: Vec<reference::Binding>,
+        /// Time step in ms
+        #[clap(long, default_value = "0.01")]
+        dt: f64,
+        /// Integration method, one of euler, cnexp, rk4
+        #[clap(short, long, default_value = "cnexp")]
+        method: reference::Method,
+        /// Write ouput under this prefix
+        #[clap(short, long, default_value = ".")]
+        dir: String,
+    },
+    /// Check references, ids, and component types across a whole model
+    Check {
+        /// NeuroML2 compliant XML file
+        #[clap(required = true)]
+        nml: Vec<String>,
+    },
+    /// Check morphologies and print statistics per segment group
+    Morph {
+        /// NeuroML2 compliant XML file
+        nml: Vec<String>,
+        /// Cell id to check, if not given will visit _all_ cells.
+        #[clap(short, long)]
+        cell: Option<String>,
+    },
+    /// Convert morphologies between NML2 and SWC
+    Swc {
+        /// SWC files are converted to NML2 morphologies, NML2 files have the
+        /// morphologies of their cells written as SWC
+        #[clap(required = true)]
+        files: Vec<String>,
+        /// Cell id to convert, if not given will visit _all_ cells.
+        #[clap(short, long)]
+        cell: Option<String>,
         /// Write ouput under this prefix
         #[clap(short, long, default_value = ".")]
         dir: String,
     },
     /// DWIM creation of an Arbor simulation template
     Bundle {
-        /// NeuroML2 compliant XML file
+        /// NeuroML2 compliant XML file or LEMS simulation file (LEMS_*.xml)
+        #[clap(required = true)]
         nml: Vec<String>,
         /// Try to combine channels per segment group
         #[clap(short, long)]
         super_mechanisms: bool,
+        /// Place threshold detectors from spikeThresh on this locset instead
+        /// of the proximal end of their segment group, eg '(location 0 0.5)'
+        #[clap(long)]
+        detector: Option<String>,
+        /// Also write morphologies as SWC to mrf/<id>.swc
+        #[clap(long)]
+        swc: bool,
+        /// Override a parameter before export, given as path=value; the path
+        /// is either an NML2 parameter path like those listed in the NMODL
+        /// header, eg `naChan/gates[m]/forwardRate/midpoint=-30mV`, or a
+        /// channel density's `condDensity` or `erev`, eg
+        /// `naChans/condDensity=100mS_per_cm2`; prefix the cell, eg
+        /// `hhcell/naChans/condDensity`, if several cells use that id
+        #[clap(long)]
+        set: Vec<sweep::Assignment>,
+        /// Sweep a parameter over a list of values, given as path=v0,v1,...
+        /// with paths as for --set; writes one variant per point of the grid
+        /// spanned by all sweeps to sweep/<n>, see sweep/index.csv
+        #[clap(long)]
+        sweep: Vec<sweep::Axis>,
         /// Prefix to put bundle
         dir: String,
     },
 }
 
 fn get_runtime_types(lems: &mut LemsFile, nml: &[String]) -> Result<()> {
-    neuroml::process_files(nml, |_, node| {
+    let paths = lems.include_dir.clone();
+    neuroml::process_files(&paths, nml, |_, node| {
         if node.tag_name().name() == "ComponentType" {
-            let ct: lems::raw::ComponentType = xml::XML::from_node(node);
+            let ct: lems::raw::ComponentType = xml::XML::from_node(node)?;
             lems.add_component_type(&ct)?;
         }
         Ok(())
     })
 }
 
-fn main() -> Result<()> {
+fn main() {
     let collector = tracing_subscriber::fmt()
         .with_max_level(tracing::Level::INFO)
         .with_target(false)
@@ -95,25 +306,197 @@ fn main() -> Result<()> {
     let _guard = tracing::subscriber::set_global_default(collector);
 
     let opts = Cli::parse();
-    let mut lems = lems::file::LemsFile::from(&opts.include_dir, &opts.core)?;
+    if let Err(e) = run(opts) {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+/// Search paths for core types. Without built-in core types, fall back to
+/// those of a bootstrapped working copy, or fail early instead of on the first
+/// missing include.
+fn include_dirs(include_dir: &[String]) -> Result<Vec<String>> {
+    if !include_dir.is_empty() || lems::has_builtin_core_types() {
+        return Ok(include_dir.to_vec());
+    }
+    if std::path::Path::new(lems::CORE_DIR).is_dir() {
+        return Ok(vec![lems::CORE_DIR.to_string()]);
+    }
+    Err(error::Error::Lems {
+        what: format!(
+            "No NML2 core types: nmlcc was built without {} and it is not found here; run bootstrap.sh and rebuild, or pass --include-dir",
+            lems::CORE_DIR
+        ),
+    })
+}
+
+fn run(opts: Cli) -> Result<()> {
+    let include_dir = include_dirs(&opts.include_dir)?;
+    let mut lems = lems::file::LemsFile::from(&include_dir, &opts.core)?;
     match opts.cmd {
         Cmd::Nmodl {
             nml,
             r#type,
             parameter,
             dir,
+            dry_run,
+            set,
+        } => {
+            get_runtime_types(&mut lems, &nml)?;
+            let set = sweep::Overrides::new(&lems, &nml, &set)?;
+            if dry_run {
+                nmodl::dry_run(&lems, &nml, &r#type.as_deref(), &parameter, &set)?;
+            } else {
+                nmodl::export(&lems, &nml, &r#type.as_deref(), &parameter, &dir, &set)?;
+            }
+        }
+        Cmd::Ir { nml, id, parameter } => {
+            get_runtime_types(&mut lems, &nml)?;
+            ir::export(&lems, &nml, &id, &parameter)?;
+        }
+        Cmd::Acc {
+            nml,
+            cell,
+            detector,
+            dir,
+            set,
+            sweep,
+        } => {
+            get_runtime_types(&mut lems, &nml)?;
+            let set = sweep::Overrides::new(&lems, &nml, &set)?;
+            let sweep = sweep::Sweep::new(&lems, &nml, &sweep)?;
+            let (cell, detector) = (cell.as_deref(), detector.as_deref());
+            acc::export(&lems, &nml, &cell, &detector, &dir, &set)?;
+            sweep.visit(&set, &dir, |ovr, dir| {
+                acc::export(&lems, &nml, &cell, &detector, dir, ovr)
+            })?;
+        }
+        Cmd::Gates {
+            nml,
+            channel,
+            voltage,
+            calcium,
+            temperature,
+            dir,
+        } => {
+            get_runtime_types(&mut lems, &nml)?;
+            gates::export(
+                &lems,
+                &nml,
+                &channel.as_deref(),
+                &voltage,
+                &calcium,
+                temperature,
+                &dir,
+            )?;
+        }
+        Cmd::Simulate {
+            nml,
+            channel,
+            parameter,
+            capacitance,
+            clamp,
+            bind,
+            v0,
+            t_final,
+            dt,
+            method,
+            dir,
+        } => {
+            get_runtime_types(&mut lems, &nml)?;
+            let mut cmp = reference::Compartment::new(capacitance);
+            cmp.clamp = clamp;
+            cmp.env.extend(bind.into_iter().map(|b| (b.name, b.value)));
+            let run = reference::Run {
+                t_final,
+                dt,
+                v0,
+                method,
+            };
+            reference::export(
+                &lems,
+                &nml,
+                &channel,
+                &parameter.as_deref(),
+                &cmp,
+                &run,
+                &dir,
+            )?;
+        }
+        Cmd::Vclamp {
+            nml,
+            channel,
+            holding,
+            hold,
+            steps,
+            duration,
+            test,
+            test_duration,
+            parameter,
+            bind,
+            dt,
+            method,
+            dir,
         } => {
             get_runtime_types(&mut lems, &nml)?;
-            nmodl::export(&lems, &nml, &r#type.as_deref(), &parameter, &dir)?;
+            let mut env = reference::Compartment::new(0.0).env;
+            env.extend(bind.into_iter().map(|b| (b.name, b.value)));
+            let protocol = vclamp::Protocol {
+                holding,
+                hold,
+                steps,
+                duration,
+                test,
+                test_duration,
+                dt,
+                method,
+            };
+            vclamp::export(
+                &lems,
+                &nml,
+                &channel.as_deref(),
+                &parameter.as_deref(),
+                &env,
+                &protocol,
+                &dir,
+            )?;
+        }
+        Cmd::Check { nml } => {
+            get_runtime_types(&mut lems, &nml)?;
+            check::report(&lems, &nml)?;
+        }
+        Cmd::Morph { nml, cell } => morphology::report(&lems.include_dir, &nml, &cell.as_deref())?,
+        Cmd::Swc { files, cell, dir } => {
+            let (swcs, nml): (Vec<_>, Vec<_>) = files.into_iter().partition(|f| swc::is_swc(f));
+            swc::import(&swcs, &dir)?;
+            if !nml.is_empty() {
+                swc::export(&lems.include_dir, &nml, &cell.as_deref(), &dir)?;
+            }
         }
-        Cmd::Acc { nml, cell, dir } => acc::export(&lems, &nml, &cell.as_deref(), &dir)?,
         Cmd::Bundle {
             nml,
             dir,
             super_mechanisms,
+            detector,
+            swc,
+            set,
+            sweep,
         } => {
             get_runtime_types(&mut lems, &nml)?;
-            bundle::export(&lems, &nml, &dir, super_mechanisms)?;
+            let set = sweep::Overrides::new(&lems, &nml, &set)?;
+            let sweep = sweep::Sweep::new(&lems, &nml, &sweep)?;
+            bundle::export(
+                &lems,
+                &nml,
+                &dir,
+                super_mechanisms,
+                &detector.as_deref(),
+                &set,
+                &sweep,
+            )?;
+            if swc {
+                swc::export(&lems.include_dir, &nml, &None, &format!("{}/mrf", dir))?;
+            }
         }
     }
     Ok(())
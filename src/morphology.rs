@@ -0,0 +1,786 @@
+use std::collections::HashMap as Map;
+use std::collections::HashSet as Set;
+
+use roxmltree::Node;
+use tracing::warn;
+
+use crate::{
+    acc::Sexp,
+    error::{Error, Result},
+    neuroml::raw::{
+        Morphology as RawMorphology, MorphologyBody, PathBody, Point3DWithDiam, Segment,
+        SegmentBody, SegmentGroupBody, SegmentParent, SubTreeBody,
+    },
+    xml::XML,
+};
+
+fn nml2_error<T: Into<String>>(what: T) -> Error {
+    Error::Nml { what: what.into() }
+}
+
+/// Point and radius, all in `um`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub r: f64,
+}
+
+impl Point {
+    pub fn distance(&self, other: &Point) -> f64 {
+        ((self.x - other.x).powi(2) + (self.y - other.y).powi(2) + (self.z - other.z).powi(2))
+            .sqrt()
+    }
+
+    pub fn lerp(&self, other: &Point, f: f64) -> Point {
+        Point {
+            x: self.x + f * (other.x - self.x),
+            y: self.y + f * (other.y - self.y),
+            z: self.z + f * (other.z - self.z),
+            r: self.r + f * (other.r - self.r),
+        }
+    }
+}
+
+impl From<&Point3DWithDiam> for Point {
+    fn from(p: &Point3DWithDiam) -> Self {
+        Point {
+            x: p.x,
+            y: p.y,
+            z: p.z,
+            r: p.diameter / 2.0,
+        }
+    }
+}
+
+impl Sexp for Point {
+    fn to_sexp(&self) -> String {
+        format!("(point {} {} {} {})", self.x, self.y, self.z, self.r)
+    }
+}
+
+/// Segment tag derived from the standard NML2 groups, uses the SWC convention.
+fn tag_of(group: &str) -> Option<i64> {
+    match group {
+        "soma_group" => Some(1),
+        "axon_group" => Some(2),
+        "dendrite_group" => Some(3),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Seg {
+    /// NML2 id
+    pub id: i64,
+    pub name: Option<String>,
+    /// Index of parent in the tree
+    pub parent: Option<usize>,
+    pub proximal: Point,
+    pub distal: Point,
+    pub tag: i64,
+}
+
+/// One item in a segment group, see `SegmentGroupBody`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Item {
+    Member(usize),
+    Include(String),
+    /// Explicit set of segments computed from a `path` or `subTree`
+    Segments(Vec<usize>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Group {
+    pub id: String,
+    pub items: Vec<Item>,
+}
+
+/// Segment tree built from a NML2 `<morphology>`. Segments are ordered such
+/// that each Arbor branch, ie each unbranched run of segments, is contiguous
+/// and parents precede their children; a segment's index in `segments` is its
+/// Arbor id.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Morphology {
+    pub id: String,
+    pub segments: Vec<Seg>,
+    pub groups: Vec<Group>,
+}
+
+impl Morphology {
+    /// Build from a `<morphology>` element, warning about suspicious bits
+    pub fn from_node(node: &Node) -> Result<Self> {
+        let raw: RawMorphology = XML::from_node(node)?;
+        for Issue { what, .. } in check(&raw).iter().filter(|i| !i.error) {
+            warn!("Morphology {}: {}", raw.id, what);
+        }
+        Morphology::new(&raw).map_err(|e| e.at(node))
+    }
+
+    pub fn new(raw: &RawMorphology) -> Result<Self> {
+        let mut segs = Vec::new();
+        let mut groups = Vec::new();
+        for item in &raw.body {
+            match item {
+                MorphologyBody::segment(s) => segs.push(s),
+                MorphologyBody::segmentGroup(g) => groups.push(g),
+                _ => {}
+            }
+        }
+
+        // Map NML2 id to position in the input and collect children
+        let mut index = Map::new();
+        for (ix, seg) in segs.iter().enumerate() {
+            if index.insert(seg.id, ix).is_some() {
+                return Err(nml2_error(format!(
+                    "Duplicate segment id {} in morphology {}",
+                    seg.id, raw.id
+                )));
+            }
+        }
+        let mut roots = Vec::new();
+        let mut children: Vec<Vec<usize>> = vec![Vec::new(); segs.len()];
+        for (ix, seg) in segs.iter().enumerate() {
+            if let Some(p) = parent_of(seg) {
+                let p = index.get(&p.segment).ok_or_else(|| {
+                    nml2_error(format!(
+                        "Segment {} has unknown parent {}",
+                        seg.id, p.segment
+                    ))
+                })?;
+                children[*p].push(ix);
+            } else {
+                roots.push(ix);
+            }
+        }
+        if roots.is_empty() && !segs.is_empty() {
+            return Err(nml2_error(format!(
+                "Morphology {} has no root segment",
+                raw.id
+            )));
+        }
+
+        // Lay out branches depth first; a segment starts a new branch if it is
+        // a root or has siblings.
+        let mut order = Vec::new();
+        let mut todo = roots.iter().rev().copied().collect::<Vec<_>>();
+        while let Some(mut ix) = todo.pop() {
+            loop {
+                order.push(ix);
+                match children[ix].as_slice() {
+                    [c] => ix = *c,
+                    cs => {
+                        todo.extend(cs.iter().rev());
+                        break;
+                    }
+                }
+            }
+        }
+        if order.len() != segs.len() {
+            return Err(nml2_error(format!(
+                "Morphology {} contains a cycle",
+                raw.id
+            )));
+        }
+        let arbor = order
+            .iter()
+            .enumerate()
+            .map(|(a, n)| (*n, a))
+            .collect::<Map<_, _>>();
+
+        let mut segments: Vec<Seg> = Vec::new();
+        for ix in &order {
+            let seg = segs[*ix];
+            let distal = distal(seg)
+                .ok_or_else(|| nml2_error(format!("Segment {} has no distal point", seg.id)))?;
+            let proximal = proximal(seg);
+            let (parent, proximal) = if let Some(p) = parent_of(seg) {
+                let parent = arbor[&index[&p.segment]];
+                let f = p.fractionAlong;
+                let prox = proximal.unwrap_or_else(|| {
+                    let p = &segments[parent];
+                    p.proximal.lerp(&p.distal, f)
+                });
+                (Some(parent), prox)
+            } else {
+                let prox = proximal.ok_or_else(|| {
+                    nml2_error(format!("Root segment {} has no proximal point", seg.id))
+                })?;
+                (None, prox)
+            };
+            segments.push(Seg {
+                id: seg.id,
+                name: seg.name.clone(),
+                parent,
+                proximal,
+                distal,
+                tag: 0,
+            });
+        }
+
+        // Spherical somata are stored as a single point; like Arbor's own NML2
+        // reader turn them into a cylinder of the same surface.
+        if let [root, ..] = segments.as_mut_slice() {
+            if root.proximal == root.distal {
+                root.proximal.y -= root.proximal.r;
+                root.distal.y += root.distal.r;
+            }
+        }
+
+        let ids = index
+            .keys()
+            .map(|id| (*id, arbor[&index[id]]))
+            .collect::<Map<_, _>>();
+        let seg = |id: i64, g: &str| -> Result<usize> {
+            ids.get(&id).copied().ok_or_else(|| {
+                nml2_error(format!(
+                    "Segment group {} references unknown segment {}",
+                    g, id
+                ))
+            })
+        };
+
+        let mut result = Vec::new();
+        for group in &groups {
+            let mut items = Vec::new();
+            for item in &group.body {
+                match item {
+                    SegmentGroupBody::member(m) => {
+                        items.push(Item::Member(seg(m.segment, &group.id)?))
+                    }
+                    SegmentGroupBody::include(i) => {
+                        if !groups.iter().any(|g| g.id == i.segmentGroup) {
+                            return Err(nml2_error(format!(
+                                "Segment group {} includes unknown group {}",
+                                group.id, i.segmentGroup
+                            )));
+                        }
+                        items.push(Item::Include(i.segmentGroup.clone()))
+                    }
+                    SegmentGroupBody::path(p) => {
+                        let (mut from, mut to) = (None, None);
+                        for b in &p.body {
+                            match b {
+                                PathBody::from(f) => from = Some(seg(f.segment, &group.id)?),
+                                PathBody::to(t) => to = Some(seg(t.segment, &group.id)?),
+                            }
+                        }
+                        items.push(Item::Segments(path(&segments, from, to, &group.id)?));
+                    }
+                    SegmentGroupBody::subTree(s) => {
+                        let (mut from, mut to) = (None, None);
+                        for b in &s.body {
+                            match b {
+                                SubTreeBody::from(f) => from = Some(seg(f.segment, &group.id)?),
+                                SubTreeBody::to(t) => to = Some(seg(t.segment, &group.id)?),
+                            }
+                        }
+                        items.push(Item::Segments(path(&segments, from, to, &group.id)?));
+                    }
+                    _ => {}
+                }
+            }
+            result.push(Group {
+                id: group.id.clone(),
+                items,
+            });
+        }
+
+        let mut morph = Morphology {
+            id: raw.id.clone(),
+            segments,
+            groups: result,
+        };
+        for group in &morph.groups {
+            if let Some(tag) = tag_of(&group.id) {
+                for ix in morph.members(&group.id) {
+                    morph.segments[ix].tag = tag;
+                }
+            }
+        }
+        Ok(morph)
+    }
+
+    /// All segments in group `id`, including those of included groups.
+    pub fn members(&self, id: &str) -> Vec<usize> {
+        let mut result = Set::new();
+        let mut seen = Set::new();
+        let mut todo = vec![id];
+        while let Some(id) = todo.pop() {
+            if !seen.insert(id) {
+                continue;
+            }
+            for group in self.groups.iter().filter(|g| g.id == id) {
+                for item in &group.items {
+                    match item {
+                        Item::Member(ix) => {
+                            result.insert(*ix);
+                        }
+                        Item::Include(g) => todo.push(g),
+                        Item::Segments(ixs) => result.extend(ixs),
+                    }
+                }
+            }
+        }
+        let mut result = result.into_iter().collect::<Vec<_>>();
+        result.sort_unstable();
+        result
+    }
+
+    /// Length and lateral area of segment `ix`, treating it as a truncated
+    /// cone
+    pub fn measure(&self, ix: usize) -> (f64, f64) {
+        let Seg {
+            proximal: p,
+            distal: d,
+            ..
+        } = &self.segments[ix];
+        let l = p.distance(d);
+        let a = std::f64::consts::PI * (p.r + d.r) * (l.powi(2) + (p.r - d.r).powi(2)).sqrt();
+        (l, a)
+    }
+
+    /// Arbor branches as (parent branch, segments). A branch starts at each
+    /// root and each child of a fork.
+    pub fn branches(&self) -> Vec<(Option<usize>, Vec<usize>)> {
+        let mut children = vec![0; self.segments.len()];
+        for seg in &self.segments {
+            if let Some(p) = seg.parent {
+                children[p] += 1;
+            }
+        }
+        let mut result: Vec<(Option<usize>, Vec<usize>)> = Vec::new();
+        let mut branch_of: Vec<usize> = Vec::new();
+        for (ix, seg) in self.segments.iter().enumerate() {
+            match seg.parent {
+                Some(p) if children[p] == 1 => {
+                    let b = branch_of[p];
+                    result[b].1.push(ix);
+                    branch_of.push(b);
+                }
+                p => {
+                    branch_of.push(result.len());
+                    result.push((p.map(|p| branch_of[p]), vec![ix]));
+                }
+            }
+        }
+        result
+    }
+
+    /// Arbor label dictionary: one region per segment id and segment name,
+    /// and one per segment group.
+    pub fn labels(&self) -> Vec<(String, String)> {
+        let mut result = Vec::new();
+        let mut seen = Set::new();
+        for (ix, seg) in self.segments.iter().enumerate() {
+            result.push((seg.id.to_string(), format!("(segment {})", ix)));
+            seen.insert(seg.id.to_string());
+        }
+        for group in &self.groups {
+            if !seen.insert(group.id.clone()) {
+                warn!("Segment group {} shadows a segment id, skipping", group.id);
+                continue;
+            }
+            let mut items = Vec::new();
+            for item in &group.items {
+                match item {
+                    Item::Member(ix) => items.push(format!("(segment {})", ix)),
+                    Item::Include(g) => items.push(format!("(region \"{}\")", g)),
+                    Item::Segments(ixs) => {
+                        items.extend(ixs.iter().map(|ix| format!("(segment {})", ix)))
+                    }
+                }
+            }
+            result.push((group.id.clone(), join(&items)));
+        }
+        let mut names: Vec<(&str, Vec<String>)> = Vec::new();
+        for (ix, seg) in self.segments.iter().enumerate() {
+            if let Some(name) = &seg.name {
+                let item = format!("(segment {})", ix);
+                if let Some((_, items)) = names.iter_mut().find(|(n, _)| n == name) {
+                    items.push(item);
+                } else {
+                    names.push((name, vec![item]));
+                }
+            }
+        }
+        for (name, items) in names {
+            if !seen.insert(name.to_string()) {
+                warn!("Segment name {} clashes with another label, skipping", name);
+                continue;
+            }
+            result.push((name.to_string(), join(&items)));
+        }
+        if seen.insert(String::from("all")) {
+            result.push((String::from("all"), String::from("(all)")));
+        }
+        result
+    }
+}
+
+impl Sexp for Morphology {
+    fn to_sexp(&self) -> String {
+        let mut result = String::from("(morphology");
+        for (ix, (parent, segs)) in self.branches().iter().enumerate() {
+            let parent = parent.map(|p| p as i64).unwrap_or(-1);
+            result.push_str(&format!("\n      (branch {} {}", ix, parent));
+            for s in segs {
+                let seg = &self.segments[*s];
+                result.push_str(&format!(
+                    "\n        (segment {} {} {} {})",
+                    s,
+                    seg.proximal.to_sexp(),
+                    seg.distal.to_sexp(),
+                    seg.tag
+                ));
+            }
+            result.push(')');
+        }
+        result.push(')');
+        result
+    }
+}
+
+/// Combine regions, Arbor's `join` needs at least two arguments.
+fn join(items: &[String]) -> String {
+    match items {
+        [] => String::from("(region-nil)"),
+        [item] => item.clone(),
+        items => format!("(join {})", items.join(" ")),
+    }
+}
+
+fn proximal(seg: &Segment) -> Option<Point> {
+    seg.body.iter().find_map(|b| match b {
+        SegmentBody::proximal(p) => Some(Point::from(p)),
+        _ => None,
+    })
+}
+
+fn distal(seg: &Segment) -> Option<Point> {
+    seg.body.iter().find_map(|b| match b {
+        SegmentBody::distal(p) => Some(Point::from(p)),
+        _ => None,
+    })
+}
+
+/// Where `seg` starts: its proximal point or, lacking that, its attachment
+/// on the parent. Gives up after `depth` ancestors, eg on cycles.
+fn start_of(segs: &Map<i64, &Segment>, seg: &Segment, depth: usize) -> Option<Point> {
+    if let Some(p) = proximal(seg) {
+        return Some(p);
+    }
+    let p = parent_of(seg)?;
+    let parent = segs.get(&p.segment)?;
+    if depth == 0 {
+        return None;
+    }
+    Some(start_of(segs, parent, depth - 1)?.lerp(&distal(parent)?, p.fractionAlong))
+}
+
+fn parent_of(seg: &Segment) -> Option<&SegmentParent> {
+    seg.body.iter().find_map(|b| match b {
+        SegmentBody::parent(p) => Some(p),
+        _ => None,
+    })
+}
+
+/// Segments between `from` and `to`, both inclusive. Without `from` start at
+/// the root, without `to` take the full subtree below `from`.
+fn path(segs: &[Seg], from: Option<usize>, to: Option<usize>, group: &str) -> Result<Vec<usize>> {
+    match (from, to) {
+        (from, Some(to)) => {
+            let mut result = Vec::new();
+            let mut cur = Some(to);
+            while let Some(ix) = cur {
+                result.push(ix);
+                if Some(ix) == from {
+                    break;
+                }
+                cur = segs[ix].parent;
+            }
+            if let Some(from) = from {
+                if result.last() != Some(&from) {
+                    return Err(nml2_error(format!(
+                        "Path in segment group {} does not connect segments {} and {}",
+                        group, segs[from].id, segs[to].id
+                    )));
+                }
+            }
+            result.reverse();
+            Ok(result)
+        }
+        (Some(from), None) => {
+            // Children come after their parents, so a single sweep suffices.
+            let mut inside = vec![false; segs.len()];
+            inside[from] = true;
+            let mut result = vec![from];
+            for (ix, seg) in segs.iter().enumerate().skip(from + 1) {
+                if seg.parent.map(|p| inside[p]).unwrap_or(false) {
+                    inside[ix] = true;
+                    result.push(ix);
+                }
+            }
+            Ok(result)
+        }
+        (None, None) => Ok((0..segs.len()).collect()),
+    }
+}
+
+/// Problem found in a morphology, see `check`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Issue {
+    /// Errors make the morphology unusable, others are merely suspicious.
+    pub error: bool,
+    pub what: String,
+}
+
+impl Issue {
+    pub fn error(what: String) -> Self {
+        Issue { error: true, what }
+    }
+
+    pub fn warning(what: String) -> Self {
+        Issue { error: false, what }
+    }
+}
+
+/// Look for all problems in `raw` instead of stopping at the first one like
+/// `Morphology::new`: duplicate ids, missing parents and points, disconnected
+/// segments, zero-length segments, proximal points not matching the parent,
+/// and segment groups referencing unknown segments or groups.
+pub fn check(raw: &RawMorphology) -> Vec<Issue> {
+    let mut result = Vec::new();
+    let mut segs = Map::new();
+    let mut groups = Map::new();
+    for item in &raw.body {
+        let dup = match item {
+            MorphologyBody::segment(s) => segs
+                .insert(s.id, s)
+                .map(|_| Issue::error(format!("Duplicate segment id {}", s.id))),
+            MorphologyBody::segmentGroup(g) => groups
+                .insert(g.id.as_str(), g)
+                .map(|_| Issue::warning(format!("Duplicate segment group {}", g.id))),
+            _ => None,
+        };
+        result.extend(dup);
+    }
+    let mut ids = segs.keys().copied().collect::<Vec<_>>();
+    ids.sort_unstable();
+
+    let mut roots = Vec::new();
+    let mut children: Map<i64, Vec<i64>> = Map::new();
+    for id in &ids {
+        let seg = segs[id];
+        let dist = distal(seg);
+        if dist.is_none() {
+            result.push(Issue::error(format!("Segment {} has no distal point", id)));
+        }
+        let prox = proximal(seg);
+        if let Some(p) = parent_of(seg) {
+            if !(0.0..=1.0).contains(&p.fractionAlong) {
+                result.push(Issue::error(format!(
+                    "Segment {} has fractionAlong {} outside of [0, 1]",
+                    id, p.fractionAlong
+                )));
+            } else if p.fractionAlong != 1.0 {
+                result.push(Issue::warning(format!(
+                    "Segment {} attaches at fraction {} of its parent; Arbor connects to the distal end",
+                    id, p.fractionAlong
+                )));
+            }
+            // Without an explicit proximal point, the segment starts at its
+            // attachment on the parent
+            let mut start = prox;
+            if let Some(parent) = segs.get(&p.segment) {
+                children.entry(p.segment).or_default().push(*id);
+                if let (Some(pp), Some(pd)) = (start_of(&segs, parent, ids.len()), distal(parent)) {
+                    let at = pp.lerp(&pd, p.fractionAlong);
+                    if let Some(prox) = prox {
+                        let d = prox.distance(&at);
+                        if d > 1e-6 * (1.0 + at.r) {
+                            result.push(Issue::warning(format!(
+                                "Proximal point of segment {} is {}um away from its attachment on parent {}",
+                                id, d, p.segment
+                            )));
+                        }
+                    } else {
+                        start = Some(at);
+                    }
+                }
+            } else {
+                result.push(Issue::error(format!(
+                    "Segment {} has missing parent {}",
+                    id, p.segment
+                )));
+            }
+            if let (Some(p), Some(d)) = (start, dist) {
+                if p.distance(&d) == 0.0 {
+                    result.push(Issue::warning(format!("Segment {} has zero length", id)));
+                }
+            }
+        } else {
+            if prox.is_none() {
+                result.push(Issue::error(format!(
+                    "Root segment {} has no proximal point",
+                    id
+                )));
+            }
+            roots.push(*id);
+        }
+    }
+
+    // Everything not reachable from the first root is disconnected
+    let mut reached = Set::new();
+    let mut todo = roots.iter().take(1).copied().collect::<Vec<_>>();
+    while let Some(id) = todo.pop() {
+        if reached.insert(id) {
+            todo.extend(children.get(&id).into_iter().flatten());
+        }
+    }
+    if roots.is_empty() && !ids.is_empty() {
+        result.push(Issue::error(String::from("No root segment")));
+    }
+    let lost = ids
+        .iter()
+        .filter(|id| !reached.contains(id))
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>();
+    if !lost.is_empty() && !roots.is_empty() {
+        result.push(Issue::error(format!(
+            "Segments not connected to root {}: {}",
+            roots[0],
+            lost.join(", ")
+        )));
+    }
+
+    let mut gids = groups.keys().copied().collect::<Vec<_>>();
+    gids.sort_unstable();
+    for gid in gids {
+        let mut refs = Vec::new();
+        for item in &groups[gid].body {
+            match item {
+                SegmentGroupBody::member(m) => refs.push(m.segment),
+                SegmentGroupBody::include(i) if !groups.contains_key(i.segmentGroup.as_str()) => {
+                    result.push(Issue::error(format!(
+                        "Segment group {} includes unknown group {}",
+                        gid, i.segmentGroup
+                    )))
+                }
+                SegmentGroupBody::path(p) => refs.extend(p.body.iter().map(|b| match b {
+                    PathBody::from(x) | PathBody::to(x) => x.segment,
+                })),
+                SegmentGroupBody::subTree(t) => refs.extend(t.body.iter().map(|b| match b {
+                    SubTreeBody::from(x) | SubTreeBody::to(x) => x.segment,
+                })),
+                _ => {}
+            }
+        }
+        for r in refs {
+            if !segs.contains_key(&r) {
+                result.push(Issue::error(format!(
+                    "Segment group {} references unknown segment {}",
+                    gid, r
+                )));
+            }
+        }
+    }
+    result
+}
+
+/// Check the morphologies of all cells in `nml`, or just `cell`, and print
+/// problems and per group statistics.
+pub fn report(paths: &[String], nml: &[String], cell: &Option<&str>) -> Result<()> {
+    let mut errors = 0;
+    for (id, raw) in crate::cell::morphologies(paths, nml)? {
+        if let Some(cell) = cell {
+            if id != *cell {
+                continue;
+            }
+        }
+        let raw = if let Some(raw) = raw {
+            raw
+        } else {
+            println!("Cell {}: no morphology\n", id);
+            continue;
+        };
+        println!("Cell {}, morphology {}", id, raw.id);
+        let issues = check(&raw);
+        for Issue { error, what } in &issues {
+            println!("  {}: {}", if *error { "error" } else { "warning" }, what);
+        }
+        if issues.iter().any(|i| i.error) {
+            errors += issues.iter().filter(|i| i.error).count();
+            println!();
+            continue;
+        }
+
+        let mrf = Morphology::new(&raw)?;
+        // Cables with numberInternalDivisions spread these compartments
+        // evenly across their segments, all other segments count as one.
+        let mut compartments = vec![1.0; mrf.segments.len()];
+        for item in &raw.body {
+            if let MorphologyBody::segmentGroup(g) = item {
+                for it in &g.body {
+                    if let SegmentGroupBody::property(p) = it {
+                        if p.tag == "numberInternalDivisions" {
+                            let n = p.value.parse::<f64>().map_err(|_| {
+                                nml2_error(format!(
+                                    "Segment group {} has non-numeric numberInternalDivisions {}",
+                                    g.id, p.value
+                                ))
+                            })?;
+                            let members = mrf.members(&g.id);
+                            for m in &members {
+                                compartments[*m] = n / members.len() as f64;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        let mut rows = vec![(String::from("all"), (0..mrf.segments.len()).collect())];
+        rows.extend(
+            mrf.groups
+                .iter()
+                .filter(|g| g.id != "all")
+                .map(|g| (g.id.clone(), mrf.members(&g.id))),
+        );
+        let width = rows.iter().map(|r| r.0.len()).max().unwrap_or_default();
+        println!(
+            "  {:width$}  {:>8}  {:>12}  {:>12}  {:>12}",
+            "group",
+            "segments",
+            "compartments",
+            "length/um",
+            "area/um^2",
+            width = width
+        );
+        for (group, segs) in rows {
+            let (l, a) = segs.iter().fold((0.0, 0.0), |(l, a), ix: &usize| {
+                let (dl, da) = mrf.measure(*ix);
+                (l + dl, a + da)
+            });
+            let n = segs.iter().map(|ix| compartments[*ix]).sum::<f64>();
+            println!(
+                "  {:width$}  {:>8}  {:>12}  {:>12.3}  {:>12.3}",
+                group,
+                segs.len(),
+                (n * 100.0).round() / 100.0,
+                l,
+                a,
+                width = width
+            );
+        }
+        println!();
+    }
+    if errors > 0 {
+        Err(nml2_error(format!(
+            "Found {} error(s) in morphologies",
+            errors
+        )))
+    } else {
+        Ok(())
+    }
+}
@@ -1,8 +1,15 @@
 #![allow(dead_code, unused)]
 
+use std::collections::HashMap as Map;
 use tracing::trace;
 
-use crate::{error::Result, expr::Quantity, lems::file::LemsFile, neuroml::raw};
+use crate::{
+    error::{Error, Result},
+    expr::Quantity,
+    lems::file::LemsFile,
+    neuroml::{process_files, raw},
+    xml::XML,
+};
 
 pub struct Network {
     pub temp: f64,
@@ -10,8 +17,10 @@ pub struct Network {
 
 impl Network {
     pub fn new(lems: &LemsFile, net: &raw::Network) -> Result<Self> {
-        let t = net.temperature.as_deref().unwrap_or("0K");
-        let t = Quantity::parse(t)?;
+        let t = net.temperature.clone().unwrap_or(Quantity {
+            value: 0.0,
+            unit: Some(String::from("K")),
+        });
         let t = lems.normalise_quantity(&t)?;
 
         use raw::NetworkBody::*;
@@ -40,3 +49,188 @@ fn proj(p: &raw::Projection) -> Result<()> {
 fn stim(p: &raw::ExplicitInput) -> Result<()> {
     Ok(())
 }
+
+/// Stimulus on a single cell, from either `inputList` or `explicitInput`, or
+/// a connection of a `projection`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Input {
+    /// Unique label; `<inputList id>_<input id>` or `<input>_<n>` for the
+    /// n-th `explicitInput` of `input`, `<projection id>_<connection id>`
+    /// for connections
+    pub label: String,
+    /// Id of the input component, eg a `pulseGenerator`; for connections
+    /// that of the presynaptic cell, eg a `spikeArray`
+    pub source: String,
+    /// Synapse and delay of a connection, `None` for other inputs
+    pub synapse: Option<(String, Quantity)>,
+    /// Component id of the targetted cell, its population and instance index
+    pub cell: String,
+    pub population: String,
+    pub index: i64,
+    pub segment: i64,
+    pub fraction: f64,
+    /// Weight for event based inputs, from `inputW`
+    pub weight: f64,
+}
+
+impl Input {
+    /// Arbor locset for the input's position, relying on segment labels
+    pub fn locset(&self) -> String {
+        format!(
+            "(on-components {} (region \"{}\"))",
+            self.fraction, self.segment
+        )
+    }
+}
+
+/// Collect all inputs from networks found in `nml`, searching includes in
+/// `paths`
+pub fn inputs(paths: &[String], nml: &[String]) -> Result<Vec<Input>> {
+    let mut result = Vec::new();
+    process_files(paths, nml, |_, node| {
+        if node.tag_name().name() == "network" {
+            let net: raw::Network = XML::from_node(node)?;
+            result.append(&mut network_inputs(&net)?);
+        }
+        Ok(())
+    })?;
+    Ok(result)
+}
+
+fn network_inputs(net: &raw::Network) -> Result<Vec<Input>> {
+    use raw::NetworkBody::*;
+    let pops = net
+        .body
+        .iter()
+        .filter_map(|it| match it {
+            population(p) => Some((p.id.as_str(), p.component.as_str())),
+            _ => None,
+        })
+        .collect::<Map<_, _>>();
+    let cell = |pop: &str| -> Result<String> {
+        pops.get(pop).map(|c| c.to_string()).ok_or(Error::Nml {
+            what: format!("Unknown population {} in network {}", pop, net.id),
+        })
+    };
+
+    let mut result = Vec::new();
+    let mut count: Map<&str, usize> = Map::new();
+    for item in &net.body {
+        match item {
+            explicitInput(e) => {
+                let (pop, index) = cell_ref(&e.target)?;
+                let n = count.entry(&e.input).or_default();
+                result.push(Input {
+                    label: format!("{}_{}", e.input, n),
+                    source: e.input.clone(),
+                    synapse: None,
+                    cell: cell(&pop)?,
+                    population: pop,
+                    index,
+                    segment: 0,
+                    fraction: 0.5,
+                    weight: 1.0,
+                });
+                *n += 1;
+            }
+            inputList(l) => {
+                use raw::InputListBody::*;
+                for it in &l.body {
+                    let (id, target, segment, fraction, weight) = match it {
+                        input(i) => (i.id, &i.target, i.segmentId, i.fractionAlong, 1.0),
+                        inputW(i) => (i.id, &i.target, i.segmentId, i.fractionAlong, i.weight),
+                    };
+                    let (pop, index) = cell_ref(target)?;
+                    if pop != l.population {
+                        return Err(Error::Nml {
+                            what: format!(
+                                "Input {} of inputList {} targets {}, not population {}",
+                                id, l.id, target, l.population
+                            ),
+                        });
+                    }
+                    result.push(Input {
+                        label: format!("{}_{}", l.id, id),
+                        source: l.component.clone(),
+                        synapse: None,
+                        cell: cell(&pop)?,
+                        population: pop,
+                        index,
+                        segment: segment.unwrap_or(0),
+                        fraction: fraction.unwrap_or(0.5),
+                        weight,
+                    });
+                }
+            }
+            projection(p) => {
+                use raw::ProjectionBody::*;
+                let zero = Quantity {
+                    value: 0.0,
+                    unit: Some(String::from("ms")),
+                };
+                for it in &p.body {
+                    let (id, pre, post, segment, fraction, weight, delay) = match it {
+                        connection(c) => (
+                            c.id,
+                            &c.preCellId,
+                            &c.postCellId,
+                            c.postSegmentId,
+                            c.postFractionAlong,
+                            1.0,
+                            &zero,
+                        ),
+                        connectionWD(c) => (
+                            c.id,
+                            &c.preCellId,
+                            &c.postCellId,
+                            c.postSegmentId,
+                            c.postFractionAlong,
+                            c.weight,
+                            &c.delay,
+                        ),
+                    };
+                    let (pre, _) = cell_ref(pre)?;
+                    let (pop, index) = cell_ref(post)?;
+                    if pre != p.presynapticPopulation || pop != p.postsynapticPopulation {
+                        return Err(Error::Nml {
+                            what: format!(
+                                "Connection {} of projection {} does not connect {} to {}",
+                                id, p.id, p.presynapticPopulation, p.postsynapticPopulation
+                            ),
+                        });
+                    }
+                    result.push(Input {
+                        label: format!("{}_{}", p.id, id),
+                        source: cell(&pre)?,
+                        synapse: Some((p.synapse.clone(), delay.clone())),
+                        cell: cell(&pop)?,
+                        population: pop,
+                        index,
+                        segment,
+                        fraction,
+                        weight,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(result)
+}
+
+/// Split a cell reference like `pop[3]`, `../pop[3]`, or `../pop/3/cell`
+/// into population and index.
+pub fn cell_ref(target: &str) -> Result<(String, i64)> {
+    let err = || Error::Nml {
+        what: format!("Cannot parse cell reference {:?}", target),
+    };
+    let target = target.trim_start_matches("../");
+    let (pop, idx) = if let Some((pop, rest)) = target.split_once('[') {
+        (pop, rest.strip_suffix(']').ok_or_else(err)?)
+    } else {
+        let mut parts = target.split('/');
+        (parts.next().ok_or_else(err)?, parts.next().ok_or_else(err)?)
+    };
+    let idx = idx.parse::<i64>().map_err(|_| err())?;
+    Ok((pop.to_string(), idx))
+}
@@ -1,45 +1,144 @@
 use roxmltree::Node;
 use std::collections::HashSet;
-use std::path::PathBuf;
+use std::path::Path;
 use tracing::trace;
 
-use crate::error::{Error, Result};
+use crate::{
+    error::{Error, Result},
+    lems::is_core_type_file,
+    resolve::{Resolver, Trail},
+};
 
 pub mod raw;
 
-pub fn process_files<F>(nmls: &[String], mut f: F) -> Result<()>
+/// Visit all nodes of the NML2 files `nmls` and everything they include. Both
+/// `<include href>` and LEMS `<Include file>` are followed, the latter
+/// allowing to pull in `ComponentType` definitions and to start from a LEMS
+/// run file. Includes are resolved relative to the including file, then in
+/// the search `paths`, and finally via `NML2_PATH`; LEMS includes of core
+/// type files are skipped, as these have been loaded already, see
+/// `is_core_type_file`.
+pub fn process_files<F>(paths: &[String], nmls: &[String], mut f: F) -> Result<()>
 where
     F: FnMut(&str, &Node) -> Result<()>,
 {
+    let resolver = Resolver::new(paths);
     let mut todo = Vec::new();
     for nml in nmls {
-        todo.push(PathBuf::from(&nml).canonicalize()?);
+        let nml = resolver.resolve(nml, Some(Path::new(".")))?;
+        let trail = Trail::default().push(&nml)?;
+        todo.push((nml, trail));
     }
     let mut seen = HashSet::new();
-    while let Some(nml) = todo.pop() {
-        if seen.contains(&nml) {
+    while let Some((nml, trail)) = todo.pop() {
+        if !seen.insert(nml.clone()) {
             continue;
         }
         trace!("Reading NML2 file {:?}", nml);
-        seen.insert(nml.clone());
         let xml = std::fs::read_to_string(&nml)?;
-        let tree = roxmltree::Document::parse(&xml)?;
-        if tree.root_element().tag_name().name() != "neuroml" {
-            return Err(Error::Nml {
-                what: format!("Not a NeuroML2 file {:?}", nml),
-            });
-        }
+        let tree =
+            roxmltree::Document::parse(&xml).map_err(|e| Error::from(e).in_file(&nml, &xml))?;
+        let root = tree.root_element();
+        let href = match root.tag_name().name() {
+            "neuroml" => "href",
+            "Lems" => "file",
+            _ => {
+                return Err(Error::Nml {
+                    what: format!("Not a NeuroML2 or LEMS file {:?}", nml),
+                }
+                .at(&root)
+                .in_file(&nml, &xml))
+            }
+        };
+        let base = nml.parent().unwrap();
         for node in tree.descendants() {
-            f(nml.to_str().unwrap(), &node)?;
-            if node.tag_name().name() == "include" {
-                if let Some(fd) = node.attribute("href") {
-                    let mut nml = nml.parent().unwrap().to_path_buf();
-                    nml.push(fd);
-                    nml = nml.canonicalize()?;
-                    todo.push(nml);
+            f(nml.to_str().unwrap(), &node).map_err(|e| e.at(&node).in_file(&nml, &xml))?;
+            if node.tag_name().name().eq_ignore_ascii_case("include") {
+                if let Some(fd) = node.attribute(href) {
+                    let inc = resolver.resolve(fd, Some(base));
+                    if href == "file" && is_core_type_file(&resolver, fd, inc.as_deref().ok()) {
+                        trace!("Skipping core type include {}", fd);
+                        continue;
+                    }
+                    let inc = inc
+                        .and_then(|inc| Ok((inc.clone(), trail.push(&inc)?)))
+                        .map_err(|e| e.at(&node).in_file(&nml, &xml))?;
+                    todo.push(inc);
                 }
             }
         }
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixture::Scratch;
+
+    #[test]
+    fn test_include_search_path() {
+        let dir = Scratch::new("include-search-path");
+        let nml = [dir.write(
+            "model/cell.nml",
+            r#"<neuroml id="cell"><include href="chan.nml"/></neuroml>"#,
+        )];
+        dir.write(
+            "lib/chan.nml",
+            r#"<neuroml id="chan"><ionChannel id="na"/></neuroml>"#,
+        );
+        let lib = dir.path().join("lib");
+        let count = |paths: &[String]| {
+            let mut result = 0;
+            process_files(paths, &nml, |_, node| {
+                if node.has_tag_name("ionChannel") {
+                    result += 1;
+                }
+                Ok(())
+            })
+            .map(|_| result)
+        };
+        assert!(count(&[]).is_err());
+        assert_eq!(count(&[lib.to_str().unwrap().to_string()]).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_core_type_includes() {
+        let dir = Scratch::new("core-includes");
+        let core = dir.write(
+            "core/Cells.xml",
+            r#"<Lems><ComponentType name="coreCell"/></Lems>"#,
+        );
+        let core = std::path::Path::new(&core).parent().unwrap();
+        let run = [dir.write(
+            "model/LEMS_run.xml",
+            r#"<Lems><Include file="Cells.xml"/></Lems>"#,
+        )];
+        let local = r#"<Lems><ComponentType name="myCell"/></Lems>"#;
+        let types = |paths: &[String]| {
+            let mut result = Vec::new();
+            process_files(paths, &run, |_, node| {
+                if node.has_tag_name("ComponentType") {
+                    result.push(node.attribute("name").unwrap().to_string());
+                }
+                Ok(())
+            })
+            .map(|_| result)
+        };
+        let core = [core.to_str().unwrap().to_string()];
+        // A model's own Cells.xml is read, even if core types are searched elsewhere
+        dir.write("model/Cells.xml", local);
+        assert_eq!(types(&core).unwrap(), vec![String::from("myCell")]);
+        // The one core types are read from is not
+        std::fs::remove_file(dir.path().join("model/Cells.xml")).unwrap();
+        assert_eq!(types(&core).unwrap(), Vec::<String>::new());
+        // Neither are embedded ones, or copies thereof
+        assert_eq!(types(&[]).is_ok(), cfg!(core_types));
+        if cfg!(core_types) {
+            let cells =
+                std::fs::read_to_string(format!("{}/Cells.xml", crate::lems::CORE_DIR)).unwrap();
+            dir.write("model/Cells.xml", &cells);
+            assert_eq!(types(&[]).unwrap(), Vec::<String>::new());
+        }
+    }
+}
@@ -6,7 +6,260 @@
 
 use roxmltree::Node;
 
-use crate::xml::XML;
+use crate::error::Result;
+use crate::xml::{close_tag, def_attr, open_tag, opt_attr, req_attr, unexpected_tag, write_attr, XML};
+use crate::expr::Nml2Quantity;
+use crate::xml::{inner_xml, write_inner};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockTypes {
+    voltageConcDepBlockMechanism,
+}
+
+impl FromStr for BlockTypes {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "voltageConcDepBlockMechanism" => Ok(BlockTypes::voltageConcDepBlockMechanism),
+            _ => Err(format!("Expected one of voltageConcDepBlockMechanism, got {:?}", s)),
+        }
+    }
+}
+
+impl fmt::Display for BlockTypes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            BlockTypes::voltageConcDepBlockMechanism => "voltageConcDepBlockMechanism",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    Path_Length_from_root,
+}
+
+impl FromStr for Metric {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "Path Length from root" => Ok(Metric::Path_Length_from_root),
+            _ => Err(format!("Expected one of Path Length from root, got {:?}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Metric {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Metric::Path_Length_from_root => "Path Length from root",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlasticityTypes {
+    tsodyksMarkramDepMechanism,
+    tsodyksMarkramDepFacMechanism,
+}
+
+impl FromStr for PlasticityTypes {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "tsodyksMarkramDepMechanism" => Ok(PlasticityTypes::tsodyksMarkramDepMechanism),
+            "tsodyksMarkramDepFacMechanism" => Ok(PlasticityTypes::tsodyksMarkramDepFacMechanism),
+            _ => Err(format!("Expected one of tsodyksMarkramDepMechanism, tsodyksMarkramDepFacMechanism, got {:?}", s)),
+        }
+    }
+}
+
+impl fmt::Display for PlasticityTypes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            PlasticityTypes::tsodyksMarkramDepMechanism => "tsodyksMarkramDepMechanism",
+            PlasticityTypes::tsodyksMarkramDepFacMechanism => "tsodyksMarkramDepFacMechanism",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum allowedSpaces {
+    Euclidean_1D,
+    Euclidean_2D,
+    Euclidean_3D,
+    Grid_1D,
+    Grid_2D,
+    Grid_3D,
+}
+
+impl FromStr for allowedSpaces {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "Euclidean_1D" => Ok(allowedSpaces::Euclidean_1D),
+            "Euclidean_2D" => Ok(allowedSpaces::Euclidean_2D),
+            "Euclidean_3D" => Ok(allowedSpaces::Euclidean_3D),
+            "Grid_1D" => Ok(allowedSpaces::Grid_1D),
+            "Grid_2D" => Ok(allowedSpaces::Grid_2D),
+            "Grid_3D" => Ok(allowedSpaces::Grid_3D),
+            _ => Err(format!("Expected one of Euclidean_1D, Euclidean_2D, Euclidean_3D, Grid_1D, Grid_2D, Grid_3D, got {:?}", s)),
+        }
+    }
+}
+
+impl fmt::Display for allowedSpaces {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            allowedSpaces::Euclidean_1D => "Euclidean_1D",
+            allowedSpaces::Euclidean_2D => "Euclidean_2D",
+            allowedSpaces::Euclidean_3D => "Euclidean_3D",
+            allowedSpaces::Grid_1D => "Grid_1D",
+            allowedSpaces::Grid_2D => "Grid_2D",
+            allowedSpaces::Grid_3D => "Grid_3D",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum channelTypes {
+    ionChannelPassive,
+    ionChannelHH,
+}
+
+impl FromStr for channelTypes {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "ionChannelPassive" => Ok(channelTypes::ionChannelPassive),
+            "ionChannelHH" => Ok(channelTypes::ionChannelHH),
+            _ => Err(format!("Expected one of ionChannelPassive, ionChannelHH, got {:?}", s)),
+        }
+    }
+}
+
+impl fmt::Display for channelTypes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            channelTypes::ionChannelPassive => "ionChannelPassive",
+            channelTypes::ionChannelHH => "ionChannelHH",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum gateTypes {
+    gateHHrates,
+    gateHHratesTau,
+    gateHHtauInf,
+    gateHHratesInf,
+    gateHHratesTauInf,
+    gateHHInstantaneous,
+    gateKS,
+    gateFractional,
+}
+
+impl FromStr for gateTypes {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "gateHHrates" => Ok(gateTypes::gateHHrates),
+            "gateHHratesTau" => Ok(gateTypes::gateHHratesTau),
+            "gateHHtauInf" => Ok(gateTypes::gateHHtauInf),
+            "gateHHratesInf" => Ok(gateTypes::gateHHratesInf),
+            "gateHHratesTauInf" => Ok(gateTypes::gateHHratesTauInf),
+            "gateHHInstantaneous" => Ok(gateTypes::gateHHInstantaneous),
+            "gateKS" => Ok(gateTypes::gateKS),
+            "gateFractional" => Ok(gateTypes::gateFractional),
+            _ => Err(format!("Expected one of gateHHrates, gateHHratesTau, gateHHtauInf, gateHHratesInf, gateHHratesTauInf, gateHHInstantaneous, gateKS, gateFractional, got {:?}", s)),
+        }
+    }
+}
+
+impl fmt::Display for gateTypes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            gateTypes::gateHHrates => "gateHHrates",
+            gateTypes::gateHHratesTau => "gateHHratesTau",
+            gateTypes::gateHHtauInf => "gateHHtauInf",
+            gateTypes::gateHHratesInf => "gateHHratesInf",
+            gateTypes::gateHHratesTauInf => "gateHHratesTauInf",
+            gateTypes::gateHHInstantaneous => "gateHHInstantaneous",
+            gateTypes::gateKS => "gateKS",
+            gateTypes::gateFractional => "gateFractional",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum networkTypes {
+    network,
+    networkWithTemperature,
+}
+
+impl FromStr for networkTypes {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "network" => Ok(networkTypes::network),
+            "networkWithTemperature" => Ok(networkTypes::networkWithTemperature),
+            _ => Err(format!("Expected one of network, networkWithTemperature, got {:?}", s)),
+        }
+    }
+}
+
+impl fmt::Display for networkTypes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            networkTypes::network => "network",
+            networkTypes::networkWithTemperature => "networkWithTemperature",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum populationTypes {
+    population,
+    populationList,
+}
+
+impl FromStr for populationTypes {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "population" => Ok(populationTypes::population),
+            "populationList" => Ok(populationTypes::populationList),
+            _ => Err(format!("Expected one of population, populationList, got {:?}", s)),
+        }
+    }
+}
+
+impl fmt::Display for populationTypes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            populationTypes::population => "population",
+            populationTypes::populationList => "populationList",
+        };
+        write!(f, "{}", s)
+    }
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum MembranePropertiesBody {
@@ -24,36 +277,69 @@ pub enum MembranePropertiesBody {
     initMembPotential(InitMembPotential),
 }
 
+#[allow(dead_code)]
+impl MembranePropertiesBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            MembranePropertiesBody::channelPopulation(x) => x.to_xml("channelPopulation", depth, out),
+            MembranePropertiesBody::channelDensity(x) => x.to_xml("channelDensity", depth, out),
+            MembranePropertiesBody::channelDensityVShift(x) => x.to_xml("channelDensityVShift", depth, out),
+            MembranePropertiesBody::channelDensityNernst(x) => x.to_xml("channelDensityNernst", depth, out),
+            MembranePropertiesBody::channelDensityGHK(x) => x.to_xml("channelDensityGHK", depth, out),
+            MembranePropertiesBody::channelDensityGHK2(x) => x.to_xml("channelDensityGHK2", depth, out),
+            MembranePropertiesBody::channelDensityNonUniform(x) => x.to_xml("channelDensityNonUniform", depth, out),
+            MembranePropertiesBody::channelDensityNonUniformNernst(x) => x.to_xml("channelDensityNonUniformNernst", depth, out),
+            MembranePropertiesBody::channelDensityNonUniformGHK(x) => x.to_xml("channelDensityNonUniformGHK", depth, out),
+            MembranePropertiesBody::spikeThresh(x) => x.to_xml("spikeThresh", depth, out),
+            MembranePropertiesBody::specificCapacitance(x) => x.to_xml("specificCapacitance", depth, out),
+            MembranePropertiesBody::initMembPotential(x) => x.to_xml("initMembPotential", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct MembraneProperties {
     pub body: Vec<MembranePropertiesBody>
 }
 
 impl XML for MembraneProperties {
-    fn from_node(node: &Node) -> Self {
+    fn from_node(node: &Node) -> Result<Self> {
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "channelPopulation" => body.push(MembranePropertiesBody::channelPopulation(ChannelPopulation::from_node(&child))),
-                "channelDensity" => body.push(MembranePropertiesBody::channelDensity(ChannelDensity::from_node(&child))),
-                "channelDensityVShift" => body.push(MembranePropertiesBody::channelDensityVShift(ChannelDensityVShift::from_node(&child))),
-                "channelDensityNernst" => body.push(MembranePropertiesBody::channelDensityNernst(ChannelDensityNernst::from_node(&child))),
-                "channelDensityGHK" => body.push(MembranePropertiesBody::channelDensityGHK(ChannelDensityGHK::from_node(&child))),
-                "channelDensityGHK2" => body.push(MembranePropertiesBody::channelDensityGHK2(ChannelDensityGHK2::from_node(&child))),
-                "channelDensityNonUniform" => body.push(MembranePropertiesBody::channelDensityNonUniform(ChannelDensityNonUniform::from_node(&child))),
-                "channelDensityNonUniformNernst" => body.push(MembranePropertiesBody::channelDensityNonUniformNernst(ChannelDensityNonUniformNernst::from_node(&child))),
-                "channelDensityNonUniformGHK" => body.push(MembranePropertiesBody::channelDensityNonUniformGHK(ChannelDensityNonUniformGHK::from_node(&child))),
-                "spikeThresh" => body.push(MembranePropertiesBody::spikeThresh(SpikeThresh::from_node(&child))),
-                "specificCapacitance" => body.push(MembranePropertiesBody::specificCapacitance(SpecificCapacitance::from_node(&child))),
-                "initMembPotential" => body.push(MembranePropertiesBody::initMembPotential(InitMembPotential::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of MembraneProperties.", t)
+                "channelPopulation" => body.push(MembranePropertiesBody::channelPopulation(ChannelPopulation::from_node(&child)?)),
+                "channelDensity" => body.push(MembranePropertiesBody::channelDensity(ChannelDensity::from_node(&child)?)),
+                "channelDensityVShift" => body.push(MembranePropertiesBody::channelDensityVShift(ChannelDensityVShift::from_node(&child)?)),
+                "channelDensityNernst" => body.push(MembranePropertiesBody::channelDensityNernst(ChannelDensityNernst::from_node(&child)?)),
+                "channelDensityGHK" => body.push(MembranePropertiesBody::channelDensityGHK(ChannelDensityGHK::from_node(&child)?)),
+                "channelDensityGHK2" => body.push(MembranePropertiesBody::channelDensityGHK2(ChannelDensityGHK2::from_node(&child)?)),
+                "channelDensityNonUniform" => body.push(MembranePropertiesBody::channelDensityNonUniform(ChannelDensityNonUniform::from_node(&child)?)),
+                "channelDensityNonUniformNernst" => body.push(MembranePropertiesBody::channelDensityNonUniformNernst(ChannelDensityNonUniformNernst::from_node(&child)?)),
+                "channelDensityNonUniformGHK" => body.push(MembranePropertiesBody::channelDensityNonUniformGHK(ChannelDensityNonUniformGHK::from_node(&child)?)),
+                "spikeThresh" => body.push(MembranePropertiesBody::spikeThresh(SpikeThresh::from_node(&child)?)),
+                "specificCapacitance" => body.push(MembranePropertiesBody::specificCapacitance(SpecificCapacitance::from_node(&child)?)),
+                "initMembPotential" => body.push(MembranePropertiesBody::initMembPotential(InitMembPotential::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "MembraneProperties")),
             };
         }
-        MembraneProperties {
+        Ok(MembraneProperties {
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -64,26 +350,49 @@ pub enum PathBody {
     to(SegmentEndPoint),
 }
 
+#[allow(dead_code)]
+impl PathBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            PathBody::from(x) => x.to_xml("from", depth, out),
+            PathBody::to(x) => x.to_xml("to", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Path {
     pub body: Vec<PathBody>
 }
 
 impl XML for Path {
-    fn from_node(node: &Node) -> Self {
+    fn from_node(node: &Node) -> Result<Self> {
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "from" => body.push(PathBody::from(SegmentEndPoint::from_node(&child))),
-                "to" => body.push(PathBody::to(SegmentEndPoint::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of Path.", t)
+                "from" => body.push(PathBody::from(SegmentEndPoint::from_node(&child)?)),
+                "to" => body.push(PathBody::to(SegmentEndPoint::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "Path")),
             };
         }
-        Path {
+        Ok(Path {
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -94,11 +403,17 @@ pub struct Include {
 }
 
 impl XML for Include {
-    fn from_node(node: &Node) -> Self {
-        let segmentGroup = node.attribute("segmentGroup").map(|s| s.to_string()).unwrap();
-        Include {
+    fn from_node(node: &Node) -> Result<Self> {
+        let segmentGroup = req_attr::<String>(node, "segmentGroup")?;
+        Ok(Include {
             segmentGroup,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "segmentGroup", &self.segmentGroup);
+        out.push_str("/>\n");
     }
 }
 
@@ -109,11 +424,22 @@ pub enum PulseGeneratorBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl PulseGeneratorBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            PulseGeneratorBody::notes(x) => x.to_xml("notes", depth, out),
+            PulseGeneratorBody::property(x) => x.to_xml("property", depth, out),
+            PulseGeneratorBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct PulseGenerator {
-    pub delay: String,
-    pub duration: String,
-    pub amplitude: String,
+    pub delay: Nml2Quantity,
+    pub duration: Nml2Quantity,
+    pub amplitude: Nml2Quantity,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -121,26 +447,26 @@ pub struct PulseGenerator {
 }
 
 impl XML for PulseGenerator {
-    fn from_node(node: &Node) -> Self {
-        let delay = node.attribute("delay").map(|s| s.to_string()).unwrap();
-        let duration = node.attribute("duration").map(|s| s.to_string()).unwrap();
-        let amplitude = node.attribute("amplitude").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let delay = req_attr::<Nml2Quantity>(node, "delay")?;
+        let duration = req_attr::<Nml2Quantity>(node, "duration")?;
+        let amplitude = req_attr::<Nml2Quantity>(node, "amplitude")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(PulseGeneratorBody::notes(String::from_node(&child))),
-                "property" => body.push(PulseGeneratorBody::property(Property::from_node(&child))),
-                "annotation" => body.push(PulseGeneratorBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of PulseGenerator.", t)
+                "notes" => body.push(PulseGeneratorBody::notes(String::from_node(&child)?)),
+                "property" => body.push(PulseGeneratorBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(PulseGeneratorBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "PulseGenerator")),
             };
         }
-        PulseGenerator {
+        Ok(PulseGenerator {
             delay,
             duration,
             amplitude,
@@ -148,6 +474,25 @@ impl XML for PulseGenerator {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "delay", &self.delay);
+        write_attr(out, "duration", &self.duration);
+        write_attr(out, "amplitude", &self.amplitude);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -168,11 +513,31 @@ pub enum IonChannelHHBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl IonChannelHHBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            IonChannelHHBody::gate(x) => x.to_xml("gate", depth, out),
+            IonChannelHHBody::gateHHrates(x) => x.to_xml("gateHHrates", depth, out),
+            IonChannelHHBody::gateHHratesTau(x) => x.to_xml("gateHHratesTau", depth, out),
+            IonChannelHHBody::gateHHtauInf(x) => x.to_xml("gateHHtauInf", depth, out),
+            IonChannelHHBody::gateHHratesInf(x) => x.to_xml("gateHHratesInf", depth, out),
+            IonChannelHHBody::gateHHratesTauInf(x) => x.to_xml("gateHHratesTauInf", depth, out),
+            IonChannelHHBody::gateHHInstantaneous(x) => x.to_xml("gateHHInstantaneous", depth, out),
+            IonChannelHHBody::gateFractional(x) => x.to_xml("gateFractional", depth, out),
+            IonChannelHHBody::q10ConductanceScaling(x) => x.to_xml("q10ConductanceScaling", depth, out),
+            IonChannelHHBody::notes(x) => x.to_xml("notes", depth, out),
+            IonChannelHHBody::property(x) => x.to_xml("property", depth, out),
+            IonChannelHHBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct IonChannelHH {
     pub species: Option<String>,
-    pub r#type: Option<String>,
-    pub conductance: Option<String>,
+    pub r#type: Option<channelTypes>,
+    pub conductance: Option<Nml2Quantity>,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -180,35 +545,35 @@ pub struct IonChannelHH {
 }
 
 impl XML for IonChannelHH {
-    fn from_node(node: &Node) -> Self {
-        let species = node.attribute("species").map(|s| s.to_string());
-        let r#type = node.attribute("type").map(|s| s.to_string());
-        let conductance = node.attribute("conductance").map(|s| s.to_string());
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let species = opt_attr::<String>(node, "species")?;
+        let r#type = opt_attr::<channelTypes>(node, "type")?;
+        let conductance = opt_attr::<Nml2Quantity>(node, "conductance")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "gate" => body.push(IonChannelHHBody::gate(GateHHUndetermined::from_node(&child))),
-                "gateHHrates" => body.push(IonChannelHHBody::gateHHrates(GateHHRates::from_node(&child))),
-                "gateHHratesTau" => body.push(IonChannelHHBody::gateHHratesTau(GateHHRatesTau::from_node(&child))),
-                "gateHHtauInf" => body.push(IonChannelHHBody::gateHHtauInf(GateHHTauInf::from_node(&child))),
-                "gateHHratesInf" => body.push(IonChannelHHBody::gateHHratesInf(GateHHRatesInf::from_node(&child))),
-                "gateHHratesTauInf" => body.push(IonChannelHHBody::gateHHratesTauInf(GateHHRatesTauInf::from_node(&child))),
-                "gateHHInstantaneous" => body.push(IonChannelHHBody::gateHHInstantaneous(GateHHInstantaneous::from_node(&child))),
-                "gateFractional" => body.push(IonChannelHHBody::gateFractional(GateFractional::from_node(&child))),
-                "q10ConductanceScaling" => body.push(IonChannelHHBody::q10ConductanceScaling(Q10ConductanceScaling::from_node(&child))),
-                "notes" => body.push(IonChannelHHBody::notes(String::from_node(&child))),
-                "property" => body.push(IonChannelHHBody::property(Property::from_node(&child))),
-                "annotation" => body.push(IonChannelHHBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of IonChannelHH.", t)
+                "gate" => body.push(IonChannelHHBody::gate(GateHHUndetermined::from_node(&child)?)),
+                "gateHHrates" => body.push(IonChannelHHBody::gateHHrates(GateHHRates::from_node(&child)?)),
+                "gateHHratesTau" => body.push(IonChannelHHBody::gateHHratesTau(GateHHRatesTau::from_node(&child)?)),
+                "gateHHtauInf" => body.push(IonChannelHHBody::gateHHtauInf(GateHHTauInf::from_node(&child)?)),
+                "gateHHratesInf" => body.push(IonChannelHHBody::gateHHratesInf(GateHHRatesInf::from_node(&child)?)),
+                "gateHHratesTauInf" => body.push(IonChannelHHBody::gateHHratesTauInf(GateHHRatesTauInf::from_node(&child)?)),
+                "gateHHInstantaneous" => body.push(IonChannelHHBody::gateHHInstantaneous(GateHHInstantaneous::from_node(&child)?)),
+                "gateFractional" => body.push(IonChannelHHBody::gateFractional(GateFractional::from_node(&child)?)),
+                "q10ConductanceScaling" => body.push(IonChannelHHBody::q10ConductanceScaling(Q10ConductanceScaling::from_node(&child)?)),
+                "notes" => body.push(IonChannelHHBody::notes(String::from_node(&child)?)),
+                "property" => body.push(IonChannelHHBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(IonChannelHHBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "IonChannelHH")),
             };
         }
-        IonChannelHH {
+        Ok(IonChannelHH {
             species,
             r#type,
             conductance,
@@ -216,6 +581,25 @@ impl XML for IonChannelHH {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if let Some(x) = &self.species { write_attr(out, "species", x); }
+        if let Some(x) = &self.r#type { write_attr(out, "type", x); }
+        if let Some(x) = &self.conductance { write_attr(out, "conductance", x); }
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -225,6 +609,15 @@ pub enum ChannelDensityNonUniformGHKBody {
     variableParameter(VariableParameter),
 }
 
+#[allow(dead_code)]
+impl ChannelDensityNonUniformGHKBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            ChannelDensityNonUniformGHKBody::variableParameter(x) => x.to_xml("variableParameter", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ChannelDensityNonUniformGHK {
     pub ionChannel: String,
@@ -235,27 +628,44 @@ pub struct ChannelDensityNonUniformGHK {
 }
 
 impl XML for ChannelDensityNonUniformGHK {
-    fn from_node(node: &Node) -> Self {
-        let ionChannel = node.attribute("ionChannel").map(|s| s.to_string()).unwrap();
-        let ion = node.attribute("ion").map(|s| s.to_string()).unwrap();
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let ionChannel = req_attr::<String>(node, "ionChannel")?;
+        let ion = req_attr::<String>(node, "ion")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "variableParameter" => body.push(ChannelDensityNonUniformGHKBody::variableParameter(VariableParameter::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of ChannelDensityNonUniformGHK.", t)
+                "variableParameter" => body.push(ChannelDensityNonUniformGHKBody::variableParameter(VariableParameter::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "ChannelDensityNonUniformGHK")),
             };
         }
-        ChannelDensityNonUniformGHK {
+        Ok(ChannelDensityNonUniformGHK {
             ionChannel,
             ion,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "ionChannel", &self.ionChannel);
+        write_attr(out, "ion", &self.ion);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -267,13 +677,20 @@ pub struct Case {
 }
 
 impl XML for Case {
-    fn from_node(node: &Node) -> Self {
-        let condition = node.attribute("condition").map(|s| s.to_string());
-        let value = node.attribute("value").map(|s| s.to_string()).unwrap();
-        Case {
+    fn from_node(node: &Node) -> Result<Self> {
+        let condition = opt_attr::<String>(node, "condition")?;
+        let value = req_attr::<String>(node, "value")?;
+        Ok(Case {
             condition,
             value,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if let Some(x) = &self.condition { write_attr(out, "condition", x); }
+        write_attr(out, "value", &self.value);
+        out.push_str("/>\n");
     }
 }
 
@@ -292,18 +709,18 @@ pub struct ContinuousConnection {
 }
 
 impl XML for ContinuousConnection {
-    fn from_node(node: &Node) -> Self {
-        let preComponent = node.attribute("preComponent").map(|s| s.to_string()).unwrap();
-        let postComponent = node.attribute("postComponent").map(|s| s.to_string()).unwrap();
-        let preCell = node.attribute("preCell").map(|s| s.to_string()).unwrap();
-        let preSegment = node.attribute("preSegment").or(Some("0")).map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let preFractionAlong = node.attribute("preFractionAlong").or(Some("0.5")).map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let postCell = node.attribute("postCell").map(|s| s.to_string()).unwrap();
-        let postSegment = node.attribute("postSegment").or(Some("0")).map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let postFractionAlong = node.attribute("postFractionAlong").or(Some("0.5")).map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let id = node.attribute("id").map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
-        ContinuousConnection {
+    fn from_node(node: &Node) -> Result<Self> {
+        let preComponent = req_attr::<String>(node, "preComponent")?;
+        let postComponent = req_attr::<String>(node, "postComponent")?;
+        let preCell = req_attr::<String>(node, "preCell")?;
+        let preSegment = def_attr::<i64>(node, "preSegment", "0")?;
+        let preFractionAlong = def_attr::<f64>(node, "preFractionAlong", "0.5")?;
+        let postCell = req_attr::<String>(node, "postCell")?;
+        let postSegment = def_attr::<i64>(node, "postSegment", "0")?;
+        let postFractionAlong = def_attr::<f64>(node, "postFractionAlong", "0.5")?;
+        let id = req_attr::<i64>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
+        Ok(ContinuousConnection {
             preComponent,
             postComponent,
             preCell,
@@ -314,7 +731,22 @@ impl XML for ContinuousConnection {
             postFractionAlong,
             id,
             neuroLexId,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "preComponent", &self.preComponent);
+        write_attr(out, "postComponent", &self.postComponent);
+        write_attr(out, "preCell", &self.preCell);
+        write_attr(out, "preSegment", &self.preSegment);
+        write_attr(out, "preFractionAlong", &self.preFractionAlong);
+        write_attr(out, "postCell", &self.postCell);
+        write_attr(out, "postSegment", &self.postSegment);
+        write_attr(out, "postFractionAlong", &self.postFractionAlong);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -325,20 +757,27 @@ pub struct InhomogeneousValue {
 }
 
 impl XML for InhomogeneousValue {
-    fn from_node(node: &Node) -> Self {
-        let inhomogeneousParameter = node.attribute("inhomogeneousParameter").map(|s| s.to_string()).unwrap();
-        let value = node.attribute("value").map(|s| s.to_string()).unwrap();
-        InhomogeneousValue {
+    fn from_node(node: &Node) -> Result<Self> {
+        let inhomogeneousParameter = req_attr::<String>(node, "inhomogeneousParameter")?;
+        let value = req_attr::<String>(node, "value")?;
+        Ok(InhomogeneousValue {
             inhomogeneousParameter,
             value,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "inhomogeneousParameter", &self.inhomogeneousParameter);
+        write_attr(out, "value", &self.value);
+        out.push_str("/>\n");
     }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ChannelDensityGHK {
     pub ionChannel: String,
-    pub permeability: String,
+    pub permeability: Nml2Quantity,
     pub segmentGroup: String,
     pub segment: Option<String>,
     pub ion: String,
@@ -347,15 +786,15 @@ pub struct ChannelDensityGHK {
 }
 
 impl XML for ChannelDensityGHK {
-    fn from_node(node: &Node) -> Self {
-        let ionChannel = node.attribute("ionChannel").map(|s| s.to_string()).unwrap();
-        let permeability = node.attribute("permeability").map(|s| s.to_string()).unwrap();
-        let segmentGroup = node.attribute("segmentGroup").or(Some("all")).map(|s| s.to_string()).unwrap();
-        let segment = node.attribute("segment").map(|s| s.to_string());
-        let ion = node.attribute("ion").map(|s| s.to_string()).unwrap();
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
-        ChannelDensityGHK {
+    fn from_node(node: &Node) -> Result<Self> {
+        let ionChannel = req_attr::<String>(node, "ionChannel")?;
+        let permeability = req_attr::<Nml2Quantity>(node, "permeability")?;
+        let segmentGroup = def_attr::<String>(node, "segmentGroup", "all")?;
+        let segment = opt_attr::<String>(node, "segment")?;
+        let ion = req_attr::<String>(node, "ion")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
+        Ok(ChannelDensityGHK {
             ionChannel,
             permeability,
             segmentGroup,
@@ -363,7 +802,19 @@ impl XML for ChannelDensityGHK {
             ion,
             id,
             neuroLexId,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "ionChannel", &self.ionChannel);
+        write_attr(out, "permeability", &self.permeability);
+        write_attr(out, "segmentGroup", &self.segmentGroup);
+        if let Some(x) = &self.segment { write_attr(out, "segment", x); }
+        write_attr(out, "ion", &self.ion);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -372,27 +823,38 @@ pub struct Species {
     pub id: String,
     pub concentrationModel: String,
     pub ion: Option<String>,
-    pub initialConcentration: String,
-    pub initialExtConcentration: String,
+    pub initialConcentration: Nml2Quantity,
+    pub initialExtConcentration: Nml2Quantity,
     pub segmentGroup: String,
 }
 
 impl XML for Species {
-    fn from_node(node: &Node) -> Self {
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let concentrationModel = node.attribute("concentrationModel").map(|s| s.to_string()).unwrap();
-        let ion = node.attribute("ion").map(|s| s.to_string());
-        let initialConcentration = node.attribute("initialConcentration").map(|s| s.to_string()).unwrap();
-        let initialExtConcentration = node.attribute("initialExtConcentration").map(|s| s.to_string()).unwrap();
-        let segmentGroup = node.attribute("segmentGroup").or(Some("all")).map(|s| s.to_string()).unwrap();
-        Species {
+    fn from_node(node: &Node) -> Result<Self> {
+        let id = req_attr::<String>(node, "id")?;
+        let concentrationModel = req_attr::<String>(node, "concentrationModel")?;
+        let ion = opt_attr::<String>(node, "ion")?;
+        let initialConcentration = req_attr::<Nml2Quantity>(node, "initialConcentration")?;
+        let initialExtConcentration = req_attr::<Nml2Quantity>(node, "initialExtConcentration")?;
+        let segmentGroup = def_attr::<String>(node, "segmentGroup", "all")?;
+        Ok(Species {
             id,
             concentrationModel,
             ion,
             initialConcentration,
             initialExtConcentration,
             segmentGroup,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "id", &self.id);
+        write_attr(out, "concentrationModel", &self.concentrationModel);
+        if let Some(x) = &self.ion { write_attr(out, "ion", x); }
+        write_attr(out, "initialConcentration", &self.initialConcentration);
+        write_attr(out, "initialExtConcentration", &self.initialExtConcentration);
+        write_attr(out, "segmentGroup", &self.segmentGroup);
+        out.push_str("/>\n");
     }
 }
 
@@ -403,6 +865,17 @@ pub enum BasePynnSynapseBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl BasePynnSynapseBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            BasePynnSynapseBody::notes(x) => x.to_xml("notes", depth, out),
+            BasePynnSynapseBody::property(x) => x.to_xml("property", depth, out),
+            BasePynnSynapseBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct BasePynnSynapse {
     pub tau_syn: f64,
@@ -413,29 +886,46 @@ pub struct BasePynnSynapse {
 }
 
 impl XML for BasePynnSynapse {
-    fn from_node(node: &Node) -> Self {
-        let tau_syn = node.attribute("tau_syn").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let tau_syn = req_attr::<f64>(node, "tau_syn")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(BasePynnSynapseBody::notes(String::from_node(&child))),
-                "property" => body.push(BasePynnSynapseBody::property(Property::from_node(&child))),
-                "annotation" => body.push(BasePynnSynapseBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of BasePynnSynapse.", t)
+                "notes" => body.push(BasePynnSynapseBody::notes(String::from_node(&child)?)),
+                "property" => body.push(BasePynnSynapseBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(BasePynnSynapseBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "BasePynnSynapse")),
             };
         }
-        BasePynnSynapse {
+        Ok(BasePynnSynapse {
             tau_syn,
             metaid,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "tau_syn", &self.tau_syn);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -445,6 +935,15 @@ pub enum ChannelDensityNonUniformNernstBody {
     variableParameter(VariableParameter),
 }
 
+#[allow(dead_code)]
+impl ChannelDensityNonUniformNernstBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            ChannelDensityNonUniformNernstBody::variableParameter(x) => x.to_xml("variableParameter", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ChannelDensityNonUniformNernst {
     pub ionChannel: String,
@@ -455,27 +954,44 @@ pub struct ChannelDensityNonUniformNernst {
 }
 
 impl XML for ChannelDensityNonUniformNernst {
-    fn from_node(node: &Node) -> Self {
-        let ionChannel = node.attribute("ionChannel").map(|s| s.to_string()).unwrap();
-        let ion = node.attribute("ion").map(|s| s.to_string()).unwrap();
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let ionChannel = req_attr::<String>(node, "ionChannel")?;
+        let ion = req_attr::<String>(node, "ion")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "variableParameter" => body.push(ChannelDensityNonUniformNernstBody::variableParameter(VariableParameter::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of ChannelDensityNonUniformNernst.", t)
+                "variableParameter" => body.push(ChannelDensityNonUniformNernstBody::variableParameter(VariableParameter::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "ChannelDensityNonUniformNernst")),
             };
         }
-        ChannelDensityNonUniformNernst {
+        Ok(ChannelDensityNonUniformNernst {
             ionChannel,
             ion,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "ionChannel", &self.ionChannel);
+        write_attr(out, "ion", &self.ion);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -487,12 +1003,23 @@ pub enum FixedFactorConcentrationModelBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl FixedFactorConcentrationModelBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            FixedFactorConcentrationModelBody::notes(x) => x.to_xml("notes", depth, out),
+            FixedFactorConcentrationModelBody::property(x) => x.to_xml("property", depth, out),
+            FixedFactorConcentrationModelBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct FixedFactorConcentrationModel {
     pub ion: String,
-    pub restingConc: String,
-    pub decayConstant: String,
-    pub rho: String,
+    pub restingConc: Nml2Quantity,
+    pub decayConstant: Nml2Quantity,
+    pub rho: Nml2Quantity,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -500,27 +1027,27 @@ pub struct FixedFactorConcentrationModel {
 }
 
 impl XML for FixedFactorConcentrationModel {
-    fn from_node(node: &Node) -> Self {
-        let ion = node.attribute("ion").map(|s| s.to_string()).unwrap();
-        let restingConc = node.attribute("restingConc").map(|s| s.to_string()).unwrap();
-        let decayConstant = node.attribute("decayConstant").map(|s| s.to_string()).unwrap();
-        let rho = node.attribute("rho").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let ion = req_attr::<String>(node, "ion")?;
+        let restingConc = req_attr::<Nml2Quantity>(node, "restingConc")?;
+        let decayConstant = req_attr::<Nml2Quantity>(node, "decayConstant")?;
+        let rho = req_attr::<Nml2Quantity>(node, "rho")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(FixedFactorConcentrationModelBody::notes(String::from_node(&child))),
-                "property" => body.push(FixedFactorConcentrationModelBody::property(Property::from_node(&child))),
-                "annotation" => body.push(FixedFactorConcentrationModelBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of FixedFactorConcentrationModel.", t)
+                "notes" => body.push(FixedFactorConcentrationModelBody::notes(String::from_node(&child)?)),
+                "property" => body.push(FixedFactorConcentrationModelBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(FixedFactorConcentrationModelBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "FixedFactorConcentrationModel")),
             };
         }
-        FixedFactorConcentrationModel {
+        Ok(FixedFactorConcentrationModel {
             ion,
             restingConc,
             decayConstant,
@@ -529,6 +1056,26 @@ impl XML for FixedFactorConcentrationModel {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "ion", &self.ion);
+        write_attr(out, "restingConc", &self.restingConc);
+        write_attr(out, "decayConstant", &self.decayConstant);
+        write_attr(out, "rho", &self.rho);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -540,12 +1087,23 @@ pub enum DecayingPoolConcentrationModelBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl DecayingPoolConcentrationModelBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            DecayingPoolConcentrationModelBody::notes(x) => x.to_xml("notes", depth, out),
+            DecayingPoolConcentrationModelBody::property(x) => x.to_xml("property", depth, out),
+            DecayingPoolConcentrationModelBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct DecayingPoolConcentrationModel {
     pub ion: String,
-    pub restingConc: String,
-    pub decayConstant: String,
-    pub shellThickness: String,
+    pub restingConc: Nml2Quantity,
+    pub decayConstant: Nml2Quantity,
+    pub shellThickness: Nml2Quantity,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -553,27 +1111,27 @@ pub struct DecayingPoolConcentrationModel {
 }
 
 impl XML for DecayingPoolConcentrationModel {
-    fn from_node(node: &Node) -> Self {
-        let ion = node.attribute("ion").map(|s| s.to_string()).unwrap();
-        let restingConc = node.attribute("restingConc").map(|s| s.to_string()).unwrap();
-        let decayConstant = node.attribute("decayConstant").map(|s| s.to_string()).unwrap();
-        let shellThickness = node.attribute("shellThickness").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let ion = req_attr::<String>(node, "ion")?;
+        let restingConc = req_attr::<Nml2Quantity>(node, "restingConc")?;
+        let decayConstant = req_attr::<Nml2Quantity>(node, "decayConstant")?;
+        let shellThickness = req_attr::<Nml2Quantity>(node, "shellThickness")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(DecayingPoolConcentrationModelBody::notes(String::from_node(&child))),
-                "property" => body.push(DecayingPoolConcentrationModelBody::property(Property::from_node(&child))),
-                "annotation" => body.push(DecayingPoolConcentrationModelBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of DecayingPoolConcentrationModel.", t)
+                "notes" => body.push(DecayingPoolConcentrationModelBody::notes(String::from_node(&child)?)),
+                "property" => body.push(DecayingPoolConcentrationModelBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(DecayingPoolConcentrationModelBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "DecayingPoolConcentrationModel")),
             };
         }
-        DecayingPoolConcentrationModel {
+        Ok(DecayingPoolConcentrationModel {
             ion,
             restingConc,
             decayConstant,
@@ -582,6 +1140,26 @@ impl XML for DecayingPoolConcentrationModel {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "ion", &self.ion);
+        write_attr(out, "restingConc", &self.restingConc);
+        write_attr(out, "decayConstant", &self.decayConstant);
+        write_attr(out, "shellThickness", &self.shellThickness);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -593,6 +1171,17 @@ pub enum ExpCurrSynapseBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl ExpCurrSynapseBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            ExpCurrSynapseBody::notes(x) => x.to_xml("notes", depth, out),
+            ExpCurrSynapseBody::property(x) => x.to_xml("property", depth, out),
+            ExpCurrSynapseBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ExpCurrSynapse {
     pub tau_syn: f64,
@@ -603,29 +1192,46 @@ pub struct ExpCurrSynapse {
 }
 
 impl XML for ExpCurrSynapse {
-    fn from_node(node: &Node) -> Self {
-        let tau_syn = node.attribute("tau_syn").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let tau_syn = req_attr::<f64>(node, "tau_syn")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(ExpCurrSynapseBody::notes(String::from_node(&child))),
-                "property" => body.push(ExpCurrSynapseBody::property(Property::from_node(&child))),
-                "annotation" => body.push(ExpCurrSynapseBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of ExpCurrSynapse.", t)
+                "notes" => body.push(ExpCurrSynapseBody::notes(String::from_node(&child)?)),
+                "property" => body.push(ExpCurrSynapseBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(ExpCurrSynapseBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "ExpCurrSynapse")),
             };
         }
-        ExpCurrSynapse {
+        Ok(ExpCurrSynapse {
             tau_syn,
             metaid,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "tau_syn", &self.tau_syn);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -637,13 +1243,20 @@ pub struct BaseNonNegativeIntegerId {
 }
 
 impl XML for BaseNonNegativeIntegerId {
-    fn from_node(node: &Node) -> Self {
-        let id = node.attribute("id").map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
-        BaseNonNegativeIntegerId {
+    fn from_node(node: &Node) -> Result<Self> {
+        let id = req_attr::<i64>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
+        Ok(BaseNonNegativeIntegerId {
             id,
             neuroLexId,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -654,6 +1267,17 @@ pub enum basePyNNIaFCellBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl basePyNNIaFCellBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            basePyNNIaFCellBody::notes(x) => x.to_xml("notes", depth, out),
+            basePyNNIaFCellBody::property(x) => x.to_xml("property", depth, out),
+            basePyNNIaFCellBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct basePyNNIaFCell {
     pub tau_m: f64,
@@ -673,33 +1297,33 @@ pub struct basePyNNIaFCell {
 }
 
 impl XML for basePyNNIaFCell {
-    fn from_node(node: &Node) -> Self {
-        let tau_m = node.attribute("tau_m").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let tau_refrac = node.attribute("tau_refrac").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let v_reset = node.attribute("v_reset").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let v_rest = node.attribute("v_rest").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let v_thresh = node.attribute("v_thresh").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let cm = node.attribute("cm").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let i_offset = node.attribute("i_offset").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let tau_syn_E = node.attribute("tau_syn_E").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let tau_syn_I = node.attribute("tau_syn_I").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let v_init = node.attribute("v_init").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let tau_m = req_attr::<f64>(node, "tau_m")?;
+        let tau_refrac = req_attr::<f64>(node, "tau_refrac")?;
+        let v_reset = req_attr::<f64>(node, "v_reset")?;
+        let v_rest = req_attr::<f64>(node, "v_rest")?;
+        let v_thresh = req_attr::<f64>(node, "v_thresh")?;
+        let cm = req_attr::<f64>(node, "cm")?;
+        let i_offset = req_attr::<f64>(node, "i_offset")?;
+        let tau_syn_E = req_attr::<f64>(node, "tau_syn_E")?;
+        let tau_syn_I = req_attr::<f64>(node, "tau_syn_I")?;
+        let v_init = req_attr::<f64>(node, "v_init")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(basePyNNIaFCellBody::notes(String::from_node(&child))),
-                "property" => body.push(basePyNNIaFCellBody::property(Property::from_node(&child))),
-                "annotation" => body.push(basePyNNIaFCellBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of basePyNNIaFCell.", t)
+                "notes" => body.push(basePyNNIaFCellBody::notes(String::from_node(&child)?)),
+                "property" => body.push(basePyNNIaFCellBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(basePyNNIaFCellBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "basePyNNIaFCell")),
             };
         }
-        basePyNNIaFCell {
+        Ok(basePyNNIaFCell {
             tau_m,
             tau_refrac,
             v_reset,
@@ -714,6 +1338,32 @@ impl XML for basePyNNIaFCell {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "tau_m", &self.tau_m);
+        write_attr(out, "tau_refrac", &self.tau_refrac);
+        write_attr(out, "v_reset", &self.v_reset);
+        write_attr(out, "v_rest", &self.v_rest);
+        write_attr(out, "v_thresh", &self.v_thresh);
+        write_attr(out, "cm", &self.cm);
+        write_attr(out, "i_offset", &self.i_offset);
+        write_attr(out, "tau_syn_E", &self.tau_syn_E);
+        write_attr(out, "tau_syn_I", &self.tau_syn_I);
+        write_attr(out, "v_init", &self.v_init);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -725,12 +1375,23 @@ pub enum ExpTwoSynapseBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl ExpTwoSynapseBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            ExpTwoSynapseBody::notes(x) => x.to_xml("notes", depth, out),
+            ExpTwoSynapseBody::property(x) => x.to_xml("property", depth, out),
+            ExpTwoSynapseBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ExpTwoSynapse {
-    pub tauDecay: String,
-    pub tauRise: String,
-    pub gbase: String,
-    pub erev: String,
+    pub tauDecay: Nml2Quantity,
+    pub tauRise: Nml2Quantity,
+    pub gbase: Nml2Quantity,
+    pub erev: Nml2Quantity,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -738,27 +1399,27 @@ pub struct ExpTwoSynapse {
 }
 
 impl XML for ExpTwoSynapse {
-    fn from_node(node: &Node) -> Self {
-        let tauDecay = node.attribute("tauDecay").map(|s| s.to_string()).unwrap();
-        let tauRise = node.attribute("tauRise").map(|s| s.to_string()).unwrap();
-        let gbase = node.attribute("gbase").map(|s| s.to_string()).unwrap();
-        let erev = node.attribute("erev").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let tauDecay = req_attr::<Nml2Quantity>(node, "tauDecay")?;
+        let tauRise = req_attr::<Nml2Quantity>(node, "tauRise")?;
+        let gbase = req_attr::<Nml2Quantity>(node, "gbase")?;
+        let erev = req_attr::<Nml2Quantity>(node, "erev")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(ExpTwoSynapseBody::notes(String::from_node(&child))),
-                "property" => body.push(ExpTwoSynapseBody::property(Property::from_node(&child))),
-                "annotation" => body.push(ExpTwoSynapseBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of ExpTwoSynapse.", t)
+                "notes" => body.push(ExpTwoSynapseBody::notes(String::from_node(&child)?)),
+                "property" => body.push(ExpTwoSynapseBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(ExpTwoSynapseBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "ExpTwoSynapse")),
             };
         }
-        ExpTwoSynapse {
+        Ok(ExpTwoSynapse {
             tauDecay,
             tauRise,
             gbase,
@@ -767,6 +1428,26 @@ impl XML for ExpTwoSynapse {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "tauDecay", &self.tauDecay);
+        write_attr(out, "tauRise", &self.tauRise);
+        write_attr(out, "gbase", &self.gbase);
+        write_attr(out, "erev", &self.erev);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -778,6 +1459,17 @@ pub enum IF_cond_expBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl IF_cond_expBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            IF_cond_expBody::notes(x) => x.to_xml("notes", depth, out),
+            IF_cond_expBody::property(x) => x.to_xml("property", depth, out),
+            IF_cond_expBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct IF_cond_exp {
     pub e_rev_E: f64,
@@ -799,35 +1491,35 @@ pub struct IF_cond_exp {
 }
 
 impl XML for IF_cond_exp {
-    fn from_node(node: &Node) -> Self {
-        let e_rev_E = node.attribute("e_rev_E").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let e_rev_I = node.attribute("e_rev_I").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let tau_m = node.attribute("tau_m").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let tau_refrac = node.attribute("tau_refrac").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let v_reset = node.attribute("v_reset").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let v_rest = node.attribute("v_rest").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let v_thresh = node.attribute("v_thresh").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let cm = node.attribute("cm").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let i_offset = node.attribute("i_offset").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let tau_syn_E = node.attribute("tau_syn_E").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let tau_syn_I = node.attribute("tau_syn_I").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let v_init = node.attribute("v_init").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let e_rev_E = req_attr::<f64>(node, "e_rev_E")?;
+        let e_rev_I = req_attr::<f64>(node, "e_rev_I")?;
+        let tau_m = req_attr::<f64>(node, "tau_m")?;
+        let tau_refrac = req_attr::<f64>(node, "tau_refrac")?;
+        let v_reset = req_attr::<f64>(node, "v_reset")?;
+        let v_rest = req_attr::<f64>(node, "v_rest")?;
+        let v_thresh = req_attr::<f64>(node, "v_thresh")?;
+        let cm = req_attr::<f64>(node, "cm")?;
+        let i_offset = req_attr::<f64>(node, "i_offset")?;
+        let tau_syn_E = req_attr::<f64>(node, "tau_syn_E")?;
+        let tau_syn_I = req_attr::<f64>(node, "tau_syn_I")?;
+        let v_init = req_attr::<f64>(node, "v_init")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(IF_cond_expBody::notes(String::from_node(&child))),
-                "property" => body.push(IF_cond_expBody::property(Property::from_node(&child))),
-                "annotation" => body.push(IF_cond_expBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of IF_cond_exp.", t)
+                "notes" => body.push(IF_cond_expBody::notes(String::from_node(&child)?)),
+                "property" => body.push(IF_cond_expBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(IF_cond_expBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "IF_cond_exp")),
             };
         }
-        IF_cond_exp {
+        Ok(IF_cond_exp {
             e_rev_E,
             e_rev_I,
             tau_m,
@@ -844,6 +1536,34 @@ impl XML for IF_cond_exp {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "e_rev_E", &self.e_rev_E);
+        write_attr(out, "e_rev_I", &self.e_rev_I);
+        write_attr(out, "tau_m", &self.tau_m);
+        write_attr(out, "tau_refrac", &self.tau_refrac);
+        write_attr(out, "v_reset", &self.v_reset);
+        write_attr(out, "v_rest", &self.v_rest);
+        write_attr(out, "v_thresh", &self.v_thresh);
+        write_attr(out, "cm", &self.cm);
+        write_attr(out, "i_offset", &self.i_offset);
+        write_attr(out, "tau_syn_E", &self.tau_syn_E);
+        write_attr(out, "tau_syn_I", &self.tau_syn_I);
+        write_attr(out, "v_init", &self.v_init);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -863,18 +1583,18 @@ pub struct ContinuousConnectionInstance {
 }
 
 impl XML for ContinuousConnectionInstance {
-    fn from_node(node: &Node) -> Self {
-        let preComponent = node.attribute("preComponent").map(|s| s.to_string()).unwrap();
-        let postComponent = node.attribute("postComponent").map(|s| s.to_string()).unwrap();
-        let preCell = node.attribute("preCell").map(|s| s.to_string()).unwrap();
-        let preSegment = node.attribute("preSegment").or(Some("0")).map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let preFractionAlong = node.attribute("preFractionAlong").or(Some("0.5")).map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let postCell = node.attribute("postCell").map(|s| s.to_string()).unwrap();
-        let postSegment = node.attribute("postSegment").or(Some("0")).map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let postFractionAlong = node.attribute("postFractionAlong").or(Some("0.5")).map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let id = node.attribute("id").map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
-        ContinuousConnectionInstance {
+    fn from_node(node: &Node) -> Result<Self> {
+        let preComponent = req_attr::<String>(node, "preComponent")?;
+        let postComponent = req_attr::<String>(node, "postComponent")?;
+        let preCell = req_attr::<String>(node, "preCell")?;
+        let preSegment = def_attr::<i64>(node, "preSegment", "0")?;
+        let preFractionAlong = def_attr::<f64>(node, "preFractionAlong", "0.5")?;
+        let postCell = req_attr::<String>(node, "postCell")?;
+        let postSegment = def_attr::<i64>(node, "postSegment", "0")?;
+        let postFractionAlong = def_attr::<f64>(node, "postFractionAlong", "0.5")?;
+        let id = req_attr::<i64>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
+        Ok(ContinuousConnectionInstance {
             preComponent,
             postComponent,
             preCell,
@@ -885,7 +1605,22 @@ impl XML for ContinuousConnectionInstance {
             postFractionAlong,
             id,
             neuroLexId,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "preComponent", &self.preComponent);
+        write_attr(out, "postComponent", &self.postComponent);
+        write_attr(out, "preCell", &self.preCell);
+        write_attr(out, "preSegment", &self.preSegment);
+        write_attr(out, "preFractionAlong", &self.preFractionAlong);
+        write_attr(out, "postCell", &self.postCell);
+        write_attr(out, "postSegment", &self.postSegment);
+        write_attr(out, "postFractionAlong", &self.postFractionAlong);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -896,11 +1631,22 @@ pub enum BaseConductanceBasedSynapseTwoBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl BaseConductanceBasedSynapseTwoBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            BaseConductanceBasedSynapseTwoBody::notes(x) => x.to_xml("notes", depth, out),
+            BaseConductanceBasedSynapseTwoBody::property(x) => x.to_xml("property", depth, out),
+            BaseConductanceBasedSynapseTwoBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct BaseConductanceBasedSynapseTwo {
-    pub gbase1: String,
-    pub gbase2: String,
-    pub erev: String,
+    pub gbase1: Nml2Quantity,
+    pub gbase2: Nml2Quantity,
+    pub erev: Nml2Quantity,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -908,26 +1654,26 @@ pub struct BaseConductanceBasedSynapseTwo {
 }
 
 impl XML for BaseConductanceBasedSynapseTwo {
-    fn from_node(node: &Node) -> Self {
-        let gbase1 = node.attribute("gbase1").map(|s| s.to_string()).unwrap();
-        let gbase2 = node.attribute("gbase2").map(|s| s.to_string()).unwrap();
-        let erev = node.attribute("erev").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let gbase1 = req_attr::<Nml2Quantity>(node, "gbase1")?;
+        let gbase2 = req_attr::<Nml2Quantity>(node, "gbase2")?;
+        let erev = req_attr::<Nml2Quantity>(node, "erev")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(BaseConductanceBasedSynapseTwoBody::notes(String::from_node(&child))),
-                "property" => body.push(BaseConductanceBasedSynapseTwoBody::property(Property::from_node(&child))),
-                "annotation" => body.push(BaseConductanceBasedSynapseTwoBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of BaseConductanceBasedSynapseTwo.", t)
+                "notes" => body.push(BaseConductanceBasedSynapseTwoBody::notes(String::from_node(&child)?)),
+                "property" => body.push(BaseConductanceBasedSynapseTwoBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(BaseConductanceBasedSynapseTwoBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "BaseConductanceBasedSynapseTwo")),
             };
         }
-        BaseConductanceBasedSynapseTwo {
+        Ok(BaseConductanceBasedSynapseTwo {
             gbase1,
             gbase2,
             erev,
@@ -935,27 +1681,54 @@ impl XML for BaseConductanceBasedSynapseTwo {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "gbase1", &self.gbase1);
+        write_attr(out, "gbase2", &self.gbase2);
+        write_attr(out, "erev", &self.erev);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Spike {
-    pub time: String,
+    pub time: Nml2Quantity,
     pub id: i64,
     pub neuroLexId: Option<String>,
 }
 
 impl XML for Spike {
-    fn from_node(node: &Node) -> Self {
-        let time = node.attribute("time").map(|s| s.to_string()).unwrap();
-        let id = node.attribute("id").map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
-        Spike {
+    fn from_node(node: &Node) -> Result<Self> {
+        let time = req_attr::<Nml2Quantity>(node, "time")?;
+        let id = req_attr::<i64>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
+        Ok(Spike {
             time,
             id,
             neuroLexId,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "time", &self.time);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -966,6 +1739,17 @@ pub enum SegmentBody {
     distal(Point3DWithDiam),
 }
 
+#[allow(dead_code)]
+impl SegmentBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            SegmentBody::parent(x) => x.to_xml("parent", depth, out),
+            SegmentBody::proximal(x) => x.to_xml("proximal", depth, out),
+            SegmentBody::distal(x) => x.to_xml("distal", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Segment {
     pub name: Option<String>,
@@ -975,27 +1759,43 @@ pub struct Segment {
 }
 
 impl XML for Segment {
-    fn from_node(node: &Node) -> Self {
-        let name = node.attribute("name").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let name = opt_attr::<String>(node, "name")?;
+        let id = req_attr::<i64>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "parent" => body.push(SegmentBody::parent(SegmentParent::from_node(&child))),
-                "proximal" => body.push(SegmentBody::proximal(Point3DWithDiam::from_node(&child))),
-                "distal" => body.push(SegmentBody::distal(Point3DWithDiam::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of Segment.", t)
+                "parent" => body.push(SegmentBody::parent(SegmentParent::from_node(&child)?)),
+                "proximal" => body.push(SegmentBody::proximal(Point3DWithDiam::from_node(&child)?)),
+                "distal" => body.push(SegmentBody::distal(Point3DWithDiam::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "Segment")),
             };
         }
-        Segment {
+        Ok(Segment {
             name,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if let Some(x) = &self.name { write_attr(out, "name", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -1008,6 +1808,18 @@ pub enum TimedSynapticInputBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl TimedSynapticInputBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            TimedSynapticInputBody::spike(x) => x.to_xml("spike", depth, out),
+            TimedSynapticInputBody::notes(x) => x.to_xml("notes", depth, out),
+            TimedSynapticInputBody::property(x) => x.to_xml("property", depth, out),
+            TimedSynapticInputBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct TimedSynapticInput {
     pub synapse: String,
@@ -1019,32 +1831,50 @@ pub struct TimedSynapticInput {
 }
 
 impl XML for TimedSynapticInput {
-    fn from_node(node: &Node) -> Self {
-        let synapse = node.attribute("synapse").map(|s| s.to_string()).unwrap();
-        let spikeTarget = node.attribute("spikeTarget").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let synapse = req_attr::<String>(node, "synapse")?;
+        let spikeTarget = req_attr::<String>(node, "spikeTarget")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "spike" => body.push(TimedSynapticInputBody::spike(Spike::from_node(&child))),
-                "notes" => body.push(TimedSynapticInputBody::notes(String::from_node(&child))),
-                "property" => body.push(TimedSynapticInputBody::property(Property::from_node(&child))),
-                "annotation" => body.push(TimedSynapticInputBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of TimedSynapticInput.", t)
+                "spike" => body.push(TimedSynapticInputBody::spike(Spike::from_node(&child)?)),
+                "notes" => body.push(TimedSynapticInputBody::notes(String::from_node(&child)?)),
+                "property" => body.push(TimedSynapticInputBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(TimedSynapticInputBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "TimedSynapticInput")),
             };
         }
-        TimedSynapticInput {
+        Ok(TimedSynapticInput {
             synapse,
             spikeTarget,
             metaid,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "synapse", &self.synapse);
+        write_attr(out, "spikeTarget", &self.spikeTarget);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -1055,20 +1885,32 @@ pub struct ForwardTransition {
     pub to: String,
     pub id: String,
     pub neuroLexId: Option<String>,
+    pub body: String,
 }
 
 impl XML for ForwardTransition {
-    fn from_node(node: &Node) -> Self {
-        let from = node.attribute("from").map(|s| s.to_string()).unwrap();
-        let to = node.attribute("to").map(|s| s.to_string()).unwrap();
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
-        ForwardTransition {
+    fn from_node(node: &Node) -> Result<Self> {
+        let from = req_attr::<String>(node, "from")?;
+        let to = req_attr::<String>(node, "to")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
+        let body = inner_xml(node);
+        Ok(ForwardTransition {
             from,
             to,
             id,
             neuroLexId,
-        }
+            body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "from", &self.from);
+        write_attr(out, "to", &self.to);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        write_inner(out, tag, &self.body);
     }
 }
 
@@ -1077,6 +1919,15 @@ pub enum ExtracellularPropertiesBody {
     species(Species),
 }
 
+#[allow(dead_code)]
+impl ExtracellularPropertiesBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            ExtracellularPropertiesBody::species(x) => x.to_xml("species", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ExtracellularProperties {
     pub id: String,
@@ -1085,23 +1936,38 @@ pub struct ExtracellularProperties {
 }
 
 impl XML for ExtracellularProperties {
-    fn from_node(node: &Node) -> Self {
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "species" => body.push(ExtracellularPropertiesBody::species(Species::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of ExtracellularProperties.", t)
+                "species" => body.push(ExtracellularPropertiesBody::species(Species::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "ExtracellularProperties")),
             };
         }
-        ExtracellularProperties {
+        Ok(ExtracellularProperties {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -1115,17 +1981,26 @@ pub struct LEMS_Property {
 }
 
 impl XML for LEMS_Property {
-    fn from_node(node: &Node) -> Self {
-        let defaultValue = node.attribute("defaultValue").map(|s| s.parse::<f64>().unwrap());
-        let name = node.attribute("name").map(|s| s.to_string()).unwrap();
-        let dimension = node.attribute("dimension").map(|s| s.to_string()).unwrap();
-        let description = node.attribute("description").map(|s| s.to_string());
-        LEMS_Property {
+    fn from_node(node: &Node) -> Result<Self> {
+        let defaultValue = opt_attr::<f64>(node, "defaultValue")?;
+        let name = req_attr::<String>(node, "name")?;
+        let dimension = req_attr::<String>(node, "dimension")?;
+        let description = opt_attr::<String>(node, "description")?;
+        Ok(LEMS_Property {
             defaultValue,
             name,
             dimension,
             description,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if let Some(x) = &self.defaultValue { write_attr(out, "defaultValue", x); }
+        write_attr(out, "name", &self.name);
+        write_attr(out, "dimension", &self.dimension);
+        if let Some(x) = &self.description { write_attr(out, "description", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -1136,6 +2011,17 @@ pub enum EIF_cond_alpha_isfa_istaBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl EIF_cond_alpha_isfa_istaBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            EIF_cond_alpha_isfa_istaBody::notes(x) => x.to_xml("notes", depth, out),
+            EIF_cond_alpha_isfa_istaBody::property(x) => x.to_xml("property", depth, out),
+            EIF_cond_alpha_isfa_istaBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct EIF_cond_alpha_isfa_ista {
     pub a: f64,
@@ -1162,40 +2048,40 @@ pub struct EIF_cond_alpha_isfa_ista {
 }
 
 impl XML for EIF_cond_alpha_isfa_ista {
-    fn from_node(node: &Node) -> Self {
-        let a = node.attribute("a").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let b = node.attribute("b").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let delta_T = node.attribute("delta_T").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let tau_w = node.attribute("tau_w").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let v_spike = node.attribute("v_spike").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let e_rev_E = node.attribute("e_rev_E").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let e_rev_I = node.attribute("e_rev_I").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let tau_m = node.attribute("tau_m").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let tau_refrac = node.attribute("tau_refrac").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let v_reset = node.attribute("v_reset").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let v_rest = node.attribute("v_rest").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let v_thresh = node.attribute("v_thresh").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let cm = node.attribute("cm").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let i_offset = node.attribute("i_offset").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let tau_syn_E = node.attribute("tau_syn_E").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let tau_syn_I = node.attribute("tau_syn_I").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let v_init = node.attribute("v_init").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let a = req_attr::<f64>(node, "a")?;
+        let b = req_attr::<f64>(node, "b")?;
+        let delta_T = req_attr::<f64>(node, "delta_T")?;
+        let tau_w = req_attr::<f64>(node, "tau_w")?;
+        let v_spike = req_attr::<f64>(node, "v_spike")?;
+        let e_rev_E = req_attr::<f64>(node, "e_rev_E")?;
+        let e_rev_I = req_attr::<f64>(node, "e_rev_I")?;
+        let tau_m = req_attr::<f64>(node, "tau_m")?;
+        let tau_refrac = req_attr::<f64>(node, "tau_refrac")?;
+        let v_reset = req_attr::<f64>(node, "v_reset")?;
+        let v_rest = req_attr::<f64>(node, "v_rest")?;
+        let v_thresh = req_attr::<f64>(node, "v_thresh")?;
+        let cm = req_attr::<f64>(node, "cm")?;
+        let i_offset = req_attr::<f64>(node, "i_offset")?;
+        let tau_syn_E = req_attr::<f64>(node, "tau_syn_E")?;
+        let tau_syn_I = req_attr::<f64>(node, "tau_syn_I")?;
+        let v_init = req_attr::<f64>(node, "v_init")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(EIF_cond_alpha_isfa_istaBody::notes(String::from_node(&child))),
-                "property" => body.push(EIF_cond_alpha_isfa_istaBody::property(Property::from_node(&child))),
-                "annotation" => body.push(EIF_cond_alpha_isfa_istaBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of EIF_cond_alpha_isfa_ista.", t)
+                "notes" => body.push(EIF_cond_alpha_isfa_istaBody::notes(String::from_node(&child)?)),
+                "property" => body.push(EIF_cond_alpha_isfa_istaBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(EIF_cond_alpha_isfa_istaBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "EIF_cond_alpha_isfa_ista")),
             };
         }
-        EIF_cond_alpha_isfa_ista {
+        Ok(EIF_cond_alpha_isfa_ista {
             a,
             b,
             delta_T,
@@ -1217,6 +2103,39 @@ impl XML for EIF_cond_alpha_isfa_ista {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "a", &self.a);
+        write_attr(out, "b", &self.b);
+        write_attr(out, "delta_T", &self.delta_T);
+        write_attr(out, "tau_w", &self.tau_w);
+        write_attr(out, "v_spike", &self.v_spike);
+        write_attr(out, "e_rev_E", &self.e_rev_E);
+        write_attr(out, "e_rev_I", &self.e_rev_I);
+        write_attr(out, "tau_m", &self.tau_m);
+        write_attr(out, "tau_refrac", &self.tau_refrac);
+        write_attr(out, "v_reset", &self.v_reset);
+        write_attr(out, "v_rest", &self.v_rest);
+        write_attr(out, "v_thresh", &self.v_thresh);
+        write_attr(out, "cm", &self.cm);
+        write_attr(out, "i_offset", &self.i_offset);
+        write_attr(out, "tau_syn_E", &self.tau_syn_E);
+        write_attr(out, "tau_syn_I", &self.tau_syn_I);
+        write_attr(out, "v_init", &self.v_init);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -1227,6 +2146,16 @@ pub enum GateHHInstantaneousBody {
     steadyState(HHVariable),
 }
 
+#[allow(dead_code)]
+impl GateHHInstantaneousBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            GateHHInstantaneousBody::notes(x) => x.to_xml("notes", depth, out),
+            GateHHInstantaneousBody::steadyState(x) => x.to_xml("steadyState", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct GateHHInstantaneous {
     pub instances: i64,
@@ -1236,26 +2165,42 @@ pub struct GateHHInstantaneous {
 }
 
 impl XML for GateHHInstantaneous {
-    fn from_node(node: &Node) -> Self {
-        let instances = node.attribute("instances").map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let instances = req_attr::<i64>(node, "instances")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(GateHHInstantaneousBody::notes(String::from_node(&child))),
-                "steadyState" => body.push(GateHHInstantaneousBody::steadyState(HHVariable::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of GateHHInstantaneous.", t)
+                "notes" => body.push(GateHHInstantaneousBody::notes(String::from_node(&child)?)),
+                "steadyState" => body.push(GateHHInstantaneousBody::steadyState(HHVariable::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "GateHHInstantaneous")),
             };
         }
-        GateHHInstantaneous {
+        Ok(GateHHInstantaneous {
             instances,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "instances", &self.instances);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -1269,17 +2214,26 @@ pub struct StateVariable {
 }
 
 impl XML for StateVariable {
-    fn from_node(node: &Node) -> Self {
-        let name = node.attribute("name").map(|s| s.to_string()).unwrap();
-        let dimension = node.attribute("dimension").map(|s| s.to_string()).unwrap();
-        let description = node.attribute("description").map(|s| s.to_string());
-        let exposure = node.attribute("exposure").map(|s| s.to_string());
-        StateVariable {
+    fn from_node(node: &Node) -> Result<Self> {
+        let name = req_attr::<String>(node, "name")?;
+        let dimension = req_attr::<String>(node, "dimension")?;
+        let description = opt_attr::<String>(node, "description")?;
+        let exposure = opt_attr::<String>(node, "exposure")?;
+        Ok(StateVariable {
             name,
             dimension,
             description,
             exposure,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "name", &self.name);
+        write_attr(out, "dimension", &self.dimension);
+        if let Some(x) = &self.description { write_attr(out, "description", x); }
+        if let Some(x) = &self.exposure { write_attr(out, "exposure", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -1290,6 +2244,17 @@ pub enum GateFractionalBody {
     subGate(GateFractionalSubgate),
 }
 
+#[allow(dead_code)]
+impl GateFractionalBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            GateFractionalBody::notes(x) => x.to_xml("notes", depth, out),
+            GateFractionalBody::q10Settings(x) => x.to_xml("q10Settings", depth, out),
+            GateFractionalBody::subGate(x) => x.to_xml("subGate", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct GateFractional {
     pub instances: i64,
@@ -1299,27 +2264,43 @@ pub struct GateFractional {
 }
 
 impl XML for GateFractional {
-    fn from_node(node: &Node) -> Self {
-        let instances = node.attribute("instances").map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let instances = req_attr::<i64>(node, "instances")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(GateFractionalBody::notes(String::from_node(&child))),
-                "q10Settings" => body.push(GateFractionalBody::q10Settings(Q10Settings::from_node(&child))),
-                "subGate" => body.push(GateFractionalBody::subGate(GateFractionalSubgate::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of GateFractional.", t)
+                "notes" => body.push(GateFractionalBody::notes(String::from_node(&child)?)),
+                "q10Settings" => body.push(GateFractionalBody::q10Settings(Q10Settings::from_node(&child)?)),
+                "subGate" => body.push(GateFractionalBody::subGate(GateFractionalSubgate::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "GateFractional")),
             };
         }
-        GateFractional {
+        Ok(GateFractional {
             instances,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "instances", &self.instances);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -1331,10 +2312,21 @@ pub enum SpikeGeneratorRandomBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl SpikeGeneratorRandomBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            SpikeGeneratorRandomBody::notes(x) => x.to_xml("notes", depth, out),
+            SpikeGeneratorRandomBody::property(x) => x.to_xml("property", depth, out),
+            SpikeGeneratorRandomBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct SpikeGeneratorRandom {
-    pub maxISI: String,
-    pub minISI: String,
+    pub maxISI: Nml2Quantity,
+    pub minISI: Nml2Quantity,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -1342,31 +2334,49 @@ pub struct SpikeGeneratorRandom {
 }
 
 impl XML for SpikeGeneratorRandom {
-    fn from_node(node: &Node) -> Self {
-        let maxISI = node.attribute("maxISI").map(|s| s.to_string()).unwrap();
-        let minISI = node.attribute("minISI").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let maxISI = req_attr::<Nml2Quantity>(node, "maxISI")?;
+        let minISI = req_attr::<Nml2Quantity>(node, "minISI")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(SpikeGeneratorRandomBody::notes(String::from_node(&child))),
-                "property" => body.push(SpikeGeneratorRandomBody::property(Property::from_node(&child))),
-                "annotation" => body.push(SpikeGeneratorRandomBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of SpikeGeneratorRandom.", t)
+                "notes" => body.push(SpikeGeneratorRandomBody::notes(String::from_node(&child)?)),
+                "property" => body.push(SpikeGeneratorRandomBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(SpikeGeneratorRandomBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "SpikeGeneratorRandom")),
             };
         }
-        SpikeGeneratorRandom {
+        Ok(SpikeGeneratorRandom {
             maxISI,
             minISI,
             metaid,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "maxISI", &self.maxISI);
+        write_attr(out, "minISI", &self.minISI);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -1378,11 +2388,22 @@ pub enum ExpOneSynapseBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl ExpOneSynapseBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            ExpOneSynapseBody::notes(x) => x.to_xml("notes", depth, out),
+            ExpOneSynapseBody::property(x) => x.to_xml("property", depth, out),
+            ExpOneSynapseBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ExpOneSynapse {
-    pub tauDecay: String,
-    pub gbase: String,
-    pub erev: String,
+    pub tauDecay: Nml2Quantity,
+    pub gbase: Nml2Quantity,
+    pub erev: Nml2Quantity,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -1390,26 +2411,26 @@ pub struct ExpOneSynapse {
 }
 
 impl XML for ExpOneSynapse {
-    fn from_node(node: &Node) -> Self {
-        let tauDecay = node.attribute("tauDecay").map(|s| s.to_string()).unwrap();
-        let gbase = node.attribute("gbase").map(|s| s.to_string()).unwrap();
-        let erev = node.attribute("erev").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let tauDecay = req_attr::<Nml2Quantity>(node, "tauDecay")?;
+        let gbase = req_attr::<Nml2Quantity>(node, "gbase")?;
+        let erev = req_attr::<Nml2Quantity>(node, "erev")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(ExpOneSynapseBody::notes(String::from_node(&child))),
-                "property" => body.push(ExpOneSynapseBody::property(Property::from_node(&child))),
-                "annotation" => body.push(ExpOneSynapseBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of ExpOneSynapse.", t)
+                "notes" => body.push(ExpOneSynapseBody::notes(String::from_node(&child)?)),
+                "property" => body.push(ExpOneSynapseBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(ExpOneSynapseBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "ExpOneSynapse")),
             };
         }
-        ExpOneSynapse {
+        Ok(ExpOneSynapse {
             tauDecay,
             gbase,
             erev,
@@ -1417,6 +2438,25 @@ impl XML for ExpOneSynapse {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "tauDecay", &self.tauDecay);
+        write_attr(out, "gbase", &self.gbase);
+        write_attr(out, "erev", &self.erev);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -1431,6 +2471,20 @@ pub enum CompoundInputBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl CompoundInputBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            CompoundInputBody::pulseGenerator(x) => x.to_xml("pulseGenerator", depth, out),
+            CompoundInputBody::sineGenerator(x) => x.to_xml("sineGenerator", depth, out),
+            CompoundInputBody::rampGenerator(x) => x.to_xml("rampGenerator", depth, out),
+            CompoundInputBody::notes(x) => x.to_xml("notes", depth, out),
+            CompoundInputBody::property(x) => x.to_xml("property", depth, out),
+            CompoundInputBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct CompoundInput {
     pub metaid: Option<String>,
@@ -1440,30 +2494,46 @@ pub struct CompoundInput {
 }
 
 impl XML for CompoundInput {
-    fn from_node(node: &Node) -> Self {
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "pulseGenerator" => body.push(CompoundInputBody::pulseGenerator(PulseGenerator::from_node(&child))),
-                "sineGenerator" => body.push(CompoundInputBody::sineGenerator(SineGenerator::from_node(&child))),
-                "rampGenerator" => body.push(CompoundInputBody::rampGenerator(RampGenerator::from_node(&child))),
-                "notes" => body.push(CompoundInputBody::notes(String::from_node(&child))),
-                "property" => body.push(CompoundInputBody::property(Property::from_node(&child))),
-                "annotation" => body.push(CompoundInputBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of CompoundInput.", t)
+                "pulseGenerator" => body.push(CompoundInputBody::pulseGenerator(PulseGenerator::from_node(&child)?)),
+                "sineGenerator" => body.push(CompoundInputBody::sineGenerator(SineGenerator::from_node(&child)?)),
+                "rampGenerator" => body.push(CompoundInputBody::rampGenerator(RampGenerator::from_node(&child)?)),
+                "notes" => body.push(CompoundInputBody::notes(String::from_node(&child)?)),
+                "property" => body.push(CompoundInputBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(CompoundInputBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "CompoundInput")),
             };
         }
-        CompoundInput {
+        Ok(CompoundInput {
             metaid,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -1482,17 +2552,17 @@ pub struct ElectricalConnectionInstance {
 }
 
 impl XML for ElectricalConnectionInstance {
-    fn from_node(node: &Node) -> Self {
-        let synapse = node.attribute("synapse").map(|s| s.to_string()).unwrap();
-        let preCell = node.attribute("preCell").map(|s| s.to_string()).unwrap();
-        let preSegment = node.attribute("preSegment").or(Some("0")).map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let preFractionAlong = node.attribute("preFractionAlong").or(Some("0.5")).map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let postCell = node.attribute("postCell").map(|s| s.to_string()).unwrap();
-        let postSegment = node.attribute("postSegment").or(Some("0")).map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let postFractionAlong = node.attribute("postFractionAlong").or(Some("0.5")).map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let id = node.attribute("id").map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
-        ElectricalConnectionInstance {
+    fn from_node(node: &Node) -> Result<Self> {
+        let synapse = req_attr::<String>(node, "synapse")?;
+        let preCell = req_attr::<String>(node, "preCell")?;
+        let preSegment = def_attr::<i64>(node, "preSegment", "0")?;
+        let preFractionAlong = def_attr::<f64>(node, "preFractionAlong", "0.5")?;
+        let postCell = req_attr::<String>(node, "postCell")?;
+        let postSegment = def_attr::<i64>(node, "postSegment", "0")?;
+        let postFractionAlong = def_attr::<f64>(node, "postFractionAlong", "0.5")?;
+        let id = req_attr::<i64>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
+        Ok(ElectricalConnectionInstance {
             synapse,
             preCell,
             preSegment,
@@ -1502,7 +2572,21 @@ impl XML for ElectricalConnectionInstance {
             postFractionAlong,
             id,
             neuroLexId,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "synapse", &self.synapse);
+        write_attr(out, "preCell", &self.preCell);
+        write_attr(out, "preSegment", &self.preSegment);
+        write_attr(out, "preFractionAlong", &self.preFractionAlong);
+        write_attr(out, "postCell", &self.postCell);
+        write_attr(out, "postSegment", &self.postSegment);
+        write_attr(out, "postFractionAlong", &self.postFractionAlong);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -1513,9 +2597,20 @@ pub enum PoissonFiringSynapseBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl PoissonFiringSynapseBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            PoissonFiringSynapseBody::notes(x) => x.to_xml("notes", depth, out),
+            PoissonFiringSynapseBody::property(x) => x.to_xml("property", depth, out),
+            PoissonFiringSynapseBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct PoissonFiringSynapse {
-    pub averageRate: String,
+    pub averageRate: Nml2Quantity,
     pub synapse: String,
     pub spikeTarget: String,
     pub metaid: Option<String>,
@@ -1525,26 +2620,26 @@ pub struct PoissonFiringSynapse {
 }
 
 impl XML for PoissonFiringSynapse {
-    fn from_node(node: &Node) -> Self {
-        let averageRate = node.attribute("averageRate").map(|s| s.to_string()).unwrap();
-        let synapse = node.attribute("synapse").map(|s| s.to_string()).unwrap();
-        let spikeTarget = node.attribute("spikeTarget").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let averageRate = req_attr::<Nml2Quantity>(node, "averageRate")?;
+        let synapse = req_attr::<String>(node, "synapse")?;
+        let spikeTarget = req_attr::<String>(node, "spikeTarget")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(PoissonFiringSynapseBody::notes(String::from_node(&child))),
-                "property" => body.push(PoissonFiringSynapseBody::property(Property::from_node(&child))),
-                "annotation" => body.push(PoissonFiringSynapseBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of PoissonFiringSynapse.", t)
+                "notes" => body.push(PoissonFiringSynapseBody::notes(String::from_node(&child)?)),
+                "property" => body.push(PoissonFiringSynapseBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(PoissonFiringSynapseBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "PoissonFiringSynapse")),
             };
         }
-        PoissonFiringSynapse {
+        Ok(PoissonFiringSynapse {
             averageRate,
             synapse,
             spikeTarget,
@@ -1552,6 +2647,25 @@ impl XML for PoissonFiringSynapse {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "averageRate", &self.averageRate);
+        write_attr(out, "synapse", &self.synapse);
+        write_attr(out, "spikeTarget", &self.spikeTarget);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -1565,11 +2679,24 @@ pub enum PopulationBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl PopulationBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            PopulationBody::layout(x) => x.to_xml("layout", depth, out),
+            PopulationBody::instance(x) => x.to_xml("instance", depth, out),
+            PopulationBody::notes(x) => x.to_xml("notes", depth, out),
+            PopulationBody::property(x) => x.to_xml("property", depth, out),
+            PopulationBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Population {
     pub component: String,
     pub size: Option<i64>,
-    pub r#type: Option<String>,
+    pub r#type: Option<populationTypes>,
     pub extracellularProperties: Option<String>,
     pub metaid: Option<String>,
     pub id: String,
@@ -1578,29 +2705,29 @@ pub struct Population {
 }
 
 impl XML for Population {
-    fn from_node(node: &Node) -> Self {
-        let component = node.attribute("component").map(|s| s.to_string()).unwrap();
-        let size = node.attribute("size").map(|s| s.parse::<i64>().unwrap());
-        let r#type = node.attribute("type").map(|s| s.to_string());
-        let extracellularProperties = node.attribute("extracellularProperties").map(|s| s.to_string());
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let component = req_attr::<String>(node, "component")?;
+        let size = opt_attr::<i64>(node, "size")?;
+        let r#type = opt_attr::<populationTypes>(node, "type")?;
+        let extracellularProperties = opt_attr::<String>(node, "extracellularProperties")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "layout" => body.push(PopulationBody::layout(Layout::from_node(&child))),
-                "instance" => body.push(PopulationBody::instance(Instance::from_node(&child))),
-                "notes" => body.push(PopulationBody::notes(String::from_node(&child))),
-                "property" => body.push(PopulationBody::property(Property::from_node(&child))),
-                "annotation" => body.push(PopulationBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of Population.", t)
+                "layout" => body.push(PopulationBody::layout(Layout::from_node(&child)?)),
+                "instance" => body.push(PopulationBody::instance(Instance::from_node(&child)?)),
+                "notes" => body.push(PopulationBody::notes(String::from_node(&child)?)),
+                "property" => body.push(PopulationBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(PopulationBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "Population")),
             };
         }
-        Population {
+        Ok(Population {
             component,
             size,
             r#type,
@@ -1609,6 +2736,26 @@ impl XML for Population {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "component", &self.component);
+        if let Some(x) = &self.size { write_attr(out, "size", x); }
+        if let Some(x) = &self.r#type { write_attr(out, "type", x); }
+        if let Some(x) = &self.extracellularProperties { write_attr(out, "extracellularProperties", x); }
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -1616,7 +2763,7 @@ impl XML for Population {
 #[derive(Debug, Clone, PartialEq)]
 pub struct ConnectionWD {
     pub weight: f64,
-    pub delay: String,
+    pub delay: Nml2Quantity,
     pub preCellId: String,
     pub preSegmentId: i64,
     pub preFractionAlong: f64,
@@ -1628,18 +2775,18 @@ pub struct ConnectionWD {
 }
 
 impl XML for ConnectionWD {
-    fn from_node(node: &Node) -> Self {
-        let weight = node.attribute("weight").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let delay = node.attribute("delay").map(|s| s.to_string()).unwrap();
-        let preCellId = node.attribute("preCellId").map(|s| s.to_string()).unwrap();
-        let preSegmentId = node.attribute("preSegmentId").or(Some("0")).map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let preFractionAlong = node.attribute("preFractionAlong").or(Some("0.5")).map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let postCellId = node.attribute("postCellId").map(|s| s.to_string()).unwrap();
-        let postSegmentId = node.attribute("postSegmentId").or(Some("0")).map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let postFractionAlong = node.attribute("postFractionAlong").or(Some("0.5")).map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let id = node.attribute("id").map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
-        ConnectionWD {
+    fn from_node(node: &Node) -> Result<Self> {
+        let weight = req_attr::<f64>(node, "weight")?;
+        let delay = req_attr::<Nml2Quantity>(node, "delay")?;
+        let preCellId = req_attr::<String>(node, "preCellId")?;
+        let preSegmentId = def_attr::<i64>(node, "preSegmentId", "0")?;
+        let preFractionAlong = def_attr::<f64>(node, "preFractionAlong", "0.5")?;
+        let postCellId = req_attr::<String>(node, "postCellId")?;
+        let postSegmentId = def_attr::<i64>(node, "postSegmentId", "0")?;
+        let postFractionAlong = def_attr::<f64>(node, "postFractionAlong", "0.5")?;
+        let id = req_attr::<i64>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
+        Ok(ConnectionWD {
             weight,
             delay,
             preCellId,
@@ -1650,7 +2797,22 @@ impl XML for ConnectionWD {
             postFractionAlong,
             id,
             neuroLexId,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "weight", &self.weight);
+        write_attr(out, "delay", &self.delay);
+        write_attr(out, "preCellId", &self.preCellId);
+        write_attr(out, "preSegmentId", &self.preSegmentId);
+        write_attr(out, "preFractionAlong", &self.preFractionAlong);
+        write_attr(out, "postCellId", &self.postCellId);
+        write_attr(out, "postSegmentId", &self.postSegmentId);
+        write_attr(out, "postFractionAlong", &self.postFractionAlong);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -1659,34 +2821,59 @@ pub enum SpaceBody {
     structure(SpaceStructure),
 }
 
+#[allow(dead_code)]
+impl SpaceBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            SpaceBody::structure(x) => x.to_xml("structure", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Space {
-    pub basedOn: Option<String>,
+    pub basedOn: Option<allowedSpaces>,
     pub id: String,
     pub neuroLexId: Option<String>,
     pub body: Vec<SpaceBody>
 }
 
 impl XML for Space {
-    fn from_node(node: &Node) -> Self {
-        let basedOn = node.attribute("basedOn").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let basedOn = opt_attr::<allowedSpaces>(node, "basedOn")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "structure" => body.push(SpaceBody::structure(SpaceStructure::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of Space.", t)
+                "structure" => body.push(SpaceBody::structure(SpaceStructure::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "Space")),
             };
         }
-        Space {
+        Ok(Space {
             basedOn,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if let Some(x) = &self.basedOn { write_attr(out, "basedOn", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -1698,10 +2885,21 @@ pub enum BaseConductanceBasedSynapseBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl BaseConductanceBasedSynapseBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            BaseConductanceBasedSynapseBody::notes(x) => x.to_xml("notes", depth, out),
+            BaseConductanceBasedSynapseBody::property(x) => x.to_xml("property", depth, out),
+            BaseConductanceBasedSynapseBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct BaseConductanceBasedSynapse {
-    pub gbase: String,
-    pub erev: String,
+    pub gbase: Nml2Quantity,
+    pub erev: Nml2Quantity,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -1709,31 +2907,49 @@ pub struct BaseConductanceBasedSynapse {
 }
 
 impl XML for BaseConductanceBasedSynapse {
-    fn from_node(node: &Node) -> Self {
-        let gbase = node.attribute("gbase").map(|s| s.to_string()).unwrap();
-        let erev = node.attribute("erev").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let gbase = req_attr::<Nml2Quantity>(node, "gbase")?;
+        let erev = req_attr::<Nml2Quantity>(node, "erev")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(BaseConductanceBasedSynapseBody::notes(String::from_node(&child))),
-                "property" => body.push(BaseConductanceBasedSynapseBody::property(Property::from_node(&child))),
-                "annotation" => body.push(BaseConductanceBasedSynapseBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of BaseConductanceBasedSynapse.", t)
+                "notes" => body.push(BaseConductanceBasedSynapseBody::notes(String::from_node(&child)?)),
+                "property" => body.push(BaseConductanceBasedSynapseBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(BaseConductanceBasedSynapseBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "BaseConductanceBasedSynapse")),
             };
         }
-        BaseConductanceBasedSynapse {
+        Ok(BaseConductanceBasedSynapse {
             gbase,
             erev,
             metaid,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "gbase", &self.gbase);
+        write_attr(out, "erev", &self.erev);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -1743,6 +2959,15 @@ pub enum VariableParameterBody {
     inhomogeneousValue(InhomogeneousValue),
 }
 
+#[allow(dead_code)]
+impl VariableParameterBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            VariableParameterBody::inhomogeneousValue(x) => x.to_xml("inhomogeneousValue", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct VariableParameter {
     pub parameter: String,
@@ -1751,23 +2976,38 @@ pub struct VariableParameter {
 }
 
 impl XML for VariableParameter {
-    fn from_node(node: &Node) -> Self {
-        let parameter = node.attribute("parameter").map(|s| s.to_string()).unwrap();
-        let segmentGroup = node.attribute("segmentGroup").map(|s| s.to_string()).unwrap();
+    fn from_node(node: &Node) -> Result<Self> {
+        let parameter = req_attr::<String>(node, "parameter")?;
+        let segmentGroup = req_attr::<String>(node, "segmentGroup")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "inhomogeneousValue" => body.push(VariableParameterBody::inhomogeneousValue(InhomogeneousValue::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of VariableParameter.", t)
+                "inhomogeneousValue" => body.push(VariableParameterBody::inhomogeneousValue(InhomogeneousValue::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "VariableParameter")),
             };
         }
-        VariableParameter {
+        Ok(VariableParameter {
             parameter,
             segmentGroup,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "parameter", &self.parameter);
+        write_attr(out, "segmentGroup", &self.segmentGroup);
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -1779,6 +3019,17 @@ pub enum LayoutBody {
     unstructured(UnstructuredLayout),
 }
 
+#[allow(dead_code)]
+impl LayoutBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            LayoutBody::random(x) => x.to_xml("random", depth, out),
+            LayoutBody::grid(x) => x.to_xml("grid", depth, out),
+            LayoutBody::unstructured(x) => x.to_xml("unstructured", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Layout {
     pub space: Option<String>,
@@ -1786,23 +3037,37 @@ pub struct Layout {
 }
 
 impl XML for Layout {
-    fn from_node(node: &Node) -> Self {
-        let space = node.attribute("space").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let space = opt_attr::<String>(node, "space")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "random" => body.push(LayoutBody::random(RandomLayout::from_node(&child))),
-                "grid" => body.push(LayoutBody::grid(GridLayout::from_node(&child))),
-                "unstructured" => body.push(LayoutBody::unstructured(UnstructuredLayout::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of Layout.", t)
+                "random" => body.push(LayoutBody::random(RandomLayout::from_node(&child)?)),
+                "grid" => body.push(LayoutBody::grid(GridLayout::from_node(&child)?)),
+                "unstructured" => body.push(LayoutBody::unstructured(UnstructuredLayout::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "Layout")),
             };
         }
-        Layout {
+        Ok(Layout {
             space,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if let Some(x) = &self.space { write_attr(out, "space", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -1814,9 +3079,20 @@ pub enum SpikeGeneratorPoissonBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl SpikeGeneratorPoissonBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            SpikeGeneratorPoissonBody::notes(x) => x.to_xml("notes", depth, out),
+            SpikeGeneratorPoissonBody::property(x) => x.to_xml("property", depth, out),
+            SpikeGeneratorPoissonBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct SpikeGeneratorPoisson {
-    pub averageRate: String,
+    pub averageRate: Nml2Quantity,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -1824,29 +3100,46 @@ pub struct SpikeGeneratorPoisson {
 }
 
 impl XML for SpikeGeneratorPoisson {
-    fn from_node(node: &Node) -> Self {
-        let averageRate = node.attribute("averageRate").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let averageRate = req_attr::<Nml2Quantity>(node, "averageRate")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(SpikeGeneratorPoissonBody::notes(String::from_node(&child))),
-                "property" => body.push(SpikeGeneratorPoissonBody::property(Property::from_node(&child))),
-                "annotation" => body.push(SpikeGeneratorPoissonBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of SpikeGeneratorPoisson.", t)
+                "notes" => body.push(SpikeGeneratorPoissonBody::notes(String::from_node(&child)?)),
+                "property" => body.push(SpikeGeneratorPoissonBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(SpikeGeneratorPoissonBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "SpikeGeneratorPoisson")),
             };
         }
-        SpikeGeneratorPoisson {
+        Ok(SpikeGeneratorPoisson {
             averageRate,
             metaid,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "averageRate", &self.averageRate);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -1856,10 +3149,19 @@ pub enum ChannelDensityNonUniformBody {
     variableParameter(VariableParameter),
 }
 
+#[allow(dead_code)]
+impl ChannelDensityNonUniformBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            ChannelDensityNonUniformBody::variableParameter(x) => x.to_xml("variableParameter", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ChannelDensityNonUniform {
     pub ionChannel: String,
-    pub erev: String,
+    pub erev: Nml2Quantity,
     pub ion: String,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -1867,29 +3169,47 @@ pub struct ChannelDensityNonUniform {
 }
 
 impl XML for ChannelDensityNonUniform {
-    fn from_node(node: &Node) -> Self {
-        let ionChannel = node.attribute("ionChannel").map(|s| s.to_string()).unwrap();
-        let erev = node.attribute("erev").map(|s| s.to_string()).unwrap();
-        let ion = node.attribute("ion").map(|s| s.to_string()).unwrap();
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let ionChannel = req_attr::<String>(node, "ionChannel")?;
+        let erev = req_attr::<Nml2Quantity>(node, "erev")?;
+        let ion = req_attr::<String>(node, "ion")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "variableParameter" => body.push(ChannelDensityNonUniformBody::variableParameter(VariableParameter::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of ChannelDensityNonUniform.", t)
+                "variableParameter" => body.push(ChannelDensityNonUniformBody::variableParameter(VariableParameter::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "ChannelDensityNonUniform")),
             };
         }
-        ChannelDensityNonUniform {
+        Ok(ChannelDensityNonUniform {
             ionChannel,
             erev,
             ion,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "ionChannel", &self.ionChannel);
+        write_attr(out, "erev", &self.erev);
+        write_attr(out, "ion", &self.ion);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -1901,12 +3221,23 @@ pub enum IafTauCellBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl IafTauCellBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            IafTauCellBody::notes(x) => x.to_xml("notes", depth, out),
+            IafTauCellBody::property(x) => x.to_xml("property", depth, out),
+            IafTauCellBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct IafTauCell {
-    pub leakReversal: String,
-    pub thresh: String,
-    pub reset: String,
-    pub tau: String,
+    pub leakReversal: Nml2Quantity,
+    pub thresh: Nml2Quantity,
+    pub reset: Nml2Quantity,
+    pub tau: Nml2Quantity,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -1914,27 +3245,27 @@ pub struct IafTauCell {
 }
 
 impl XML for IafTauCell {
-    fn from_node(node: &Node) -> Self {
-        let leakReversal = node.attribute("leakReversal").map(|s| s.to_string()).unwrap();
-        let thresh = node.attribute("thresh").map(|s| s.to_string()).unwrap();
-        let reset = node.attribute("reset").map(|s| s.to_string()).unwrap();
-        let tau = node.attribute("tau").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let leakReversal = req_attr::<Nml2Quantity>(node, "leakReversal")?;
+        let thresh = req_attr::<Nml2Quantity>(node, "thresh")?;
+        let reset = req_attr::<Nml2Quantity>(node, "reset")?;
+        let tau = req_attr::<Nml2Quantity>(node, "tau")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(IafTauCellBody::notes(String::from_node(&child))),
-                "property" => body.push(IafTauCellBody::property(Property::from_node(&child))),
-                "annotation" => body.push(IafTauCellBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of IafTauCell.", t)
+                "notes" => body.push(IafTauCellBody::notes(String::from_node(&child)?)),
+                "property" => body.push(IafTauCellBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(IafTauCellBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "IafTauCell")),
             };
         }
-        IafTauCell {
+        Ok(IafTauCell {
             leakReversal,
             thresh,
             reset,
@@ -1943,6 +3274,26 @@ impl XML for IafTauCell {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "leakReversal", &self.leakReversal);
+        write_attr(out, "thresh", &self.thresh);
+        write_attr(out, "reset", &self.reset);
+        write_attr(out, "tau", &self.tau);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -1955,38 +3306,55 @@ pub struct GridLayout {
 }
 
 impl XML for GridLayout {
-    fn from_node(node: &Node) -> Self {
-        let xSize = node.attribute("xSize").map(|s| s.parse::<i64>().unwrap());
-        let ySize = node.attribute("ySize").map(|s| s.parse::<i64>().unwrap());
-        let zSize = node.attribute("zSize").map(|s| s.parse::<i64>().unwrap());
-        GridLayout {
+    fn from_node(node: &Node) -> Result<Self> {
+        let xSize = opt_attr::<i64>(node, "xSize")?;
+        let ySize = opt_attr::<i64>(node, "ySize")?;
+        let zSize = opt_attr::<i64>(node, "zSize")?;
+        Ok(GridLayout {
             xSize,
             ySize,
             zSize,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if let Some(x) = &self.xSize { write_attr(out, "xSize", x); }
+        if let Some(x) = &self.ySize { write_attr(out, "ySize", x); }
+        if let Some(x) = &self.zSize { write_attr(out, "zSize", x); }
+        out.push_str("/>\n");
     }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct HHRate {
     pub r#type: String,
-    pub rate: Option<String>,
-    pub midpoint: Option<String>,
-    pub scale: Option<String>,
+    pub rate: Option<Nml2Quantity>,
+    pub midpoint: Option<Nml2Quantity>,
+    pub scale: Option<Nml2Quantity>,
 }
 
 impl XML for HHRate {
-    fn from_node(node: &Node) -> Self {
-        let r#type = node.attribute("type").map(|s| s.to_string()).unwrap();
-        let rate = node.attribute("rate").map(|s| s.to_string());
-        let midpoint = node.attribute("midpoint").map(|s| s.to_string());
-        let scale = node.attribute("scale").map(|s| s.to_string());
-        HHRate {
+    fn from_node(node: &Node) -> Result<Self> {
+        let r#type = req_attr::<String>(node, "type")?;
+        let rate = opt_attr::<Nml2Quantity>(node, "rate")?;
+        let midpoint = opt_attr::<Nml2Quantity>(node, "midpoint")?;
+        let scale = opt_attr::<Nml2Quantity>(node, "scale")?;
+        Ok(HHRate {
             r#type,
             rate,
             midpoint,
             scale,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "type", &self.r#type);
+        if let Some(x) = &self.rate { write_attr(out, "rate", x); }
+        if let Some(x) = &self.midpoint { write_attr(out, "midpoint", x); }
+        if let Some(x) = &self.scale { write_attr(out, "scale", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -2008,10 +3376,32 @@ pub enum NetworkBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl NetworkBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            NetworkBody::space(x) => x.to_xml("space", depth, out),
+            NetworkBody::region(x) => x.to_xml("region", depth, out),
+            NetworkBody::extracellularProperties(x) => x.to_xml("extracellularProperties", depth, out),
+            NetworkBody::population(x) => x.to_xml("population", depth, out),
+            NetworkBody::cellSet(x) => x.to_xml("cellSet", depth, out),
+            NetworkBody::synapticConnection(x) => x.to_xml("synapticConnection", depth, out),
+            NetworkBody::projection(x) => x.to_xml("projection", depth, out),
+            NetworkBody::electricalProjection(x) => x.to_xml("electricalProjection", depth, out),
+            NetworkBody::continuousProjection(x) => x.to_xml("continuousProjection", depth, out),
+            NetworkBody::explicitInput(x) => x.to_xml("explicitInput", depth, out),
+            NetworkBody::inputList(x) => x.to_xml("inputList", depth, out),
+            NetworkBody::notes(x) => x.to_xml("notes", depth, out),
+            NetworkBody::property(x) => x.to_xml("property", depth, out),
+            NetworkBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Network {
-    pub r#type: Option<String>,
-    pub temperature: Option<String>,
+    pub r#type: Option<networkTypes>,
+    pub temperature: Option<Nml2Quantity>,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -2019,42 +3409,60 @@ pub struct Network {
 }
 
 impl XML for Network {
-    fn from_node(node: &Node) -> Self {
-        let r#type = node.attribute("type").map(|s| s.to_string());
-        let temperature = node.attribute("temperature").map(|s| s.to_string());
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let r#type = opt_attr::<networkTypes>(node, "type")?;
+        let temperature = opt_attr::<Nml2Quantity>(node, "temperature")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "space" => body.push(NetworkBody::space(Space::from_node(&child))),
-                "region" => body.push(NetworkBody::region(Region::from_node(&child))),
-                "extracellularProperties" => body.push(NetworkBody::extracellularProperties(ExtracellularPropertiesLocal::from_node(&child))),
-                "population" => body.push(NetworkBody::population(Population::from_node(&child))),
-                "cellSet" => body.push(NetworkBody::cellSet(CellSet::from_node(&child))),
-                "synapticConnection" => body.push(NetworkBody::synapticConnection(SynapticConnection::from_node(&child))),
-                "projection" => body.push(NetworkBody::projection(Projection::from_node(&child))),
-                "electricalProjection" => body.push(NetworkBody::electricalProjection(ElectricalProjection::from_node(&child))),
-                "continuousProjection" => body.push(NetworkBody::continuousProjection(ContinuousProjection::from_node(&child))),
-                "explicitInput" => body.push(NetworkBody::explicitInput(ExplicitInput::from_node(&child))),
-                "inputList" => body.push(NetworkBody::inputList(InputList::from_node(&child))),
-                "notes" => body.push(NetworkBody::notes(String::from_node(&child))),
-                "property" => body.push(NetworkBody::property(Property::from_node(&child))),
-                "annotation" => body.push(NetworkBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of Network.", t)
+                "space" => body.push(NetworkBody::space(Space::from_node(&child)?)),
+                "region" => body.push(NetworkBody::region(Region::from_node(&child)?)),
+                "extracellularProperties" => body.push(NetworkBody::extracellularProperties(ExtracellularPropertiesLocal::from_node(&child)?)),
+                "population" => body.push(NetworkBody::population(Population::from_node(&child)?)),
+                "cellSet" => body.push(NetworkBody::cellSet(CellSet::from_node(&child)?)),
+                "synapticConnection" => body.push(NetworkBody::synapticConnection(SynapticConnection::from_node(&child)?)),
+                "projection" => body.push(NetworkBody::projection(Projection::from_node(&child)?)),
+                "electricalProjection" => body.push(NetworkBody::electricalProjection(ElectricalProjection::from_node(&child)?)),
+                "continuousProjection" => body.push(NetworkBody::continuousProjection(ContinuousProjection::from_node(&child)?)),
+                "explicitInput" => body.push(NetworkBody::explicitInput(ExplicitInput::from_node(&child)?)),
+                "inputList" => body.push(NetworkBody::inputList(InputList::from_node(&child)?)),
+                "notes" => body.push(NetworkBody::notes(String::from_node(&child)?)),
+                "property" => body.push(NetworkBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(NetworkBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "Network")),
             };
         }
-        Network {
+        Ok(Network {
             r#type,
             temperature,
             metaid,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if let Some(x) = &self.r#type { write_attr(out, "type", x); }
+        if let Some(x) = &self.temperature { write_attr(out, "temperature", x); }
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -2066,14 +3474,25 @@ pub enum ExpThreeSynapseBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl ExpThreeSynapseBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            ExpThreeSynapseBody::notes(x) => x.to_xml("notes", depth, out),
+            ExpThreeSynapseBody::property(x) => x.to_xml("property", depth, out),
+            ExpThreeSynapseBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ExpThreeSynapse {
-    pub tauDecay1: String,
-    pub tauDecay2: String,
-    pub tauRise: String,
-    pub gbase1: String,
-    pub gbase2: String,
-    pub erev: String,
+    pub tauDecay1: Nml2Quantity,
+    pub tauDecay2: Nml2Quantity,
+    pub tauRise: Nml2Quantity,
+    pub gbase1: Nml2Quantity,
+    pub gbase2: Nml2Quantity,
+    pub erev: Nml2Quantity,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -2081,29 +3500,29 @@ pub struct ExpThreeSynapse {
 }
 
 impl XML for ExpThreeSynapse {
-    fn from_node(node: &Node) -> Self {
-        let tauDecay1 = node.attribute("tauDecay1").map(|s| s.to_string()).unwrap();
-        let tauDecay2 = node.attribute("tauDecay2").map(|s| s.to_string()).unwrap();
-        let tauRise = node.attribute("tauRise").map(|s| s.to_string()).unwrap();
-        let gbase1 = node.attribute("gbase1").map(|s| s.to_string()).unwrap();
-        let gbase2 = node.attribute("gbase2").map(|s| s.to_string()).unwrap();
-        let erev = node.attribute("erev").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let tauDecay1 = req_attr::<Nml2Quantity>(node, "tauDecay1")?;
+        let tauDecay2 = req_attr::<Nml2Quantity>(node, "tauDecay2")?;
+        let tauRise = req_attr::<Nml2Quantity>(node, "tauRise")?;
+        let gbase1 = req_attr::<Nml2Quantity>(node, "gbase1")?;
+        let gbase2 = req_attr::<Nml2Quantity>(node, "gbase2")?;
+        let erev = req_attr::<Nml2Quantity>(node, "erev")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(ExpThreeSynapseBody::notes(String::from_node(&child))),
-                "property" => body.push(ExpThreeSynapseBody::property(Property::from_node(&child))),
-                "annotation" => body.push(ExpThreeSynapseBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of ExpThreeSynapse.", t)
+                "notes" => body.push(ExpThreeSynapseBody::notes(String::from_node(&child)?)),
+                "property" => body.push(ExpThreeSynapseBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(ExpThreeSynapseBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "ExpThreeSynapse")),
             };
         }
-        ExpThreeSynapse {
+        Ok(ExpThreeSynapse {
             tauDecay1,
             tauDecay2,
             tauRise,
@@ -2114,24 +3533,53 @@ impl XML for ExpThreeSynapse {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "tauDecay1", &self.tauDecay1);
+        write_attr(out, "tauDecay2", &self.tauDecay2);
+        write_attr(out, "tauRise", &self.tauRise);
+        write_attr(out, "gbase1", &self.gbase1);
+        write_attr(out, "gbase2", &self.gbase2);
+        write_attr(out, "erev", &self.erev);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Resistivity {
-    pub value: String,
+    pub value: Nml2Quantity,
     pub segmentGroup: String,
 }
 
 impl XML for Resistivity {
-    fn from_node(node: &Node) -> Self {
-        let value = node.attribute("value").map(|s| s.to_string()).unwrap();
-        let segmentGroup = node.attribute("segmentGroup").or(Some("all")).map(|s| s.to_string()).unwrap();
-        Resistivity {
+    fn from_node(node: &Node) -> Result<Self> {
+        let value = req_attr::<Nml2Quantity>(node, "value")?;
+        let segmentGroup = def_attr::<String>(node, "segmentGroup", "all")?;
+        Ok(Resistivity {
             value,
             segmentGroup,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "value", &self.value);
+        write_attr(out, "segmentGroup", &self.segmentGroup);
+        out.push_str("/>\n");
     }
 }
 
@@ -2147,6 +3595,22 @@ pub enum SegmentGroupBody {
     inhomogeneousParameter(InhomogeneousParameter),
 }
 
+#[allow(dead_code)]
+impl SegmentGroupBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            SegmentGroupBody::notes(x) => x.to_xml("notes", depth, out),
+            SegmentGroupBody::property(x) => x.to_xml("property", depth, out),
+            SegmentGroupBody::annotation(x) => x.to_xml("annotation", depth, out),
+            SegmentGroupBody::member(x) => x.to_xml("member", depth, out),
+            SegmentGroupBody::include(x) => x.to_xml("include", depth, out),
+            SegmentGroupBody::path(x) => x.to_xml("path", depth, out),
+            SegmentGroupBody::subTree(x) => x.to_xml("subTree", depth, out),
+            SegmentGroupBody::inhomogeneousParameter(x) => x.to_xml("inhomogeneousParameter", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct SegmentGroup {
     pub id: String,
@@ -2155,30 +3619,45 @@ pub struct SegmentGroup {
 }
 
 impl XML for SegmentGroup {
-    fn from_node(node: &Node) -> Self {
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(SegmentGroupBody::notes(String::from_node(&child))),
-                "property" => body.push(SegmentGroupBody::property(Property::from_node(&child))),
-                "annotation" => body.push(SegmentGroupBody::annotation(Annotation::from_node(&child))),
-                "member" => body.push(SegmentGroupBody::member(Member::from_node(&child))),
-                "include" => body.push(SegmentGroupBody::include(Include::from_node(&child))),
-                "path" => body.push(SegmentGroupBody::path(Path::from_node(&child))),
-                "subTree" => body.push(SegmentGroupBody::subTree(SubTree::from_node(&child))),
-                "inhomogeneousParameter" => body.push(SegmentGroupBody::inhomogeneousParameter(InhomogeneousParameter::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of SegmentGroup.", t)
+                "notes" => body.push(SegmentGroupBody::notes(String::from_node(&child)?)),
+                "property" => body.push(SegmentGroupBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(SegmentGroupBody::annotation(Annotation::from_node(&child)?)),
+                "member" => body.push(SegmentGroupBody::member(Member::from_node(&child)?)),
+                "include" => body.push(SegmentGroupBody::include(Include::from_node(&child)?)),
+                "path" => body.push(SegmentGroupBody::path(Path::from_node(&child)?)),
+                "subTree" => body.push(SegmentGroupBody::subTree(SubTree::from_node(&child)?)),
+                "inhomogeneousParameter" => body.push(SegmentGroupBody::inhomogeneousParameter(InhomogeneousParameter::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "SegmentGroup")),
             };
         }
-        SegmentGroup {
+        Ok(SegmentGroup {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -2191,15 +3670,23 @@ pub struct Exposure {
 }
 
 impl XML for Exposure {
-    fn from_node(node: &Node) -> Self {
-        let name = node.attribute("name").map(|s| s.to_string()).unwrap();
-        let dimension = node.attribute("dimension").map(|s| s.to_string()).unwrap();
-        let description = node.attribute("description").map(|s| s.to_string());
-        Exposure {
+    fn from_node(node: &Node) -> Result<Self> {
+        let name = req_attr::<String>(node, "name")?;
+        let dimension = req_attr::<String>(node, "dimension")?;
+        let description = opt_attr::<String>(node, "description")?;
+        Ok(Exposure {
             name,
             dimension,
             description,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "name", &self.name);
+        write_attr(out, "dimension", &self.dimension);
+        if let Some(x) = &self.description { write_attr(out, "description", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -2210,9 +3697,20 @@ pub enum GapJunctionBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl GapJunctionBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            GapJunctionBody::notes(x) => x.to_xml("notes", depth, out),
+            GapJunctionBody::property(x) => x.to_xml("property", depth, out),
+            GapJunctionBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct GapJunction {
-    pub conductance: String,
+    pub conductance: Nml2Quantity,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -2220,29 +3718,46 @@ pub struct GapJunction {
 }
 
 impl XML for GapJunction {
-    fn from_node(node: &Node) -> Self {
-        let conductance = node.attribute("conductance").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let conductance = req_attr::<Nml2Quantity>(node, "conductance")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(GapJunctionBody::notes(String::from_node(&child))),
-                "property" => body.push(GapJunctionBody::property(Property::from_node(&child))),
-                "annotation" => body.push(GapJunctionBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of GapJunction.", t)
+                "notes" => body.push(GapJunctionBody::notes(String::from_node(&child)?)),
+                "property" => body.push(GapJunctionBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(GapJunctionBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "GapJunction")),
             };
         }
-        GapJunction {
+        Ok(GapJunction {
             conductance,
             metaid,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "conductance", &self.conductance);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -2256,6 +3771,19 @@ pub enum GateHHRatesTauBody {
     timeCourse(HHTime),
 }
 
+#[allow(dead_code)]
+impl GateHHRatesTauBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            GateHHRatesTauBody::notes(x) => x.to_xml("notes", depth, out),
+            GateHHRatesTauBody::q10Settings(x) => x.to_xml("q10Settings", depth, out),
+            GateHHRatesTauBody::forwardRate(x) => x.to_xml("forwardRate", depth, out),
+            GateHHRatesTauBody::reverseRate(x) => x.to_xml("reverseRate", depth, out),
+            GateHHRatesTauBody::timeCourse(x) => x.to_xml("timeCourse", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct GateHHRatesTau {
     pub instances: i64,
@@ -2265,29 +3793,45 @@ pub struct GateHHRatesTau {
 }
 
 impl XML for GateHHRatesTau {
-    fn from_node(node: &Node) -> Self {
-        let instances = node.attribute("instances").map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let instances = req_attr::<i64>(node, "instances")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(GateHHRatesTauBody::notes(String::from_node(&child))),
-                "q10Settings" => body.push(GateHHRatesTauBody::q10Settings(Q10Settings::from_node(&child))),
-                "forwardRate" => body.push(GateHHRatesTauBody::forwardRate(HHRate::from_node(&child))),
-                "reverseRate" => body.push(GateHHRatesTauBody::reverseRate(HHRate::from_node(&child))),
-                "timeCourse" => body.push(GateHHRatesTauBody::timeCourse(HHTime::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of GateHHRatesTau.", t)
+                "notes" => body.push(GateHHRatesTauBody::notes(String::from_node(&child)?)),
+                "q10Settings" => body.push(GateHHRatesTauBody::q10Settings(Q10Settings::from_node(&child)?)),
+                "forwardRate" => body.push(GateHHRatesTauBody::forwardRate(HHRate::from_node(&child)?)),
+                "reverseRate" => body.push(GateHHRatesTauBody::reverseRate(HHRate::from_node(&child)?)),
+                "timeCourse" => body.push(GateHHRatesTauBody::timeCourse(HHTime::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "GateHHRatesTau")),
             };
         }
-        GateHHRatesTau {
+        Ok(GateHHRatesTau {
             instances,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "instances", &self.instances);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -2299,13 +3843,24 @@ pub enum RampGeneratorBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl RampGeneratorBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            RampGeneratorBody::notes(x) => x.to_xml("notes", depth, out),
+            RampGeneratorBody::property(x) => x.to_xml("property", depth, out),
+            RampGeneratorBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct RampGenerator {
-    pub delay: String,
-    pub duration: String,
-    pub startAmplitude: String,
-    pub finishAmplitude: String,
-    pub baselineAmplitude: String,
+    pub delay: Nml2Quantity,
+    pub duration: Nml2Quantity,
+    pub startAmplitude: Nml2Quantity,
+    pub finishAmplitude: Nml2Quantity,
+    pub baselineAmplitude: Nml2Quantity,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -2313,28 +3868,28 @@ pub struct RampGenerator {
 }
 
 impl XML for RampGenerator {
-    fn from_node(node: &Node) -> Self {
-        let delay = node.attribute("delay").map(|s| s.to_string()).unwrap();
-        let duration = node.attribute("duration").map(|s| s.to_string()).unwrap();
-        let startAmplitude = node.attribute("startAmplitude").map(|s| s.to_string()).unwrap();
-        let finishAmplitude = node.attribute("finishAmplitude").map(|s| s.to_string()).unwrap();
-        let baselineAmplitude = node.attribute("baselineAmplitude").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let delay = req_attr::<Nml2Quantity>(node, "delay")?;
+        let duration = req_attr::<Nml2Quantity>(node, "duration")?;
+        let startAmplitude = req_attr::<Nml2Quantity>(node, "startAmplitude")?;
+        let finishAmplitude = req_attr::<Nml2Quantity>(node, "finishAmplitude")?;
+        let baselineAmplitude = req_attr::<Nml2Quantity>(node, "baselineAmplitude")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(RampGeneratorBody::notes(String::from_node(&child))),
-                "property" => body.push(RampGeneratorBody::property(Property::from_node(&child))),
-                "annotation" => body.push(RampGeneratorBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of RampGenerator.", t)
+                "notes" => body.push(RampGeneratorBody::notes(String::from_node(&child)?)),
+                "property" => body.push(RampGeneratorBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(RampGeneratorBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "RampGenerator")),
             };
         }
-        RampGenerator {
+        Ok(RampGenerator {
             delay,
             duration,
             startAmplitude,
@@ -2344,6 +3899,27 @@ impl XML for RampGenerator {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "delay", &self.delay);
+        write_attr(out, "duration", &self.duration);
+        write_attr(out, "startAmplitude", &self.startAmplitude);
+        write_attr(out, "finishAmplitude", &self.finishAmplitude);
+        write_attr(out, "baselineAmplitude", &self.baselineAmplitude);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -2355,6 +3931,17 @@ pub enum DoubleSynapseBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl DoubleSynapseBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            DoubleSynapseBody::notes(x) => x.to_xml("notes", depth, out),
+            DoubleSynapseBody::property(x) => x.to_xml("property", depth, out),
+            DoubleSynapseBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct DoubleSynapse {
     pub synapse1: String,
@@ -2368,27 +3955,27 @@ pub struct DoubleSynapse {
 }
 
 impl XML for DoubleSynapse {
-    fn from_node(node: &Node) -> Self {
-        let synapse1 = node.attribute("synapse1").map(|s| s.to_string()).unwrap();
-        let synapse2 = node.attribute("synapse2").map(|s| s.to_string()).unwrap();
-        let synapse1Path = node.attribute("synapse1Path").map(|s| s.to_string()).unwrap();
-        let synapse2Path = node.attribute("synapse2Path").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let synapse1 = req_attr::<String>(node, "synapse1")?;
+        let synapse2 = req_attr::<String>(node, "synapse2")?;
+        let synapse1Path = req_attr::<String>(node, "synapse1Path")?;
+        let synapse2Path = req_attr::<String>(node, "synapse2Path")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(DoubleSynapseBody::notes(String::from_node(&child))),
-                "property" => body.push(DoubleSynapseBody::property(Property::from_node(&child))),
-                "annotation" => body.push(DoubleSynapseBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of DoubleSynapse.", t)
+                "notes" => body.push(DoubleSynapseBody::notes(String::from_node(&child)?)),
+                "property" => body.push(DoubleSynapseBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(DoubleSynapseBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "DoubleSynapse")),
             };
         }
-        DoubleSynapse {
+        Ok(DoubleSynapse {
             synapse1,
             synapse2,
             synapse1Path,
@@ -2397,6 +3984,26 @@ impl XML for DoubleSynapse {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "synapse1", &self.synapse1);
+        write_attr(out, "synapse2", &self.synapse2);
+        write_attr(out, "synapse1Path", &self.synapse1Path);
+        write_attr(out, "synapse2Path", &self.synapse2Path);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -2408,13 +4015,20 @@ pub struct Base {
 }
 
 impl XML for Base {
-    fn from_node(node: &Node) -> Self {
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
-        Base {
+    fn from_node(node: &Node) -> Result<Self> {
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
+        Ok(Base {
             id,
             neuroLexId,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -2422,22 +4036,31 @@ impl XML for Base {
 pub struct Constant {
     pub name: String,
     pub dimension: String,
-    pub value: String,
+    pub value: Nml2Quantity,
     pub description: Option<String>,
 }
 
 impl XML for Constant {
-    fn from_node(node: &Node) -> Self {
-        let name = node.attribute("name").map(|s| s.to_string()).unwrap();
-        let dimension = node.attribute("dimension").map(|s| s.to_string()).unwrap();
-        let value = node.attribute("value").map(|s| s.to_string()).unwrap();
-        let description = node.attribute("description").map(|s| s.to_string());
-        Constant {
+    fn from_node(node: &Node) -> Result<Self> {
+        let name = req_attr::<String>(node, "name")?;
+        let dimension = req_attr::<String>(node, "dimension")?;
+        let value = req_attr::<Nml2Quantity>(node, "value")?;
+        let description = opt_attr::<String>(node, "description")?;
+        Ok(Constant {
             name,
             dimension,
             value,
             description,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "name", &self.name);
+        write_attr(out, "dimension", &self.dimension);
+        write_attr(out, "value", &self.value);
+        if let Some(x) = &self.description { write_attr(out, "description", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -2450,6 +4073,19 @@ pub enum CellBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl CellBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            CellBody::morphology(x) => x.to_xml("morphology", depth, out),
+            CellBody::biophysicalProperties(x) => x.to_xml("biophysicalProperties", depth, out),
+            CellBody::notes(x) => x.to_xml("notes", depth, out),
+            CellBody::property(x) => x.to_xml("property", depth, out),
+            CellBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Cell {
     pub morphology: Option<String>,
@@ -2461,33 +4097,51 @@ pub struct Cell {
 }
 
 impl XML for Cell {
-    fn from_node(node: &Node) -> Self {
-        let morphology = node.attribute("morphology").map(|s| s.to_string());
-        let biophysicalProperties = node.attribute("biophysicalProperties").map(|s| s.to_string());
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let morphology = opt_attr::<String>(node, "morphology")?;
+        let biophysicalProperties = opt_attr::<String>(node, "biophysicalProperties")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "morphology" => body.push(CellBody::morphology(Morphology::from_node(&child))),
-                "biophysicalProperties" => body.push(CellBody::biophysicalProperties(BiophysicalProperties::from_node(&child))),
-                "notes" => body.push(CellBody::notes(String::from_node(&child))),
-                "property" => body.push(CellBody::property(Property::from_node(&child))),
-                "annotation" => body.push(CellBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of Cell.", t)
+                "morphology" => body.push(CellBody::morphology(Morphology::from_node(&child)?)),
+                "biophysicalProperties" => body.push(CellBody::biophysicalProperties(BiophysicalProperties::from_node(&child)?)),
+                "notes" => body.push(CellBody::notes(String::from_node(&child)?)),
+                "property" => body.push(CellBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(CellBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "Cell")),
             };
         }
-        Cell {
+        Ok(Cell {
             morphology,
             biophysicalProperties,
             metaid,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if let Some(x) = &self.morphology { write_attr(out, "morphology", x); }
+        if let Some(x) = &self.biophysicalProperties { write_attr(out, "biophysicalProperties", x); }
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -2498,6 +4152,16 @@ pub enum InputListBody {
     inputW(InputW),
 }
 
+#[allow(dead_code)]
+impl InputListBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            InputListBody::input(x) => x.to_xml("input", depth, out),
+            InputListBody::inputW(x) => x.to_xml("inputW", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct InputList {
     pub population: String,
@@ -2508,28 +4172,45 @@ pub struct InputList {
 }
 
 impl XML for InputList {
-    fn from_node(node: &Node) -> Self {
-        let population = node.attribute("population").map(|s| s.to_string()).unwrap();
-        let component = node.attribute("component").map(|s| s.to_string()).unwrap();
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let population = req_attr::<String>(node, "population")?;
+        let component = req_attr::<String>(node, "component")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "input" => body.push(InputListBody::input(Input::from_node(&child))),
-                "inputW" => body.push(InputListBody::inputW(InputW::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of InputList.", t)
+                "input" => body.push(InputListBody::input(Input::from_node(&child)?)),
+                "inputW" => body.push(InputListBody::inputW(InputW::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "InputList")),
             };
         }
-        InputList {
+        Ok(InputList {
             population,
             component,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "population", &self.population);
+        write_attr(out, "component", &self.component);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -2541,18 +4222,29 @@ pub enum Izhikevich2007CellBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl Izhikevich2007CellBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            Izhikevich2007CellBody::notes(x) => x.to_xml("notes", depth, out),
+            Izhikevich2007CellBody::property(x) => x.to_xml("property", depth, out),
+            Izhikevich2007CellBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Izhikevich2007Cell {
-    pub v0: String,
-    pub k: String,
-    pub vr: String,
-    pub vt: String,
-    pub vpeak: String,
-    pub a: String,
-    pub b: String,
-    pub c: String,
-    pub d: String,
-    pub C: String,
+    pub v0: Nml2Quantity,
+    pub k: Nml2Quantity,
+    pub vr: Nml2Quantity,
+    pub vt: Nml2Quantity,
+    pub vpeak: Nml2Quantity,
+    pub a: Nml2Quantity,
+    pub b: Nml2Quantity,
+    pub c: Nml2Quantity,
+    pub d: Nml2Quantity,
+    pub C: Nml2Quantity,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -2560,33 +4252,33 @@ pub struct Izhikevich2007Cell {
 }
 
 impl XML for Izhikevich2007Cell {
-    fn from_node(node: &Node) -> Self {
-        let v0 = node.attribute("v0").map(|s| s.to_string()).unwrap();
-        let k = node.attribute("k").map(|s| s.to_string()).unwrap();
-        let vr = node.attribute("vr").map(|s| s.to_string()).unwrap();
-        let vt = node.attribute("vt").map(|s| s.to_string()).unwrap();
-        let vpeak = node.attribute("vpeak").map(|s| s.to_string()).unwrap();
-        let a = node.attribute("a").map(|s| s.to_string()).unwrap();
-        let b = node.attribute("b").map(|s| s.to_string()).unwrap();
-        let c = node.attribute("c").map(|s| s.to_string()).unwrap();
-        let d = node.attribute("d").map(|s| s.to_string()).unwrap();
-        let C = node.attribute("C").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let v0 = req_attr::<Nml2Quantity>(node, "v0")?;
+        let k = req_attr::<Nml2Quantity>(node, "k")?;
+        let vr = req_attr::<Nml2Quantity>(node, "vr")?;
+        let vt = req_attr::<Nml2Quantity>(node, "vt")?;
+        let vpeak = req_attr::<Nml2Quantity>(node, "vpeak")?;
+        let a = req_attr::<Nml2Quantity>(node, "a")?;
+        let b = req_attr::<Nml2Quantity>(node, "b")?;
+        let c = req_attr::<Nml2Quantity>(node, "c")?;
+        let d = req_attr::<Nml2Quantity>(node, "d")?;
+        let C = req_attr::<Nml2Quantity>(node, "C")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(Izhikevich2007CellBody::notes(String::from_node(&child))),
-                "property" => body.push(Izhikevich2007CellBody::property(Property::from_node(&child))),
-                "annotation" => body.push(Izhikevich2007CellBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of Izhikevich2007Cell.", t)
+                "notes" => body.push(Izhikevich2007CellBody::notes(String::from_node(&child)?)),
+                "property" => body.push(Izhikevich2007CellBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(Izhikevich2007CellBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "Izhikevich2007Cell")),
             };
         }
-        Izhikevich2007Cell {
+        Ok(Izhikevich2007Cell {
             v0,
             k,
             vr,
@@ -2601,6 +4293,32 @@ impl XML for Izhikevich2007Cell {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "v0", &self.v0);
+        write_attr(out, "k", &self.k);
+        write_attr(out, "vr", &self.vr);
+        write_attr(out, "vt", &self.vt);
+        write_attr(out, "vpeak", &self.vpeak);
+        write_attr(out, "a", &self.a);
+        write_attr(out, "b", &self.b);
+        write_attr(out, "c", &self.c);
+        write_attr(out, "d", &self.d);
+        write_attr(out, "C", &self.C);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -2613,15 +4331,23 @@ pub struct Requirement {
 }
 
 impl XML for Requirement {
-    fn from_node(node: &Node) -> Self {
-        let name = node.attribute("name").map(|s| s.to_string()).unwrap();
-        let dimension = node.attribute("dimension").map(|s| s.to_string()).unwrap();
-        let description = node.attribute("description").map(|s| s.to_string());
-        Requirement {
+    fn from_node(node: &Node) -> Result<Self> {
+        let name = req_attr::<String>(node, "name")?;
+        let dimension = req_attr::<String>(node, "dimension")?;
+        let description = opt_attr::<String>(node, "description")?;
+        Ok(Requirement {
             name,
             dimension,
             description,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "name", &self.name);
+        write_attr(out, "dimension", &self.dimension);
+        if let Some(x) = &self.description { write_attr(out, "description", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -2631,26 +4357,49 @@ pub enum IntracellularPropertiesBody {
     resistivity(Resistivity),
 }
 
+#[allow(dead_code)]
+impl IntracellularPropertiesBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            IntracellularPropertiesBody::species(x) => x.to_xml("species", depth, out),
+            IntracellularPropertiesBody::resistivity(x) => x.to_xml("resistivity", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct IntracellularProperties {
     pub body: Vec<IntracellularPropertiesBody>
 }
 
 impl XML for IntracellularProperties {
-    fn from_node(node: &Node) -> Self {
+    fn from_node(node: &Node) -> Result<Self> {
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "species" => body.push(IntracellularPropertiesBody::species(Species::from_node(&child))),
-                "resistivity" => body.push(IntracellularPropertiesBody::resistivity(Resistivity::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of IntracellularProperties.", t)
+                "species" => body.push(IntracellularPropertiesBody::species(Species::from_node(&child)?)),
+                "resistivity" => body.push(IntracellularPropertiesBody::resistivity(Resistivity::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "IntracellularProperties")),
             };
         }
-        IntracellularProperties {
+        Ok(IntracellularProperties {
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -2662,13 +4411,20 @@ pub struct SegmentParent {
 }
 
 impl XML for SegmentParent {
-    fn from_node(node: &Node) -> Self {
-        let segment = node.attribute("segment").map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let fractionAlong = node.attribute("fractionAlong").or(Some("1")).map(|s| s.parse::<f64>().unwrap()).unwrap();
-        SegmentParent {
+    fn from_node(node: &Node) -> Result<Self> {
+        let segment = req_attr::<i64>(node, "segment")?;
+        let fractionAlong = def_attr::<f64>(node, "fractionAlong", "1")?;
+        Ok(SegmentParent {
             segment,
             fractionAlong,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "segment", &self.segment);
+        write_attr(out, "fractionAlong", &self.fractionAlong);
+        out.push_str("/>\n");
     }
 }
 
@@ -2679,6 +4435,17 @@ pub enum basePyNNCellBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl basePyNNCellBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            basePyNNCellBody::notes(x) => x.to_xml("notes", depth, out),
+            basePyNNCellBody::property(x) => x.to_xml("property", depth, out),
+            basePyNNCellBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct basePyNNCell {
     pub cm: f64,
@@ -2693,28 +4460,28 @@ pub struct basePyNNCell {
 }
 
 impl XML for basePyNNCell {
-    fn from_node(node: &Node) -> Self {
-        let cm = node.attribute("cm").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let i_offset = node.attribute("i_offset").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let tau_syn_E = node.attribute("tau_syn_E").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let tau_syn_I = node.attribute("tau_syn_I").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let v_init = node.attribute("v_init").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let cm = req_attr::<f64>(node, "cm")?;
+        let i_offset = req_attr::<f64>(node, "i_offset")?;
+        let tau_syn_E = req_attr::<f64>(node, "tau_syn_E")?;
+        let tau_syn_I = req_attr::<f64>(node, "tau_syn_I")?;
+        let v_init = req_attr::<f64>(node, "v_init")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(basePyNNCellBody::notes(String::from_node(&child))),
-                "property" => body.push(basePyNNCellBody::property(Property::from_node(&child))),
-                "annotation" => body.push(basePyNNCellBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of basePyNNCell.", t)
+                "notes" => body.push(basePyNNCellBody::notes(String::from_node(&child)?)),
+                "property" => body.push(basePyNNCellBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(basePyNNCellBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "basePyNNCell")),
             };
         }
-        basePyNNCell {
+        Ok(basePyNNCell {
             cm,
             i_offset,
             tau_syn_E,
@@ -2724,6 +4491,27 @@ impl XML for basePyNNCell {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "cm", &self.cm);
+        write_attr(out, "i_offset", &self.i_offset);
+        write_attr(out, "tau_syn_E", &self.tau_syn_E);
+        write_attr(out, "tau_syn_I", &self.tau_syn_I);
+        write_attr(out, "v_init", &self.v_init);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -2735,11 +4523,22 @@ pub enum SpikeSourcePoissonBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl SpikeSourcePoissonBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            SpikeSourcePoissonBody::notes(x) => x.to_xml("notes", depth, out),
+            SpikeSourcePoissonBody::property(x) => x.to_xml("property", depth, out),
+            SpikeSourcePoissonBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct SpikeSourcePoisson {
-    pub start: String,
-    pub duration: String,
-    pub rate: String,
+    pub start: Nml2Quantity,
+    pub duration: Nml2Quantity,
+    pub rate: Nml2Quantity,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -2747,26 +4546,26 @@ pub struct SpikeSourcePoisson {
 }
 
 impl XML for SpikeSourcePoisson {
-    fn from_node(node: &Node) -> Self {
-        let start = node.attribute("start").map(|s| s.to_string()).unwrap();
-        let duration = node.attribute("duration").map(|s| s.to_string()).unwrap();
-        let rate = node.attribute("rate").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let start = req_attr::<Nml2Quantity>(node, "start")?;
+        let duration = req_attr::<Nml2Quantity>(node, "duration")?;
+        let rate = req_attr::<Nml2Quantity>(node, "rate")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(SpikeSourcePoissonBody::notes(String::from_node(&child))),
-                "property" => body.push(SpikeSourcePoissonBody::property(Property::from_node(&child))),
-                "annotation" => body.push(SpikeSourcePoissonBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of SpikeSourcePoisson.", t)
+                "notes" => body.push(SpikeSourcePoissonBody::notes(String::from_node(&child)?)),
+                "property" => body.push(SpikeSourcePoissonBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(SpikeSourcePoissonBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "SpikeSourcePoisson")),
             };
         }
-        SpikeSourcePoisson {
+        Ok(SpikeSourcePoisson {
             start,
             duration,
             rate,
@@ -2774,6 +4573,25 @@ impl XML for SpikeSourcePoisson {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "start", &self.start);
+        write_attr(out, "duration", &self.duration);
+        write_attr(out, "rate", &self.rate);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -2785,10 +4603,21 @@ pub enum SpikeGeneratorRefPoissonBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl SpikeGeneratorRefPoissonBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            SpikeGeneratorRefPoissonBody::notes(x) => x.to_xml("notes", depth, out),
+            SpikeGeneratorRefPoissonBody::property(x) => x.to_xml("property", depth, out),
+            SpikeGeneratorRefPoissonBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct SpikeGeneratorRefPoisson {
-    pub minimumISI: String,
-    pub averageRate: String,
+    pub minimumISI: Nml2Quantity,
+    pub averageRate: Nml2Quantity,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -2796,31 +4625,49 @@ pub struct SpikeGeneratorRefPoisson {
 }
 
 impl XML for SpikeGeneratorRefPoisson {
-    fn from_node(node: &Node) -> Self {
-        let minimumISI = node.attribute("minimumISI").map(|s| s.to_string()).unwrap();
-        let averageRate = node.attribute("averageRate").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let minimumISI = req_attr::<Nml2Quantity>(node, "minimumISI")?;
+        let averageRate = req_attr::<Nml2Quantity>(node, "averageRate")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(SpikeGeneratorRefPoissonBody::notes(String::from_node(&child))),
-                "property" => body.push(SpikeGeneratorRefPoissonBody::property(Property::from_node(&child))),
-                "annotation" => body.push(SpikeGeneratorRefPoissonBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of SpikeGeneratorRefPoisson.", t)
+                "notes" => body.push(SpikeGeneratorRefPoissonBody::notes(String::from_node(&child)?)),
+                "property" => body.push(SpikeGeneratorRefPoissonBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(SpikeGeneratorRefPoissonBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "SpikeGeneratorRefPoisson")),
             };
         }
-        SpikeGeneratorRefPoisson {
+        Ok(SpikeGeneratorRefPoisson {
             minimumISI,
             averageRate,
             metaid,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "minimumISI", &self.minimumISI);
+        write_attr(out, "averageRate", &self.averageRate);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -2830,10 +4677,19 @@ pub enum ChannelDensityNernstBody {
     variableParameter(VariableParameter),
 }
 
+#[allow(dead_code)]
+impl ChannelDensityNernstBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            ChannelDensityNernstBody::variableParameter(x) => x.to_xml("variableParameter", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ChannelDensityNernst {
     pub ionChannel: String,
-    pub condDensity: Option<String>,
+    pub condDensity: Option<Nml2Quantity>,
     pub segmentGroup: String,
     pub segment: Option<String>,
     pub ion: String,
@@ -2843,25 +4699,25 @@ pub struct ChannelDensityNernst {
 }
 
 impl XML for ChannelDensityNernst {
-    fn from_node(node: &Node) -> Self {
-        let ionChannel = node.attribute("ionChannel").map(|s| s.to_string()).unwrap();
-        let condDensity = node.attribute("condDensity").map(|s| s.to_string());
-        let segmentGroup = node.attribute("segmentGroup").or(Some("all")).map(|s| s.to_string()).unwrap();
-        let segment = node.attribute("segment").map(|s| s.to_string());
-        let ion = node.attribute("ion").map(|s| s.to_string()).unwrap();
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let ionChannel = req_attr::<String>(node, "ionChannel")?;
+        let condDensity = opt_attr::<Nml2Quantity>(node, "condDensity")?;
+        let segmentGroup = def_attr::<String>(node, "segmentGroup", "all")?;
+        let segment = opt_attr::<String>(node, "segment")?;
+        let ion = req_attr::<String>(node, "ion")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "variableParameter" => body.push(ChannelDensityNernstBody::variableParameter(VariableParameter::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of ChannelDensityNernst.", t)
+                "variableParameter" => body.push(ChannelDensityNernstBody::variableParameter(VariableParameter::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "ChannelDensityNernst")),
             };
         }
-        ChannelDensityNernst {
+        Ok(ChannelDensityNernst {
             ionChannel,
             condDensity,
             segmentGroup,
@@ -2870,6 +4726,26 @@ impl XML for ChannelDensityNernst {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "ionChannel", &self.ionChannel);
+        if let Some(x) = &self.condDensity { write_attr(out, "condDensity", x); }
+        write_attr(out, "segmentGroup", &self.segmentGroup);
+        if let Some(x) = &self.segment { write_attr(out, "segment", x); }
+        write_attr(out, "ion", &self.ion);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -2882,15 +4758,23 @@ pub struct NamedDimensionalType {
 }
 
 impl XML for NamedDimensionalType {
-    fn from_node(node: &Node) -> Self {
-        let name = node.attribute("name").map(|s| s.to_string()).unwrap();
-        let dimension = node.attribute("dimension").map(|s| s.to_string()).unwrap();
-        let description = node.attribute("description").map(|s| s.to_string());
-        NamedDimensionalType {
+    fn from_node(node: &Node) -> Result<Self> {
+        let name = req_attr::<String>(node, "name")?;
+        let dimension = req_attr::<String>(node, "dimension")?;
+        let description = opt_attr::<String>(node, "description")?;
+        Ok(NamedDimensionalType {
             name,
             dimension,
             description,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "name", &self.name);
+        write_attr(out, "dimension", &self.dimension);
+        if let Some(x) = &self.description { write_attr(out, "description", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -2901,13 +4785,20 @@ pub struct TimeDerivative {
 }
 
 impl XML for TimeDerivative {
-    fn from_node(node: &Node) -> Self {
-        let variable = node.attribute("variable").map(|s| s.to_string()).unwrap();
-        let value = node.attribute("value").map(|s| s.to_string()).unwrap();
-        TimeDerivative {
+    fn from_node(node: &Node) -> Result<Self> {
+        let variable = req_attr::<String>(node, "variable")?;
+        let value = req_attr::<String>(node, "value")?;
+        Ok(TimeDerivative {
             variable,
             value,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "variable", &self.variable);
+        write_attr(out, "value", &self.value);
+        out.push_str("/>\n");
     }
 }
 
@@ -2917,26 +4808,49 @@ pub enum IntracellularProperties2CaPoolsBody {
     resistivity(Resistivity),
 }
 
+#[allow(dead_code)]
+impl IntracellularProperties2CaPoolsBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            IntracellularProperties2CaPoolsBody::species(x) => x.to_xml("species", depth, out),
+            IntracellularProperties2CaPoolsBody::resistivity(x) => x.to_xml("resistivity", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct IntracellularProperties2CaPools {
     pub body: Vec<IntracellularProperties2CaPoolsBody>
 }
 
 impl XML for IntracellularProperties2CaPools {
-    fn from_node(node: &Node) -> Self {
+    fn from_node(node: &Node) -> Result<Self> {
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "species" => body.push(IntracellularProperties2CaPoolsBody::species(Species::from_node(&child))),
-                "resistivity" => body.push(IntracellularProperties2CaPoolsBody::resistivity(Resistivity::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of IntracellularProperties2CaPools.", t)
+                "species" => body.push(IntracellularProperties2CaPoolsBody::species(Species::from_node(&child)?)),
+                "resistivity" => body.push(IntracellularProperties2CaPoolsBody::resistivity(Resistivity::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "IntracellularProperties2CaPools")),
             };
         }
-        IntracellularProperties2CaPools {
+        Ok(IntracellularProperties2CaPools {
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -2949,15 +4863,23 @@ pub struct Location {
 }
 
 impl XML for Location {
-    fn from_node(node: &Node) -> Self {
-        let x = node.attribute("x").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let y = node.attribute("y").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let z = node.attribute("z").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        Location {
+    fn from_node(node: &Node) -> Result<Self> {
+        let x = req_attr::<f64>(node, "x")?;
+        let y = req_attr::<f64>(node, "y")?;
+        let z = req_attr::<f64>(node, "z")?;
+        Ok(Location {
             x,
             y,
             z,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "x", &self.x);
+        write_attr(out, "y", &self.y);
+        write_attr(out, "z", &self.z);
+        out.push_str("/>\n");
     }
 }
 
@@ -2968,6 +4890,17 @@ pub enum BaseVoltageDepSynapseBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl BaseVoltageDepSynapseBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            BaseVoltageDepSynapseBody::notes(x) => x.to_xml("notes", depth, out),
+            BaseVoltageDepSynapseBody::property(x) => x.to_xml("property", depth, out),
+            BaseVoltageDepSynapseBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct BaseVoltageDepSynapse {
     pub metaid: Option<String>,
@@ -2977,27 +4910,43 @@ pub struct BaseVoltageDepSynapse {
 }
 
 impl XML for BaseVoltageDepSynapse {
-    fn from_node(node: &Node) -> Self {
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(BaseVoltageDepSynapseBody::notes(String::from_node(&child))),
-                "property" => body.push(BaseVoltageDepSynapseBody::property(Property::from_node(&child))),
-                "annotation" => body.push(BaseVoltageDepSynapseBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of BaseVoltageDepSynapse.", t)
+                "notes" => body.push(BaseVoltageDepSynapseBody::notes(String::from_node(&child)?)),
+                "property" => body.push(BaseVoltageDepSynapseBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(BaseVoltageDepSynapseBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "BaseVoltageDepSynapse")),
             };
         }
-        BaseVoltageDepSynapse {
+        Ok(BaseVoltageDepSynapse {
             metaid,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -3009,6 +4958,17 @@ pub enum AlphaCondSynapseBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl AlphaCondSynapseBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            AlphaCondSynapseBody::notes(x) => x.to_xml("notes", depth, out),
+            AlphaCondSynapseBody::property(x) => x.to_xml("property", depth, out),
+            AlphaCondSynapseBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct AlphaCondSynapse {
     pub e_rev: f64,
@@ -3020,31 +4980,49 @@ pub struct AlphaCondSynapse {
 }
 
 impl XML for AlphaCondSynapse {
-    fn from_node(node: &Node) -> Self {
-        let e_rev = node.attribute("e_rev").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let tau_syn = node.attribute("tau_syn").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let e_rev = req_attr::<f64>(node, "e_rev")?;
+        let tau_syn = req_attr::<f64>(node, "tau_syn")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(AlphaCondSynapseBody::notes(String::from_node(&child))),
-                "property" => body.push(AlphaCondSynapseBody::property(Property::from_node(&child))),
-                "annotation" => body.push(AlphaCondSynapseBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of AlphaCondSynapse.", t)
+                "notes" => body.push(AlphaCondSynapseBody::notes(String::from_node(&child)?)),
+                "property" => body.push(AlphaCondSynapseBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(AlphaCondSynapseBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "AlphaCondSynapse")),
             };
         }
-        AlphaCondSynapse {
+        Ok(AlphaCondSynapse {
             e_rev,
             tau_syn,
             metaid,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "e_rev", &self.e_rev);
+        write_attr(out, "tau_syn", &self.tau_syn);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -3056,6 +5034,17 @@ pub enum BaseSynapseBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl BaseSynapseBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            BaseSynapseBody::notes(x) => x.to_xml("notes", depth, out),
+            BaseSynapseBody::property(x) => x.to_xml("property", depth, out),
+            BaseSynapseBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct BaseSynapse {
     pub metaid: Option<String>,
@@ -3065,27 +5054,43 @@ pub struct BaseSynapse {
 }
 
 impl XML for BaseSynapse {
-    fn from_node(node: &Node) -> Self {
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(BaseSynapseBody::notes(String::from_node(&child))),
-                "property" => body.push(BaseSynapseBody::property(Property::from_node(&child))),
-                "annotation" => body.push(BaseSynapseBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of BaseSynapse.", t)
+                "notes" => body.push(BaseSynapseBody::notes(String::from_node(&child)?)),
+                "property" => body.push(BaseSynapseBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(BaseSynapseBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "BaseSynapse")),
             };
         }
-        BaseSynapse {
+        Ok(BaseSynapse {
             metaid,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -3098,10 +5103,22 @@ pub enum IonChannelKSBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl IonChannelKSBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            IonChannelKSBody::gateKS(x) => x.to_xml("gateKS", depth, out),
+            IonChannelKSBody::notes(x) => x.to_xml("notes", depth, out),
+            IonChannelKSBody::property(x) => x.to_xml("property", depth, out),
+            IonChannelKSBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct IonChannelKS {
     pub species: Option<String>,
-    pub conductance: Option<String>,
+    pub conductance: Option<Nml2Quantity>,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -3109,32 +5126,50 @@ pub struct IonChannelKS {
 }
 
 impl XML for IonChannelKS {
-    fn from_node(node: &Node) -> Self {
-        let species = node.attribute("species").map(|s| s.to_string());
-        let conductance = node.attribute("conductance").map(|s| s.to_string());
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let species = opt_attr::<String>(node, "species")?;
+        let conductance = opt_attr::<Nml2Quantity>(node, "conductance")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "gateKS" => body.push(IonChannelKSBody::gateKS(GateKS::from_node(&child))),
-                "notes" => body.push(IonChannelKSBody::notes(String::from_node(&child))),
-                "property" => body.push(IonChannelKSBody::property(Property::from_node(&child))),
-                "annotation" => body.push(IonChannelKSBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of IonChannelKS.", t)
+                "gateKS" => body.push(IonChannelKSBody::gateKS(GateKS::from_node(&child)?)),
+                "notes" => body.push(IonChannelKSBody::notes(String::from_node(&child)?)),
+                "property" => body.push(IonChannelKSBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(IonChannelKSBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "IonChannelKS")),
             };
         }
-        IonChannelKS {
+        Ok(IonChannelKS {
             species,
             conductance,
             metaid,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if let Some(x) = &self.species { write_attr(out, "species", x); }
+        if let Some(x) = &self.conductance { write_attr(out, "conductance", x); }
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -3152,16 +5187,16 @@ pub struct BaseConnectionOldFormat {
 }
 
 impl XML for BaseConnectionOldFormat {
-    fn from_node(node: &Node) -> Self {
-        let preCellId = node.attribute("preCellId").map(|s| s.to_string()).unwrap();
-        let preSegmentId = node.attribute("preSegmentId").or(Some("0")).map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let preFractionAlong = node.attribute("preFractionAlong").or(Some("0.5")).map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let postCellId = node.attribute("postCellId").map(|s| s.to_string()).unwrap();
-        let postSegmentId = node.attribute("postSegmentId").or(Some("0")).map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let postFractionAlong = node.attribute("postFractionAlong").or(Some("0.5")).map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let id = node.attribute("id").map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
-        BaseConnectionOldFormat {
+    fn from_node(node: &Node) -> Result<Self> {
+        let preCellId = req_attr::<String>(node, "preCellId")?;
+        let preSegmentId = def_attr::<i64>(node, "preSegmentId", "0")?;
+        let preFractionAlong = def_attr::<f64>(node, "preFractionAlong", "0.5")?;
+        let postCellId = req_attr::<String>(node, "postCellId")?;
+        let postSegmentId = def_attr::<i64>(node, "postSegmentId", "0")?;
+        let postFractionAlong = def_attr::<f64>(node, "postFractionAlong", "0.5")?;
+        let id = req_attr::<i64>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
+        Ok(BaseConnectionOldFormat {
             preCellId,
             preSegmentId,
             preFractionAlong,
@@ -3170,7 +5205,20 @@ impl XML for BaseConnectionOldFormat {
             postFractionAlong,
             id,
             neuroLexId,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "preCellId", &self.preCellId);
+        write_attr(out, "preSegmentId", &self.preSegmentId);
+        write_attr(out, "preFractionAlong", &self.preFractionAlong);
+        write_attr(out, "postCellId", &self.postCellId);
+        write_attr(out, "postSegmentId", &self.postSegmentId);
+        write_attr(out, "postFractionAlong", &self.postFractionAlong);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -3181,15 +5229,26 @@ pub enum VoltageClampTripleBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl VoltageClampTripleBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            VoltageClampTripleBody::notes(x) => x.to_xml("notes", depth, out),
+            VoltageClampTripleBody::property(x) => x.to_xml("property", depth, out),
+            VoltageClampTripleBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct VoltageClampTriple {
     pub active: f64,
-    pub delay: String,
-    pub duration: String,
-    pub conditioningVoltage: String,
-    pub testingVoltage: String,
-    pub returnVoltage: String,
-    pub simpleSeriesResistance: String,
+    pub delay: Nml2Quantity,
+    pub duration: Nml2Quantity,
+    pub conditioningVoltage: Nml2Quantity,
+    pub testingVoltage: Nml2Quantity,
+    pub returnVoltage: Nml2Quantity,
+    pub simpleSeriesResistance: Nml2Quantity,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -3197,30 +5256,30 @@ pub struct VoltageClampTriple {
 }
 
 impl XML for VoltageClampTriple {
-    fn from_node(node: &Node) -> Self {
-        let active = node.attribute("active").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let delay = node.attribute("delay").map(|s| s.to_string()).unwrap();
-        let duration = node.attribute("duration").map(|s| s.to_string()).unwrap();
-        let conditioningVoltage = node.attribute("conditioningVoltage").map(|s| s.to_string()).unwrap();
-        let testingVoltage = node.attribute("testingVoltage").map(|s| s.to_string()).unwrap();
-        let returnVoltage = node.attribute("returnVoltage").map(|s| s.to_string()).unwrap();
-        let simpleSeriesResistance = node.attribute("simpleSeriesResistance").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let active = req_attr::<f64>(node, "active")?;
+        let delay = req_attr::<Nml2Quantity>(node, "delay")?;
+        let duration = req_attr::<Nml2Quantity>(node, "duration")?;
+        let conditioningVoltage = req_attr::<Nml2Quantity>(node, "conditioningVoltage")?;
+        let testingVoltage = req_attr::<Nml2Quantity>(node, "testingVoltage")?;
+        let returnVoltage = req_attr::<Nml2Quantity>(node, "returnVoltage")?;
+        let simpleSeriesResistance = req_attr::<Nml2Quantity>(node, "simpleSeriesResistance")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(VoltageClampTripleBody::notes(String::from_node(&child))),
-                "property" => body.push(VoltageClampTripleBody::property(Property::from_node(&child))),
-                "annotation" => body.push(VoltageClampTripleBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of VoltageClampTriple.", t)
+                "notes" => body.push(VoltageClampTripleBody::notes(String::from_node(&child)?)),
+                "property" => body.push(VoltageClampTripleBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(VoltageClampTripleBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "VoltageClampTriple")),
             };
         }
-        VoltageClampTriple {
+        Ok(VoltageClampTriple {
             active,
             delay,
             duration,
@@ -3232,6 +5291,29 @@ impl XML for VoltageClampTriple {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "active", &self.active);
+        write_attr(out, "delay", &self.delay);
+        write_attr(out, "duration", &self.duration);
+        write_attr(out, "conditioningVoltage", &self.conditioningVoltage);
+        write_attr(out, "testingVoltage", &self.testingVoltage);
+        write_attr(out, "returnVoltage", &self.returnVoltage);
+        write_attr(out, "simpleSeriesResistance", &self.simpleSeriesResistance);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -3243,13 +5325,20 @@ pub struct BaseConnection {
 }
 
 impl XML for BaseConnection {
-    fn from_node(node: &Node) -> Self {
-        let id = node.attribute("id").map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
-        BaseConnection {
+    fn from_node(node: &Node) -> Result<Self> {
+        let id = req_attr::<i64>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
+        Ok(BaseConnection {
             id,
             neuroLexId,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -3260,13 +5349,24 @@ pub enum RampGeneratorDLBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl RampGeneratorDLBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            RampGeneratorDLBody::notes(x) => x.to_xml("notes", depth, out),
+            RampGeneratorDLBody::property(x) => x.to_xml("property", depth, out),
+            RampGeneratorDLBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct RampGeneratorDL {
-    pub delay: String,
-    pub duration: String,
-    pub startAmplitude: String,
-    pub finishAmplitude: String,
-    pub baselineAmplitude: String,
+    pub delay: Nml2Quantity,
+    pub duration: Nml2Quantity,
+    pub startAmplitude: Nml2Quantity,
+    pub finishAmplitude: Nml2Quantity,
+    pub baselineAmplitude: Nml2Quantity,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -3274,28 +5374,28 @@ pub struct RampGeneratorDL {
 }
 
 impl XML for RampGeneratorDL {
-    fn from_node(node: &Node) -> Self {
-        let delay = node.attribute("delay").map(|s| s.to_string()).unwrap();
-        let duration = node.attribute("duration").map(|s| s.to_string()).unwrap();
-        let startAmplitude = node.attribute("startAmplitude").map(|s| s.to_string()).unwrap();
-        let finishAmplitude = node.attribute("finishAmplitude").map(|s| s.to_string()).unwrap();
-        let baselineAmplitude = node.attribute("baselineAmplitude").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let delay = req_attr::<Nml2Quantity>(node, "delay")?;
+        let duration = req_attr::<Nml2Quantity>(node, "duration")?;
+        let startAmplitude = req_attr::<Nml2Quantity>(node, "startAmplitude")?;
+        let finishAmplitude = req_attr::<Nml2Quantity>(node, "finishAmplitude")?;
+        let baselineAmplitude = req_attr::<Nml2Quantity>(node, "baselineAmplitude")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(RampGeneratorDLBody::notes(String::from_node(&child))),
-                "property" => body.push(RampGeneratorDLBody::property(Property::from_node(&child))),
-                "annotation" => body.push(RampGeneratorDLBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of RampGeneratorDL.", t)
+                "notes" => body.push(RampGeneratorDLBody::notes(String::from_node(&child)?)),
+                "property" => body.push(RampGeneratorDLBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(RampGeneratorDLBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "RampGeneratorDL")),
             };
         }
-        RampGeneratorDL {
+        Ok(RampGeneratorDL {
             delay,
             duration,
             startAmplitude,
@@ -3305,6 +5405,27 @@ impl XML for RampGeneratorDL {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "delay", &self.delay);
+        write_attr(out, "duration", &self.duration);
+        write_attr(out, "startAmplitude", &self.startAmplitude);
+        write_attr(out, "finishAmplitude", &self.finishAmplitude);
+        write_attr(out, "baselineAmplitude", &self.baselineAmplitude);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -3316,29 +5437,40 @@ pub enum PinskyRinzelCA3CellBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl PinskyRinzelCA3CellBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            PinskyRinzelCA3CellBody::notes(x) => x.to_xml("notes", depth, out),
+            PinskyRinzelCA3CellBody::property(x) => x.to_xml("property", depth, out),
+            PinskyRinzelCA3CellBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct PinskyRinzelCA3Cell {
-    pub iSoma: String,
-    pub iDend: String,
-    pub gc: String,
-    pub gLs: String,
-    pub gLd: String,
-    pub gNa: String,
-    pub gKdr: String,
-    pub gCa: String,
-    pub gKahp: String,
-    pub gKC: String,
-    pub gNmda: String,
-    pub gAmpa: String,
-    pub eNa: String,
-    pub eCa: String,
-    pub eK: String,
-    pub eL: String,
-    pub qd0: String,
-    pub pp: String,
-    pub alphac: String,
-    pub betac: String,
-    pub cm: String,
+    pub iSoma: Nml2Quantity,
+    pub iDend: Nml2Quantity,
+    pub gc: Nml2Quantity,
+    pub gLs: Nml2Quantity,
+    pub gLd: Nml2Quantity,
+    pub gNa: Nml2Quantity,
+    pub gKdr: Nml2Quantity,
+    pub gCa: Nml2Quantity,
+    pub gKahp: Nml2Quantity,
+    pub gKC: Nml2Quantity,
+    pub gNmda: Nml2Quantity,
+    pub gAmpa: Nml2Quantity,
+    pub eNa: Nml2Quantity,
+    pub eCa: Nml2Quantity,
+    pub eK: Nml2Quantity,
+    pub eL: Nml2Quantity,
+    pub qd0: Nml2Quantity,
+    pub pp: Nml2Quantity,
+    pub alphac: Nml2Quantity,
+    pub betac: Nml2Quantity,
+    pub cm: Nml2Quantity,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -3346,44 +5478,44 @@ pub struct PinskyRinzelCA3Cell {
 }
 
 impl XML for PinskyRinzelCA3Cell {
-    fn from_node(node: &Node) -> Self {
-        let iSoma = node.attribute("iSoma").map(|s| s.to_string()).unwrap();
-        let iDend = node.attribute("iDend").map(|s| s.to_string()).unwrap();
-        let gc = node.attribute("gc").map(|s| s.to_string()).unwrap();
-        let gLs = node.attribute("gLs").map(|s| s.to_string()).unwrap();
-        let gLd = node.attribute("gLd").map(|s| s.to_string()).unwrap();
-        let gNa = node.attribute("gNa").map(|s| s.to_string()).unwrap();
-        let gKdr = node.attribute("gKdr").map(|s| s.to_string()).unwrap();
-        let gCa = node.attribute("gCa").map(|s| s.to_string()).unwrap();
-        let gKahp = node.attribute("gKahp").map(|s| s.to_string()).unwrap();
-        let gKC = node.attribute("gKC").map(|s| s.to_string()).unwrap();
-        let gNmda = node.attribute("gNmda").map(|s| s.to_string()).unwrap();
-        let gAmpa = node.attribute("gAmpa").map(|s| s.to_string()).unwrap();
-        let eNa = node.attribute("eNa").map(|s| s.to_string()).unwrap();
-        let eCa = node.attribute("eCa").map(|s| s.to_string()).unwrap();
-        let eK = node.attribute("eK").map(|s| s.to_string()).unwrap();
-        let eL = node.attribute("eL").map(|s| s.to_string()).unwrap();
-        let qd0 = node.attribute("qd0").map(|s| s.to_string()).unwrap();
-        let pp = node.attribute("pp").map(|s| s.to_string()).unwrap();
-        let alphac = node.attribute("alphac").map(|s| s.to_string()).unwrap();
-        let betac = node.attribute("betac").map(|s| s.to_string()).unwrap();
-        let cm = node.attribute("cm").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let iSoma = req_attr::<Nml2Quantity>(node, "iSoma")?;
+        let iDend = req_attr::<Nml2Quantity>(node, "iDend")?;
+        let gc = req_attr::<Nml2Quantity>(node, "gc")?;
+        let gLs = req_attr::<Nml2Quantity>(node, "gLs")?;
+        let gLd = req_attr::<Nml2Quantity>(node, "gLd")?;
+        let gNa = req_attr::<Nml2Quantity>(node, "gNa")?;
+        let gKdr = req_attr::<Nml2Quantity>(node, "gKdr")?;
+        let gCa = req_attr::<Nml2Quantity>(node, "gCa")?;
+        let gKahp = req_attr::<Nml2Quantity>(node, "gKahp")?;
+        let gKC = req_attr::<Nml2Quantity>(node, "gKC")?;
+        let gNmda = req_attr::<Nml2Quantity>(node, "gNmda")?;
+        let gAmpa = req_attr::<Nml2Quantity>(node, "gAmpa")?;
+        let eNa = req_attr::<Nml2Quantity>(node, "eNa")?;
+        let eCa = req_attr::<Nml2Quantity>(node, "eCa")?;
+        let eK = req_attr::<Nml2Quantity>(node, "eK")?;
+        let eL = req_attr::<Nml2Quantity>(node, "eL")?;
+        let qd0 = req_attr::<Nml2Quantity>(node, "qd0")?;
+        let pp = req_attr::<Nml2Quantity>(node, "pp")?;
+        let alphac = req_attr::<Nml2Quantity>(node, "alphac")?;
+        let betac = req_attr::<Nml2Quantity>(node, "betac")?;
+        let cm = req_attr::<Nml2Quantity>(node, "cm")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(PinskyRinzelCA3CellBody::notes(String::from_node(&child))),
-                "property" => body.push(PinskyRinzelCA3CellBody::property(Property::from_node(&child))),
-                "annotation" => body.push(PinskyRinzelCA3CellBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of PinskyRinzelCA3Cell.", t)
+                "notes" => body.push(PinskyRinzelCA3CellBody::notes(String::from_node(&child)?)),
+                "property" => body.push(PinskyRinzelCA3CellBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(PinskyRinzelCA3CellBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "PinskyRinzelCA3Cell")),
             };
         }
-        PinskyRinzelCA3Cell {
+        Ok(PinskyRinzelCA3Cell {
             iSoma,
             iDend,
             gc,
@@ -3409,6 +5541,43 @@ impl XML for PinskyRinzelCA3Cell {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "iSoma", &self.iSoma);
+        write_attr(out, "iDend", &self.iDend);
+        write_attr(out, "gc", &self.gc);
+        write_attr(out, "gLs", &self.gLs);
+        write_attr(out, "gLd", &self.gLd);
+        write_attr(out, "gNa", &self.gNa);
+        write_attr(out, "gKdr", &self.gKdr);
+        write_attr(out, "gCa", &self.gCa);
+        write_attr(out, "gKahp", &self.gKahp);
+        write_attr(out, "gKC", &self.gKC);
+        write_attr(out, "gNmda", &self.gNmda);
+        write_attr(out, "gAmpa", &self.gAmpa);
+        write_attr(out, "eNa", &self.eNa);
+        write_attr(out, "eCa", &self.eCa);
+        write_attr(out, "eK", &self.eK);
+        write_attr(out, "eL", &self.eL);
+        write_attr(out, "qd0", &self.qd0);
+        write_attr(out, "pp", &self.pp);
+        write_attr(out, "alphac", &self.alphac);
+        write_attr(out, "betac", &self.betac);
+        write_attr(out, "cm", &self.cm);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -3420,13 +5589,24 @@ pub enum SineGeneratorDLBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl SineGeneratorDLBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            SineGeneratorDLBody::notes(x) => x.to_xml("notes", depth, out),
+            SineGeneratorDLBody::property(x) => x.to_xml("property", depth, out),
+            SineGeneratorDLBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct SineGeneratorDL {
-    pub delay: String,
-    pub phase: String,
-    pub duration: String,
-    pub amplitude: String,
-    pub period: String,
+    pub delay: Nml2Quantity,
+    pub phase: Nml2Quantity,
+    pub duration: Nml2Quantity,
+    pub amplitude: Nml2Quantity,
+    pub period: Nml2Quantity,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -3434,28 +5614,28 @@ pub struct SineGeneratorDL {
 }
 
 impl XML for SineGeneratorDL {
-    fn from_node(node: &Node) -> Self {
-        let delay = node.attribute("delay").map(|s| s.to_string()).unwrap();
-        let phase = node.attribute("phase").map(|s| s.to_string()).unwrap();
-        let duration = node.attribute("duration").map(|s| s.to_string()).unwrap();
-        let amplitude = node.attribute("amplitude").map(|s| s.to_string()).unwrap();
-        let period = node.attribute("period").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let delay = req_attr::<Nml2Quantity>(node, "delay")?;
+        let phase = req_attr::<Nml2Quantity>(node, "phase")?;
+        let duration = req_attr::<Nml2Quantity>(node, "duration")?;
+        let amplitude = req_attr::<Nml2Quantity>(node, "amplitude")?;
+        let period = req_attr::<Nml2Quantity>(node, "period")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(SineGeneratorDLBody::notes(String::from_node(&child))),
-                "property" => body.push(SineGeneratorDLBody::property(Property::from_node(&child))),
-                "annotation" => body.push(SineGeneratorDLBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of SineGeneratorDL.", t)
+                "notes" => body.push(SineGeneratorDLBody::notes(String::from_node(&child)?)),
+                "property" => body.push(SineGeneratorDLBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(SineGeneratorDLBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "SineGeneratorDL")),
             };
         }
-        SineGeneratorDL {
+        Ok(SineGeneratorDL {
             delay,
             phase,
             duration,
@@ -3465,6 +5645,27 @@ impl XML for SineGeneratorDL {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "delay", &self.delay);
+        write_attr(out, "phase", &self.phase);
+        write_attr(out, "duration", &self.duration);
+        write_attr(out, "amplitude", &self.amplitude);
+        write_attr(out, "period", &self.period);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -3480,43 +5681,75 @@ pub enum GateHHUndeterminedBody {
     subGate(GateFractionalSubgate),
 }
 
+#[allow(dead_code)]
+impl GateHHUndeterminedBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            GateHHUndeterminedBody::notes(x) => x.to_xml("notes", depth, out),
+            GateHHUndeterminedBody::q10Settings(x) => x.to_xml("q10Settings", depth, out),
+            GateHHUndeterminedBody::forwardRate(x) => x.to_xml("forwardRate", depth, out),
+            GateHHUndeterminedBody::reverseRate(x) => x.to_xml("reverseRate", depth, out),
+            GateHHUndeterminedBody::timeCourse(x) => x.to_xml("timeCourse", depth, out),
+            GateHHUndeterminedBody::steadyState(x) => x.to_xml("steadyState", depth, out),
+            GateHHUndeterminedBody::subGate(x) => x.to_xml("subGate", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct GateHHUndetermined {
     pub instances: i64,
-    pub r#type: String,
+    pub r#type: gateTypes,
     pub id: String,
     pub neuroLexId: Option<String>,
     pub body: Vec<GateHHUndeterminedBody>
 }
 
 impl XML for GateHHUndetermined {
-    fn from_node(node: &Node) -> Self {
-        let instances = node.attribute("instances").map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let r#type = node.attribute("type").map(|s| s.to_string()).unwrap();
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let instances = req_attr::<i64>(node, "instances")?;
+        let r#type = req_attr::<gateTypes>(node, "type")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(GateHHUndeterminedBody::notes(String::from_node(&child))),
-                "q10Settings" => body.push(GateHHUndeterminedBody::q10Settings(Q10Settings::from_node(&child))),
-                "forwardRate" => body.push(GateHHUndeterminedBody::forwardRate(HHRate::from_node(&child))),
-                "reverseRate" => body.push(GateHHUndeterminedBody::reverseRate(HHRate::from_node(&child))),
-                "timeCourse" => body.push(GateHHUndeterminedBody::timeCourse(HHTime::from_node(&child))),
-                "steadyState" => body.push(GateHHUndeterminedBody::steadyState(HHVariable::from_node(&child))),
-                "subGate" => body.push(GateHHUndeterminedBody::subGate(GateFractionalSubgate::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of GateHHUndetermined.", t)
+                "notes" => body.push(GateHHUndeterminedBody::notes(String::from_node(&child)?)),
+                "q10Settings" => body.push(GateHHUndeterminedBody::q10Settings(Q10Settings::from_node(&child)?)),
+                "forwardRate" => body.push(GateHHUndeterminedBody::forwardRate(HHRate::from_node(&child)?)),
+                "reverseRate" => body.push(GateHHUndeterminedBody::reverseRate(HHRate::from_node(&child)?)),
+                "timeCourse" => body.push(GateHHUndeterminedBody::timeCourse(HHTime::from_node(&child)?)),
+                "steadyState" => body.push(GateHHUndeterminedBody::steadyState(HHVariable::from_node(&child)?)),
+                "subGate" => body.push(GateHHUndeterminedBody::subGate(GateFractionalSubgate::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "GateHHUndetermined")),
             };
         }
-        GateHHUndetermined {
+        Ok(GateHHUndetermined {
             instances,
             r#type,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "instances", &self.instances);
+        write_attr(out, "type", &self.r#type);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -3524,26 +5757,36 @@ impl XML for GateHHUndetermined {
 #[derive(Debug, Clone, PartialEq)]
 pub struct HHTime {
     pub r#type: String,
-    pub rate: Option<String>,
-    pub midpoint: Option<String>,
-    pub scale: Option<String>,
-    pub tau: Option<String>,
+    pub rate: Option<Nml2Quantity>,
+    pub midpoint: Option<Nml2Quantity>,
+    pub scale: Option<Nml2Quantity>,
+    pub tau: Option<Nml2Quantity>,
 }
 
 impl XML for HHTime {
-    fn from_node(node: &Node) -> Self {
-        let r#type = node.attribute("type").map(|s| s.to_string()).unwrap();
-        let rate = node.attribute("rate").map(|s| s.to_string());
-        let midpoint = node.attribute("midpoint").map(|s| s.to_string());
-        let scale = node.attribute("scale").map(|s| s.to_string());
-        let tau = node.attribute("tau").map(|s| s.to_string());
-        HHTime {
+    fn from_node(node: &Node) -> Result<Self> {
+        let r#type = req_attr::<String>(node, "type")?;
+        let rate = opt_attr::<Nml2Quantity>(node, "rate")?;
+        let midpoint = opt_attr::<Nml2Quantity>(node, "midpoint")?;
+        let scale = opt_attr::<Nml2Quantity>(node, "scale")?;
+        let tau = opt_attr::<Nml2Quantity>(node, "tau")?;
+        Ok(HHTime {
             r#type,
             rate,
             midpoint,
             scale,
             tau,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "type", &self.r#type);
+        if let Some(x) = &self.rate { write_attr(out, "rate", x); }
+        if let Some(x) = &self.midpoint { write_attr(out, "midpoint", x); }
+        if let Some(x) = &self.scale { write_attr(out, "scale", x); }
+        if let Some(x) = &self.tau { write_attr(out, "tau", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -3554,6 +5797,17 @@ pub enum StandaloneBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl StandaloneBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            StandaloneBody::notes(x) => x.to_xml("notes", depth, out),
+            StandaloneBody::property(x) => x.to_xml("property", depth, out),
+            StandaloneBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Standalone {
     pub metaid: Option<String>,
@@ -3563,27 +5817,43 @@ pub struct Standalone {
 }
 
 impl XML for Standalone {
-    fn from_node(node: &Node) -> Self {
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(StandaloneBody::notes(String::from_node(&child))),
-                "property" => body.push(StandaloneBody::property(Property::from_node(&child))),
-                "annotation" => body.push(StandaloneBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of Standalone.", t)
+                "notes" => body.push(StandaloneBody::notes(String::from_node(&child)?)),
+                "property" => body.push(StandaloneBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(StandaloneBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "Standalone")),
             };
         }
-        Standalone {
+        Ok(Standalone {
             metaid,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -3595,6 +5865,17 @@ pub enum AlphaCurrSynapseBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl AlphaCurrSynapseBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            AlphaCurrSynapseBody::notes(x) => x.to_xml("notes", depth, out),
+            AlphaCurrSynapseBody::property(x) => x.to_xml("property", depth, out),
+            AlphaCurrSynapseBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct AlphaCurrSynapse {
     pub tau_syn: f64,
@@ -3605,29 +5886,46 @@ pub struct AlphaCurrSynapse {
 }
 
 impl XML for AlphaCurrSynapse {
-    fn from_node(node: &Node) -> Self {
-        let tau_syn = node.attribute("tau_syn").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let tau_syn = req_attr::<f64>(node, "tau_syn")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(AlphaCurrSynapseBody::notes(String::from_node(&child))),
-                "property" => body.push(AlphaCurrSynapseBody::property(Property::from_node(&child))),
-                "annotation" => body.push(AlphaCurrSynapseBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of AlphaCurrSynapse.", t)
+                "notes" => body.push(AlphaCurrSynapseBody::notes(String::from_node(&child)?)),
+                "property" => body.push(AlphaCurrSynapseBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(AlphaCurrSynapseBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "AlphaCurrSynapse")),
             };
         }
-        AlphaCurrSynapse {
+        Ok(AlphaCurrSynapse {
             tau_syn,
             metaid,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "tau_syn", &self.tau_syn);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -3639,6 +5937,17 @@ pub enum ElectricalProjectionBody {
     electricalConnectionInstanceW(ElectricalConnectionInstanceW),
 }
 
+#[allow(dead_code)]
+impl ElectricalProjectionBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            ElectricalProjectionBody::electricalConnection(x) => x.to_xml("electricalConnection", depth, out),
+            ElectricalProjectionBody::electricalConnectionInstance(x) => x.to_xml("electricalConnectionInstance", depth, out),
+            ElectricalProjectionBody::electricalConnectionInstanceW(x) => x.to_xml("electricalConnectionInstanceW", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ElectricalProjection {
     pub presynapticPopulation: String,
@@ -3649,29 +5958,46 @@ pub struct ElectricalProjection {
 }
 
 impl XML for ElectricalProjection {
-    fn from_node(node: &Node) -> Self {
-        let presynapticPopulation = node.attribute("presynapticPopulation").map(|s| s.to_string()).unwrap();
-        let postsynapticPopulation = node.attribute("postsynapticPopulation").map(|s| s.to_string()).unwrap();
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let presynapticPopulation = req_attr::<String>(node, "presynapticPopulation")?;
+        let postsynapticPopulation = req_attr::<String>(node, "postsynapticPopulation")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "electricalConnection" => body.push(ElectricalProjectionBody::electricalConnection(ElectricalConnection::from_node(&child))),
-                "electricalConnectionInstance" => body.push(ElectricalProjectionBody::electricalConnectionInstance(ElectricalConnectionInstance::from_node(&child))),
-                "electricalConnectionInstanceW" => body.push(ElectricalProjectionBody::electricalConnectionInstanceW(ElectricalConnectionInstanceW::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of ElectricalProjection.", t)
+                "electricalConnection" => body.push(ElectricalProjectionBody::electricalConnection(ElectricalConnection::from_node(&child)?)),
+                "electricalConnectionInstance" => body.push(ElectricalProjectionBody::electricalConnectionInstance(ElectricalConnectionInstance::from_node(&child)?)),
+                "electricalConnectionInstanceW" => body.push(ElectricalProjectionBody::electricalConnectionInstanceW(ElectricalConnectionInstanceW::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "ElectricalProjection")),
             };
         }
-        ElectricalProjection {
+        Ok(ElectricalProjection {
             presynapticPopulation,
             postsynapticPopulation,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "presynapticPopulation", &self.presynapticPopulation);
+        write_attr(out, "postsynapticPopulation", &self.postsynapticPopulation);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -3692,12 +6018,32 @@ pub enum IonChannelVShiftBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl IonChannelVShiftBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            IonChannelVShiftBody::gate(x) => x.to_xml("gate", depth, out),
+            IonChannelVShiftBody::gateHHrates(x) => x.to_xml("gateHHrates", depth, out),
+            IonChannelVShiftBody::gateHHratesTau(x) => x.to_xml("gateHHratesTau", depth, out),
+            IonChannelVShiftBody::gateHHtauInf(x) => x.to_xml("gateHHtauInf", depth, out),
+            IonChannelVShiftBody::gateHHratesInf(x) => x.to_xml("gateHHratesInf", depth, out),
+            IonChannelVShiftBody::gateHHratesTauInf(x) => x.to_xml("gateHHratesTauInf", depth, out),
+            IonChannelVShiftBody::gateHHInstantaneous(x) => x.to_xml("gateHHInstantaneous", depth, out),
+            IonChannelVShiftBody::gateFractional(x) => x.to_xml("gateFractional", depth, out),
+            IonChannelVShiftBody::q10ConductanceScaling(x) => x.to_xml("q10ConductanceScaling", depth, out),
+            IonChannelVShiftBody::notes(x) => x.to_xml("notes", depth, out),
+            IonChannelVShiftBody::property(x) => x.to_xml("property", depth, out),
+            IonChannelVShiftBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct IonChannelVShift {
-    pub vShift: String,
+    pub vShift: Nml2Quantity,
     pub species: Option<String>,
-    pub r#type: Option<String>,
-    pub conductance: Option<String>,
+    pub r#type: Option<channelTypes>,
+    pub conductance: Option<Nml2Quantity>,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -3705,36 +6051,36 @@ pub struct IonChannelVShift {
 }
 
 impl XML for IonChannelVShift {
-    fn from_node(node: &Node) -> Self {
-        let vShift = node.attribute("vShift").map(|s| s.to_string()).unwrap();
-        let species = node.attribute("species").map(|s| s.to_string());
-        let r#type = node.attribute("type").map(|s| s.to_string());
-        let conductance = node.attribute("conductance").map(|s| s.to_string());
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let vShift = req_attr::<Nml2Quantity>(node, "vShift")?;
+        let species = opt_attr::<String>(node, "species")?;
+        let r#type = opt_attr::<channelTypes>(node, "type")?;
+        let conductance = opt_attr::<Nml2Quantity>(node, "conductance")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "gate" => body.push(IonChannelVShiftBody::gate(GateHHUndetermined::from_node(&child))),
-                "gateHHrates" => body.push(IonChannelVShiftBody::gateHHrates(GateHHRates::from_node(&child))),
-                "gateHHratesTau" => body.push(IonChannelVShiftBody::gateHHratesTau(GateHHRatesTau::from_node(&child))),
-                "gateHHtauInf" => body.push(IonChannelVShiftBody::gateHHtauInf(GateHHTauInf::from_node(&child))),
-                "gateHHratesInf" => body.push(IonChannelVShiftBody::gateHHratesInf(GateHHRatesInf::from_node(&child))),
-                "gateHHratesTauInf" => body.push(IonChannelVShiftBody::gateHHratesTauInf(GateHHRatesTauInf::from_node(&child))),
-                "gateHHInstantaneous" => body.push(IonChannelVShiftBody::gateHHInstantaneous(GateHHInstantaneous::from_node(&child))),
-                "gateFractional" => body.push(IonChannelVShiftBody::gateFractional(GateFractional::from_node(&child))),
-                "q10ConductanceScaling" => body.push(IonChannelVShiftBody::q10ConductanceScaling(Q10ConductanceScaling::from_node(&child))),
-                "notes" => body.push(IonChannelVShiftBody::notes(String::from_node(&child))),
-                "property" => body.push(IonChannelVShiftBody::property(Property::from_node(&child))),
-                "annotation" => body.push(IonChannelVShiftBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of IonChannelVShift.", t)
+                "gate" => body.push(IonChannelVShiftBody::gate(GateHHUndetermined::from_node(&child)?)),
+                "gateHHrates" => body.push(IonChannelVShiftBody::gateHHrates(GateHHRates::from_node(&child)?)),
+                "gateHHratesTau" => body.push(IonChannelVShiftBody::gateHHratesTau(GateHHRatesTau::from_node(&child)?)),
+                "gateHHtauInf" => body.push(IonChannelVShiftBody::gateHHtauInf(GateHHTauInf::from_node(&child)?)),
+                "gateHHratesInf" => body.push(IonChannelVShiftBody::gateHHratesInf(GateHHRatesInf::from_node(&child)?)),
+                "gateHHratesTauInf" => body.push(IonChannelVShiftBody::gateHHratesTauInf(GateHHRatesTauInf::from_node(&child)?)),
+                "gateHHInstantaneous" => body.push(IonChannelVShiftBody::gateHHInstantaneous(GateHHInstantaneous::from_node(&child)?)),
+                "gateFractional" => body.push(IonChannelVShiftBody::gateFractional(GateFractional::from_node(&child)?)),
+                "q10ConductanceScaling" => body.push(IonChannelVShiftBody::q10ConductanceScaling(Q10ConductanceScaling::from_node(&child)?)),
+                "notes" => body.push(IonChannelVShiftBody::notes(String::from_node(&child)?)),
+                "property" => body.push(IonChannelVShiftBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(IonChannelVShiftBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "IonChannelVShift")),
             };
         }
-        IonChannelVShift {
+        Ok(IonChannelVShift {
             vShift,
             species,
             r#type,
@@ -3743,6 +6089,26 @@ impl XML for IonChannelVShift {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "vShift", &self.vShift);
+        if let Some(x) = &self.species { write_attr(out, "species", x); }
+        if let Some(x) = &self.r#type { write_attr(out, "type", x); }
+        if let Some(x) = &self.conductance { write_attr(out, "conductance", x); }
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -3754,13 +6120,24 @@ pub enum SineGeneratorBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl SineGeneratorBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            SineGeneratorBody::notes(x) => x.to_xml("notes", depth, out),
+            SineGeneratorBody::property(x) => x.to_xml("property", depth, out),
+            SineGeneratorBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct SineGenerator {
-    pub delay: String,
-    pub phase: String,
-    pub duration: String,
-    pub amplitude: String,
-    pub period: String,
+    pub delay: Nml2Quantity,
+    pub phase: Nml2Quantity,
+    pub duration: Nml2Quantity,
+    pub amplitude: Nml2Quantity,
+    pub period: Nml2Quantity,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -3768,28 +6145,28 @@ pub struct SineGenerator {
 }
 
 impl XML for SineGenerator {
-    fn from_node(node: &Node) -> Self {
-        let delay = node.attribute("delay").map(|s| s.to_string()).unwrap();
-        let phase = node.attribute("phase").map(|s| s.to_string()).unwrap();
-        let duration = node.attribute("duration").map(|s| s.to_string()).unwrap();
-        let amplitude = node.attribute("amplitude").map(|s| s.to_string()).unwrap();
-        let period = node.attribute("period").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let delay = req_attr::<Nml2Quantity>(node, "delay")?;
+        let phase = req_attr::<Nml2Quantity>(node, "phase")?;
+        let duration = req_attr::<Nml2Quantity>(node, "duration")?;
+        let amplitude = req_attr::<Nml2Quantity>(node, "amplitude")?;
+        let period = req_attr::<Nml2Quantity>(node, "period")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(SineGeneratorBody::notes(String::from_node(&child))),
-                "property" => body.push(SineGeneratorBody::property(Property::from_node(&child))),
-                "annotation" => body.push(SineGeneratorBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of SineGenerator.", t)
+                "notes" => body.push(SineGeneratorBody::notes(String::from_node(&child)?)),
+                "property" => body.push(SineGeneratorBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(SineGeneratorBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "SineGenerator")),
             };
         }
-        SineGenerator {
+        Ok(SineGenerator {
             delay,
             phase,
             duration,
@@ -3799,6 +6176,27 @@ impl XML for SineGenerator {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "delay", &self.delay);
+        write_attr(out, "phase", &self.phase);
+        write_attr(out, "duration", &self.duration);
+        write_attr(out, "amplitude", &self.amplitude);
+        write_attr(out, "period", &self.period);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -3809,11 +6207,17 @@ pub struct Member {
 }
 
 impl XML for Member {
-    fn from_node(node: &Node) -> Self {
-        let segment = node.attribute("segment").map(|s| s.parse::<i64>().unwrap()).unwrap();
-        Member {
+    fn from_node(node: &Node) -> Result<Self> {
+        let segment = req_attr::<i64>(node, "segment")?;
+        Ok(Member {
             segment,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "segment", &self.segment);
+        out.push_str("/>\n");
     }
 }
 
@@ -3823,11 +6227,17 @@ pub struct DistalDetails {
 }
 
 impl XML for DistalDetails {
-    fn from_node(node: &Node) -> Self {
-        let normalizationEnd = node.attribute("normalizationEnd").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        DistalDetails {
+    fn from_node(node: &Node) -> Result<Self> {
+        let normalizationEnd = req_attr::<f64>(node, "normalizationEnd")?;
+        Ok(DistalDetails {
             normalizationEnd,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "normalizationEnd", &self.normalizationEnd);
+        out.push_str("/>\n");
     }
 }
 
@@ -3836,25 +6246,47 @@ pub enum ExtracellularPropertiesLocalBody {
     species(Species),
 }
 
+#[allow(dead_code)]
+impl ExtracellularPropertiesLocalBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            ExtracellularPropertiesLocalBody::species(x) => x.to_xml("species", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ExtracellularPropertiesLocal {
     pub body: Vec<ExtracellularPropertiesLocalBody>
 }
 
 impl XML for ExtracellularPropertiesLocal {
-    fn from_node(node: &Node) -> Self {
+    fn from_node(node: &Node) -> Result<Self> {
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "species" => body.push(ExtracellularPropertiesLocalBody::species(Species::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of ExtracellularPropertiesLocal.", t)
+                "species" => body.push(ExtracellularPropertiesLocalBody::species(Species::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "ExtracellularPropertiesLocal")),
             };
         }
-        ExtracellularPropertiesLocal {
+        Ok(ExtracellularPropertiesLocal {
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -3863,22 +6295,31 @@ impl XML for ExtracellularPropertiesLocal {
 pub struct HHVariable {
     pub r#type: String,
     pub rate: Option<f64>,
-    pub midpoint: Option<String>,
-    pub scale: Option<String>,
+    pub midpoint: Option<Nml2Quantity>,
+    pub scale: Option<Nml2Quantity>,
 }
 
 impl XML for HHVariable {
-    fn from_node(node: &Node) -> Self {
-        let r#type = node.attribute("type").map(|s| s.to_string()).unwrap();
-        let rate = node.attribute("rate").map(|s| s.parse::<f64>().unwrap());
-        let midpoint = node.attribute("midpoint").map(|s| s.to_string());
-        let scale = node.attribute("scale").map(|s| s.to_string());
-        HHVariable {
+    fn from_node(node: &Node) -> Result<Self> {
+        let r#type = req_attr::<String>(node, "type")?;
+        let rate = opt_attr::<f64>(node, "rate")?;
+        let midpoint = opt_attr::<Nml2Quantity>(node, "midpoint")?;
+        let scale = opt_attr::<Nml2Quantity>(node, "scale")?;
+        Ok(HHVariable {
             r#type,
             rate,
             midpoint,
             scale,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "type", &self.r#type);
+        if let Some(x) = &self.rate { write_attr(out, "rate", x); }
+        if let Some(x) = &self.midpoint { write_attr(out, "midpoint", x); }
+        if let Some(x) = &self.scale { write_attr(out, "scale", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -3895,16 +6336,16 @@ pub struct BaseConnectionNewFormat {
 }
 
 impl XML for BaseConnectionNewFormat {
-    fn from_node(node: &Node) -> Self {
-        let preCell = node.attribute("preCell").map(|s| s.to_string()).unwrap();
-        let preSegment = node.attribute("preSegment").or(Some("0")).map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let preFractionAlong = node.attribute("preFractionAlong").or(Some("0.5")).map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let postCell = node.attribute("postCell").map(|s| s.to_string()).unwrap();
-        let postSegment = node.attribute("postSegment").or(Some("0")).map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let postFractionAlong = node.attribute("postFractionAlong").or(Some("0.5")).map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let id = node.attribute("id").map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
-        BaseConnectionNewFormat {
+    fn from_node(node: &Node) -> Result<Self> {
+        let preCell = req_attr::<String>(node, "preCell")?;
+        let preSegment = def_attr::<i64>(node, "preSegment", "0")?;
+        let preFractionAlong = def_attr::<f64>(node, "preFractionAlong", "0.5")?;
+        let postCell = req_attr::<String>(node, "postCell")?;
+        let postSegment = def_attr::<i64>(node, "postSegment", "0")?;
+        let postFractionAlong = def_attr::<f64>(node, "postFractionAlong", "0.5")?;
+        let id = req_attr::<i64>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
+        Ok(BaseConnectionNewFormat {
             preCell,
             preSegment,
             preFractionAlong,
@@ -3913,7 +6354,20 @@ impl XML for BaseConnectionNewFormat {
             postFractionAlong,
             id,
             neuroLexId,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "preCell", &self.preCell);
+        write_attr(out, "preSegment", &self.preSegment);
+        write_attr(out, "preFractionAlong", &self.preFractionAlong);
+        write_attr(out, "postCell", &self.postCell);
+        write_attr(out, "postSegment", &self.postSegment);
+        write_attr(out, "postFractionAlong", &self.postFractionAlong);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -3924,6 +6378,17 @@ pub enum EIF_cond_exp_isfa_istaBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl EIF_cond_exp_isfa_istaBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            EIF_cond_exp_isfa_istaBody::notes(x) => x.to_xml("notes", depth, out),
+            EIF_cond_exp_isfa_istaBody::property(x) => x.to_xml("property", depth, out),
+            EIF_cond_exp_isfa_istaBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct EIF_cond_exp_isfa_ista {
     pub a: f64,
@@ -3950,40 +6415,40 @@ pub struct EIF_cond_exp_isfa_ista {
 }
 
 impl XML for EIF_cond_exp_isfa_ista {
-    fn from_node(node: &Node) -> Self {
-        let a = node.attribute("a").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let b = node.attribute("b").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let delta_T = node.attribute("delta_T").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let tau_w = node.attribute("tau_w").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let v_spike = node.attribute("v_spike").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let e_rev_E = node.attribute("e_rev_E").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let e_rev_I = node.attribute("e_rev_I").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let tau_m = node.attribute("tau_m").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let tau_refrac = node.attribute("tau_refrac").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let v_reset = node.attribute("v_reset").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let v_rest = node.attribute("v_rest").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let v_thresh = node.attribute("v_thresh").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let cm = node.attribute("cm").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let i_offset = node.attribute("i_offset").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let tau_syn_E = node.attribute("tau_syn_E").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let tau_syn_I = node.attribute("tau_syn_I").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let v_init = node.attribute("v_init").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let a = req_attr::<f64>(node, "a")?;
+        let b = req_attr::<f64>(node, "b")?;
+        let delta_T = req_attr::<f64>(node, "delta_T")?;
+        let tau_w = req_attr::<f64>(node, "tau_w")?;
+        let v_spike = req_attr::<f64>(node, "v_spike")?;
+        let e_rev_E = req_attr::<f64>(node, "e_rev_E")?;
+        let e_rev_I = req_attr::<f64>(node, "e_rev_I")?;
+        let tau_m = req_attr::<f64>(node, "tau_m")?;
+        let tau_refrac = req_attr::<f64>(node, "tau_refrac")?;
+        let v_reset = req_attr::<f64>(node, "v_reset")?;
+        let v_rest = req_attr::<f64>(node, "v_rest")?;
+        let v_thresh = req_attr::<f64>(node, "v_thresh")?;
+        let cm = req_attr::<f64>(node, "cm")?;
+        let i_offset = req_attr::<f64>(node, "i_offset")?;
+        let tau_syn_E = req_attr::<f64>(node, "tau_syn_E")?;
+        let tau_syn_I = req_attr::<f64>(node, "tau_syn_I")?;
+        let v_init = req_attr::<f64>(node, "v_init")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(EIF_cond_exp_isfa_istaBody::notes(String::from_node(&child))),
-                "property" => body.push(EIF_cond_exp_isfa_istaBody::property(Property::from_node(&child))),
-                "annotation" => body.push(EIF_cond_exp_isfa_istaBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of EIF_cond_exp_isfa_ista.", t)
+                "notes" => body.push(EIF_cond_exp_isfa_istaBody::notes(String::from_node(&child)?)),
+                "property" => body.push(EIF_cond_exp_isfa_istaBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(EIF_cond_exp_isfa_istaBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "EIF_cond_exp_isfa_ista")),
             };
         }
-        EIF_cond_exp_isfa_ista {
+        Ok(EIF_cond_exp_isfa_ista {
             a,
             b,
             delta_T,
@@ -4005,6 +6470,39 @@ impl XML for EIF_cond_exp_isfa_ista {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "a", &self.a);
+        write_attr(out, "b", &self.b);
+        write_attr(out, "delta_T", &self.delta_T);
+        write_attr(out, "tau_w", &self.tau_w);
+        write_attr(out, "v_spike", &self.v_spike);
+        write_attr(out, "e_rev_E", &self.e_rev_E);
+        write_attr(out, "e_rev_I", &self.e_rev_I);
+        write_attr(out, "tau_m", &self.tau_m);
+        write_attr(out, "tau_refrac", &self.tau_refrac);
+        write_attr(out, "v_reset", &self.v_reset);
+        write_attr(out, "v_rest", &self.v_rest);
+        write_attr(out, "v_thresh", &self.v_thresh);
+        write_attr(out, "cm", &self.cm);
+        write_attr(out, "i_offset", &self.i_offset);
+        write_attr(out, "tau_syn_E", &self.tau_syn_E);
+        write_attr(out, "tau_syn_I", &self.tau_syn_I);
+        write_attr(out, "v_init", &self.v_init);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -4018,17 +6516,26 @@ pub struct SynapticConnection {
 }
 
 impl XML for SynapticConnection {
-    fn from_node(node: &Node) -> Self {
-        let from = node.attribute("from").map(|s| s.to_string()).unwrap();
-        let to = node.attribute("to").map(|s| s.to_string()).unwrap();
-        let synapse = node.attribute("synapse").map(|s| s.to_string()).unwrap();
-        let destination = node.attribute("destination").map(|s| s.to_string());
-        SynapticConnection {
+    fn from_node(node: &Node) -> Result<Self> {
+        let from = req_attr::<String>(node, "from")?;
+        let to = req_attr::<String>(node, "to")?;
+        let synapse = req_attr::<String>(node, "synapse")?;
+        let destination = opt_attr::<String>(node, "destination")?;
+        Ok(SynapticConnection {
             from,
             to,
             synapse,
             destination,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "from", &self.from);
+        write_attr(out, "to", &self.to);
+        write_attr(out, "synapse", &self.synapse);
+        if let Some(x) = &self.destination { write_attr(out, "destination", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -4039,6 +6546,17 @@ pub enum ContinuousProjectionBody {
     continuousConnectionInstanceW(ContinuousConnectionInstanceW),
 }
 
+#[allow(dead_code)]
+impl ContinuousProjectionBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            ContinuousProjectionBody::continuousConnection(x) => x.to_xml("continuousConnection", depth, out),
+            ContinuousProjectionBody::continuousConnectionInstance(x) => x.to_xml("continuousConnectionInstance", depth, out),
+            ContinuousProjectionBody::continuousConnectionInstanceW(x) => x.to_xml("continuousConnectionInstanceW", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ContinuousProjection {
     pub presynapticPopulation: String,
@@ -4049,29 +6567,46 @@ pub struct ContinuousProjection {
 }
 
 impl XML for ContinuousProjection {
-    fn from_node(node: &Node) -> Self {
-        let presynapticPopulation = node.attribute("presynapticPopulation").map(|s| s.to_string()).unwrap();
-        let postsynapticPopulation = node.attribute("postsynapticPopulation").map(|s| s.to_string()).unwrap();
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let presynapticPopulation = req_attr::<String>(node, "presynapticPopulation")?;
+        let postsynapticPopulation = req_attr::<String>(node, "postsynapticPopulation")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "continuousConnection" => body.push(ContinuousProjectionBody::continuousConnection(ContinuousConnection::from_node(&child))),
-                "continuousConnectionInstance" => body.push(ContinuousProjectionBody::continuousConnectionInstance(ContinuousConnectionInstance::from_node(&child))),
-                "continuousConnectionInstanceW" => body.push(ContinuousProjectionBody::continuousConnectionInstanceW(ContinuousConnectionInstanceW::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of ContinuousProjection.", t)
+                "continuousConnection" => body.push(ContinuousProjectionBody::continuousConnection(ContinuousConnection::from_node(&child)?)),
+                "continuousConnectionInstance" => body.push(ContinuousProjectionBody::continuousConnectionInstance(ContinuousConnectionInstance::from_node(&child)?)),
+                "continuousConnectionInstanceW" => body.push(ContinuousProjectionBody::continuousConnectionInstanceW(ContinuousConnectionInstanceW::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "ContinuousProjection")),
             };
         }
-        ContinuousProjection {
+        Ok(ContinuousProjection {
             presynapticPopulation,
             postsynapticPopulation,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "presynapticPopulation", &self.presynapticPopulation);
+        write_attr(out, "postsynapticPopulation", &self.postsynapticPopulation);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -4092,19 +6627,19 @@ pub struct ContinuousConnectionInstanceW {
 }
 
 impl XML for ContinuousConnectionInstanceW {
-    fn from_node(node: &Node) -> Self {
-        let weight = node.attribute("weight").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let preComponent = node.attribute("preComponent").map(|s| s.to_string()).unwrap();
-        let postComponent = node.attribute("postComponent").map(|s| s.to_string()).unwrap();
-        let preCell = node.attribute("preCell").map(|s| s.to_string()).unwrap();
-        let preSegment = node.attribute("preSegment").or(Some("0")).map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let preFractionAlong = node.attribute("preFractionAlong").or(Some("0.5")).map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let postCell = node.attribute("postCell").map(|s| s.to_string()).unwrap();
-        let postSegment = node.attribute("postSegment").or(Some("0")).map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let postFractionAlong = node.attribute("postFractionAlong").or(Some("0.5")).map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let id = node.attribute("id").map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
-        ContinuousConnectionInstanceW {
+    fn from_node(node: &Node) -> Result<Self> {
+        let weight = req_attr::<f64>(node, "weight")?;
+        let preComponent = req_attr::<String>(node, "preComponent")?;
+        let postComponent = req_attr::<String>(node, "postComponent")?;
+        let preCell = req_attr::<String>(node, "preCell")?;
+        let preSegment = def_attr::<i64>(node, "preSegment", "0")?;
+        let preFractionAlong = def_attr::<f64>(node, "preFractionAlong", "0.5")?;
+        let postCell = req_attr::<String>(node, "postCell")?;
+        let postSegment = def_attr::<i64>(node, "postSegment", "0")?;
+        let postFractionAlong = def_attr::<f64>(node, "postFractionAlong", "0.5")?;
+        let id = req_attr::<i64>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
+        Ok(ContinuousConnectionInstanceW {
             weight,
             preComponent,
             postComponent,
@@ -4116,7 +6651,23 @@ impl XML for ContinuousConnectionInstanceW {
             postFractionAlong,
             id,
             neuroLexId,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "weight", &self.weight);
+        write_attr(out, "preComponent", &self.preComponent);
+        write_attr(out, "postComponent", &self.postComponent);
+        write_attr(out, "preCell", &self.preCell);
+        write_attr(out, "preSegment", &self.preSegment);
+        write_attr(out, "preFractionAlong", &self.preFractionAlong);
+        write_attr(out, "postCell", &self.postCell);
+        write_attr(out, "postSegment", &self.postSegment);
+        write_attr(out, "postFractionAlong", &self.postFractionAlong);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -4127,9 +6678,20 @@ pub enum LinearGradedSynapseBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl LinearGradedSynapseBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            LinearGradedSynapseBody::notes(x) => x.to_xml("notes", depth, out),
+            LinearGradedSynapseBody::property(x) => x.to_xml("property", depth, out),
+            LinearGradedSynapseBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct LinearGradedSynapse {
-    pub conductance: String,
+    pub conductance: Nml2Quantity,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -4137,29 +6699,46 @@ pub struct LinearGradedSynapse {
 }
 
 impl XML for LinearGradedSynapse {
-    fn from_node(node: &Node) -> Self {
-        let conductance = node.attribute("conductance").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let conductance = req_attr::<Nml2Quantity>(node, "conductance")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(LinearGradedSynapseBody::notes(String::from_node(&child))),
-                "property" => body.push(LinearGradedSynapseBody::property(Property::from_node(&child))),
-                "annotation" => body.push(LinearGradedSynapseBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of LinearGradedSynapse.", t)
+                "notes" => body.push(LinearGradedSynapseBody::notes(String::from_node(&child)?)),
+                "property" => body.push(LinearGradedSynapseBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(LinearGradedSynapseBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "LinearGradedSynapse")),
             };
         }
-        LinearGradedSynapse {
+        Ok(LinearGradedSynapse {
             conductance,
             metaid,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "conductance", &self.conductance);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -4171,13 +6750,20 @@ pub struct Property {
 }
 
 impl XML for Property {
-    fn from_node(node: &Node) -> Self {
-        let tag = node.attribute("tag").map(|s| s.to_string()).unwrap();
-        let value = node.attribute("value").map(|s| s.to_string()).unwrap();
-        Property {
+    fn from_node(node: &Node) -> Result<Self> {
+        let tag = req_attr::<String>(node, "tag")?;
+        let value = req_attr::<String>(node, "value")?;
+        Ok(Property {
             tag,
             value,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "tag", &self.tag);
+        write_attr(out, "value", &self.value);
+        out.push_str("/>\n");
     }
 }
 
@@ -4189,6 +6775,18 @@ pub enum IonChannelScalableBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl IonChannelScalableBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            IonChannelScalableBody::q10ConductanceScaling(x) => x.to_xml("q10ConductanceScaling", depth, out),
+            IonChannelScalableBody::notes(x) => x.to_xml("notes", depth, out),
+            IonChannelScalableBody::property(x) => x.to_xml("property", depth, out),
+            IonChannelScalableBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct IonChannelScalable {
     pub metaid: Option<String>,
@@ -4198,28 +6796,44 @@ pub struct IonChannelScalable {
 }
 
 impl XML for IonChannelScalable {
-    fn from_node(node: &Node) -> Self {
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "q10ConductanceScaling" => body.push(IonChannelScalableBody::q10ConductanceScaling(Q10ConductanceScaling::from_node(&child))),
-                "notes" => body.push(IonChannelScalableBody::notes(String::from_node(&child))),
-                "property" => body.push(IonChannelScalableBody::property(Property::from_node(&child))),
-                "annotation" => body.push(IonChannelScalableBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of IonChannelScalable.", t)
+                "q10ConductanceScaling" => body.push(IonChannelScalableBody::q10ConductanceScaling(Q10ConductanceScaling::from_node(&child)?)),
+                "notes" => body.push(IonChannelScalableBody::notes(String::from_node(&child)?)),
+                "property" => body.push(IonChannelScalableBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(IonChannelScalableBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "IonChannelScalable")),
             };
         }
-        IonChannelScalable {
+        Ok(IonChannelScalable {
             metaid,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -4230,11 +6844,17 @@ pub struct SegmentEndPoint {
 }
 
 impl XML for SegmentEndPoint {
-    fn from_node(node: &Node) -> Self {
-        let segment = node.attribute("segment").map(|s| s.parse::<i64>().unwrap()).unwrap();
-        SegmentEndPoint {
+    fn from_node(node: &Node) -> Result<Self> {
+        let segment = req_attr::<i64>(node, "segment")?;
+        Ok(SegmentEndPoint {
             segment,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "segment", &self.segment);
+        out.push_str("/>\n");
     }
 }
 
@@ -4243,11 +6863,20 @@ pub enum ChannelDensityBody {
     variableParameter(VariableParameter),
 }
 
+#[allow(dead_code)]
+impl ChannelDensityBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            ChannelDensityBody::variableParameter(x) => x.to_xml("variableParameter", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ChannelDensity {
     pub ionChannel: String,
-    pub condDensity: Option<String>,
-    pub erev: String,
+    pub condDensity: Option<Nml2Quantity>,
+    pub erev: Nml2Quantity,
     pub segmentGroup: String,
     pub segment: Option<i64>,
     pub ion: String,
@@ -4257,26 +6886,26 @@ pub struct ChannelDensity {
 }
 
 impl XML for ChannelDensity {
-    fn from_node(node: &Node) -> Self {
-        let ionChannel = node.attribute("ionChannel").map(|s| s.to_string()).unwrap();
-        let condDensity = node.attribute("condDensity").map(|s| s.to_string());
-        let erev = node.attribute("erev").map(|s| s.to_string()).unwrap();
-        let segmentGroup = node.attribute("segmentGroup").or(Some("all")).map(|s| s.to_string()).unwrap();
-        let segment = node.attribute("segment").map(|s| s.parse::<i64>().unwrap());
-        let ion = node.attribute("ion").map(|s| s.to_string()).unwrap();
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let ionChannel = req_attr::<String>(node, "ionChannel")?;
+        let condDensity = opt_attr::<Nml2Quantity>(node, "condDensity")?;
+        let erev = req_attr::<Nml2Quantity>(node, "erev")?;
+        let segmentGroup = def_attr::<String>(node, "segmentGroup", "all")?;
+        let segment = opt_attr::<i64>(node, "segment")?;
+        let ion = req_attr::<String>(node, "ion")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "variableParameter" => body.push(ChannelDensityBody::variableParameter(VariableParameter::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of ChannelDensity.", t)
+                "variableParameter" => body.push(ChannelDensityBody::variableParameter(VariableParameter::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "ChannelDensity")),
             };
         }
-        ChannelDensity {
+        Ok(ChannelDensity {
             ionChannel,
             condDensity,
             erev,
@@ -4286,6 +6915,27 @@ impl XML for ChannelDensity {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "ionChannel", &self.ionChannel);
+        if let Some(x) = &self.condDensity { write_attr(out, "condDensity", x); }
+        write_attr(out, "erev", &self.erev);
+        write_attr(out, "segmentGroup", &self.segmentGroup);
+        if let Some(x) = &self.segment { write_attr(out, "segment", x); }
+        write_attr(out, "ion", &self.ion);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -4301,6 +6951,21 @@ pub enum ComponentTypeBody {
     Dynamics(Dynamics),
 }
 
+#[allow(dead_code)]
+impl ComponentTypeBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            ComponentTypeBody::Property(x) => x.to_xml("Property", depth, out),
+            ComponentTypeBody::Parameter(x) => x.to_xml("Parameter", depth, out),
+            ComponentTypeBody::Constant(x) => x.to_xml("Constant", depth, out),
+            ComponentTypeBody::Exposure(x) => x.to_xml("Exposure", depth, out),
+            ComponentTypeBody::Requirement(x) => x.to_xml("Requirement", depth, out),
+            ComponentTypeBody::InstanceRequirement(x) => x.to_xml("InstanceRequirement", depth, out),
+            ComponentTypeBody::Dynamics(x) => x.to_xml("Dynamics", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ComponentType {
     pub name: String,
@@ -4310,31 +6975,47 @@ pub struct ComponentType {
 }
 
 impl XML for ComponentType {
-    fn from_node(node: &Node) -> Self {
-        let name = node.attribute("name").map(|s| s.to_string()).unwrap();
-        let extends = node.attribute("extends").map(|s| s.to_string());
-        let description = node.attribute("description").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let name = req_attr::<String>(node, "name")?;
+        let extends = opt_attr::<String>(node, "extends")?;
+        let description = opt_attr::<String>(node, "description")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "Property" => body.push(ComponentTypeBody::Property(LEMS_Property::from_node(&child))),
-                "Parameter" => body.push(ComponentTypeBody::Parameter(Parameter::from_node(&child))),
-                "Constant" => body.push(ComponentTypeBody::Constant(Constant::from_node(&child))),
-                "Exposure" => body.push(ComponentTypeBody::Exposure(Exposure::from_node(&child))),
-                "Requirement" => body.push(ComponentTypeBody::Requirement(Requirement::from_node(&child))),
-                "InstanceRequirement" => body.push(ComponentTypeBody::InstanceRequirement(InstanceRequirement::from_node(&child))),
-                "Dynamics" => body.push(ComponentTypeBody::Dynamics(Dynamics::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of ComponentType.", t)
+                "Property" => body.push(ComponentTypeBody::Property(LEMS_Property::from_node(&child)?)),
+                "Parameter" => body.push(ComponentTypeBody::Parameter(Parameter::from_node(&child)?)),
+                "Constant" => body.push(ComponentTypeBody::Constant(Constant::from_node(&child)?)),
+                "Exposure" => body.push(ComponentTypeBody::Exposure(Exposure::from_node(&child)?)),
+                "Requirement" => body.push(ComponentTypeBody::Requirement(Requirement::from_node(&child)?)),
+                "InstanceRequirement" => body.push(ComponentTypeBody::InstanceRequirement(InstanceRequirement::from_node(&child)?)),
+                "Dynamics" => body.push(ComponentTypeBody::Dynamics(Dynamics::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "ComponentType")),
             };
         }
-        ComponentType {
+        Ok(ComponentType {
             name,
             extends,
             description,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "name", &self.name);
+        if let Some(x) = &self.extends { write_attr(out, "extends", x); }
+        if let Some(x) = &self.description { write_attr(out, "description", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -4345,11 +7026,17 @@ pub struct ProximalDetails {
 }
 
 impl XML for ProximalDetails {
-    fn from_node(node: &Node) -> Self {
-        let translationStart = node.attribute("translationStart").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        ProximalDetails {
+    fn from_node(node: &Node) -> Result<Self> {
+        let translationStart = req_attr::<f64>(node, "translationStart")?;
+        Ok(ProximalDetails {
             translationStart,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "translationStart", &self.translationStart);
+        out.push_str("/>\n");
     }
 }
 
@@ -4360,14 +7047,25 @@ pub enum IafRefCellBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl IafRefCellBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            IafRefCellBody::notes(x) => x.to_xml("notes", depth, out),
+            IafRefCellBody::property(x) => x.to_xml("property", depth, out),
+            IafRefCellBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct IafRefCell {
-    pub refract: String,
-    pub leakReversal: String,
-    pub thresh: String,
-    pub reset: String,
-    pub C: String,
-    pub leakConductance: String,
+    pub refract: Nml2Quantity,
+    pub leakReversal: Nml2Quantity,
+    pub thresh: Nml2Quantity,
+    pub reset: Nml2Quantity,
+    pub C: Nml2Quantity,
+    pub leakConductance: Nml2Quantity,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -4375,29 +7073,29 @@ pub struct IafRefCell {
 }
 
 impl XML for IafRefCell {
-    fn from_node(node: &Node) -> Self {
-        let refract = node.attribute("refract").map(|s| s.to_string()).unwrap();
-        let leakReversal = node.attribute("leakReversal").map(|s| s.to_string()).unwrap();
-        let thresh = node.attribute("thresh").map(|s| s.to_string()).unwrap();
-        let reset = node.attribute("reset").map(|s| s.to_string()).unwrap();
-        let C = node.attribute("C").map(|s| s.to_string()).unwrap();
-        let leakConductance = node.attribute("leakConductance").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let refract = req_attr::<Nml2Quantity>(node, "refract")?;
+        let leakReversal = req_attr::<Nml2Quantity>(node, "leakReversal")?;
+        let thresh = req_attr::<Nml2Quantity>(node, "thresh")?;
+        let reset = req_attr::<Nml2Quantity>(node, "reset")?;
+        let C = req_attr::<Nml2Quantity>(node, "C")?;
+        let leakConductance = req_attr::<Nml2Quantity>(node, "leakConductance")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(IafRefCellBody::notes(String::from_node(&child))),
-                "property" => body.push(IafRefCellBody::property(Property::from_node(&child))),
-                "annotation" => body.push(IafRefCellBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of IafRefCell.", t)
+                "notes" => body.push(IafRefCellBody::notes(String::from_node(&child)?)),
+                "property" => body.push(IafRefCellBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(IafRefCellBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "IafRefCell")),
             };
         }
-        IafRefCell {
+        Ok(IafRefCell {
             refract,
             leakReversal,
             thresh,
@@ -4408,6 +7106,28 @@ impl XML for IafRefCell {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "refract", &self.refract);
+        write_attr(out, "leakReversal", &self.leakReversal);
+        write_attr(out, "thresh", &self.thresh);
+        write_attr(out, "reset", &self.reset);
+        write_attr(out, "C", &self.C);
+        write_attr(out, "leakConductance", &self.leakConductance);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -4418,11 +7138,17 @@ pub struct IncludeType {
 }
 
 impl XML for IncludeType {
-    fn from_node(node: &Node) -> Self {
-        let href = node.attribute("href").map(|s| s.to_string()).unwrap();
-        IncludeType {
+    fn from_node(node: &Node) -> Result<Self> {
+        let href = req_attr::<String>(node, "href")?;
+        Ok(IncludeType {
             href,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "href", &self.href);
+        out.push_str("/>\n");
     }
 }
 
@@ -4433,12 +7159,23 @@ pub enum VoltageClampBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl VoltageClampBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            VoltageClampBody::notes(x) => x.to_xml("notes", depth, out),
+            VoltageClampBody::property(x) => x.to_xml("property", depth, out),
+            VoltageClampBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct VoltageClamp {
-    pub delay: String,
-    pub duration: String,
-    pub targetVoltage: String,
-    pub simpleSeriesResistance: String,
+    pub delay: Nml2Quantity,
+    pub duration: Nml2Quantity,
+    pub targetVoltage: Nml2Quantity,
+    pub simpleSeriesResistance: Nml2Quantity,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -4446,27 +7183,27 @@ pub struct VoltageClamp {
 }
 
 impl XML for VoltageClamp {
-    fn from_node(node: &Node) -> Self {
-        let delay = node.attribute("delay").map(|s| s.to_string()).unwrap();
-        let duration = node.attribute("duration").map(|s| s.to_string()).unwrap();
-        let targetVoltage = node.attribute("targetVoltage").map(|s| s.to_string()).unwrap();
-        let simpleSeriesResistance = node.attribute("simpleSeriesResistance").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let delay = req_attr::<Nml2Quantity>(node, "delay")?;
+        let duration = req_attr::<Nml2Quantity>(node, "duration")?;
+        let targetVoltage = req_attr::<Nml2Quantity>(node, "targetVoltage")?;
+        let simpleSeriesResistance = req_attr::<Nml2Quantity>(node, "simpleSeriesResistance")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(VoltageClampBody::notes(String::from_node(&child))),
-                "property" => body.push(VoltageClampBody::property(Property::from_node(&child))),
-                "annotation" => body.push(VoltageClampBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of VoltageClamp.", t)
+                "notes" => body.push(VoltageClampBody::notes(String::from_node(&child)?)),
+                "property" => body.push(VoltageClampBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(VoltageClampBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "VoltageClamp")),
             };
         }
-        VoltageClamp {
+        Ok(VoltageClamp {
             delay,
             duration,
             targetVoltage,
@@ -4475,6 +7212,26 @@ impl XML for VoltageClamp {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "delay", &self.delay);
+        write_attr(out, "duration", &self.duration);
+        write_attr(out, "targetVoltage", &self.targetVoltage);
+        write_attr(out, "simpleSeriesResistance", &self.simpleSeriesResistance);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -4486,13 +7243,20 @@ pub struct InstanceRequirement {
 }
 
 impl XML for InstanceRequirement {
-    fn from_node(node: &Node) -> Self {
-        let name = node.attribute("name").map(|s| s.to_string()).unwrap();
-        let r#type = node.attribute("type").map(|s| s.to_string()).unwrap();
-        InstanceRequirement {
+    fn from_node(node: &Node) -> Result<Self> {
+        let name = req_attr::<String>(node, "name")?;
+        let r#type = req_attr::<String>(node, "type")?;
+        Ok(InstanceRequirement {
             name,
             r#type,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "name", &self.name);
+        write_attr(out, "type", &self.r#type);
+        out.push_str("/>\n");
     }
 }
 
@@ -4503,9 +7267,20 @@ pub enum FitzHughNagumoCellBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl FitzHughNagumoCellBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            FitzHughNagumoCellBody::notes(x) => x.to_xml("notes", depth, out),
+            FitzHughNagumoCellBody::property(x) => x.to_xml("property", depth, out),
+            FitzHughNagumoCellBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct FitzHughNagumoCell {
-    pub I: String,
+    pub I: Nml2Quantity,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -4513,29 +7288,46 @@ pub struct FitzHughNagumoCell {
 }
 
 impl XML for FitzHughNagumoCell {
-    fn from_node(node: &Node) -> Self {
-        let I = node.attribute("I").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let I = req_attr::<Nml2Quantity>(node, "I")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(FitzHughNagumoCellBody::notes(String::from_node(&child))),
-                "property" => body.push(FitzHughNagumoCellBody::property(Property::from_node(&child))),
-                "annotation" => body.push(FitzHughNagumoCellBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of FitzHughNagumoCell.", t)
+                "notes" => body.push(FitzHughNagumoCellBody::notes(String::from_node(&child)?)),
+                "property" => body.push(FitzHughNagumoCellBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(FitzHughNagumoCellBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "FitzHughNagumoCell")),
             };
         }
-        FitzHughNagumoCell {
+        Ok(FitzHughNagumoCell {
             I,
             metaid,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "I", &self.I);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -4550,6 +7342,20 @@ pub enum CompoundInputDLBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl CompoundInputDLBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            CompoundInputDLBody::pulseGeneratorDL(x) => x.to_xml("pulseGeneratorDL", depth, out),
+            CompoundInputDLBody::sineGeneratorDL(x) => x.to_xml("sineGeneratorDL", depth, out),
+            CompoundInputDLBody::rampGeneratorDL(x) => x.to_xml("rampGeneratorDL", depth, out),
+            CompoundInputDLBody::notes(x) => x.to_xml("notes", depth, out),
+            CompoundInputDLBody::property(x) => x.to_xml("property", depth, out),
+            CompoundInputDLBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct CompoundInputDL {
     pub metaid: Option<String>,
@@ -4559,30 +7365,46 @@ pub struct CompoundInputDL {
 }
 
 impl XML for CompoundInputDL {
-    fn from_node(node: &Node) -> Self {
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "pulseGeneratorDL" => body.push(CompoundInputDLBody::pulseGeneratorDL(PulseGeneratorDL::from_node(&child))),
-                "sineGeneratorDL" => body.push(CompoundInputDLBody::sineGeneratorDL(SineGeneratorDL::from_node(&child))),
-                "rampGeneratorDL" => body.push(CompoundInputDLBody::rampGeneratorDL(RampGeneratorDL::from_node(&child))),
-                "notes" => body.push(CompoundInputDLBody::notes(String::from_node(&child))),
-                "property" => body.push(CompoundInputDLBody::property(Property::from_node(&child))),
-                "annotation" => body.push(CompoundInputDLBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of CompoundInputDL.", t)
+                "pulseGeneratorDL" => body.push(CompoundInputDLBody::pulseGeneratorDL(PulseGeneratorDL::from_node(&child)?)),
+                "sineGeneratorDL" => body.push(CompoundInputDLBody::sineGeneratorDL(SineGeneratorDL::from_node(&child)?)),
+                "rampGeneratorDL" => body.push(CompoundInputDLBody::rampGeneratorDL(RampGeneratorDL::from_node(&child)?)),
+                "notes" => body.push(CompoundInputDLBody::notes(String::from_node(&child)?)),
+                "property" => body.push(CompoundInputDLBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(CompoundInputDLBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "CompoundInputDL")),
             };
         }
-        CompoundInputDL {
+        Ok(CompoundInputDL {
             metaid,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -4596,34 +7418,50 @@ pub struct NamedDimensionalVariable {
 }
 
 impl XML for NamedDimensionalVariable {
-    fn from_node(node: &Node) -> Self {
-        let name = node.attribute("name").map(|s| s.to_string()).unwrap();
-        let dimension = node.attribute("dimension").map(|s| s.to_string()).unwrap();
-        let description = node.attribute("description").map(|s| s.to_string());
-        let exposure = node.attribute("exposure").map(|s| s.to_string());
-        NamedDimensionalVariable {
+    fn from_node(node: &Node) -> Result<Self> {
+        let name = req_attr::<String>(node, "name")?;
+        let dimension = req_attr::<String>(node, "dimension")?;
+        let description = opt_attr::<String>(node, "description")?;
+        let exposure = opt_attr::<String>(node, "exposure")?;
+        Ok(NamedDimensionalVariable {
             name,
             dimension,
             description,
             exposure,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "name", &self.name);
+        write_attr(out, "dimension", &self.dimension);
+        if let Some(x) = &self.description { write_attr(out, "description", x); }
+        if let Some(x) = &self.exposure { write_attr(out, "exposure", x); }
+        out.push_str("/>\n");
     }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Q10ConductanceScaling {
-    pub q10Factor: String,
-    pub experimentalTemp: String,
+    pub q10Factor: Nml2Quantity,
+    pub experimentalTemp: Nml2Quantity,
 }
 
 impl XML for Q10ConductanceScaling {
-    fn from_node(node: &Node) -> Self {
-        let q10Factor = node.attribute("q10Factor").map(|s| s.to_string()).unwrap();
-        let experimentalTemp = node.attribute("experimentalTemp").map(|s| s.to_string()).unwrap();
-        Q10ConductanceScaling {
+    fn from_node(node: &Node) -> Result<Self> {
+        let q10Factor = req_attr::<Nml2Quantity>(node, "q10Factor")?;
+        let experimentalTemp = req_attr::<Nml2Quantity>(node, "experimentalTemp")?;
+        Ok(Q10ConductanceScaling {
             q10Factor,
             experimentalTemp,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "q10Factor", &self.q10Factor);
+        write_attr(out, "experimentalTemp", &self.experimentalTemp);
+        out.push_str("/>\n");
     }
 }
 
@@ -4636,6 +7474,19 @@ pub enum GateHHRatesInfBody {
     steadyState(HHVariable),
 }
 
+#[allow(dead_code)]
+impl GateHHRatesInfBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            GateHHRatesInfBody::notes(x) => x.to_xml("notes", depth, out),
+            GateHHRatesInfBody::q10Settings(x) => x.to_xml("q10Settings", depth, out),
+            GateHHRatesInfBody::forwardRate(x) => x.to_xml("forwardRate", depth, out),
+            GateHHRatesInfBody::reverseRate(x) => x.to_xml("reverseRate", depth, out),
+            GateHHRatesInfBody::steadyState(x) => x.to_xml("steadyState", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct GateHHRatesInf {
     pub instances: i64,
@@ -4645,29 +7496,45 @@ pub struct GateHHRatesInf {
 }
 
 impl XML for GateHHRatesInf {
-    fn from_node(node: &Node) -> Self {
-        let instances = node.attribute("instances").map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let instances = req_attr::<i64>(node, "instances")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(GateHHRatesInfBody::notes(String::from_node(&child))),
-                "q10Settings" => body.push(GateHHRatesInfBody::q10Settings(Q10Settings::from_node(&child))),
-                "forwardRate" => body.push(GateHHRatesInfBody::forwardRate(HHRate::from_node(&child))),
-                "reverseRate" => body.push(GateHHRatesInfBody::reverseRate(HHRate::from_node(&child))),
-                "steadyState" => body.push(GateHHRatesInfBody::steadyState(HHVariable::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of GateHHRatesInf.", t)
+                "notes" => body.push(GateHHRatesInfBody::notes(String::from_node(&child)?)),
+                "q10Settings" => body.push(GateHHRatesInfBody::q10Settings(Q10Settings::from_node(&child)?)),
+                "forwardRate" => body.push(GateHHRatesInfBody::forwardRate(HHRate::from_node(&child)?)),
+                "reverseRate" => body.push(GateHHRatesInfBody::reverseRate(HHRate::from_node(&child)?)),
+                "steadyState" => body.push(GateHHRatesInfBody::steadyState(HHVariable::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "GateHHRatesInf")),
             };
         }
-        GateHHRatesInf {
+        Ok(GateHHRatesInf {
             instances,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "instances", &self.instances);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -4680,15 +7547,23 @@ pub struct ExplicitInput {
 }
 
 impl XML for ExplicitInput {
-    fn from_node(node: &Node) -> Self {
-        let target = node.attribute("target").map(|s| s.to_string()).unwrap();
-        let input = node.attribute("input").map(|s| s.to_string()).unwrap();
-        let destination = node.attribute("destination").map(|s| s.to_string());
-        ExplicitInput {
+    fn from_node(node: &Node) -> Result<Self> {
+        let target = req_attr::<String>(node, "target")?;
+        let input = req_attr::<String>(node, "input")?;
+        let destination = opt_attr::<String>(node, "destination")?;
+        Ok(ExplicitInput {
             target,
             input,
             destination,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "target", &self.target);
+        write_attr(out, "input", &self.input);
+        if let Some(x) = &self.destination { write_attr(out, "destination", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -4708,11 +7583,31 @@ pub enum IonChannelBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl IonChannelBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            IonChannelBody::gate(x) => x.to_xml("gate", depth, out),
+            IonChannelBody::gateHHrates(x) => x.to_xml("gateHHrates", depth, out),
+            IonChannelBody::gateHHratesTau(x) => x.to_xml("gateHHratesTau", depth, out),
+            IonChannelBody::gateHHtauInf(x) => x.to_xml("gateHHtauInf", depth, out),
+            IonChannelBody::gateHHratesInf(x) => x.to_xml("gateHHratesInf", depth, out),
+            IonChannelBody::gateHHratesTauInf(x) => x.to_xml("gateHHratesTauInf", depth, out),
+            IonChannelBody::gateHHInstantaneous(x) => x.to_xml("gateHHInstantaneous", depth, out),
+            IonChannelBody::gateFractional(x) => x.to_xml("gateFractional", depth, out),
+            IonChannelBody::q10ConductanceScaling(x) => x.to_xml("q10ConductanceScaling", depth, out),
+            IonChannelBody::notes(x) => x.to_xml("notes", depth, out),
+            IonChannelBody::property(x) => x.to_xml("property", depth, out),
+            IonChannelBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct IonChannel {
     pub species: Option<String>,
-    pub r#type: Option<String>,
-    pub conductance: Option<String>,
+    pub r#type: Option<channelTypes>,
+    pub conductance: Option<Nml2Quantity>,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -4720,35 +7615,35 @@ pub struct IonChannel {
 }
 
 impl XML for IonChannel {
-    fn from_node(node: &Node) -> Self {
-        let species = node.attribute("species").map(|s| s.to_string());
-        let r#type = node.attribute("type").map(|s| s.to_string());
-        let conductance = node.attribute("conductance").map(|s| s.to_string());
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let species = opt_attr::<String>(node, "species")?;
+        let r#type = opt_attr::<channelTypes>(node, "type")?;
+        let conductance = opt_attr::<Nml2Quantity>(node, "conductance")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "gate" => body.push(IonChannelBody::gate(GateHHUndetermined::from_node(&child))),
-                "gateHHrates" => body.push(IonChannelBody::gateHHrates(GateHHRates::from_node(&child))),
-                "gateHHratesTau" => body.push(IonChannelBody::gateHHratesTau(GateHHRatesTau::from_node(&child))),
-                "gateHHtauInf" => body.push(IonChannelBody::gateHHtauInf(GateHHTauInf::from_node(&child))),
-                "gateHHratesInf" => body.push(IonChannelBody::gateHHratesInf(GateHHRatesInf::from_node(&child))),
-                "gateHHratesTauInf" => body.push(IonChannelBody::gateHHratesTauInf(GateHHRatesTauInf::from_node(&child))),
-                "gateHHInstantaneous" => body.push(IonChannelBody::gateHHInstantaneous(GateHHInstantaneous::from_node(&child))),
-                "gateFractional" => body.push(IonChannelBody::gateFractional(GateFractional::from_node(&child))),
-                "q10ConductanceScaling" => body.push(IonChannelBody::q10ConductanceScaling(Q10ConductanceScaling::from_node(&child))),
-                "notes" => body.push(IonChannelBody::notes(String::from_node(&child))),
-                "property" => body.push(IonChannelBody::property(Property::from_node(&child))),
-                "annotation" => body.push(IonChannelBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of IonChannel.", t)
+                "gate" => body.push(IonChannelBody::gate(GateHHUndetermined::from_node(&child)?)),
+                "gateHHrates" => body.push(IonChannelBody::gateHHrates(GateHHRates::from_node(&child)?)),
+                "gateHHratesTau" => body.push(IonChannelBody::gateHHratesTau(GateHHRatesTau::from_node(&child)?)),
+                "gateHHtauInf" => body.push(IonChannelBody::gateHHtauInf(GateHHTauInf::from_node(&child)?)),
+                "gateHHratesInf" => body.push(IonChannelBody::gateHHratesInf(GateHHRatesInf::from_node(&child)?)),
+                "gateHHratesTauInf" => body.push(IonChannelBody::gateHHratesTauInf(GateHHRatesTauInf::from_node(&child)?)),
+                "gateHHInstantaneous" => body.push(IonChannelBody::gateHHInstantaneous(GateHHInstantaneous::from_node(&child)?)),
+                "gateFractional" => body.push(IonChannelBody::gateFractional(GateFractional::from_node(&child)?)),
+                "q10ConductanceScaling" => body.push(IonChannelBody::q10ConductanceScaling(Q10ConductanceScaling::from_node(&child)?)),
+                "notes" => body.push(IonChannelBody::notes(String::from_node(&child)?)),
+                "property" => body.push(IonChannelBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(IonChannelBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "IonChannel")),
             };
         }
-        IonChannel {
+        Ok(IonChannel {
             species,
             r#type,
             conductance,
@@ -4756,6 +7651,25 @@ impl XML for IonChannel {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if let Some(x) = &self.species { write_attr(out, "species", x); }
+        if let Some(x) = &self.r#type { write_attr(out, "type", x); }
+        if let Some(x) = &self.conductance { write_attr(out, "conductance", x); }
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -4767,13 +7681,24 @@ pub enum IafTauRefCellBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl IafTauRefCellBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            IafTauRefCellBody::notes(x) => x.to_xml("notes", depth, out),
+            IafTauRefCellBody::property(x) => x.to_xml("property", depth, out),
+            IafTauRefCellBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct IafTauRefCell {
-    pub refract: String,
-    pub leakReversal: String,
-    pub thresh: String,
-    pub reset: String,
-    pub tau: String,
+    pub refract: Nml2Quantity,
+    pub leakReversal: Nml2Quantity,
+    pub thresh: Nml2Quantity,
+    pub reset: Nml2Quantity,
+    pub tau: Nml2Quantity,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -4781,28 +7706,28 @@ pub struct IafTauRefCell {
 }
 
 impl XML for IafTauRefCell {
-    fn from_node(node: &Node) -> Self {
-        let refract = node.attribute("refract").map(|s| s.to_string()).unwrap();
-        let leakReversal = node.attribute("leakReversal").map(|s| s.to_string()).unwrap();
-        let thresh = node.attribute("thresh").map(|s| s.to_string()).unwrap();
-        let reset = node.attribute("reset").map(|s| s.to_string()).unwrap();
-        let tau = node.attribute("tau").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let refract = req_attr::<Nml2Quantity>(node, "refract")?;
+        let leakReversal = req_attr::<Nml2Quantity>(node, "leakReversal")?;
+        let thresh = req_attr::<Nml2Quantity>(node, "thresh")?;
+        let reset = req_attr::<Nml2Quantity>(node, "reset")?;
+        let tau = req_attr::<Nml2Quantity>(node, "tau")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(IafTauRefCellBody::notes(String::from_node(&child))),
-                "property" => body.push(IafTauRefCellBody::property(Property::from_node(&child))),
-                "annotation" => body.push(IafTauRefCellBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of IafTauRefCell.", t)
+                "notes" => body.push(IafTauRefCellBody::notes(String::from_node(&child)?)),
+                "property" => body.push(IafTauRefCellBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(IafTauRefCellBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "IafTauRefCell")),
             };
         }
-        IafTauRefCell {
+        Ok(IafTauRefCell {
             refract,
             leakReversal,
             thresh,
@@ -4812,6 +7737,27 @@ impl XML for IafTauRefCell {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "refract", &self.refract);
+        write_attr(out, "leakReversal", &self.leakReversal);
+        write_attr(out, "thresh", &self.thresh);
+        write_attr(out, "reset", &self.reset);
+        write_attr(out, "tau", &self.tau);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -4823,11 +7769,22 @@ pub enum TransientPoissonFiringSynapseBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl TransientPoissonFiringSynapseBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            TransientPoissonFiringSynapseBody::notes(x) => x.to_xml("notes", depth, out),
+            TransientPoissonFiringSynapseBody::property(x) => x.to_xml("property", depth, out),
+            TransientPoissonFiringSynapseBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct TransientPoissonFiringSynapse {
-    pub averageRate: String,
-    pub delay: String,
-    pub duration: String,
+    pub averageRate: Nml2Quantity,
+    pub delay: Nml2Quantity,
+    pub duration: Nml2Quantity,
     pub synapse: String,
     pub spikeTarget: String,
     pub metaid: Option<String>,
@@ -4837,28 +7794,28 @@ pub struct TransientPoissonFiringSynapse {
 }
 
 impl XML for TransientPoissonFiringSynapse {
-    fn from_node(node: &Node) -> Self {
-        let averageRate = node.attribute("averageRate").map(|s| s.to_string()).unwrap();
-        let delay = node.attribute("delay").map(|s| s.to_string()).unwrap();
-        let duration = node.attribute("duration").map(|s| s.to_string()).unwrap();
-        let synapse = node.attribute("synapse").map(|s| s.to_string()).unwrap();
-        let spikeTarget = node.attribute("spikeTarget").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let averageRate = req_attr::<Nml2Quantity>(node, "averageRate")?;
+        let delay = req_attr::<Nml2Quantity>(node, "delay")?;
+        let duration = req_attr::<Nml2Quantity>(node, "duration")?;
+        let synapse = req_attr::<String>(node, "synapse")?;
+        let spikeTarget = req_attr::<String>(node, "spikeTarget")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(TransientPoissonFiringSynapseBody::notes(String::from_node(&child))),
-                "property" => body.push(TransientPoissonFiringSynapseBody::property(Property::from_node(&child))),
-                "annotation" => body.push(TransientPoissonFiringSynapseBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of TransientPoissonFiringSynapse.", t)
+                "notes" => body.push(TransientPoissonFiringSynapseBody::notes(String::from_node(&child)?)),
+                "property" => body.push(TransientPoissonFiringSynapseBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(TransientPoissonFiringSynapseBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "TransientPoissonFiringSynapse")),
             };
         }
-        TransientPoissonFiringSynapse {
+        Ok(TransientPoissonFiringSynapse {
             averageRate,
             delay,
             duration,
@@ -4868,6 +7825,27 @@ impl XML for TransientPoissonFiringSynapse {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "averageRate", &self.averageRate);
+        write_attr(out, "delay", &self.delay);
+        write_attr(out, "duration", &self.duration);
+        write_attr(out, "synapse", &self.synapse);
+        write_attr(out, "spikeTarget", &self.spikeTarget);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -4880,15 +7858,23 @@ pub struct Parameter {
 }
 
 impl XML for Parameter {
-    fn from_node(node: &Node) -> Self {
-        let name = node.attribute("name").map(|s| s.to_string()).unwrap();
-        let dimension = node.attribute("dimension").map(|s| s.to_string()).unwrap();
-        let description = node.attribute("description").map(|s| s.to_string());
-        Parameter {
+    fn from_node(node: &Node) -> Result<Self> {
+        let name = req_attr::<String>(node, "name")?;
+        let dimension = req_attr::<String>(node, "dimension")?;
+        let description = opt_attr::<String>(node, "description")?;
+        Ok(Parameter {
             name,
             dimension,
             description,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "name", &self.name);
+        write_attr(out, "dimension", &self.dimension);
+        if let Some(x) = &self.description { write_attr(out, "description", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -4898,6 +7884,16 @@ pub enum TauInfTransitionBody {
     timeCourse(HHTime),
 }
 
+#[allow(dead_code)]
+impl TauInfTransitionBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            TauInfTransitionBody::steadyState(x) => x.to_xml("steadyState", depth, out),
+            TauInfTransitionBody::timeCourse(x) => x.to_xml("timeCourse", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct TauInfTransition {
     pub from: String,
@@ -4908,28 +7904,45 @@ pub struct TauInfTransition {
 }
 
 impl XML for TauInfTransition {
-    fn from_node(node: &Node) -> Self {
-        let from = node.attribute("from").map(|s| s.to_string()).unwrap();
-        let to = node.attribute("to").map(|s| s.to_string()).unwrap();
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let from = req_attr::<String>(node, "from")?;
+        let to = req_attr::<String>(node, "to")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "steadyState" => body.push(TauInfTransitionBody::steadyState(HHVariable::from_node(&child))),
-                "timeCourse" => body.push(TauInfTransitionBody::timeCourse(HHTime::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of TauInfTransition.", t)
+                "steadyState" => body.push(TauInfTransitionBody::steadyState(HHVariable::from_node(&child)?)),
+                "timeCourse" => body.push(TauInfTransitionBody::timeCourse(HHTime::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "TauInfTransition")),
             };
         }
-        TauInfTransition {
+        Ok(TauInfTransition {
             from,
             to,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "from", &self.from);
+        write_attr(out, "to", &self.to);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -4940,6 +7953,16 @@ pub enum ProjectionBody {
     connectionWD(ConnectionWD),
 }
 
+#[allow(dead_code)]
+impl ProjectionBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            ProjectionBody::connection(x) => x.to_xml("connection", depth, out),
+            ProjectionBody::connectionWD(x) => x.to_xml("connectionWD", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Projection {
     pub synapse: String,
@@ -4951,54 +7974,81 @@ pub struct Projection {
 }
 
 impl XML for Projection {
-    fn from_node(node: &Node) -> Self {
-        let synapse = node.attribute("synapse").map(|s| s.to_string()).unwrap();
-        let presynapticPopulation = node.attribute("presynapticPopulation").map(|s| s.to_string()).unwrap();
-        let postsynapticPopulation = node.attribute("postsynapticPopulation").map(|s| s.to_string()).unwrap();
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let synapse = req_attr::<String>(node, "synapse")?;
+        let presynapticPopulation = req_attr::<String>(node, "presynapticPopulation")?;
+        let postsynapticPopulation = req_attr::<String>(node, "postsynapticPopulation")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "connection" => body.push(ProjectionBody::connection(Connection::from_node(&child))),
-                "connectionWD" => body.push(ProjectionBody::connectionWD(ConnectionWD::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of Projection.", t)
+                "connection" => body.push(ProjectionBody::connection(Connection::from_node(&child)?)),
+                "connectionWD" => body.push(ProjectionBody::connectionWD(ConnectionWD::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "Projection")),
             };
         }
-        Projection {
+        Ok(Projection {
             synapse,
             presynapticPopulation,
             postsynapticPopulation,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "synapse", &self.synapse);
+        write_attr(out, "presynapticPopulation", &self.presynapticPopulation);
+        write_attr(out, "postsynapticPopulation", &self.postsynapticPopulation);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct PlasticityMechanism {
-    pub r#type: String,
+    pub r#type: PlasticityTypes,
     pub initReleaseProb: f64,
-    pub tauRec: String,
-    pub tauFac: Option<String>,
+    pub tauRec: Nml2Quantity,
+    pub tauFac: Option<Nml2Quantity>,
 }
 
 impl XML for PlasticityMechanism {
-    fn from_node(node: &Node) -> Self {
-        let r#type = node.attribute("type").map(|s| s.to_string()).unwrap();
-        let initReleaseProb = node.attribute("initReleaseProb").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let tauRec = node.attribute("tauRec").map(|s| s.to_string()).unwrap();
-        let tauFac = node.attribute("tauFac").map(|s| s.to_string());
-        PlasticityMechanism {
+    fn from_node(node: &Node) -> Result<Self> {
+        let r#type = req_attr::<PlasticityTypes>(node, "type")?;
+        let initReleaseProb = req_attr::<f64>(node, "initReleaseProb")?;
+        let tauRec = req_attr::<Nml2Quantity>(node, "tauRec")?;
+        let tauFac = opt_attr::<Nml2Quantity>(node, "tauFac")?;
+        Ok(PlasticityMechanism {
             r#type,
             initReleaseProb,
             tauRec,
             tauFac,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "type", &self.r#type);
+        write_attr(out, "initReleaseProb", &self.initReleaseProb);
+        write_attr(out, "tauRec", &self.tauRec);
+        if let Some(x) = &self.tauFac { write_attr(out, "tauFac", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -5010,28 +8060,53 @@ pub enum DynamicsBody {
     TimeDerivative(TimeDerivative),
 }
 
+#[allow(dead_code)]
+impl DynamicsBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            DynamicsBody::StateVariable(x) => x.to_xml("StateVariable", depth, out),
+            DynamicsBody::DerivedVariable(x) => x.to_xml("DerivedVariable", depth, out),
+            DynamicsBody::ConditionalDerivedVariable(x) => x.to_xml("ConditionalDerivedVariable", depth, out),
+            DynamicsBody::TimeDerivative(x) => x.to_xml("TimeDerivative", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Dynamics {
     pub body: Vec<DynamicsBody>
 }
 
 impl XML for Dynamics {
-    fn from_node(node: &Node) -> Self {
+    fn from_node(node: &Node) -> Result<Self> {
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "StateVariable" => body.push(DynamicsBody::StateVariable(StateVariable::from_node(&child))),
-                "DerivedVariable" => body.push(DynamicsBody::DerivedVariable(DerivedVariable::from_node(&child))),
-                "ConditionalDerivedVariable" => body.push(DynamicsBody::ConditionalDerivedVariable(ConditionalDerivedVariable::from_node(&child))),
-                "TimeDerivative" => body.push(DynamicsBody::TimeDerivative(TimeDerivative::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of Dynamics.", t)
+                "StateVariable" => body.push(DynamicsBody::StateVariable(StateVariable::from_node(&child)?)),
+                "DerivedVariable" => body.push(DynamicsBody::DerivedVariable(DerivedVariable::from_node(&child)?)),
+                "ConditionalDerivedVariable" => body.push(DynamicsBody::ConditionalDerivedVariable(ConditionalDerivedVariable::from_node(&child)?)),
+                "TimeDerivative" => body.push(DynamicsBody::TimeDerivative(TimeDerivative::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "Dynamics")),
             };
         }
-        Dynamics {
+        Ok(Dynamics {
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -5039,23 +8114,32 @@ impl XML for Dynamics {
 #[derive(Debug, Clone, PartialEq)]
 pub struct Q10Settings {
     pub r#type: String,
-    pub fixedQ10: Option<String>,
-    pub q10Factor: Option<String>,
-    pub experimentalTemp: Option<String>,
+    pub fixedQ10: Option<Nml2Quantity>,
+    pub q10Factor: Option<Nml2Quantity>,
+    pub experimentalTemp: Option<Nml2Quantity>,
 }
 
 impl XML for Q10Settings {
-    fn from_node(node: &Node) -> Self {
-        let r#type = node.attribute("type").map(|s| s.to_string()).unwrap();
-        let fixedQ10 = node.attribute("fixedQ10").map(|s| s.to_string());
-        let q10Factor = node.attribute("q10Factor").map(|s| s.to_string());
-        let experimentalTemp = node.attribute("experimentalTemp").map(|s| s.to_string());
-        Q10Settings {
+    fn from_node(node: &Node) -> Result<Self> {
+        let r#type = req_attr::<String>(node, "type")?;
+        let fixedQ10 = opt_attr::<Nml2Quantity>(node, "fixedQ10")?;
+        let q10Factor = opt_attr::<Nml2Quantity>(node, "q10Factor")?;
+        let experimentalTemp = opt_attr::<Nml2Quantity>(node, "experimentalTemp")?;
+        Ok(Q10Settings {
             r#type,
             fixedQ10,
             q10Factor,
             experimentalTemp,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "type", &self.r#type);
+        if let Some(x) = &self.fixedQ10 { write_attr(out, "fixedQ10", x); }
+        if let Some(x) = &self.q10Factor { write_attr(out, "q10Factor", x); }
+        if let Some(x) = &self.experimentalTemp { write_attr(out, "experimentalTemp", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -5064,6 +8148,15 @@ pub enum ConditionalDerivedVariableBody {
     Case(Case),
 }
 
+#[allow(dead_code)]
+impl ConditionalDerivedVariableBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            ConditionalDerivedVariableBody::Case(x) => x.to_xml("Case", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ConditionalDerivedVariable {
     pub name: String,
@@ -5074,27 +8167,44 @@ pub struct ConditionalDerivedVariable {
 }
 
 impl XML for ConditionalDerivedVariable {
-    fn from_node(node: &Node) -> Self {
-        let name = node.attribute("name").map(|s| s.to_string()).unwrap();
-        let dimension = node.attribute("dimension").map(|s| s.to_string()).unwrap();
-        let description = node.attribute("description").map(|s| s.to_string());
-        let exposure = node.attribute("exposure").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let name = req_attr::<String>(node, "name")?;
+        let dimension = req_attr::<String>(node, "dimension")?;
+        let description = opt_attr::<String>(node, "description")?;
+        let exposure = opt_attr::<String>(node, "exposure")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "Case" => body.push(ConditionalDerivedVariableBody::Case(Case::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of ConditionalDerivedVariable.", t)
+                "Case" => body.push(ConditionalDerivedVariableBody::Case(Case::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "ConditionalDerivedVariable")),
             };
         }
-        ConditionalDerivedVariable {
+        Ok(ConditionalDerivedVariable {
             name,
             dimension,
             description,
             exposure,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "name", &self.name);
+        write_attr(out, "dimension", &self.dimension);
+        if let Some(x) = &self.description { write_attr(out, "description", x); }
+        if let Some(x) = &self.exposure { write_attr(out, "exposure", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -5107,6 +8217,18 @@ pub enum GateHHTauInfBody {
     steadyState(HHVariable),
 }
 
+#[allow(dead_code)]
+impl GateHHTauInfBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            GateHHTauInfBody::notes(x) => x.to_xml("notes", depth, out),
+            GateHHTauInfBody::q10Settings(x) => x.to_xml("q10Settings", depth, out),
+            GateHHTauInfBody::timeCourse(x) => x.to_xml("timeCourse", depth, out),
+            GateHHTauInfBody::steadyState(x) => x.to_xml("steadyState", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct GateHHTauInf {
     pub instances: i64,
@@ -5116,28 +8238,44 @@ pub struct GateHHTauInf {
 }
 
 impl XML for GateHHTauInf {
-    fn from_node(node: &Node) -> Self {
-        let instances = node.attribute("instances").map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let instances = req_attr::<i64>(node, "instances")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(GateHHTauInfBody::notes(String::from_node(&child))),
-                "q10Settings" => body.push(GateHHTauInfBody::q10Settings(Q10Settings::from_node(&child))),
-                "timeCourse" => body.push(GateHHTauInfBody::timeCourse(HHTime::from_node(&child))),
-                "steadyState" => body.push(GateHHTauInfBody::steadyState(HHVariable::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of GateHHTauInf.", t)
+                "notes" => body.push(GateHHTauInfBody::notes(String::from_node(&child)?)),
+                "q10Settings" => body.push(GateHHTauInfBody::q10Settings(Q10Settings::from_node(&child)?)),
+                "timeCourse" => body.push(GateHHTauInfBody::timeCourse(HHTime::from_node(&child)?)),
+                "steadyState" => body.push(GateHHTauInfBody::steadyState(HHVariable::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "GateHHTauInf")),
             };
         }
-        GateHHTauInf {
+        Ok(GateHHTauInf {
             instances,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "instances", &self.instances);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -5150,6 +8288,18 @@ pub enum SpikeArrayBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl SpikeArrayBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            SpikeArrayBody::spike(x) => x.to_xml("spike", depth, out),
+            SpikeArrayBody::notes(x) => x.to_xml("notes", depth, out),
+            SpikeArrayBody::property(x) => x.to_xml("property", depth, out),
+            SpikeArrayBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct SpikeArray {
     pub metaid: Option<String>,
@@ -5159,28 +8309,44 @@ pub struct SpikeArray {
 }
 
 impl XML for SpikeArray {
-    fn from_node(node: &Node) -> Self {
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "spike" => body.push(SpikeArrayBody::spike(Spike::from_node(&child))),
-                "notes" => body.push(SpikeArrayBody::notes(String::from_node(&child))),
-                "property" => body.push(SpikeArrayBody::property(Property::from_node(&child))),
-                "annotation" => body.push(SpikeArrayBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of SpikeArray.", t)
+                "spike" => body.push(SpikeArrayBody::spike(Spike::from_node(&child)?)),
+                "notes" => body.push(SpikeArrayBody::notes(String::from_node(&child)?)),
+                "property" => body.push(SpikeArrayBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(SpikeArrayBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "SpikeArray")),
             };
         }
-        SpikeArray {
+        Ok(SpikeArray {
             metaid,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -5191,11 +8357,17 @@ pub struct BaseWithoutId {
 }
 
 impl XML for BaseWithoutId {
-    fn from_node(node: &Node) -> Self {
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
-        BaseWithoutId {
+    fn from_node(node: &Node) -> Result<Self> {
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
+        Ok(BaseWithoutId {
             neuroLexId,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -5206,6 +8378,17 @@ pub enum basePyNNIaFCondCellBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl basePyNNIaFCondCellBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            basePyNNIaFCondCellBody::notes(x) => x.to_xml("notes", depth, out),
+            basePyNNIaFCondCellBody::property(x) => x.to_xml("property", depth, out),
+            basePyNNIaFCondCellBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct basePyNNIaFCondCell {
     pub e_rev_E: f64,
@@ -5227,35 +8410,35 @@ pub struct basePyNNIaFCondCell {
 }
 
 impl XML for basePyNNIaFCondCell {
-    fn from_node(node: &Node) -> Self {
-        let e_rev_E = node.attribute("e_rev_E").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let e_rev_I = node.attribute("e_rev_I").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let tau_m = node.attribute("tau_m").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let tau_refrac = node.attribute("tau_refrac").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let v_reset = node.attribute("v_reset").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let v_rest = node.attribute("v_rest").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let v_thresh = node.attribute("v_thresh").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let cm = node.attribute("cm").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let i_offset = node.attribute("i_offset").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let tau_syn_E = node.attribute("tau_syn_E").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let tau_syn_I = node.attribute("tau_syn_I").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let v_init = node.attribute("v_init").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let e_rev_E = req_attr::<f64>(node, "e_rev_E")?;
+        let e_rev_I = req_attr::<f64>(node, "e_rev_I")?;
+        let tau_m = req_attr::<f64>(node, "tau_m")?;
+        let tau_refrac = req_attr::<f64>(node, "tau_refrac")?;
+        let v_reset = req_attr::<f64>(node, "v_reset")?;
+        let v_rest = req_attr::<f64>(node, "v_rest")?;
+        let v_thresh = req_attr::<f64>(node, "v_thresh")?;
+        let cm = req_attr::<f64>(node, "cm")?;
+        let i_offset = req_attr::<f64>(node, "i_offset")?;
+        let tau_syn_E = req_attr::<f64>(node, "tau_syn_E")?;
+        let tau_syn_I = req_attr::<f64>(node, "tau_syn_I")?;
+        let v_init = req_attr::<f64>(node, "v_init")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(basePyNNIaFCondCellBody::notes(String::from_node(&child))),
-                "property" => body.push(basePyNNIaFCondCellBody::property(Property::from_node(&child))),
-                "annotation" => body.push(basePyNNIaFCondCellBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of basePyNNIaFCondCell.", t)
+                "notes" => body.push(basePyNNIaFCondCellBody::notes(String::from_node(&child)?)),
+                "property" => body.push(basePyNNIaFCondCellBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(basePyNNIaFCondCellBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "basePyNNIaFCondCell")),
             };
         }
-        basePyNNIaFCondCell {
+        Ok(basePyNNIaFCondCell {
             e_rev_E,
             e_rev_I,
             tau_m,
@@ -5272,6 +8455,34 @@ impl XML for basePyNNIaFCondCell {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "e_rev_E", &self.e_rev_E);
+        write_attr(out, "e_rev_I", &self.e_rev_I);
+        write_attr(out, "tau_m", &self.tau_m);
+        write_attr(out, "tau_refrac", &self.tau_refrac);
+        write_attr(out, "v_reset", &self.v_reset);
+        write_attr(out, "v_rest", &self.v_rest);
+        write_attr(out, "v_thresh", &self.v_thresh);
+        write_attr(out, "cm", &self.cm);
+        write_attr(out, "i_offset", &self.i_offset);
+        write_attr(out, "tau_syn_E", &self.tau_syn_E);
+        write_attr(out, "tau_syn_I", &self.tau_syn_I);
+        write_attr(out, "v_init", &self.v_init);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -5283,13 +8494,20 @@ pub struct OpenState {
 }
 
 impl XML for OpenState {
-    fn from_node(node: &Node) -> Self {
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
-        OpenState {
+    fn from_node(node: &Node) -> Result<Self> {
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
+        Ok(OpenState {
             id,
             neuroLexId,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -5300,13 +8518,24 @@ pub enum GradedSynapseBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl GradedSynapseBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            GradedSynapseBody::notes(x) => x.to_xml("notes", depth, out),
+            GradedSynapseBody::property(x) => x.to_xml("property", depth, out),
+            GradedSynapseBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct GradedSynapse {
-    pub conductance: String,
-    pub delta: String,
-    pub Vth: String,
-    pub k: String,
-    pub erev: String,
+    pub conductance: Nml2Quantity,
+    pub delta: Nml2Quantity,
+    pub Vth: Nml2Quantity,
+    pub k: Nml2Quantity,
+    pub erev: Nml2Quantity,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -5314,28 +8543,28 @@ pub struct GradedSynapse {
 }
 
 impl XML for GradedSynapse {
-    fn from_node(node: &Node) -> Self {
-        let conductance = node.attribute("conductance").map(|s| s.to_string()).unwrap();
-        let delta = node.attribute("delta").map(|s| s.to_string()).unwrap();
-        let Vth = node.attribute("Vth").map(|s| s.to_string()).unwrap();
-        let k = node.attribute("k").map(|s| s.to_string()).unwrap();
-        let erev = node.attribute("erev").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let conductance = req_attr::<Nml2Quantity>(node, "conductance")?;
+        let delta = req_attr::<Nml2Quantity>(node, "delta")?;
+        let Vth = req_attr::<Nml2Quantity>(node, "Vth")?;
+        let k = req_attr::<Nml2Quantity>(node, "k")?;
+        let erev = req_attr::<Nml2Quantity>(node, "erev")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(GradedSynapseBody::notes(String::from_node(&child))),
-                "property" => body.push(GradedSynapseBody::property(Property::from_node(&child))),
-                "annotation" => body.push(GradedSynapseBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of GradedSynapse.", t)
+                "notes" => body.push(GradedSynapseBody::notes(String::from_node(&child)?)),
+                "property" => body.push(GradedSynapseBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(GradedSynapseBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "GradedSynapse")),
             };
         }
-        GradedSynapse {
+        Ok(GradedSynapse {
             conductance,
             delta,
             Vth,
@@ -5345,6 +8574,27 @@ impl XML for GradedSynapse {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "conductance", &self.conductance);
+        write_attr(out, "delta", &self.delta);
+        write_attr(out, "Vth", &self.Vth);
+        write_attr(out, "k", &self.k);
+        write_attr(out, "erev", &self.erev);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -5355,38 +8605,65 @@ pub enum InhomogeneousParameterBody {
     distal(DistalDetails),
 }
 
+#[allow(dead_code)]
+impl InhomogeneousParameterBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            InhomogeneousParameterBody::proximal(x) => x.to_xml("proximal", depth, out),
+            InhomogeneousParameterBody::distal(x) => x.to_xml("distal", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct InhomogeneousParameter {
     pub variable: String,
-    pub metric: String,
+    pub metric: Metric,
     pub id: String,
     pub neuroLexId: Option<String>,
     pub body: Vec<InhomogeneousParameterBody>
 }
 
 impl XML for InhomogeneousParameter {
-    fn from_node(node: &Node) -> Self {
-        let variable = node.attribute("variable").map(|s| s.to_string()).unwrap();
-        let metric = node.attribute("metric").map(|s| s.to_string()).unwrap();
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let variable = req_attr::<String>(node, "variable")?;
+        let metric = req_attr::<Metric>(node, "metric")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "proximal" => body.push(InhomogeneousParameterBody::proximal(ProximalDetails::from_node(&child))),
-                "distal" => body.push(InhomogeneousParameterBody::distal(DistalDetails::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of InhomogeneousParameter.", t)
+                "proximal" => body.push(InhomogeneousParameterBody::proximal(ProximalDetails::from_node(&child)?)),
+                "distal" => body.push(InhomogeneousParameterBody::distal(DistalDetails::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "InhomogeneousParameter")),
             };
         }
-        InhomogeneousParameter {
+        Ok(InhomogeneousParameter {
             variable,
             metric,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "variable", &self.variable);
+        write_attr(out, "metric", &self.metric);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -5398,13 +8675,20 @@ pub struct RandomLayout {
 }
 
 impl XML for RandomLayout {
-    fn from_node(node: &Node) -> Self {
-        let number = node.attribute("number").map(|s| s.parse::<i64>().unwrap());
-        let region = node.attribute("region").map(|s| s.to_string());
-        RandomLayout {
+    fn from_node(node: &Node) -> Result<Self> {
+        let number = opt_attr::<i64>(node, "number")?;
+        let region = opt_attr::<String>(node, "region")?;
+        Ok(RandomLayout {
             number,
             region,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if let Some(x) = &self.number { write_attr(out, "number", x); }
+        if let Some(x) = &self.region { write_attr(out, "region", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -5419,6 +8703,21 @@ pub enum GateKSBody {
     tauInfTransition(TauInfTransition),
 }
 
+#[allow(dead_code)]
+impl GateKSBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            GateKSBody::notes(x) => x.to_xml("notes", depth, out),
+            GateKSBody::q10Settings(x) => x.to_xml("q10Settings", depth, out),
+            GateKSBody::closedState(x) => x.to_xml("closedState", depth, out),
+            GateKSBody::openState(x) => x.to_xml("openState", depth, out),
+            GateKSBody::forwardTransition(x) => x.to_xml("forwardTransition", depth, out),
+            GateKSBody::reverseTransition(x) => x.to_xml("reverseTransition", depth, out),
+            GateKSBody::tauInfTransition(x) => x.to_xml("tauInfTransition", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct GateKS {
     pub instances: i64,
@@ -5428,31 +8727,47 @@ pub struct GateKS {
 }
 
 impl XML for GateKS {
-    fn from_node(node: &Node) -> Self {
-        let instances = node.attribute("instances").map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let instances = req_attr::<i64>(node, "instances")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(GateKSBody::notes(String::from_node(&child))),
-                "q10Settings" => body.push(GateKSBody::q10Settings(Q10Settings::from_node(&child))),
-                "closedState" => body.push(GateKSBody::closedState(ClosedState::from_node(&child))),
-                "openState" => body.push(GateKSBody::openState(OpenState::from_node(&child))),
-                "forwardTransition" => body.push(GateKSBody::forwardTransition(ForwardTransition::from_node(&child))),
-                "reverseTransition" => body.push(GateKSBody::reverseTransition(ReverseTransition::from_node(&child))),
-                "tauInfTransition" => body.push(GateKSBody::tauInfTransition(TauInfTransition::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of GateKS.", t)
+                "notes" => body.push(GateKSBody::notes(String::from_node(&child)?)),
+                "q10Settings" => body.push(GateKSBody::q10Settings(Q10Settings::from_node(&child)?)),
+                "closedState" => body.push(GateKSBody::closedState(ClosedState::from_node(&child)?)),
+                "openState" => body.push(GateKSBody::openState(OpenState::from_node(&child)?)),
+                "forwardTransition" => body.push(GateKSBody::forwardTransition(ForwardTransition::from_node(&child)?)),
+                "reverseTransition" => body.push(GateKSBody::reverseTransition(ReverseTransition::from_node(&child)?)),
+                "tauInfTransition" => body.push(GateKSBody::tauInfTransition(TauInfTransition::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "GateKS")),
             };
         }
-        GateKS {
+        Ok(GateKS {
             instances,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "instances", &self.instances);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -5467,6 +8782,20 @@ pub enum BiophysicalPropertiesBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl BiophysicalPropertiesBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            BiophysicalPropertiesBody::membraneProperties(x) => x.to_xml("membraneProperties", depth, out),
+            BiophysicalPropertiesBody::intracellularProperties(x) => x.to_xml("intracellularProperties", depth, out),
+            BiophysicalPropertiesBody::extracellularProperties(x) => x.to_xml("extracellularProperties", depth, out),
+            BiophysicalPropertiesBody::notes(x) => x.to_xml("notes", depth, out),
+            BiophysicalPropertiesBody::property(x) => x.to_xml("property", depth, out),
+            BiophysicalPropertiesBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct BiophysicalProperties {
     pub metaid: Option<String>,
@@ -5476,30 +8805,46 @@ pub struct BiophysicalProperties {
 }
 
 impl XML for BiophysicalProperties {
-    fn from_node(node: &Node) -> Self {
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "membraneProperties" => body.push(BiophysicalPropertiesBody::membraneProperties(MembraneProperties::from_node(&child))),
-                "intracellularProperties" => body.push(BiophysicalPropertiesBody::intracellularProperties(IntracellularProperties::from_node(&child))),
-                "extracellularProperties" => body.push(BiophysicalPropertiesBody::extracellularProperties(ExtracellularProperties::from_node(&child))),
-                "notes" => body.push(BiophysicalPropertiesBody::notes(String::from_node(&child))),
-                "property" => body.push(BiophysicalPropertiesBody::property(Property::from_node(&child))),
-                "annotation" => body.push(BiophysicalPropertiesBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of BiophysicalProperties.", t)
+                "membraneProperties" => body.push(BiophysicalPropertiesBody::membraneProperties(MembraneProperties::from_node(&child)?)),
+                "intracellularProperties" => body.push(BiophysicalPropertiesBody::intracellularProperties(IntracellularProperties::from_node(&child)?)),
+                "extracellularProperties" => body.push(BiophysicalPropertiesBody::extracellularProperties(ExtracellularProperties::from_node(&child)?)),
+                "notes" => body.push(BiophysicalPropertiesBody::notes(String::from_node(&child)?)),
+                "property" => body.push(BiophysicalPropertiesBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(BiophysicalPropertiesBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "BiophysicalProperties")),
             };
         }
-        BiophysicalProperties {
+        Ok(BiophysicalProperties {
             metaid,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -5515,21 +8860,32 @@ pub struct SpaceStructure {
 }
 
 impl XML for SpaceStructure {
-    fn from_node(node: &Node) -> Self {
-        let xSpacing = node.attribute("xSpacing").map(|s| s.parse::<f64>().unwrap());
-        let ySpacing = node.attribute("ySpacing").map(|s| s.parse::<f64>().unwrap());
-        let zSpacing = node.attribute("zSpacing").map(|s| s.parse::<f64>().unwrap());
-        let xStart = node.attribute("xStart").or(Some("0")).map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let yStart = node.attribute("yStart").or(Some("0")).map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let zStart = node.attribute("zStart").or(Some("0")).map(|s| s.parse::<f64>().unwrap()).unwrap();
-        SpaceStructure {
+    fn from_node(node: &Node) -> Result<Self> {
+        let xSpacing = opt_attr::<f64>(node, "xSpacing")?;
+        let ySpacing = opt_attr::<f64>(node, "ySpacing")?;
+        let zSpacing = opt_attr::<f64>(node, "zSpacing")?;
+        let xStart = def_attr::<f64>(node, "xStart", "0")?;
+        let yStart = def_attr::<f64>(node, "yStart", "0")?;
+        let zStart = def_attr::<f64>(node, "zStart", "0")?;
+        Ok(SpaceStructure {
             xSpacing,
             ySpacing,
             zSpacing,
             xStart,
             yStart,
             zStart,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if let Some(x) = &self.xSpacing { write_attr(out, "xSpacing", x); }
+        if let Some(x) = &self.ySpacing { write_attr(out, "ySpacing", x); }
+        if let Some(x) = &self.zSpacing { write_attr(out, "zSpacing", x); }
+        write_attr(out, "xStart", &self.xStart);
+        write_attr(out, "yStart", &self.yStart);
+        write_attr(out, "zStart", &self.zStart);
+        out.push_str("/>\n");
     }
 }
 
@@ -5540,6 +8896,17 @@ pub enum BaseCellBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl BaseCellBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            BaseCellBody::notes(x) => x.to_xml("notes", depth, out),
+            BaseCellBody::property(x) => x.to_xml("property", depth, out),
+            BaseCellBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct BaseCell {
     pub metaid: Option<String>,
@@ -5549,27 +8916,43 @@ pub struct BaseCell {
 }
 
 impl XML for BaseCell {
-    fn from_node(node: &Node) -> Self {
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(BaseCellBody::notes(String::from_node(&child))),
-                "property" => body.push(BaseCellBody::property(Property::from_node(&child))),
-                "annotation" => body.push(BaseCellBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of BaseCell.", t)
+                "notes" => body.push(BaseCellBody::notes(String::from_node(&child)?)),
+                "property" => body.push(BaseCellBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(BaseCellBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "BaseCell")),
             };
         }
-        BaseCell {
+        Ok(BaseCell {
             metaid,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -5581,11 +8964,22 @@ pub enum PulseGeneratorDLBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl PulseGeneratorDLBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            PulseGeneratorDLBody::notes(x) => x.to_xml("notes", depth, out),
+            PulseGeneratorDLBody::property(x) => x.to_xml("property", depth, out),
+            PulseGeneratorDLBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct PulseGeneratorDL {
-    pub delay: String,
-    pub duration: String,
-    pub amplitude: String,
+    pub delay: Nml2Quantity,
+    pub duration: Nml2Quantity,
+    pub amplitude: Nml2Quantity,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -5593,26 +8987,26 @@ pub struct PulseGeneratorDL {
 }
 
 impl XML for PulseGeneratorDL {
-    fn from_node(node: &Node) -> Self {
-        let delay = node.attribute("delay").map(|s| s.to_string()).unwrap();
-        let duration = node.attribute("duration").map(|s| s.to_string()).unwrap();
-        let amplitude = node.attribute("amplitude").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let delay = req_attr::<Nml2Quantity>(node, "delay")?;
+        let duration = req_attr::<Nml2Quantity>(node, "duration")?;
+        let amplitude = req_attr::<Nml2Quantity>(node, "amplitude")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(PulseGeneratorDLBody::notes(String::from_node(&child))),
-                "property" => body.push(PulseGeneratorDLBody::property(Property::from_node(&child))),
-                "annotation" => body.push(PulseGeneratorDLBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of PulseGeneratorDL.", t)
+                "notes" => body.push(PulseGeneratorDLBody::notes(String::from_node(&child)?)),
+                "property" => body.push(PulseGeneratorDLBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(PulseGeneratorDLBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "PulseGeneratorDL")),
             };
         }
-        PulseGeneratorDL {
+        Ok(PulseGeneratorDL {
             delay,
             duration,
             amplitude,
@@ -5620,6 +9014,25 @@ impl XML for PulseGeneratorDL {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "delay", &self.delay);
+        write_attr(out, "duration", &self.duration);
+        write_attr(out, "amplitude", &self.amplitude);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -5630,26 +9043,49 @@ pub enum SubTreeBody {
     to(SegmentEndPoint),
 }
 
+#[allow(dead_code)]
+impl SubTreeBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            SubTreeBody::from(x) => x.to_xml("from", depth, out),
+            SubTreeBody::to(x) => x.to_xml("to", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct SubTree {
     pub body: Vec<SubTreeBody>
 }
 
 impl XML for SubTree {
-    fn from_node(node: &Node) -> Self {
+    fn from_node(node: &Node) -> Result<Self> {
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "from" => body.push(SubTreeBody::from(SegmentEndPoint::from_node(&child))),
-                "to" => body.push(SubTreeBody::to(SegmentEndPoint::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of SubTree.", t)
+                "from" => body.push(SubTreeBody::from(SegmentEndPoint::from_node(&child)?)),
+                "to" => body.push(SubTreeBody::to(SegmentEndPoint::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "SubTree")),
             };
         }
-        SubTree {
+        Ok(SubTree {
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -5662,15 +9098,23 @@ pub struct CellSet {
 }
 
 impl XML for CellSet {
-    fn from_node(node: &Node) -> Self {
-        let select = node.attribute("select").map(|s| s.to_string()).unwrap();
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
-        CellSet {
+    fn from_node(node: &Node) -> Result<Self> {
+        let select = req_attr::<String>(node, "select")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
+        Ok(CellSet {
             select,
             id,
             neuroLexId,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "select", &self.select);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -5681,6 +9125,17 @@ pub enum HH_cond_expBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl HH_cond_expBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            HH_cond_expBody::notes(x) => x.to_xml("notes", depth, out),
+            HH_cond_expBody::property(x) => x.to_xml("property", depth, out),
+            HH_cond_expBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct HH_cond_exp {
     pub v_offset: f64,
@@ -5704,37 +9159,37 @@ pub struct HH_cond_exp {
 }
 
 impl XML for HH_cond_exp {
-    fn from_node(node: &Node) -> Self {
-        let v_offset = node.attribute("v_offset").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let e_rev_E = node.attribute("e_rev_E").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let e_rev_I = node.attribute("e_rev_I").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let e_rev_K = node.attribute("e_rev_K").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let e_rev_Na = node.attribute("e_rev_Na").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let e_rev_leak = node.attribute("e_rev_leak").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let g_leak = node.attribute("g_leak").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let gbar_K = node.attribute("gbar_K").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let gbar_Na = node.attribute("gbar_Na").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let cm = node.attribute("cm").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let i_offset = node.attribute("i_offset").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let tau_syn_E = node.attribute("tau_syn_E").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let tau_syn_I = node.attribute("tau_syn_I").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let v_init = node.attribute("v_init").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let v_offset = req_attr::<f64>(node, "v_offset")?;
+        let e_rev_E = req_attr::<f64>(node, "e_rev_E")?;
+        let e_rev_I = req_attr::<f64>(node, "e_rev_I")?;
+        let e_rev_K = req_attr::<f64>(node, "e_rev_K")?;
+        let e_rev_Na = req_attr::<f64>(node, "e_rev_Na")?;
+        let e_rev_leak = req_attr::<f64>(node, "e_rev_leak")?;
+        let g_leak = req_attr::<f64>(node, "g_leak")?;
+        let gbar_K = req_attr::<f64>(node, "gbar_K")?;
+        let gbar_Na = req_attr::<f64>(node, "gbar_Na")?;
+        let cm = req_attr::<f64>(node, "cm")?;
+        let i_offset = req_attr::<f64>(node, "i_offset")?;
+        let tau_syn_E = req_attr::<f64>(node, "tau_syn_E")?;
+        let tau_syn_I = req_attr::<f64>(node, "tau_syn_I")?;
+        let v_init = req_attr::<f64>(node, "v_init")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(HH_cond_expBody::notes(String::from_node(&child))),
-                "property" => body.push(HH_cond_expBody::property(Property::from_node(&child))),
-                "annotation" => body.push(HH_cond_expBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of HH_cond_exp.", t)
+                "notes" => body.push(HH_cond_expBody::notes(String::from_node(&child)?)),
+                "property" => body.push(HH_cond_expBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(HH_cond_expBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "HH_cond_exp")),
             };
         }
-        HH_cond_exp {
+        Ok(HH_cond_exp {
             v_offset,
             e_rev_E,
             e_rev_I,
@@ -5753,24 +9208,61 @@ impl XML for HH_cond_exp {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "v_offset", &self.v_offset);
+        write_attr(out, "e_rev_E", &self.e_rev_E);
+        write_attr(out, "e_rev_I", &self.e_rev_I);
+        write_attr(out, "e_rev_K", &self.e_rev_K);
+        write_attr(out, "e_rev_Na", &self.e_rev_Na);
+        write_attr(out, "e_rev_leak", &self.e_rev_leak);
+        write_attr(out, "g_leak", &self.g_leak);
+        write_attr(out, "gbar_K", &self.gbar_K);
+        write_attr(out, "gbar_Na", &self.gbar_Na);
+        write_attr(out, "cm", &self.cm);
+        write_attr(out, "i_offset", &self.i_offset);
+        write_attr(out, "tau_syn_E", &self.tau_syn_E);
+        write_attr(out, "tau_syn_I", &self.tau_syn_I);
+        write_attr(out, "v_init", &self.v_init);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct SpecificCapacitance {
-    pub value: String,
+    pub value: Nml2Quantity,
     pub segmentGroup: String,
 }
 
 impl XML for SpecificCapacitance {
-    fn from_node(node: &Node) -> Self {
-        let value = node.attribute("value").map(|s| s.to_string()).unwrap();
-        let segmentGroup = node.attribute("segmentGroup").or(Some("all")).map(|s| s.to_string()).unwrap();
-        SpecificCapacitance {
+    fn from_node(node: &Node) -> Result<Self> {
+        let value = req_attr::<Nml2Quantity>(node, "value")?;
+        let segmentGroup = def_attr::<String>(node, "segmentGroup", "all")?;
+        Ok(SpecificCapacitance {
             value,
             segmentGroup,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "value", &self.value);
+        write_attr(out, "segmentGroup", &self.segmentGroup);
+        out.push_str("/>\n");
     }
 }
 
@@ -5787,16 +9279,16 @@ pub struct Connection {
 }
 
 impl XML for Connection {
-    fn from_node(node: &Node) -> Self {
-        let preCellId = node.attribute("preCellId").map(|s| s.to_string()).unwrap();
-        let preSegmentId = node.attribute("preSegmentId").or(Some("0")).map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let preFractionAlong = node.attribute("preFractionAlong").or(Some("0.5")).map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let postCellId = node.attribute("postCellId").map(|s| s.to_string()).unwrap();
-        let postSegmentId = node.attribute("postSegmentId").or(Some("0")).map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let postFractionAlong = node.attribute("postFractionAlong").or(Some("0.5")).map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let id = node.attribute("id").map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
-        Connection {
+    fn from_node(node: &Node) -> Result<Self> {
+        let preCellId = req_attr::<String>(node, "preCellId")?;
+        let preSegmentId = def_attr::<i64>(node, "preSegmentId", "0")?;
+        let preFractionAlong = def_attr::<f64>(node, "preFractionAlong", "0.5")?;
+        let postCellId = req_attr::<String>(node, "postCellId")?;
+        let postSegmentId = def_attr::<i64>(node, "postSegmentId", "0")?;
+        let postFractionAlong = def_attr::<f64>(node, "postFractionAlong", "0.5")?;
+        let id = req_attr::<i64>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
+        Ok(Connection {
             preCellId,
             preSegmentId,
             preFractionAlong,
@@ -5805,7 +9297,20 @@ impl XML for Connection {
             postFractionAlong,
             id,
             neuroLexId,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "preCellId", &self.preCellId);
+        write_attr(out, "preSegmentId", &self.preSegmentId);
+        write_attr(out, "preFractionAlong", &self.preFractionAlong);
+        write_attr(out, "postCellId", &self.postCellId);
+        write_attr(out, "postSegmentId", &self.postSegmentId);
+        write_attr(out, "postFractionAlong", &self.postFractionAlong);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -5816,11 +9321,22 @@ pub enum AlphaSynapseBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl AlphaSynapseBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            AlphaSynapseBody::notes(x) => x.to_xml("notes", depth, out),
+            AlphaSynapseBody::property(x) => x.to_xml("property", depth, out),
+            AlphaSynapseBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct AlphaSynapse {
-    pub tau: String,
-    pub gbase: String,
-    pub erev: String,
+    pub tau: Nml2Quantity,
+    pub gbase: Nml2Quantity,
+    pub erev: Nml2Quantity,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -5828,26 +9344,26 @@ pub struct AlphaSynapse {
 }
 
 impl XML for AlphaSynapse {
-    fn from_node(node: &Node) -> Self {
-        let tau = node.attribute("tau").map(|s| s.to_string()).unwrap();
-        let gbase = node.attribute("gbase").map(|s| s.to_string()).unwrap();
-        let erev = node.attribute("erev").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let tau = req_attr::<Nml2Quantity>(node, "tau")?;
+        let gbase = req_attr::<Nml2Quantity>(node, "gbase")?;
+        let erev = req_attr::<Nml2Quantity>(node, "erev")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(AlphaSynapseBody::notes(String::from_node(&child))),
-                "property" => body.push(AlphaSynapseBody::property(Property::from_node(&child))),
-                "annotation" => body.push(AlphaSynapseBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of AlphaSynapse.", t)
+                "notes" => body.push(AlphaSynapseBody::notes(String::from_node(&child)?)),
+                "property" => body.push(AlphaSynapseBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(AlphaSynapseBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "AlphaSynapse")),
             };
         }
-        AlphaSynapse {
+        Ok(AlphaSynapse {
             tau,
             gbase,
             erev,
@@ -5855,6 +9371,25 @@ impl XML for AlphaSynapse {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "tau", &self.tau);
+        write_attr(out, "gbase", &self.gbase);
+        write_attr(out, "erev", &self.erev);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -5865,11 +9400,17 @@ pub struct UnstructuredLayout {
 }
 
 impl XML for UnstructuredLayout {
-    fn from_node(node: &Node) -> Self {
-        let number = node.attribute("number").map(|s| s.parse::<i64>().unwrap());
-        UnstructuredLayout {
+    fn from_node(node: &Node) -> Result<Self> {
+        let number = opt_attr::<i64>(node, "number")?;
+        Ok(UnstructuredLayout {
             number,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if let Some(x) = &self.number { write_attr(out, "number", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -5880,6 +9421,17 @@ pub enum IF_cond_alphaBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl IF_cond_alphaBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            IF_cond_alphaBody::notes(x) => x.to_xml("notes", depth, out),
+            IF_cond_alphaBody::property(x) => x.to_xml("property", depth, out),
+            IF_cond_alphaBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct IF_cond_alpha {
     pub e_rev_E: f64,
@@ -5901,35 +9453,35 @@ pub struct IF_cond_alpha {
 }
 
 impl XML for IF_cond_alpha {
-    fn from_node(node: &Node) -> Self {
-        let e_rev_E = node.attribute("e_rev_E").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let e_rev_I = node.attribute("e_rev_I").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let tau_m = node.attribute("tau_m").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let tau_refrac = node.attribute("tau_refrac").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let v_reset = node.attribute("v_reset").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let v_rest = node.attribute("v_rest").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let v_thresh = node.attribute("v_thresh").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let cm = node.attribute("cm").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let i_offset = node.attribute("i_offset").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let tau_syn_E = node.attribute("tau_syn_E").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let tau_syn_I = node.attribute("tau_syn_I").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let v_init = node.attribute("v_init").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let e_rev_E = req_attr::<f64>(node, "e_rev_E")?;
+        let e_rev_I = req_attr::<f64>(node, "e_rev_I")?;
+        let tau_m = req_attr::<f64>(node, "tau_m")?;
+        let tau_refrac = req_attr::<f64>(node, "tau_refrac")?;
+        let v_reset = req_attr::<f64>(node, "v_reset")?;
+        let v_rest = req_attr::<f64>(node, "v_rest")?;
+        let v_thresh = req_attr::<f64>(node, "v_thresh")?;
+        let cm = req_attr::<f64>(node, "cm")?;
+        let i_offset = req_attr::<f64>(node, "i_offset")?;
+        let tau_syn_E = req_attr::<f64>(node, "tau_syn_E")?;
+        let tau_syn_I = req_attr::<f64>(node, "tau_syn_I")?;
+        let v_init = req_attr::<f64>(node, "v_init")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(IF_cond_alphaBody::notes(String::from_node(&child))),
-                "property" => body.push(IF_cond_alphaBody::property(Property::from_node(&child))),
-                "annotation" => body.push(IF_cond_alphaBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of IF_cond_alpha.", t)
+                "notes" => body.push(IF_cond_alphaBody::notes(String::from_node(&child)?)),
+                "property" => body.push(IF_cond_alphaBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(IF_cond_alphaBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "IF_cond_alpha")),
             };
         }
-        IF_cond_alpha {
+        Ok(IF_cond_alpha {
             e_rev_E,
             e_rev_I,
             tau_m,
@@ -5946,50 +9498,95 @@ impl XML for IF_cond_alpha {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "e_rev_E", &self.e_rev_E);
+        write_attr(out, "e_rev_I", &self.e_rev_I);
+        write_attr(out, "tau_m", &self.tau_m);
+        write_attr(out, "tau_refrac", &self.tau_refrac);
+        write_attr(out, "v_reset", &self.v_reset);
+        write_attr(out, "v_rest", &self.v_rest);
+        write_attr(out, "v_thresh", &self.v_thresh);
+        write_attr(out, "cm", &self.cm);
+        write_attr(out, "i_offset", &self.i_offset);
+        write_attr(out, "tau_syn_E", &self.tau_syn_E);
+        write_attr(out, "tau_syn_I", &self.tau_syn_I);
+        write_attr(out, "v_init", &self.v_init);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct InitMembPotential {
-    pub value: String,
+    pub value: Nml2Quantity,
     pub segmentGroup: String,
 }
 
 impl XML for InitMembPotential {
-    fn from_node(node: &Node) -> Self {
-        let value = node.attribute("value").map(|s| s.to_string()).unwrap();
-        let segmentGroup = node.attribute("segmentGroup").or(Some("all")).map(|s| s.to_string()).unwrap();
-        InitMembPotential {
+    fn from_node(node: &Node) -> Result<Self> {
+        let value = req_attr::<Nml2Quantity>(node, "value")?;
+        let segmentGroup = def_attr::<String>(node, "segmentGroup", "all")?;
+        Ok(InitMembPotential {
             value,
             segmentGroup,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "value", &self.value);
+        write_attr(out, "segmentGroup", &self.segmentGroup);
+        out.push_str("/>\n");
     }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct BlockMechanism {
-    pub r#type: String,
+    pub r#type: BlockTypes,
     pub species: String,
-    pub blockConcentration: String,
-    pub scalingConc: String,
-    pub scalingVolt: String,
+    pub blockConcentration: Nml2Quantity,
+    pub scalingConc: Nml2Quantity,
+    pub scalingVolt: Nml2Quantity,
 }
 
 impl XML for BlockMechanism {
-    fn from_node(node: &Node) -> Self {
-        let r#type = node.attribute("type").map(|s| s.to_string()).unwrap();
-        let species = node.attribute("species").map(|s| s.to_string()).unwrap();
-        let blockConcentration = node.attribute("blockConcentration").map(|s| s.to_string()).unwrap();
-        let scalingConc = node.attribute("scalingConc").map(|s| s.to_string()).unwrap();
-        let scalingVolt = node.attribute("scalingVolt").map(|s| s.to_string()).unwrap();
-        BlockMechanism {
+    fn from_node(node: &Node) -> Result<Self> {
+        let r#type = req_attr::<BlockTypes>(node, "type")?;
+        let species = req_attr::<String>(node, "species")?;
+        let blockConcentration = req_attr::<Nml2Quantity>(node, "blockConcentration")?;
+        let scalingConc = req_attr::<Nml2Quantity>(node, "scalingConc")?;
+        let scalingVolt = req_attr::<Nml2Quantity>(node, "scalingVolt")?;
+        Ok(BlockMechanism {
             r#type,
             species,
             blockConcentration,
             scalingConc,
             scalingVolt,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "type", &self.r#type);
+        write_attr(out, "species", &self.species);
+        write_attr(out, "blockConcentration", &self.blockConcentration);
+        write_attr(out, "scalingConc", &self.scalingConc);
+        write_attr(out, "scalingVolt", &self.scalingVolt);
+        out.push_str("/>\n");
     }
 }
 
@@ -5998,12 +9595,21 @@ pub enum ChannelDensityVShiftBody {
     variableParameter(VariableParameter),
 }
 
+#[allow(dead_code)]
+impl ChannelDensityVShiftBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            ChannelDensityVShiftBody::variableParameter(x) => x.to_xml("variableParameter", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ChannelDensityVShift {
-    pub vShift: String,
+    pub vShift: Nml2Quantity,
     pub ionChannel: String,
-    pub condDensity: Option<String>,
-    pub erev: String,
+    pub condDensity: Option<Nml2Quantity>,
+    pub erev: Nml2Quantity,
     pub segmentGroup: String,
     pub segment: Option<i64>,
     pub ion: String,
@@ -6013,27 +9619,27 @@ pub struct ChannelDensityVShift {
 }
 
 impl XML for ChannelDensityVShift {
-    fn from_node(node: &Node) -> Self {
-        let vShift = node.attribute("vShift").map(|s| s.to_string()).unwrap();
-        let ionChannel = node.attribute("ionChannel").map(|s| s.to_string()).unwrap();
-        let condDensity = node.attribute("condDensity").map(|s| s.to_string());
-        let erev = node.attribute("erev").map(|s| s.to_string()).unwrap();
-        let segmentGroup = node.attribute("segmentGroup").or(Some("all")).map(|s| s.to_string()).unwrap();
-        let segment = node.attribute("segment").map(|s| s.parse::<i64>().unwrap());
-        let ion = node.attribute("ion").map(|s| s.to_string()).unwrap();
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let vShift = req_attr::<Nml2Quantity>(node, "vShift")?;
+        let ionChannel = req_attr::<String>(node, "ionChannel")?;
+        let condDensity = opt_attr::<Nml2Quantity>(node, "condDensity")?;
+        let erev = req_attr::<Nml2Quantity>(node, "erev")?;
+        let segmentGroup = def_attr::<String>(node, "segmentGroup", "all")?;
+        let segment = opt_attr::<i64>(node, "segment")?;
+        let ion = req_attr::<String>(node, "ion")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "variableParameter" => body.push(ChannelDensityVShiftBody::variableParameter(VariableParameter::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of ChannelDensityVShift.", t)
+                "variableParameter" => body.push(ChannelDensityVShiftBody::variableParameter(VariableParameter::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "ChannelDensityVShift")),
             };
         }
-        ChannelDensityVShift {
+        Ok(ChannelDensityVShift {
             vShift,
             ionChannel,
             condDensity,
@@ -6044,6 +9650,28 @@ impl XML for ChannelDensityVShift {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "vShift", &self.vShift);
+        write_attr(out, "ionChannel", &self.ionChannel);
+        if let Some(x) = &self.condDensity { write_attr(out, "condDensity", x); }
+        write_attr(out, "erev", &self.erev);
+        write_attr(out, "segmentGroup", &self.segmentGroup);
+        if let Some(x) = &self.segment { write_attr(out, "segment", x); }
+        write_attr(out, "ion", &self.ion);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -6057,17 +9685,26 @@ pub struct BaseProjection {
 }
 
 impl XML for BaseProjection {
-    fn from_node(node: &Node) -> Self {
-        let presynapticPopulation = node.attribute("presynapticPopulation").map(|s| s.to_string()).unwrap();
-        let postsynapticPopulation = node.attribute("postsynapticPopulation").map(|s| s.to_string()).unwrap();
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
-        BaseProjection {
+    fn from_node(node: &Node) -> Result<Self> {
+        let presynapticPopulation = req_attr::<String>(node, "presynapticPopulation")?;
+        let postsynapticPopulation = req_attr::<String>(node, "postsynapticPopulation")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
+        Ok(BaseProjection {
             presynapticPopulation,
             postsynapticPopulation,
             id,
             neuroLexId,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "presynapticPopulation", &self.presynapticPopulation);
+        write_attr(out, "postsynapticPopulation", &self.postsynapticPopulation);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -6079,6 +9716,18 @@ pub enum GateHHRatesBody {
     reverseRate(HHRate),
 }
 
+#[allow(dead_code)]
+impl GateHHRatesBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            GateHHRatesBody::notes(x) => x.to_xml("notes", depth, out),
+            GateHHRatesBody::q10Settings(x) => x.to_xml("q10Settings", depth, out),
+            GateHHRatesBody::forwardRate(x) => x.to_xml("forwardRate", depth, out),
+            GateHHRatesBody::reverseRate(x) => x.to_xml("reverseRate", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct GateHHRates {
     pub instances: i64,
@@ -6088,28 +9737,44 @@ pub struct GateHHRates {
 }
 
 impl XML for GateHHRates {
-    fn from_node(node: &Node) -> Self {
-        let instances = node.attribute("instances").map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let instances = req_attr::<i64>(node, "instances")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(GateHHRatesBody::notes(String::from_node(&child))),
-                "q10Settings" => body.push(GateHHRatesBody::q10Settings(Q10Settings::from_node(&child))),
-                "forwardRate" => body.push(GateHHRatesBody::forwardRate(HHRate::from_node(&child))),
-                "reverseRate" => body.push(GateHHRatesBody::reverseRate(HHRate::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of GateHHRates.", t)
+                "notes" => body.push(GateHHRatesBody::notes(String::from_node(&child)?)),
+                "q10Settings" => body.push(GateHHRatesBody::q10Settings(Q10Settings::from_node(&child)?)),
+                "forwardRate" => body.push(GateHHRatesBody::forwardRate(HHRate::from_node(&child)?)),
+                "reverseRate" => body.push(GateHHRatesBody::reverseRate(HHRate::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "GateHHRates")),
             };
         }
-        GateHHRates {
+        Ok(GateHHRates {
             instances,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "instances", &self.instances);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -6123,6 +9788,19 @@ pub enum MorphologyBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl MorphologyBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            MorphologyBody::segment(x) => x.to_xml("segment", depth, out),
+            MorphologyBody::segmentGroup(x) => x.to_xml("segmentGroup", depth, out),
+            MorphologyBody::notes(x) => x.to_xml("notes", depth, out),
+            MorphologyBody::property(x) => x.to_xml("property", depth, out),
+            MorphologyBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Morphology {
     pub metaid: Option<String>,
@@ -6132,29 +9810,45 @@ pub struct Morphology {
 }
 
 impl XML for Morphology {
-    fn from_node(node: &Node) -> Self {
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "segment" => body.push(MorphologyBody::segment(Segment::from_node(&child))),
-                "segmentGroup" => body.push(MorphologyBody::segmentGroup(SegmentGroup::from_node(&child))),
-                "notes" => body.push(MorphologyBody::notes(String::from_node(&child))),
-                "property" => body.push(MorphologyBody::property(Property::from_node(&child))),
-                "annotation" => body.push(MorphologyBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of Morphology.", t)
+                "segment" => body.push(MorphologyBody::segment(Segment::from_node(&child)?)),
+                "segmentGroup" => body.push(MorphologyBody::segmentGroup(SegmentGroup::from_node(&child)?)),
+                "notes" => body.push(MorphologyBody::notes(String::from_node(&child)?)),
+                "property" => body.push(MorphologyBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(MorphologyBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "Morphology")),
             };
         }
-        Morphology {
+        Ok(Morphology {
             metaid,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -6166,9 +9860,20 @@ pub enum BaseCellMembPotCapBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl BaseCellMembPotCapBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            BaseCellMembPotCapBody::notes(x) => x.to_xml("notes", depth, out),
+            BaseCellMembPotCapBody::property(x) => x.to_xml("property", depth, out),
+            BaseCellMembPotCapBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct BaseCellMembPotCap {
-    pub C: String,
+    pub C: Nml2Quantity,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -6176,29 +9881,46 @@ pub struct BaseCellMembPotCap {
 }
 
 impl XML for BaseCellMembPotCap {
-    fn from_node(node: &Node) -> Self {
-        let C = node.attribute("C").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let C = req_attr::<Nml2Quantity>(node, "C")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(BaseCellMembPotCapBody::notes(String::from_node(&child))),
-                "property" => body.push(BaseCellMembPotCapBody::property(Property::from_node(&child))),
-                "annotation" => body.push(BaseCellMembPotCapBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of BaseCellMembPotCap.", t)
+                "notes" => body.push(BaseCellMembPotCapBody::notes(String::from_node(&child)?)),
+                "property" => body.push(BaseCellMembPotCapBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(BaseCellMembPotCapBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "BaseCellMembPotCap")),
             };
         }
-        BaseCellMembPotCap {
+        Ok(BaseCellMembPotCap {
             C,
             metaid,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "C", &self.C);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -6210,14 +9932,25 @@ pub enum FitzHughNagumo1969CellBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl FitzHughNagumo1969CellBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            FitzHughNagumo1969CellBody::notes(x) => x.to_xml("notes", depth, out),
+            FitzHughNagumo1969CellBody::property(x) => x.to_xml("property", depth, out),
+            FitzHughNagumo1969CellBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct FitzHughNagumo1969Cell {
-    pub a: String,
-    pub b: String,
-    pub I: String,
-    pub phi: String,
-    pub V0: String,
-    pub W0: String,
+    pub a: Nml2Quantity,
+    pub b: Nml2Quantity,
+    pub I: Nml2Quantity,
+    pub phi: Nml2Quantity,
+    pub V0: Nml2Quantity,
+    pub W0: Nml2Quantity,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -6225,29 +9958,29 @@ pub struct FitzHughNagumo1969Cell {
 }
 
 impl XML for FitzHughNagumo1969Cell {
-    fn from_node(node: &Node) -> Self {
-        let a = node.attribute("a").map(|s| s.to_string()).unwrap();
-        let b = node.attribute("b").map(|s| s.to_string()).unwrap();
-        let I = node.attribute("I").map(|s| s.to_string()).unwrap();
-        let phi = node.attribute("phi").map(|s| s.to_string()).unwrap();
-        let V0 = node.attribute("V0").map(|s| s.to_string()).unwrap();
-        let W0 = node.attribute("W0").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let a = req_attr::<Nml2Quantity>(node, "a")?;
+        let b = req_attr::<Nml2Quantity>(node, "b")?;
+        let I = req_attr::<Nml2Quantity>(node, "I")?;
+        let phi = req_attr::<Nml2Quantity>(node, "phi")?;
+        let V0 = req_attr::<Nml2Quantity>(node, "V0")?;
+        let W0 = req_attr::<Nml2Quantity>(node, "W0")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(FitzHughNagumo1969CellBody::notes(String::from_node(&child))),
-                "property" => body.push(FitzHughNagumo1969CellBody::property(Property::from_node(&child))),
-                "annotation" => body.push(FitzHughNagumo1969CellBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of FitzHughNagumo1969Cell.", t)
+                "notes" => body.push(FitzHughNagumo1969CellBody::notes(String::from_node(&child)?)),
+                "property" => body.push(FitzHughNagumo1969CellBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(FitzHughNagumo1969CellBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "FitzHughNagumo1969Cell")),
             };
         }
-        FitzHughNagumo1969Cell {
+        Ok(FitzHughNagumo1969Cell {
             a,
             b,
             I,
@@ -6258,6 +9991,28 @@ impl XML for FitzHughNagumo1969Cell {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "a", &self.a);
+        write_attr(out, "b", &self.b);
+        write_attr(out, "I", &self.I);
+        write_attr(out, "phi", &self.phi);
+        write_attr(out, "V0", &self.V0);
+        write_attr(out, "W0", &self.W0);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -6267,11 +10022,20 @@ pub enum ChannelPopulationBody {
     variableParameter(VariableParameter),
 }
 
+#[allow(dead_code)]
+impl ChannelPopulationBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            ChannelPopulationBody::variableParameter(x) => x.to_xml("variableParameter", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ChannelPopulation {
     pub ionChannel: String,
     pub number: i64,
-    pub erev: String,
+    pub erev: Nml2Quantity,
     pub segmentGroup: String,
     pub segment: Option<i64>,
     pub ion: String,
@@ -6281,26 +10045,26 @@ pub struct ChannelPopulation {
 }
 
 impl XML for ChannelPopulation {
-    fn from_node(node: &Node) -> Self {
-        let ionChannel = node.attribute("ionChannel").map(|s| s.to_string()).unwrap();
-        let number = node.attribute("number").map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let erev = node.attribute("erev").map(|s| s.to_string()).unwrap();
-        let segmentGroup = node.attribute("segmentGroup").or(Some("all")).map(|s| s.to_string()).unwrap();
-        let segment = node.attribute("segment").map(|s| s.parse::<i64>().unwrap());
-        let ion = node.attribute("ion").map(|s| s.to_string()).unwrap();
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let ionChannel = req_attr::<String>(node, "ionChannel")?;
+        let number = req_attr::<i64>(node, "number")?;
+        let erev = req_attr::<Nml2Quantity>(node, "erev")?;
+        let segmentGroup = def_attr::<String>(node, "segmentGroup", "all")?;
+        let segment = opt_attr::<i64>(node, "segment")?;
+        let ion = req_attr::<String>(node, "ion")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "variableParameter" => body.push(ChannelPopulationBody::variableParameter(VariableParameter::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of ChannelPopulation.", t)
+                "variableParameter" => body.push(ChannelPopulationBody::variableParameter(VariableParameter::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "ChannelPopulation")),
             };
         }
-        ChannelPopulation {
+        Ok(ChannelPopulation {
             ionChannel,
             number,
             erev,
@@ -6310,6 +10074,27 @@ impl XML for ChannelPopulation {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "ionChannel", &self.ionChannel);
+        write_attr(out, "number", &self.number);
+        write_attr(out, "erev", &self.erev);
+        write_attr(out, "segmentGroup", &self.segmentGroup);
+        if let Some(x) = &self.segment { write_attr(out, "segment", x); }
+        write_attr(out, "ion", &self.ion);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -6320,20 +10105,32 @@ pub struct ReverseTransition {
     pub to: String,
     pub id: String,
     pub neuroLexId: Option<String>,
+    pub body: String,
 }
 
 impl XML for ReverseTransition {
-    fn from_node(node: &Node) -> Self {
-        let from = node.attribute("from").map(|s| s.to_string()).unwrap();
-        let to = node.attribute("to").map(|s| s.to_string()).unwrap();
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
-        ReverseTransition {
+    fn from_node(node: &Node) -> Result<Self> {
+        let from = req_attr::<String>(node, "from")?;
+        let to = req_attr::<String>(node, "to")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
+        let body = inner_xml(node);
+        Ok(ReverseTransition {
             from,
             to,
             id,
             neuroLexId,
-        }
+            body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "from", &self.from);
+        write_attr(out, "to", &self.to);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        write_inner(out, tag, &self.body);
     }
 }
 
@@ -6347,19 +10144,29 @@ pub struct Input {
 }
 
 impl XML for Input {
-    fn from_node(node: &Node) -> Self {
-        let id = node.attribute("id").map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let target = node.attribute("target").map(|s| s.to_string()).unwrap();
-        let destination = node.attribute("destination").map(|s| s.to_string()).unwrap();
-        let segmentId = node.attribute("segmentId").map(|s| s.parse::<i64>().unwrap());
-        let fractionAlong = node.attribute("fractionAlong").map(|s| s.parse::<f64>().unwrap());
-        Input {
+    fn from_node(node: &Node) -> Result<Self> {
+        let id = req_attr::<i64>(node, "id")?;
+        let target = req_attr::<String>(node, "target")?;
+        let destination = req_attr::<String>(node, "destination")?;
+        let segmentId = opt_attr::<i64>(node, "segmentId")?;
+        let fractionAlong = opt_attr::<f64>(node, "fractionAlong")?;
+        Ok(Input {
             id,
             target,
             destination,
             segmentId,
             fractionAlong,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "id", &self.id);
+        write_attr(out, "target", &self.target);
+        write_attr(out, "destination", &self.destination);
+        if let Some(x) = &self.segmentId { write_attr(out, "segmentId", x); }
+        if let Some(x) = &self.fractionAlong { write_attr(out, "fractionAlong", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -6371,15 +10178,23 @@ pub struct Region {
 }
 
 impl XML for Region {
-    fn from_node(node: &Node) -> Self {
-        let space = node.attribute("space").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
-        Region {
+    fn from_node(node: &Node) -> Result<Self> {
+        let space = opt_attr::<String>(node, "space")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
+        Ok(Region {
             space,
             id,
             neuroLexId,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if let Some(x) = &self.space { write_attr(out, "space", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -6394,21 +10209,32 @@ pub struct DerivedVariable {
 }
 
 impl XML for DerivedVariable {
-    fn from_node(node: &Node) -> Self {
-        let value = node.attribute("value").map(|s| s.to_string());
-        let select = node.attribute("select").map(|s| s.to_string());
-        let name = node.attribute("name").map(|s| s.to_string()).unwrap();
-        let dimension = node.attribute("dimension").map(|s| s.to_string()).unwrap();
-        let description = node.attribute("description").map(|s| s.to_string());
-        let exposure = node.attribute("exposure").map(|s| s.to_string());
-        DerivedVariable {
+    fn from_node(node: &Node) -> Result<Self> {
+        let value = opt_attr::<String>(node, "value")?;
+        let select = opt_attr::<String>(node, "select")?;
+        let name = req_attr::<String>(node, "name")?;
+        let dimension = req_attr::<String>(node, "dimension")?;
+        let description = opt_attr::<String>(node, "description")?;
+        let exposure = opt_attr::<String>(node, "exposure")?;
+        Ok(DerivedVariable {
             value,
             select,
             name,
             dimension,
             description,
             exposure,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if let Some(x) = &self.value { write_attr(out, "value", x); }
+        if let Some(x) = &self.select { write_attr(out, "select", x); }
+        write_attr(out, "name", &self.name);
+        write_attr(out, "dimension", &self.dimension);
+        if let Some(x) = &self.description { write_attr(out, "description", x); }
+        if let Some(x) = &self.exposure { write_attr(out, "exposure", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -6421,17 +10247,26 @@ pub struct Point3DWithDiam {
 }
 
 impl XML for Point3DWithDiam {
-    fn from_node(node: &Node) -> Self {
-        let x = node.attribute("x").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let y = node.attribute("y").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let z = node.attribute("z").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let diameter = node.attribute("diameter").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        Point3DWithDiam {
+    fn from_node(node: &Node) -> Result<Self> {
+        let x = req_attr::<f64>(node, "x")?;
+        let y = req_attr::<f64>(node, "y")?;
+        let z = req_attr::<f64>(node, "z")?;
+        let diameter = req_attr::<f64>(node, "diameter")?;
+        Ok(Point3DWithDiam {
             x,
             y,
             z,
             diameter,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "x", &self.x);
+        write_attr(out, "y", &self.y);
+        write_attr(out, "z", &self.z);
+        write_attr(out, "diameter", &self.diameter);
+        out.push_str("/>\n");
     }
 }
 
@@ -6442,13 +10277,20 @@ pub struct ClosedState {
 }
 
 impl XML for ClosedState {
-    fn from_node(node: &Node) -> Self {
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
-        ClosedState {
+    fn from_node(node: &Node) -> Result<Self> {
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
+        Ok(ClosedState {
             id,
             neuroLexId,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -6462,6 +10304,20 @@ pub enum Cell2CaPoolsBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl Cell2CaPoolsBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            Cell2CaPoolsBody::biophysicalProperties2CaPools(x) => x.to_xml("biophysicalProperties2CaPools", depth, out),
+            Cell2CaPoolsBody::morphology(x) => x.to_xml("morphology", depth, out),
+            Cell2CaPoolsBody::biophysicalProperties(x) => x.to_xml("biophysicalProperties", depth, out),
+            Cell2CaPoolsBody::notes(x) => x.to_xml("notes", depth, out),
+            Cell2CaPoolsBody::property(x) => x.to_xml("property", depth, out),
+            Cell2CaPoolsBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Cell2CaPools {
     pub morphology: Option<String>,
@@ -6473,34 +10329,52 @@ pub struct Cell2CaPools {
 }
 
 impl XML for Cell2CaPools {
-    fn from_node(node: &Node) -> Self {
-        let morphology = node.attribute("morphology").map(|s| s.to_string());
-        let biophysicalProperties = node.attribute("biophysicalProperties").map(|s| s.to_string());
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let morphology = opt_attr::<String>(node, "morphology")?;
+        let biophysicalProperties = opt_attr::<String>(node, "biophysicalProperties")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "biophysicalProperties2CaPools" => body.push(Cell2CaPoolsBody::biophysicalProperties2CaPools(BiophysicalProperties2CaPools::from_node(&child))),
-                "morphology" => body.push(Cell2CaPoolsBody::morphology(Morphology::from_node(&child))),
-                "biophysicalProperties" => body.push(Cell2CaPoolsBody::biophysicalProperties(BiophysicalProperties::from_node(&child))),
-                "notes" => body.push(Cell2CaPoolsBody::notes(String::from_node(&child))),
-                "property" => body.push(Cell2CaPoolsBody::property(Property::from_node(&child))),
-                "annotation" => body.push(Cell2CaPoolsBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of Cell2CaPools.", t)
+                "biophysicalProperties2CaPools" => body.push(Cell2CaPoolsBody::biophysicalProperties2CaPools(BiophysicalProperties2CaPools::from_node(&child)?)),
+                "morphology" => body.push(Cell2CaPoolsBody::morphology(Morphology::from_node(&child)?)),
+                "biophysicalProperties" => body.push(Cell2CaPoolsBody::biophysicalProperties(BiophysicalProperties::from_node(&child)?)),
+                "notes" => body.push(Cell2CaPoolsBody::notes(String::from_node(&child)?)),
+                "property" => body.push(Cell2CaPoolsBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(Cell2CaPoolsBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "Cell2CaPools")),
             };
         }
-        Cell2CaPools {
+        Ok(Cell2CaPools {
             morphology,
             biophysicalProperties,
             metaid,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if let Some(x) = &self.morphology { write_attr(out, "morphology", x); }
+        if let Some(x) = &self.biophysicalProperties { write_attr(out, "biophysicalProperties", x); }
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -6512,13 +10386,24 @@ pub enum IafCellBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl IafCellBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            IafCellBody::notes(x) => x.to_xml("notes", depth, out),
+            IafCellBody::property(x) => x.to_xml("property", depth, out),
+            IafCellBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct IafCell {
-    pub leakReversal: String,
-    pub thresh: String,
-    pub reset: String,
-    pub C: String,
-    pub leakConductance: String,
+    pub leakReversal: Nml2Quantity,
+    pub thresh: Nml2Quantity,
+    pub reset: Nml2Quantity,
+    pub C: Nml2Quantity,
+    pub leakConductance: Nml2Quantity,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -6526,28 +10411,28 @@ pub struct IafCell {
 }
 
 impl XML for IafCell {
-    fn from_node(node: &Node) -> Self {
-        let leakReversal = node.attribute("leakReversal").map(|s| s.to_string()).unwrap();
-        let thresh = node.attribute("thresh").map(|s| s.to_string()).unwrap();
-        let reset = node.attribute("reset").map(|s| s.to_string()).unwrap();
-        let C = node.attribute("C").map(|s| s.to_string()).unwrap();
-        let leakConductance = node.attribute("leakConductance").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let leakReversal = req_attr::<Nml2Quantity>(node, "leakReversal")?;
+        let thresh = req_attr::<Nml2Quantity>(node, "thresh")?;
+        let reset = req_attr::<Nml2Quantity>(node, "reset")?;
+        let C = req_attr::<Nml2Quantity>(node, "C")?;
+        let leakConductance = req_attr::<Nml2Quantity>(node, "leakConductance")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(IafCellBody::notes(String::from_node(&child))),
-                "property" => body.push(IafCellBody::property(Property::from_node(&child))),
-                "annotation" => body.push(IafCellBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of IafCell.", t)
+                "notes" => body.push(IafCellBody::notes(String::from_node(&child)?)),
+                "property" => body.push(IafCellBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(IafCellBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "IafCell")),
             };
         }
-        IafCell {
+        Ok(IafCell {
             leakReversal,
             thresh,
             reset,
@@ -6557,6 +10442,27 @@ impl XML for IafCell {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "leakReversal", &self.leakReversal);
+        write_attr(out, "thresh", &self.thresh);
+        write_attr(out, "reset", &self.reset);
+        write_attr(out, "C", &self.C);
+        write_attr(out, "leakConductance", &self.leakConductance);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -6571,6 +10477,20 @@ pub enum GateHHRatesTauInfBody {
     steadyState(HHVariable),
 }
 
+#[allow(dead_code)]
+impl GateHHRatesTauInfBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            GateHHRatesTauInfBody::notes(x) => x.to_xml("notes", depth, out),
+            GateHHRatesTauInfBody::q10Settings(x) => x.to_xml("q10Settings", depth, out),
+            GateHHRatesTauInfBody::forwardRate(x) => x.to_xml("forwardRate", depth, out),
+            GateHHRatesTauInfBody::reverseRate(x) => x.to_xml("reverseRate", depth, out),
+            GateHHRatesTauInfBody::timeCourse(x) => x.to_xml("timeCourse", depth, out),
+            GateHHRatesTauInfBody::steadyState(x) => x.to_xml("steadyState", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct GateHHRatesTauInf {
     pub instances: i64,
@@ -6580,30 +10500,46 @@ pub struct GateHHRatesTauInf {
 }
 
 impl XML for GateHHRatesTauInf {
-    fn from_node(node: &Node) -> Self {
-        let instances = node.attribute("instances").map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let instances = req_attr::<i64>(node, "instances")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(GateHHRatesTauInfBody::notes(String::from_node(&child))),
-                "q10Settings" => body.push(GateHHRatesTauInfBody::q10Settings(Q10Settings::from_node(&child))),
-                "forwardRate" => body.push(GateHHRatesTauInfBody::forwardRate(HHRate::from_node(&child))),
-                "reverseRate" => body.push(GateHHRatesTauInfBody::reverseRate(HHRate::from_node(&child))),
-                "timeCourse" => body.push(GateHHRatesTauInfBody::timeCourse(HHTime::from_node(&child))),
-                "steadyState" => body.push(GateHHRatesTauInfBody::steadyState(HHVariable::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of GateHHRatesTauInf.", t)
+                "notes" => body.push(GateHHRatesTauInfBody::notes(String::from_node(&child)?)),
+                "q10Settings" => body.push(GateHHRatesTauInfBody::q10Settings(Q10Settings::from_node(&child)?)),
+                "forwardRate" => body.push(GateHHRatesTauInfBody::forwardRate(HHRate::from_node(&child)?)),
+                "reverseRate" => body.push(GateHHRatesTauInfBody::reverseRate(HHRate::from_node(&child)?)),
+                "timeCourse" => body.push(GateHHRatesTauInfBody::timeCourse(HHTime::from_node(&child)?)),
+                "steadyState" => body.push(GateHHRatesTauInfBody::steadyState(HHVariable::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "GateHHRatesTauInf")),
             };
         }
-        GateHHRatesTauInf {
+        Ok(GateHHRatesTauInf {
             instances,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "instances", &self.instances);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -6615,6 +10551,17 @@ pub enum ExpCondSynapseBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl ExpCondSynapseBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            ExpCondSynapseBody::notes(x) => x.to_xml("notes", depth, out),
+            ExpCondSynapseBody::property(x) => x.to_xml("property", depth, out),
+            ExpCondSynapseBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ExpCondSynapse {
     pub e_rev: f64,
@@ -6626,31 +10573,49 @@ pub struct ExpCondSynapse {
 }
 
 impl XML for ExpCondSynapse {
-    fn from_node(node: &Node) -> Self {
-        let e_rev = node.attribute("e_rev").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let tau_syn = node.attribute("tau_syn").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let e_rev = req_attr::<f64>(node, "e_rev")?;
+        let tau_syn = req_attr::<f64>(node, "tau_syn")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(ExpCondSynapseBody::notes(String::from_node(&child))),
-                "property" => body.push(ExpCondSynapseBody::property(Property::from_node(&child))),
-                "annotation" => body.push(ExpCondSynapseBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of ExpCondSynapse.", t)
+                "notes" => body.push(ExpCondSynapseBody::notes(String::from_node(&child)?)),
+                "property" => body.push(ExpCondSynapseBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(ExpCondSynapseBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "ExpCondSynapse")),
             };
         }
-        ExpCondSynapse {
+        Ok(ExpCondSynapse {
             e_rev,
             tau_syn,
             metaid,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "e_rev", &self.e_rev);
+        write_attr(out, "tau_syn", &self.tau_syn);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -6669,17 +10634,17 @@ pub struct ElectricalConnection {
 }
 
 impl XML for ElectricalConnection {
-    fn from_node(node: &Node) -> Self {
-        let synapse = node.attribute("synapse").map(|s| s.to_string()).unwrap();
-        let preCell = node.attribute("preCell").map(|s| s.to_string()).unwrap();
-        let preSegment = node.attribute("preSegment").or(Some("0")).map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let preFractionAlong = node.attribute("preFractionAlong").or(Some("0.5")).map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let postCell = node.attribute("postCell").map(|s| s.to_string()).unwrap();
-        let postSegment = node.attribute("postSegment").or(Some("0")).map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let postFractionAlong = node.attribute("postFractionAlong").or(Some("0.5")).map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let id = node.attribute("id").map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
-        ElectricalConnection {
+    fn from_node(node: &Node) -> Result<Self> {
+        let synapse = req_attr::<String>(node, "synapse")?;
+        let preCell = req_attr::<String>(node, "preCell")?;
+        let preSegment = def_attr::<i64>(node, "preSegment", "0")?;
+        let preFractionAlong = def_attr::<f64>(node, "preFractionAlong", "0.5")?;
+        let postCell = req_attr::<String>(node, "postCell")?;
+        let postSegment = def_attr::<i64>(node, "postSegment", "0")?;
+        let postFractionAlong = def_attr::<f64>(node, "postFractionAlong", "0.5")?;
+        let id = req_attr::<i64>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
+        Ok(ElectricalConnection {
             synapse,
             preCell,
             preSegment,
@@ -6689,7 +10654,21 @@ impl XML for ElectricalConnection {
             postFractionAlong,
             id,
             neuroLexId,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "synapse", &self.synapse);
+        write_attr(out, "preCell", &self.preCell);
+        write_attr(out, "preSegment", &self.preSegment);
+        write_attr(out, "preFractionAlong", &self.preFractionAlong);
+        write_attr(out, "postCell", &self.postCell);
+        write_attr(out, "postSegment", &self.postSegment);
+        write_attr(out, "postFractionAlong", &self.postFractionAlong);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -6710,37 +10689,71 @@ pub enum MembraneProperties2CaPoolsBody {
     initMembPotential(InitMembPotential),
 }
 
+#[allow(dead_code)]
+impl MembraneProperties2CaPoolsBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            MembraneProperties2CaPoolsBody::channelDensityNernstCa2(x) => x.to_xml("channelDensityNernstCa2", depth, out),
+            MembraneProperties2CaPoolsBody::channelPopulation(x) => x.to_xml("channelPopulation", depth, out),
+            MembraneProperties2CaPoolsBody::channelDensity(x) => x.to_xml("channelDensity", depth, out),
+            MembraneProperties2CaPoolsBody::channelDensityVShift(x) => x.to_xml("channelDensityVShift", depth, out),
+            MembraneProperties2CaPoolsBody::channelDensityNernst(x) => x.to_xml("channelDensityNernst", depth, out),
+            MembraneProperties2CaPoolsBody::channelDensityGHK(x) => x.to_xml("channelDensityGHK", depth, out),
+            MembraneProperties2CaPoolsBody::channelDensityGHK2(x) => x.to_xml("channelDensityGHK2", depth, out),
+            MembraneProperties2CaPoolsBody::channelDensityNonUniform(x) => x.to_xml("channelDensityNonUniform", depth, out),
+            MembraneProperties2CaPoolsBody::channelDensityNonUniformNernst(x) => x.to_xml("channelDensityNonUniformNernst", depth, out),
+            MembraneProperties2CaPoolsBody::channelDensityNonUniformGHK(x) => x.to_xml("channelDensityNonUniformGHK", depth, out),
+            MembraneProperties2CaPoolsBody::spikeThresh(x) => x.to_xml("spikeThresh", depth, out),
+            MembraneProperties2CaPoolsBody::specificCapacitance(x) => x.to_xml("specificCapacitance", depth, out),
+            MembraneProperties2CaPoolsBody::initMembPotential(x) => x.to_xml("initMembPotential", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct MembraneProperties2CaPools {
     pub body: Vec<MembraneProperties2CaPoolsBody>
 }
 
 impl XML for MembraneProperties2CaPools {
-    fn from_node(node: &Node) -> Self {
+    fn from_node(node: &Node) -> Result<Self> {
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "channelDensityNernstCa2" => body.push(MembraneProperties2CaPoolsBody::channelDensityNernstCa2(ChannelDensityNernstCa2::from_node(&child))),
-                "channelPopulation" => body.push(MembraneProperties2CaPoolsBody::channelPopulation(ChannelPopulation::from_node(&child))),
-                "channelDensity" => body.push(MembraneProperties2CaPoolsBody::channelDensity(ChannelDensity::from_node(&child))),
-                "channelDensityVShift" => body.push(MembraneProperties2CaPoolsBody::channelDensityVShift(ChannelDensityVShift::from_node(&child))),
-                "channelDensityNernst" => body.push(MembraneProperties2CaPoolsBody::channelDensityNernst(ChannelDensityNernst::from_node(&child))),
-                "channelDensityGHK" => body.push(MembraneProperties2CaPoolsBody::channelDensityGHK(ChannelDensityGHK::from_node(&child))),
-                "channelDensityGHK2" => body.push(MembraneProperties2CaPoolsBody::channelDensityGHK2(ChannelDensityGHK2::from_node(&child))),
-                "channelDensityNonUniform" => body.push(MembraneProperties2CaPoolsBody::channelDensityNonUniform(ChannelDensityNonUniform::from_node(&child))),
-                "channelDensityNonUniformNernst" => body.push(MembraneProperties2CaPoolsBody::channelDensityNonUniformNernst(ChannelDensityNonUniformNernst::from_node(&child))),
-                "channelDensityNonUniformGHK" => body.push(MembraneProperties2CaPoolsBody::channelDensityNonUniformGHK(ChannelDensityNonUniformGHK::from_node(&child))),
-                "spikeThresh" => body.push(MembraneProperties2CaPoolsBody::spikeThresh(SpikeThresh::from_node(&child))),
-                "specificCapacitance" => body.push(MembraneProperties2CaPoolsBody::specificCapacitance(SpecificCapacitance::from_node(&child))),
-                "initMembPotential" => body.push(MembraneProperties2CaPoolsBody::initMembPotential(InitMembPotential::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of MembraneProperties2CaPools.", t)
+                "channelDensityNernstCa2" => body.push(MembraneProperties2CaPoolsBody::channelDensityNernstCa2(ChannelDensityNernstCa2::from_node(&child)?)),
+                "channelPopulation" => body.push(MembraneProperties2CaPoolsBody::channelPopulation(ChannelPopulation::from_node(&child)?)),
+                "channelDensity" => body.push(MembraneProperties2CaPoolsBody::channelDensity(ChannelDensity::from_node(&child)?)),
+                "channelDensityVShift" => body.push(MembraneProperties2CaPoolsBody::channelDensityVShift(ChannelDensityVShift::from_node(&child)?)),
+                "channelDensityNernst" => body.push(MembraneProperties2CaPoolsBody::channelDensityNernst(ChannelDensityNernst::from_node(&child)?)),
+                "channelDensityGHK" => body.push(MembraneProperties2CaPoolsBody::channelDensityGHK(ChannelDensityGHK::from_node(&child)?)),
+                "channelDensityGHK2" => body.push(MembraneProperties2CaPoolsBody::channelDensityGHK2(ChannelDensityGHK2::from_node(&child)?)),
+                "channelDensityNonUniform" => body.push(MembraneProperties2CaPoolsBody::channelDensityNonUniform(ChannelDensityNonUniform::from_node(&child)?)),
+                "channelDensityNonUniformNernst" => body.push(MembraneProperties2CaPoolsBody::channelDensityNonUniformNernst(ChannelDensityNonUniformNernst::from_node(&child)?)),
+                "channelDensityNonUniformGHK" => body.push(MembraneProperties2CaPoolsBody::channelDensityNonUniformGHK(ChannelDensityNonUniformGHK::from_node(&child)?)),
+                "spikeThresh" => body.push(MembraneProperties2CaPoolsBody::spikeThresh(SpikeThresh::from_node(&child)?)),
+                "specificCapacitance" => body.push(MembraneProperties2CaPoolsBody::specificCapacitance(SpecificCapacitance::from_node(&child)?)),
+                "initMembPotential" => body.push(MembraneProperties2CaPoolsBody::initMembPotential(InitMembPotential::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "MembraneProperties2CaPools")),
             };
         }
-        MembraneProperties2CaPools {
+        Ok(MembraneProperties2CaPools {
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -6752,19 +10765,30 @@ pub enum AdExIaFCellBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl AdExIaFCellBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            AdExIaFCellBody::notes(x) => x.to_xml("notes", depth, out),
+            AdExIaFCellBody::property(x) => x.to_xml("property", depth, out),
+            AdExIaFCellBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct AdExIaFCell {
-    pub gL: String,
-    pub EL: String,
-    pub reset: String,
-    pub VT: String,
-    pub thresh: String,
-    pub delT: String,
-    pub tauw: String,
-    pub refract: String,
-    pub a: String,
-    pub b: String,
-    pub C: String,
+    pub gL: Nml2Quantity,
+    pub EL: Nml2Quantity,
+    pub reset: Nml2Quantity,
+    pub VT: Nml2Quantity,
+    pub thresh: Nml2Quantity,
+    pub delT: Nml2Quantity,
+    pub tauw: Nml2Quantity,
+    pub refract: Nml2Quantity,
+    pub a: Nml2Quantity,
+    pub b: Nml2Quantity,
+    pub C: Nml2Quantity,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -6772,34 +10796,34 @@ pub struct AdExIaFCell {
 }
 
 impl XML for AdExIaFCell {
-    fn from_node(node: &Node) -> Self {
-        let gL = node.attribute("gL").map(|s| s.to_string()).unwrap();
-        let EL = node.attribute("EL").map(|s| s.to_string()).unwrap();
-        let reset = node.attribute("reset").map(|s| s.to_string()).unwrap();
-        let VT = node.attribute("VT").map(|s| s.to_string()).unwrap();
-        let thresh = node.attribute("thresh").map(|s| s.to_string()).unwrap();
-        let delT = node.attribute("delT").map(|s| s.to_string()).unwrap();
-        let tauw = node.attribute("tauw").map(|s| s.to_string()).unwrap();
-        let refract = node.attribute("refract").map(|s| s.to_string()).unwrap();
-        let a = node.attribute("a").map(|s| s.to_string()).unwrap();
-        let b = node.attribute("b").map(|s| s.to_string()).unwrap();
-        let C = node.attribute("C").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let gL = req_attr::<Nml2Quantity>(node, "gL")?;
+        let EL = req_attr::<Nml2Quantity>(node, "EL")?;
+        let reset = req_attr::<Nml2Quantity>(node, "reset")?;
+        let VT = req_attr::<Nml2Quantity>(node, "VT")?;
+        let thresh = req_attr::<Nml2Quantity>(node, "thresh")?;
+        let delT = req_attr::<Nml2Quantity>(node, "delT")?;
+        let tauw = req_attr::<Nml2Quantity>(node, "tauw")?;
+        let refract = req_attr::<Nml2Quantity>(node, "refract")?;
+        let a = req_attr::<Nml2Quantity>(node, "a")?;
+        let b = req_attr::<Nml2Quantity>(node, "b")?;
+        let C = req_attr::<Nml2Quantity>(node, "C")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(AdExIaFCellBody::notes(String::from_node(&child))),
-                "property" => body.push(AdExIaFCellBody::property(Property::from_node(&child))),
-                "annotation" => body.push(AdExIaFCellBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of AdExIaFCell.", t)
+                "notes" => body.push(AdExIaFCellBody::notes(String::from_node(&child)?)),
+                "property" => body.push(AdExIaFCellBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(AdExIaFCellBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "AdExIaFCell")),
             };
         }
-        AdExIaFCell {
+        Ok(AdExIaFCell {
             gL,
             EL,
             reset,
@@ -6815,6 +10839,33 @@ impl XML for AdExIaFCell {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "gL", &self.gL);
+        write_attr(out, "EL", &self.EL);
+        write_attr(out, "reset", &self.reset);
+        write_attr(out, "VT", &self.VT);
+        write_attr(out, "thresh", &self.thresh);
+        write_attr(out, "delT", &self.delT);
+        write_attr(out, "tauw", &self.tauw);
+        write_attr(out, "refract", &self.refract);
+        write_attr(out, "a", &self.a);
+        write_attr(out, "b", &self.b);
+        write_attr(out, "C", &self.C);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -6893,6 +10944,84 @@ pub enum NeuroMLDocumentBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl NeuroMLDocumentBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            NeuroMLDocumentBody::include(x) => x.to_xml("include", depth, out),
+            NeuroMLDocumentBody::extracellularProperties(x) => x.to_xml("extracellularProperties", depth, out),
+            NeuroMLDocumentBody::intracellularProperties(x) => x.to_xml("intracellularProperties", depth, out),
+            NeuroMLDocumentBody::morphology(x) => x.to_xml("morphology", depth, out),
+            NeuroMLDocumentBody::ionChannel(x) => x.to_xml("ionChannel", depth, out),
+            NeuroMLDocumentBody::ionChannelHH(x) => x.to_xml("ionChannelHH", depth, out),
+            NeuroMLDocumentBody::ionChannelVShift(x) => x.to_xml("ionChannelVShift", depth, out),
+            NeuroMLDocumentBody::ionChannelKS(x) => x.to_xml("ionChannelKS", depth, out),
+            NeuroMLDocumentBody::decayingPoolConcentrationModel(x) => x.to_xml("decayingPoolConcentrationModel", depth, out),
+            NeuroMLDocumentBody::fixedFactorConcentrationModel(x) => x.to_xml("fixedFactorConcentrationModel", depth, out),
+            NeuroMLDocumentBody::alphaCurrentSynapse(x) => x.to_xml("alphaCurrentSynapse", depth, out),
+            NeuroMLDocumentBody::alphaSynapse(x) => x.to_xml("alphaSynapse", depth, out),
+            NeuroMLDocumentBody::expOneSynapse(x) => x.to_xml("expOneSynapse", depth, out),
+            NeuroMLDocumentBody::expTwoSynapse(x) => x.to_xml("expTwoSynapse", depth, out),
+            NeuroMLDocumentBody::expThreeSynapse(x) => x.to_xml("expThreeSynapse", depth, out),
+            NeuroMLDocumentBody::blockingPlasticSynapse(x) => x.to_xml("blockingPlasticSynapse", depth, out),
+            NeuroMLDocumentBody::doubleSynapse(x) => x.to_xml("doubleSynapse", depth, out),
+            NeuroMLDocumentBody::gapJunction(x) => x.to_xml("gapJunction", depth, out),
+            NeuroMLDocumentBody::silentSynapse(x) => x.to_xml("silentSynapse", depth, out),
+            NeuroMLDocumentBody::linearGradedSynapse(x) => x.to_xml("linearGradedSynapse", depth, out),
+            NeuroMLDocumentBody::gradedSynapse(x) => x.to_xml("gradedSynapse", depth, out),
+            NeuroMLDocumentBody::biophysicalProperties(x) => x.to_xml("biophysicalProperties", depth, out),
+            NeuroMLDocumentBody::cell(x) => x.to_xml("cell", depth, out),
+            NeuroMLDocumentBody::cell2CaPools(x) => x.to_xml("cell2CaPools", depth, out),
+            NeuroMLDocumentBody::baseCell(x) => x.to_xml("baseCell", depth, out),
+            NeuroMLDocumentBody::iafTauCell(x) => x.to_xml("iafTauCell", depth, out),
+            NeuroMLDocumentBody::iafTauRefCell(x) => x.to_xml("iafTauRefCell", depth, out),
+            NeuroMLDocumentBody::iafCell(x) => x.to_xml("iafCell", depth, out),
+            NeuroMLDocumentBody::iafRefCell(x) => x.to_xml("iafRefCell", depth, out),
+            NeuroMLDocumentBody::izhikevichCell(x) => x.to_xml("izhikevichCell", depth, out),
+            NeuroMLDocumentBody::izhikevich2007Cell(x) => x.to_xml("izhikevich2007Cell", depth, out),
+            NeuroMLDocumentBody::adExIaFCell(x) => x.to_xml("adExIaFCell", depth, out),
+            NeuroMLDocumentBody::fitzHughNagumoCell(x) => x.to_xml("fitzHughNagumoCell", depth, out),
+            NeuroMLDocumentBody::fitzHughNagumo1969Cell(x) => x.to_xml("fitzHughNagumo1969Cell", depth, out),
+            NeuroMLDocumentBody::pinskyRinzelCA3Cell(x) => x.to_xml("pinskyRinzelCA3Cell", depth, out),
+            NeuroMLDocumentBody::pulseGenerator(x) => x.to_xml("pulseGenerator", depth, out),
+            NeuroMLDocumentBody::pulseGeneratorDL(x) => x.to_xml("pulseGeneratorDL", depth, out),
+            NeuroMLDocumentBody::sineGenerator(x) => x.to_xml("sineGenerator", depth, out),
+            NeuroMLDocumentBody::sineGeneratorDL(x) => x.to_xml("sineGeneratorDL", depth, out),
+            NeuroMLDocumentBody::rampGenerator(x) => x.to_xml("rampGenerator", depth, out),
+            NeuroMLDocumentBody::rampGeneratorDL(x) => x.to_xml("rampGeneratorDL", depth, out),
+            NeuroMLDocumentBody::compoundInput(x) => x.to_xml("compoundInput", depth, out),
+            NeuroMLDocumentBody::compoundInputDL(x) => x.to_xml("compoundInputDL", depth, out),
+            NeuroMLDocumentBody::voltageClamp(x) => x.to_xml("voltageClamp", depth, out),
+            NeuroMLDocumentBody::voltageClampTriple(x) => x.to_xml("voltageClampTriple", depth, out),
+            NeuroMLDocumentBody::spikeArray(x) => x.to_xml("spikeArray", depth, out),
+            NeuroMLDocumentBody::timedSynapticInput(x) => x.to_xml("timedSynapticInput", depth, out),
+            NeuroMLDocumentBody::spikeGenerator(x) => x.to_xml("spikeGenerator", depth, out),
+            NeuroMLDocumentBody::spikeGeneratorRandom(x) => x.to_xml("spikeGeneratorRandom", depth, out),
+            NeuroMLDocumentBody::spikeGeneratorPoisson(x) => x.to_xml("spikeGeneratorPoisson", depth, out),
+            NeuroMLDocumentBody::spikeGeneratorRefPoisson(x) => x.to_xml("spikeGeneratorRefPoisson", depth, out),
+            NeuroMLDocumentBody::poissonFiringSynapse(x) => x.to_xml("poissonFiringSynapse", depth, out),
+            NeuroMLDocumentBody::transientPoissonFiringSynapse(x) => x.to_xml("transientPoissonFiringSynapse", depth, out),
+            NeuroMLDocumentBody::IF_curr_alpha(x) => x.to_xml("IF_curr_alpha", depth, out),
+            NeuroMLDocumentBody::IF_curr_exp(x) => x.to_xml("IF_curr_exp", depth, out),
+            NeuroMLDocumentBody::IF_cond_alpha(x) => x.to_xml("IF_cond_alpha", depth, out),
+            NeuroMLDocumentBody::IF_cond_exp(x) => x.to_xml("IF_cond_exp", depth, out),
+            NeuroMLDocumentBody::EIF_cond_exp_isfa_ista(x) => x.to_xml("EIF_cond_exp_isfa_ista", depth, out),
+            NeuroMLDocumentBody::EIF_cond_alpha_isfa_ista(x) => x.to_xml("EIF_cond_alpha_isfa_ista", depth, out),
+            NeuroMLDocumentBody::HH_cond_exp(x) => x.to_xml("HH_cond_exp", depth, out),
+            NeuroMLDocumentBody::expCondSynapse(x) => x.to_xml("expCondSynapse", depth, out),
+            NeuroMLDocumentBody::alphaCondSynapse(x) => x.to_xml("alphaCondSynapse", depth, out),
+            NeuroMLDocumentBody::expCurrSynapse(x) => x.to_xml("expCurrSynapse", depth, out),
+            NeuroMLDocumentBody::alphaCurrSynapse(x) => x.to_xml("alphaCurrSynapse", depth, out),
+            NeuroMLDocumentBody::SpikeSourcePoisson(x) => x.to_xml("SpikeSourcePoisson", depth, out),
+            NeuroMLDocumentBody::network(x) => x.to_xml("network", depth, out),
+            NeuroMLDocumentBody::ComponentType(x) => x.to_xml("ComponentType", depth, out),
+            NeuroMLDocumentBody::notes(x) => x.to_xml("notes", depth, out),
+            NeuroMLDocumentBody::property(x) => x.to_xml("property", depth, out),
+            NeuroMLDocumentBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct NeuroMLDocument {
     pub metaid: Option<String>,
@@ -6902,94 +11031,110 @@ pub struct NeuroMLDocument {
 }
 
 impl XML for NeuroMLDocument {
-    fn from_node(node: &Node) -> Self {
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "include" => body.push(NeuroMLDocumentBody::include(IncludeType::from_node(&child))),
-                "extracellularProperties" => body.push(NeuroMLDocumentBody::extracellularProperties(ExtracellularProperties::from_node(&child))),
-                "intracellularProperties" => body.push(NeuroMLDocumentBody::intracellularProperties(IntracellularProperties::from_node(&child))),
-                "morphology" => body.push(NeuroMLDocumentBody::morphology(Morphology::from_node(&child))),
-                "ionChannel" => body.push(NeuroMLDocumentBody::ionChannel(IonChannel::from_node(&child))),
-                "ionChannelHH" => body.push(NeuroMLDocumentBody::ionChannelHH(IonChannelHH::from_node(&child))),
-                "ionChannelVShift" => body.push(NeuroMLDocumentBody::ionChannelVShift(IonChannelVShift::from_node(&child))),
-                "ionChannelKS" => body.push(NeuroMLDocumentBody::ionChannelKS(IonChannelKS::from_node(&child))),
-                "decayingPoolConcentrationModel" => body.push(NeuroMLDocumentBody::decayingPoolConcentrationModel(DecayingPoolConcentrationModel::from_node(&child))),
-                "fixedFactorConcentrationModel" => body.push(NeuroMLDocumentBody::fixedFactorConcentrationModel(FixedFactorConcentrationModel::from_node(&child))),
-                "alphaCurrentSynapse" => body.push(NeuroMLDocumentBody::alphaCurrentSynapse(AlphaCurrentSynapse::from_node(&child))),
-                "alphaSynapse" => body.push(NeuroMLDocumentBody::alphaSynapse(AlphaSynapse::from_node(&child))),
-                "expOneSynapse" => body.push(NeuroMLDocumentBody::expOneSynapse(ExpOneSynapse::from_node(&child))),
-                "expTwoSynapse" => body.push(NeuroMLDocumentBody::expTwoSynapse(ExpTwoSynapse::from_node(&child))),
-                "expThreeSynapse" => body.push(NeuroMLDocumentBody::expThreeSynapse(ExpThreeSynapse::from_node(&child))),
-                "blockingPlasticSynapse" => body.push(NeuroMLDocumentBody::blockingPlasticSynapse(BlockingPlasticSynapse::from_node(&child))),
-                "doubleSynapse" => body.push(NeuroMLDocumentBody::doubleSynapse(DoubleSynapse::from_node(&child))),
-                "gapJunction" => body.push(NeuroMLDocumentBody::gapJunction(GapJunction::from_node(&child))),
-                "silentSynapse" => body.push(NeuroMLDocumentBody::silentSynapse(SilentSynapse::from_node(&child))),
-                "linearGradedSynapse" => body.push(NeuroMLDocumentBody::linearGradedSynapse(LinearGradedSynapse::from_node(&child))),
-                "gradedSynapse" => body.push(NeuroMLDocumentBody::gradedSynapse(GradedSynapse::from_node(&child))),
-                "biophysicalProperties" => body.push(NeuroMLDocumentBody::biophysicalProperties(BiophysicalProperties::from_node(&child))),
-                "cell" => body.push(NeuroMLDocumentBody::cell(Cell::from_node(&child))),
-                "cell2CaPools" => body.push(NeuroMLDocumentBody::cell2CaPools(Cell2CaPools::from_node(&child))),
-                "baseCell" => body.push(NeuroMLDocumentBody::baseCell(BaseCell::from_node(&child))),
-                "iafTauCell" => body.push(NeuroMLDocumentBody::iafTauCell(IafTauCell::from_node(&child))),
-                "iafTauRefCell" => body.push(NeuroMLDocumentBody::iafTauRefCell(IafTauRefCell::from_node(&child))),
-                "iafCell" => body.push(NeuroMLDocumentBody::iafCell(IafCell::from_node(&child))),
-                "iafRefCell" => body.push(NeuroMLDocumentBody::iafRefCell(IafRefCell::from_node(&child))),
-                "izhikevichCell" => body.push(NeuroMLDocumentBody::izhikevichCell(IzhikevichCell::from_node(&child))),
-                "izhikevich2007Cell" => body.push(NeuroMLDocumentBody::izhikevich2007Cell(Izhikevich2007Cell::from_node(&child))),
-                "adExIaFCell" => body.push(NeuroMLDocumentBody::adExIaFCell(AdExIaFCell::from_node(&child))),
-                "fitzHughNagumoCell" => body.push(NeuroMLDocumentBody::fitzHughNagumoCell(FitzHughNagumoCell::from_node(&child))),
-                "fitzHughNagumo1969Cell" => body.push(NeuroMLDocumentBody::fitzHughNagumo1969Cell(FitzHughNagumo1969Cell::from_node(&child))),
-                "pinskyRinzelCA3Cell" => body.push(NeuroMLDocumentBody::pinskyRinzelCA3Cell(PinskyRinzelCA3Cell::from_node(&child))),
-                "pulseGenerator" => body.push(NeuroMLDocumentBody::pulseGenerator(PulseGenerator::from_node(&child))),
-                "pulseGeneratorDL" => body.push(NeuroMLDocumentBody::pulseGeneratorDL(PulseGeneratorDL::from_node(&child))),
-                "sineGenerator" => body.push(NeuroMLDocumentBody::sineGenerator(SineGenerator::from_node(&child))),
-                "sineGeneratorDL" => body.push(NeuroMLDocumentBody::sineGeneratorDL(SineGeneratorDL::from_node(&child))),
-                "rampGenerator" => body.push(NeuroMLDocumentBody::rampGenerator(RampGenerator::from_node(&child))),
-                "rampGeneratorDL" => body.push(NeuroMLDocumentBody::rampGeneratorDL(RampGeneratorDL::from_node(&child))),
-                "compoundInput" => body.push(NeuroMLDocumentBody::compoundInput(CompoundInput::from_node(&child))),
-                "compoundInputDL" => body.push(NeuroMLDocumentBody::compoundInputDL(CompoundInputDL::from_node(&child))),
-                "voltageClamp" => body.push(NeuroMLDocumentBody::voltageClamp(VoltageClamp::from_node(&child))),
-                "voltageClampTriple" => body.push(NeuroMLDocumentBody::voltageClampTriple(VoltageClampTriple::from_node(&child))),
-                "spikeArray" => body.push(NeuroMLDocumentBody::spikeArray(SpikeArray::from_node(&child))),
-                "timedSynapticInput" => body.push(NeuroMLDocumentBody::timedSynapticInput(TimedSynapticInput::from_node(&child))),
-                "spikeGenerator" => body.push(NeuroMLDocumentBody::spikeGenerator(SpikeGenerator::from_node(&child))),
-                "spikeGeneratorRandom" => body.push(NeuroMLDocumentBody::spikeGeneratorRandom(SpikeGeneratorRandom::from_node(&child))),
-                "spikeGeneratorPoisson" => body.push(NeuroMLDocumentBody::spikeGeneratorPoisson(SpikeGeneratorPoisson::from_node(&child))),
-                "spikeGeneratorRefPoisson" => body.push(NeuroMLDocumentBody::spikeGeneratorRefPoisson(SpikeGeneratorRefPoisson::from_node(&child))),
-                "poissonFiringSynapse" => body.push(NeuroMLDocumentBody::poissonFiringSynapse(PoissonFiringSynapse::from_node(&child))),
-                "transientPoissonFiringSynapse" => body.push(NeuroMLDocumentBody::transientPoissonFiringSynapse(TransientPoissonFiringSynapse::from_node(&child))),
-                "IF_curr_alpha" => body.push(NeuroMLDocumentBody::IF_curr_alpha(IF_curr_alpha::from_node(&child))),
-                "IF_curr_exp" => body.push(NeuroMLDocumentBody::IF_curr_exp(IF_curr_exp::from_node(&child))),
-                "IF_cond_alpha" => body.push(NeuroMLDocumentBody::IF_cond_alpha(IF_cond_alpha::from_node(&child))),
-                "IF_cond_exp" => body.push(NeuroMLDocumentBody::IF_cond_exp(IF_cond_exp::from_node(&child))),
-                "EIF_cond_exp_isfa_ista" => body.push(NeuroMLDocumentBody::EIF_cond_exp_isfa_ista(EIF_cond_exp_isfa_ista::from_node(&child))),
-                "EIF_cond_alpha_isfa_ista" => body.push(NeuroMLDocumentBody::EIF_cond_alpha_isfa_ista(EIF_cond_alpha_isfa_ista::from_node(&child))),
-                "HH_cond_exp" => body.push(NeuroMLDocumentBody::HH_cond_exp(HH_cond_exp::from_node(&child))),
-                "expCondSynapse" => body.push(NeuroMLDocumentBody::expCondSynapse(ExpCondSynapse::from_node(&child))),
-                "alphaCondSynapse" => body.push(NeuroMLDocumentBody::alphaCondSynapse(AlphaCondSynapse::from_node(&child))),
-                "expCurrSynapse" => body.push(NeuroMLDocumentBody::expCurrSynapse(ExpCurrSynapse::from_node(&child))),
-                "alphaCurrSynapse" => body.push(NeuroMLDocumentBody::alphaCurrSynapse(AlphaCurrSynapse::from_node(&child))),
-                "SpikeSourcePoisson" => body.push(NeuroMLDocumentBody::SpikeSourcePoisson(SpikeSourcePoisson::from_node(&child))),
-                "network" => body.push(NeuroMLDocumentBody::network(Network::from_node(&child))),
-                "ComponentType" => body.push(NeuroMLDocumentBody::ComponentType(ComponentType::from_node(&child))),
-                "notes" => body.push(NeuroMLDocumentBody::notes(String::from_node(&child))),
-                "property" => body.push(NeuroMLDocumentBody::property(Property::from_node(&child))),
-                "annotation" => body.push(NeuroMLDocumentBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of NeuroMLDocument.", t)
+                "include" => body.push(NeuroMLDocumentBody::include(IncludeType::from_node(&child)?)),
+                "extracellularProperties" => body.push(NeuroMLDocumentBody::extracellularProperties(ExtracellularProperties::from_node(&child)?)),
+                "intracellularProperties" => body.push(NeuroMLDocumentBody::intracellularProperties(IntracellularProperties::from_node(&child)?)),
+                "morphology" => body.push(NeuroMLDocumentBody::morphology(Morphology::from_node(&child)?)),
+                "ionChannel" => body.push(NeuroMLDocumentBody::ionChannel(IonChannel::from_node(&child)?)),
+                "ionChannelHH" => body.push(NeuroMLDocumentBody::ionChannelHH(IonChannelHH::from_node(&child)?)),
+                "ionChannelVShift" => body.push(NeuroMLDocumentBody::ionChannelVShift(IonChannelVShift::from_node(&child)?)),
+                "ionChannelKS" => body.push(NeuroMLDocumentBody::ionChannelKS(IonChannelKS::from_node(&child)?)),
+                "decayingPoolConcentrationModel" => body.push(NeuroMLDocumentBody::decayingPoolConcentrationModel(DecayingPoolConcentrationModel::from_node(&child)?)),
+                "fixedFactorConcentrationModel" => body.push(NeuroMLDocumentBody::fixedFactorConcentrationModel(FixedFactorConcentrationModel::from_node(&child)?)),
+                "alphaCurrentSynapse" => body.push(NeuroMLDocumentBody::alphaCurrentSynapse(AlphaCurrentSynapse::from_node(&child)?)),
+                "alphaSynapse" => body.push(NeuroMLDocumentBody::alphaSynapse(AlphaSynapse::from_node(&child)?)),
+                "expOneSynapse" => body.push(NeuroMLDocumentBody::expOneSynapse(ExpOneSynapse::from_node(&child)?)),
+                "expTwoSynapse" => body.push(NeuroMLDocumentBody::expTwoSynapse(ExpTwoSynapse::from_node(&child)?)),
+                "expThreeSynapse" => body.push(NeuroMLDocumentBody::expThreeSynapse(ExpThreeSynapse::from_node(&child)?)),
+                "blockingPlasticSynapse" => body.push(NeuroMLDocumentBody::blockingPlasticSynapse(BlockingPlasticSynapse::from_node(&child)?)),
+                "doubleSynapse" => body.push(NeuroMLDocumentBody::doubleSynapse(DoubleSynapse::from_node(&child)?)),
+                "gapJunction" => body.push(NeuroMLDocumentBody::gapJunction(GapJunction::from_node(&child)?)),
+                "silentSynapse" => body.push(NeuroMLDocumentBody::silentSynapse(SilentSynapse::from_node(&child)?)),
+                "linearGradedSynapse" => body.push(NeuroMLDocumentBody::linearGradedSynapse(LinearGradedSynapse::from_node(&child)?)),
+                "gradedSynapse" => body.push(NeuroMLDocumentBody::gradedSynapse(GradedSynapse::from_node(&child)?)),
+                "biophysicalProperties" => body.push(NeuroMLDocumentBody::biophysicalProperties(BiophysicalProperties::from_node(&child)?)),
+                "cell" => body.push(NeuroMLDocumentBody::cell(Cell::from_node(&child)?)),
+                "cell2CaPools" => body.push(NeuroMLDocumentBody::cell2CaPools(Cell2CaPools::from_node(&child)?)),
+                "baseCell" => body.push(NeuroMLDocumentBody::baseCell(BaseCell::from_node(&child)?)),
+                "iafTauCell" => body.push(NeuroMLDocumentBody::iafTauCell(IafTauCell::from_node(&child)?)),
+                "iafTauRefCell" => body.push(NeuroMLDocumentBody::iafTauRefCell(IafTauRefCell::from_node(&child)?)),
+                "iafCell" => body.push(NeuroMLDocumentBody::iafCell(IafCell::from_node(&child)?)),
+                "iafRefCell" => body.push(NeuroMLDocumentBody::iafRefCell(IafRefCell::from_node(&child)?)),
+                "izhikevichCell" => body.push(NeuroMLDocumentBody::izhikevichCell(IzhikevichCell::from_node(&child)?)),
+                "izhikevich2007Cell" => body.push(NeuroMLDocumentBody::izhikevich2007Cell(Izhikevich2007Cell::from_node(&child)?)),
+                "adExIaFCell" => body.push(NeuroMLDocumentBody::adExIaFCell(AdExIaFCell::from_node(&child)?)),
+                "fitzHughNagumoCell" => body.push(NeuroMLDocumentBody::fitzHughNagumoCell(FitzHughNagumoCell::from_node(&child)?)),
+                "fitzHughNagumo1969Cell" => body.push(NeuroMLDocumentBody::fitzHughNagumo1969Cell(FitzHughNagumo1969Cell::from_node(&child)?)),
+                "pinskyRinzelCA3Cell" => body.push(NeuroMLDocumentBody::pinskyRinzelCA3Cell(PinskyRinzelCA3Cell::from_node(&child)?)),
+                "pulseGenerator" => body.push(NeuroMLDocumentBody::pulseGenerator(PulseGenerator::from_node(&child)?)),
+                "pulseGeneratorDL" => body.push(NeuroMLDocumentBody::pulseGeneratorDL(PulseGeneratorDL::from_node(&child)?)),
+                "sineGenerator" => body.push(NeuroMLDocumentBody::sineGenerator(SineGenerator::from_node(&child)?)),
+                "sineGeneratorDL" => body.push(NeuroMLDocumentBody::sineGeneratorDL(SineGeneratorDL::from_node(&child)?)),
+                "rampGenerator" => body.push(NeuroMLDocumentBody::rampGenerator(RampGenerator::from_node(&child)?)),
+                "rampGeneratorDL" => body.push(NeuroMLDocumentBody::rampGeneratorDL(RampGeneratorDL::from_node(&child)?)),
+                "compoundInput" => body.push(NeuroMLDocumentBody::compoundInput(CompoundInput::from_node(&child)?)),
+                "compoundInputDL" => body.push(NeuroMLDocumentBody::compoundInputDL(CompoundInputDL::from_node(&child)?)),
+                "voltageClamp" => body.push(NeuroMLDocumentBody::voltageClamp(VoltageClamp::from_node(&child)?)),
+                "voltageClampTriple" => body.push(NeuroMLDocumentBody::voltageClampTriple(VoltageClampTriple::from_node(&child)?)),
+                "spikeArray" => body.push(NeuroMLDocumentBody::spikeArray(SpikeArray::from_node(&child)?)),
+                "timedSynapticInput" => body.push(NeuroMLDocumentBody::timedSynapticInput(TimedSynapticInput::from_node(&child)?)),
+                "spikeGenerator" => body.push(NeuroMLDocumentBody::spikeGenerator(SpikeGenerator::from_node(&child)?)),
+                "spikeGeneratorRandom" => body.push(NeuroMLDocumentBody::spikeGeneratorRandom(SpikeGeneratorRandom::from_node(&child)?)),
+                "spikeGeneratorPoisson" => body.push(NeuroMLDocumentBody::spikeGeneratorPoisson(SpikeGeneratorPoisson::from_node(&child)?)),
+                "spikeGeneratorRefPoisson" => body.push(NeuroMLDocumentBody::spikeGeneratorRefPoisson(SpikeGeneratorRefPoisson::from_node(&child)?)),
+                "poissonFiringSynapse" => body.push(NeuroMLDocumentBody::poissonFiringSynapse(PoissonFiringSynapse::from_node(&child)?)),
+                "transientPoissonFiringSynapse" => body.push(NeuroMLDocumentBody::transientPoissonFiringSynapse(TransientPoissonFiringSynapse::from_node(&child)?)),
+                "IF_curr_alpha" => body.push(NeuroMLDocumentBody::IF_curr_alpha(IF_curr_alpha::from_node(&child)?)),
+                "IF_curr_exp" => body.push(NeuroMLDocumentBody::IF_curr_exp(IF_curr_exp::from_node(&child)?)),
+                "IF_cond_alpha" => body.push(NeuroMLDocumentBody::IF_cond_alpha(IF_cond_alpha::from_node(&child)?)),
+                "IF_cond_exp" => body.push(NeuroMLDocumentBody::IF_cond_exp(IF_cond_exp::from_node(&child)?)),
+                "EIF_cond_exp_isfa_ista" => body.push(NeuroMLDocumentBody::EIF_cond_exp_isfa_ista(EIF_cond_exp_isfa_ista::from_node(&child)?)),
+                "EIF_cond_alpha_isfa_ista" => body.push(NeuroMLDocumentBody::EIF_cond_alpha_isfa_ista(EIF_cond_alpha_isfa_ista::from_node(&child)?)),
+                "HH_cond_exp" => body.push(NeuroMLDocumentBody::HH_cond_exp(HH_cond_exp::from_node(&child)?)),
+                "expCondSynapse" => body.push(NeuroMLDocumentBody::expCondSynapse(ExpCondSynapse::from_node(&child)?)),
+                "alphaCondSynapse" => body.push(NeuroMLDocumentBody::alphaCondSynapse(AlphaCondSynapse::from_node(&child)?)),
+                "expCurrSynapse" => body.push(NeuroMLDocumentBody::expCurrSynapse(ExpCurrSynapse::from_node(&child)?)),
+                "alphaCurrSynapse" => body.push(NeuroMLDocumentBody::alphaCurrSynapse(AlphaCurrSynapse::from_node(&child)?)),
+                "SpikeSourcePoisson" => body.push(NeuroMLDocumentBody::SpikeSourcePoisson(SpikeSourcePoisson::from_node(&child)?)),
+                "network" => body.push(NeuroMLDocumentBody::network(Network::from_node(&child)?)),
+                "ComponentType" => body.push(NeuroMLDocumentBody::ComponentType(ComponentType::from_node(&child)?)),
+                "notes" => body.push(NeuroMLDocumentBody::notes(String::from_node(&child)?)),
+                "property" => body.push(NeuroMLDocumentBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(NeuroMLDocumentBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "NeuroMLDocument")),
             };
         }
-        NeuroMLDocument {
+        Ok(NeuroMLDocument {
             metaid,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -7004,6 +11149,20 @@ pub enum BiophysicalProperties2CaPoolsBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl BiophysicalProperties2CaPoolsBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            BiophysicalProperties2CaPoolsBody::membraneProperties2CaPools(x) => x.to_xml("membraneProperties2CaPools", depth, out),
+            BiophysicalProperties2CaPoolsBody::intracellularProperties2CaPools(x) => x.to_xml("intracellularProperties2CaPools", depth, out),
+            BiophysicalProperties2CaPoolsBody::extracellularProperties(x) => x.to_xml("extracellularProperties", depth, out),
+            BiophysicalProperties2CaPoolsBody::notes(x) => x.to_xml("notes", depth, out),
+            BiophysicalProperties2CaPoolsBody::property(x) => x.to_xml("property", depth, out),
+            BiophysicalProperties2CaPoolsBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct BiophysicalProperties2CaPools {
     pub metaid: Option<String>,
@@ -7013,30 +11172,46 @@ pub struct BiophysicalProperties2CaPools {
 }
 
 impl XML for BiophysicalProperties2CaPools {
-    fn from_node(node: &Node) -> Self {
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "membraneProperties2CaPools" => body.push(BiophysicalProperties2CaPoolsBody::membraneProperties2CaPools(MembraneProperties2CaPools::from_node(&child))),
-                "intracellularProperties2CaPools" => body.push(BiophysicalProperties2CaPoolsBody::intracellularProperties2CaPools(IntracellularProperties2CaPools::from_node(&child))),
-                "extracellularProperties" => body.push(BiophysicalProperties2CaPoolsBody::extracellularProperties(ExtracellularProperties::from_node(&child))),
-                "notes" => body.push(BiophysicalProperties2CaPoolsBody::notes(String::from_node(&child))),
-                "property" => body.push(BiophysicalProperties2CaPoolsBody::property(Property::from_node(&child))),
-                "annotation" => body.push(BiophysicalProperties2CaPoolsBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of BiophysicalProperties2CaPools.", t)
+                "membraneProperties2CaPools" => body.push(BiophysicalProperties2CaPoolsBody::membraneProperties2CaPools(MembraneProperties2CaPools::from_node(&child)?)),
+                "intracellularProperties2CaPools" => body.push(BiophysicalProperties2CaPoolsBody::intracellularProperties2CaPools(IntracellularProperties2CaPools::from_node(&child)?)),
+                "extracellularProperties" => body.push(BiophysicalProperties2CaPoolsBody::extracellularProperties(ExtracellularProperties::from_node(&child)?)),
+                "notes" => body.push(BiophysicalProperties2CaPoolsBody::notes(String::from_node(&child)?)),
+                "property" => body.push(BiophysicalProperties2CaPoolsBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(BiophysicalProperties2CaPoolsBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "BiophysicalProperties2CaPools")),
             };
         }
-        BiophysicalProperties2CaPools {
+        Ok(BiophysicalProperties2CaPools {
             metaid,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -7048,9 +11223,20 @@ pub enum SpikeGeneratorBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl SpikeGeneratorBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            SpikeGeneratorBody::notes(x) => x.to_xml("notes", depth, out),
+            SpikeGeneratorBody::property(x) => x.to_xml("property", depth, out),
+            SpikeGeneratorBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct SpikeGenerator {
-    pub period: String,
+    pub period: Nml2Quantity,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -7058,47 +11244,71 @@ pub struct SpikeGenerator {
 }
 
 impl XML for SpikeGenerator {
-    fn from_node(node: &Node) -> Self {
-        let period = node.attribute("period").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let period = req_attr::<Nml2Quantity>(node, "period")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(SpikeGeneratorBody::notes(String::from_node(&child))),
-                "property" => body.push(SpikeGeneratorBody::property(Property::from_node(&child))),
-                "annotation" => body.push(SpikeGeneratorBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of SpikeGenerator.", t)
+                "notes" => body.push(SpikeGeneratorBody::notes(String::from_node(&child)?)),
+                "property" => body.push(SpikeGeneratorBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(SpikeGeneratorBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "SpikeGenerator")),
             };
         }
-        SpikeGenerator {
+        Ok(SpikeGenerator {
             period,
             metaid,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "period", &self.period);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct SpikeThresh {
-    pub value: String,
+    pub value: Nml2Quantity,
     pub segmentGroup: String,
 }
 
 impl XML for SpikeThresh {
-    fn from_node(node: &Node) -> Self {
-        let value = node.attribute("value").map(|s| s.to_string()).unwrap();
-        let segmentGroup = node.attribute("segmentGroup").or(Some("all")).map(|s| s.to_string()).unwrap();
-        SpikeThresh {
+    fn from_node(node: &Node) -> Result<Self> {
+        let value = req_attr::<Nml2Quantity>(node, "value")?;
+        let segmentGroup = def_attr::<String>(node, "segmentGroup", "all")?;
+        Ok(SpikeThresh {
             value,
             segmentGroup,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "value", &self.value);
+        write_attr(out, "segmentGroup", &self.segmentGroup);
+        out.push_str("/>\n");
     }
 }
 
@@ -7111,17 +11321,26 @@ pub struct ReactionScheme {
 }
 
 impl XML for ReactionScheme {
-    fn from_node(node: &Node) -> Self {
-        let source = node.attribute("source").map(|s| s.to_string()).unwrap();
-        let r#type = node.attribute("type").map(|s| s.to_string()).unwrap();
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
-        ReactionScheme {
+    fn from_node(node: &Node) -> Result<Self> {
+        let source = req_attr::<String>(node, "source")?;
+        let r#type = req_attr::<String>(node, "type")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
+        Ok(ReactionScheme {
             source,
             r#type,
             id,
             neuroLexId,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "source", &self.source);
+        write_attr(out, "type", &self.r#type);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -7140,18 +11359,18 @@ pub struct ElectricalConnectionInstanceW {
 }
 
 impl XML for ElectricalConnectionInstanceW {
-    fn from_node(node: &Node) -> Self {
-        let weight = node.attribute("weight").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let synapse = node.attribute("synapse").map(|s| s.to_string()).unwrap();
-        let preCell = node.attribute("preCell").map(|s| s.to_string()).unwrap();
-        let preSegment = node.attribute("preSegment").or(Some("0")).map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let preFractionAlong = node.attribute("preFractionAlong").or(Some("0.5")).map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let postCell = node.attribute("postCell").map(|s| s.to_string()).unwrap();
-        let postSegment = node.attribute("postSegment").or(Some("0")).map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let postFractionAlong = node.attribute("postFractionAlong").or(Some("0.5")).map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let id = node.attribute("id").map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
-        ElectricalConnectionInstanceW {
+    fn from_node(node: &Node) -> Result<Self> {
+        let weight = req_attr::<f64>(node, "weight")?;
+        let synapse = req_attr::<String>(node, "synapse")?;
+        let preCell = req_attr::<String>(node, "preCell")?;
+        let preSegment = def_attr::<i64>(node, "preSegment", "0")?;
+        let preFractionAlong = def_attr::<f64>(node, "preFractionAlong", "0.5")?;
+        let postCell = req_attr::<String>(node, "postCell")?;
+        let postSegment = def_attr::<i64>(node, "postSegment", "0")?;
+        let postFractionAlong = def_attr::<f64>(node, "postFractionAlong", "0.5")?;
+        let id = req_attr::<i64>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
+        Ok(ElectricalConnectionInstanceW {
             weight,
             synapse,
             preCell,
@@ -7162,7 +11381,22 @@ impl XML for ElectricalConnectionInstanceW {
             postFractionAlong,
             id,
             neuroLexId,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "weight", &self.weight);
+        write_attr(out, "synapse", &self.synapse);
+        write_attr(out, "preCell", &self.preCell);
+        write_attr(out, "preSegment", &self.preSegment);
+        write_attr(out, "preFractionAlong", &self.preFractionAlong);
+        write_attr(out, "postCell", &self.postCell);
+        write_attr(out, "postSegment", &self.postSegment);
+        write_attr(out, "postFractionAlong", &self.postFractionAlong);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -7173,10 +11407,21 @@ pub enum AlphaCurrentSynapseBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl AlphaCurrentSynapseBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            AlphaCurrentSynapseBody::notes(x) => x.to_xml("notes", depth, out),
+            AlphaCurrentSynapseBody::property(x) => x.to_xml("property", depth, out),
+            AlphaCurrentSynapseBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct AlphaCurrentSynapse {
-    pub tau: String,
-    pub ibase: String,
+    pub tau: Nml2Quantity,
+    pub ibase: Nml2Quantity,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -7184,43 +11429,69 @@ pub struct AlphaCurrentSynapse {
 }
 
 impl XML for AlphaCurrentSynapse {
-    fn from_node(node: &Node) -> Self {
-        let tau = node.attribute("tau").map(|s| s.to_string()).unwrap();
-        let ibase = node.attribute("ibase").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let tau = req_attr::<Nml2Quantity>(node, "tau")?;
+        let ibase = req_attr::<Nml2Quantity>(node, "ibase")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(AlphaCurrentSynapseBody::notes(String::from_node(&child))),
-                "property" => body.push(AlphaCurrentSynapseBody::property(Property::from_node(&child))),
-                "annotation" => body.push(AlphaCurrentSynapseBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of AlphaCurrentSynapse.", t)
+                "notes" => body.push(AlphaCurrentSynapseBody::notes(String::from_node(&child)?)),
+                "property" => body.push(AlphaCurrentSynapseBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(AlphaCurrentSynapseBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "AlphaCurrentSynapse")),
             };
         }
-        AlphaCurrentSynapse {
+        Ok(AlphaCurrentSynapse {
             tau,
             ibase,
             metaid,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "tau", &self.tau);
+        write_attr(out, "ibase", &self.ibase);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Annotation {
+    pub body: String,
 }
 
 impl XML for Annotation {
-    fn from_node(node: &Node) -> Self {
-        Annotation {
-        }
+    fn from_node(node: &Node) -> Result<Self> {
+        let body = inner_xml(node);
+        Ok(Annotation {
+            body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_inner(out, tag, &self.body);
     }
 }
 
@@ -7231,6 +11502,17 @@ pub enum IF_curr_alphaBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl IF_curr_alphaBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            IF_curr_alphaBody::notes(x) => x.to_xml("notes", depth, out),
+            IF_curr_alphaBody::property(x) => x.to_xml("property", depth, out),
+            IF_curr_alphaBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct IF_curr_alpha {
     pub tau_m: f64,
@@ -7250,33 +11532,33 @@ pub struct IF_curr_alpha {
 }
 
 impl XML for IF_curr_alpha {
-    fn from_node(node: &Node) -> Self {
-        let tau_m = node.attribute("tau_m").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let tau_refrac = node.attribute("tau_refrac").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let v_reset = node.attribute("v_reset").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let v_rest = node.attribute("v_rest").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let v_thresh = node.attribute("v_thresh").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let cm = node.attribute("cm").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let i_offset = node.attribute("i_offset").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let tau_syn_E = node.attribute("tau_syn_E").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let tau_syn_I = node.attribute("tau_syn_I").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let v_init = node.attribute("v_init").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let tau_m = req_attr::<f64>(node, "tau_m")?;
+        let tau_refrac = req_attr::<f64>(node, "tau_refrac")?;
+        let v_reset = req_attr::<f64>(node, "v_reset")?;
+        let v_rest = req_attr::<f64>(node, "v_rest")?;
+        let v_thresh = req_attr::<f64>(node, "v_thresh")?;
+        let cm = req_attr::<f64>(node, "cm")?;
+        let i_offset = req_attr::<f64>(node, "i_offset")?;
+        let tau_syn_E = req_attr::<f64>(node, "tau_syn_E")?;
+        let tau_syn_I = req_attr::<f64>(node, "tau_syn_I")?;
+        let v_init = req_attr::<f64>(node, "v_init")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(IF_curr_alphaBody::notes(String::from_node(&child))),
-                "property" => body.push(IF_curr_alphaBody::property(Property::from_node(&child))),
-                "annotation" => body.push(IF_curr_alphaBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of IF_curr_alpha.", t)
+                "notes" => body.push(IF_curr_alphaBody::notes(String::from_node(&child)?)),
+                "property" => body.push(IF_curr_alphaBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(IF_curr_alphaBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "IF_curr_alpha")),
             };
         }
-        IF_curr_alpha {
+        Ok(IF_curr_alpha {
             tau_m,
             tau_refrac,
             v_reset,
@@ -7291,6 +11573,32 @@ impl XML for IF_curr_alpha {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "tau_m", &self.tau_m);
+        write_attr(out, "tau_refrac", &self.tau_refrac);
+        write_attr(out, "v_reset", &self.v_reset);
+        write_attr(out, "v_rest", &self.v_rest);
+        write_attr(out, "v_thresh", &self.v_thresh);
+        write_attr(out, "cm", &self.cm);
+        write_attr(out, "i_offset", &self.i_offset);
+        write_attr(out, "tau_syn_E", &self.tau_syn_E);
+        write_attr(out, "tau_syn_I", &self.tau_syn_I);
+        write_attr(out, "v_init", &self.v_init);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -7298,7 +11606,7 @@ impl XML for IF_curr_alpha {
 #[derive(Debug, Clone, PartialEq)]
 pub struct ChannelDensityGHK2 {
     pub ionChannel: String,
-    pub condDensity: Option<String>,
+    pub condDensity: Option<Nml2Quantity>,
     pub segmentGroup: String,
     pub segment: Option<String>,
     pub ion: String,
@@ -7307,15 +11615,15 @@ pub struct ChannelDensityGHK2 {
 }
 
 impl XML for ChannelDensityGHK2 {
-    fn from_node(node: &Node) -> Self {
-        let ionChannel = node.attribute("ionChannel").map(|s| s.to_string()).unwrap();
-        let condDensity = node.attribute("condDensity").map(|s| s.to_string());
-        let segmentGroup = node.attribute("segmentGroup").or(Some("all")).map(|s| s.to_string()).unwrap();
-        let segment = node.attribute("segment").map(|s| s.to_string());
-        let ion = node.attribute("ion").map(|s| s.to_string()).unwrap();
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
-        ChannelDensityGHK2 {
+    fn from_node(node: &Node) -> Result<Self> {
+        let ionChannel = req_attr::<String>(node, "ionChannel")?;
+        let condDensity = opt_attr::<Nml2Quantity>(node, "condDensity")?;
+        let segmentGroup = def_attr::<String>(node, "segmentGroup", "all")?;
+        let segment = opt_attr::<String>(node, "segment")?;
+        let ion = req_attr::<String>(node, "ion")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
+        Ok(ChannelDensityGHK2 {
             ionChannel,
             condDensity,
             segmentGroup,
@@ -7323,7 +11631,19 @@ impl XML for ChannelDensityGHK2 {
             ion,
             id,
             neuroLexId,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "ionChannel", &self.ionChannel);
+        if let Some(x) = &self.condDensity { write_attr(out, "condDensity", x); }
+        write_attr(out, "segmentGroup", &self.segmentGroup);
+        if let Some(x) = &self.segment { write_attr(out, "segment", x); }
+        write_attr(out, "ion", &self.ion);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -7332,10 +11652,19 @@ pub enum ChannelDensityNernstCa2Body {
     variableParameter(VariableParameter),
 }
 
+#[allow(dead_code)]
+impl ChannelDensityNernstCa2Body {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            ChannelDensityNernstCa2Body::variableParameter(x) => x.to_xml("variableParameter", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ChannelDensityNernstCa2 {
     pub ionChannel: String,
-    pub condDensity: Option<String>,
+    pub condDensity: Option<Nml2Quantity>,
     pub segmentGroup: String,
     pub segment: Option<String>,
     pub ion: String,
@@ -7345,25 +11674,25 @@ pub struct ChannelDensityNernstCa2 {
 }
 
 impl XML for ChannelDensityNernstCa2 {
-    fn from_node(node: &Node) -> Self {
-        let ionChannel = node.attribute("ionChannel").map(|s| s.to_string()).unwrap();
-        let condDensity = node.attribute("condDensity").map(|s| s.to_string());
-        let segmentGroup = node.attribute("segmentGroup").or(Some("all")).map(|s| s.to_string()).unwrap();
-        let segment = node.attribute("segment").map(|s| s.to_string());
-        let ion = node.attribute("ion").map(|s| s.to_string()).unwrap();
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let ionChannel = req_attr::<String>(node, "ionChannel")?;
+        let condDensity = opt_attr::<Nml2Quantity>(node, "condDensity")?;
+        let segmentGroup = def_attr::<String>(node, "segmentGroup", "all")?;
+        let segment = opt_attr::<String>(node, "segment")?;
+        let ion = req_attr::<String>(node, "ion")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "variableParameter" => body.push(ChannelDensityNernstCa2Body::variableParameter(VariableParameter::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of ChannelDensityNernstCa2.", t)
+                "variableParameter" => body.push(ChannelDensityNernstCa2Body::variableParameter(VariableParameter::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "ChannelDensityNernstCa2")),
             };
         }
-        ChannelDensityNernstCa2 {
+        Ok(ChannelDensityNernstCa2 {
             ionChannel,
             condDensity,
             segmentGroup,
@@ -7372,6 +11701,26 @@ impl XML for ChannelDensityNernstCa2 {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "ionChannel", &self.ionChannel);
+        if let Some(x) = &self.condDensity { write_attr(out, "condDensity", x); }
+        write_attr(out, "segmentGroup", &self.segmentGroup);
+        if let Some(x) = &self.segment { write_attr(out, "segment", x); }
+        write_attr(out, "ion", &self.ion);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -7385,12 +11734,25 @@ pub enum BlockingPlasticSynapseBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl BlockingPlasticSynapseBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            BlockingPlasticSynapseBody::plasticityMechanism(x) => x.to_xml("plasticityMechanism", depth, out),
+            BlockingPlasticSynapseBody::blockMechanism(x) => x.to_xml("blockMechanism", depth, out),
+            BlockingPlasticSynapseBody::notes(x) => x.to_xml("notes", depth, out),
+            BlockingPlasticSynapseBody::property(x) => x.to_xml("property", depth, out),
+            BlockingPlasticSynapseBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct BlockingPlasticSynapse {
-    pub tauDecay: String,
-    pub tauRise: String,
-    pub gbase: String,
-    pub erev: String,
+    pub tauDecay: Nml2Quantity,
+    pub tauRise: Nml2Quantity,
+    pub gbase: Nml2Quantity,
+    pub erev: Nml2Quantity,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -7398,29 +11760,29 @@ pub struct BlockingPlasticSynapse {
 }
 
 impl XML for BlockingPlasticSynapse {
-    fn from_node(node: &Node) -> Self {
-        let tauDecay = node.attribute("tauDecay").map(|s| s.to_string()).unwrap();
-        let tauRise = node.attribute("tauRise").map(|s| s.to_string()).unwrap();
-        let gbase = node.attribute("gbase").map(|s| s.to_string()).unwrap();
-        let erev = node.attribute("erev").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let tauDecay = req_attr::<Nml2Quantity>(node, "tauDecay")?;
+        let tauRise = req_attr::<Nml2Quantity>(node, "tauRise")?;
+        let gbase = req_attr::<Nml2Quantity>(node, "gbase")?;
+        let erev = req_attr::<Nml2Quantity>(node, "erev")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "plasticityMechanism" => body.push(BlockingPlasticSynapseBody::plasticityMechanism(PlasticityMechanism::from_node(&child))),
-                "blockMechanism" => body.push(BlockingPlasticSynapseBody::blockMechanism(BlockMechanism::from_node(&child))),
-                "notes" => body.push(BlockingPlasticSynapseBody::notes(String::from_node(&child))),
-                "property" => body.push(BlockingPlasticSynapseBody::property(Property::from_node(&child))),
-                "annotation" => body.push(BlockingPlasticSynapseBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of BlockingPlasticSynapse.", t)
+                "plasticityMechanism" => body.push(BlockingPlasticSynapseBody::plasticityMechanism(PlasticityMechanism::from_node(&child)?)),
+                "blockMechanism" => body.push(BlockingPlasticSynapseBody::blockMechanism(BlockMechanism::from_node(&child)?)),
+                "notes" => body.push(BlockingPlasticSynapseBody::notes(String::from_node(&child)?)),
+                "property" => body.push(BlockingPlasticSynapseBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(BlockingPlasticSynapseBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "BlockingPlasticSynapse")),
             };
         }
-        BlockingPlasticSynapse {
+        Ok(BlockingPlasticSynapse {
             tauDecay,
             tauRise,
             gbase,
@@ -7429,6 +11791,26 @@ impl XML for BlockingPlasticSynapse {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "tauDecay", &self.tauDecay);
+        write_attr(out, "tauRise", &self.tauRise);
+        write_attr(out, "gbase", &self.gbase);
+        write_attr(out, "erev", &self.erev);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -7440,14 +11822,25 @@ pub enum IzhikevichCellBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl IzhikevichCellBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            IzhikevichCellBody::notes(x) => x.to_xml("notes", depth, out),
+            IzhikevichCellBody::property(x) => x.to_xml("property", depth, out),
+            IzhikevichCellBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct IzhikevichCell {
-    pub v0: String,
-    pub thresh: String,
-    pub a: String,
-    pub b: String,
-    pub c: String,
-    pub d: String,
+    pub v0: Nml2Quantity,
+    pub thresh: Nml2Quantity,
+    pub a: Nml2Quantity,
+    pub b: Nml2Quantity,
+    pub c: Nml2Quantity,
+    pub d: Nml2Quantity,
     pub metaid: Option<String>,
     pub id: String,
     pub neuroLexId: Option<String>,
@@ -7455,29 +11848,29 @@ pub struct IzhikevichCell {
 }
 
 impl XML for IzhikevichCell {
-    fn from_node(node: &Node) -> Self {
-        let v0 = node.attribute("v0").map(|s| s.to_string()).unwrap();
-        let thresh = node.attribute("thresh").map(|s| s.to_string()).unwrap();
-        let a = node.attribute("a").map(|s| s.to_string()).unwrap();
-        let b = node.attribute("b").map(|s| s.to_string()).unwrap();
-        let c = node.attribute("c").map(|s| s.to_string()).unwrap();
-        let d = node.attribute("d").map(|s| s.to_string()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let v0 = req_attr::<Nml2Quantity>(node, "v0")?;
+        let thresh = req_attr::<Nml2Quantity>(node, "thresh")?;
+        let a = req_attr::<Nml2Quantity>(node, "a")?;
+        let b = req_attr::<Nml2Quantity>(node, "b")?;
+        let c = req_attr::<Nml2Quantity>(node, "c")?;
+        let d = req_attr::<Nml2Quantity>(node, "d")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(IzhikevichCellBody::notes(String::from_node(&child))),
-                "property" => body.push(IzhikevichCellBody::property(Property::from_node(&child))),
-                "annotation" => body.push(IzhikevichCellBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of IzhikevichCell.", t)
+                "notes" => body.push(IzhikevichCellBody::notes(String::from_node(&child)?)),
+                "property" => body.push(IzhikevichCellBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(IzhikevichCellBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "IzhikevichCell")),
             };
         }
-        IzhikevichCell {
+        Ok(IzhikevichCell {
             v0,
             thresh,
             a,
@@ -7488,6 +11881,28 @@ impl XML for IzhikevichCell {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "v0", &self.v0);
+        write_attr(out, "thresh", &self.thresh);
+        write_attr(out, "a", &self.a);
+        write_attr(out, "b", &self.b);
+        write_attr(out, "c", &self.c);
+        write_attr(out, "d", &self.d);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -7499,6 +11914,17 @@ pub enum SilentSynapseBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl SilentSynapseBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            SilentSynapseBody::notes(x) => x.to_xml("notes", depth, out),
+            SilentSynapseBody::property(x) => x.to_xml("property", depth, out),
+            SilentSynapseBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct SilentSynapse {
     pub metaid: Option<String>,
@@ -7508,27 +11934,43 @@ pub struct SilentSynapse {
 }
 
 impl XML for SilentSynapse {
-    fn from_node(node: &Node) -> Self {
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(SilentSynapseBody::notes(String::from_node(&child))),
-                "property" => body.push(SilentSynapseBody::property(Property::from_node(&child))),
-                "annotation" => body.push(SilentSynapseBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of SilentSynapse.", t)
+                "notes" => body.push(SilentSynapseBody::notes(String::from_node(&child)?)),
+                "property" => body.push(SilentSynapseBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(SilentSynapseBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "SilentSynapse")),
             };
         }
-        SilentSynapse {
+        Ok(SilentSynapse {
             metaid,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -7540,6 +11982,17 @@ pub enum BaseCurrentBasedSynapseBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl BaseCurrentBasedSynapseBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            BaseCurrentBasedSynapseBody::notes(x) => x.to_xml("notes", depth, out),
+            BaseCurrentBasedSynapseBody::property(x) => x.to_xml("property", depth, out),
+            BaseCurrentBasedSynapseBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct BaseCurrentBasedSynapse {
     pub metaid: Option<String>,
@@ -7549,27 +12002,43 @@ pub struct BaseCurrentBasedSynapse {
 }
 
 impl XML for BaseCurrentBasedSynapse {
-    fn from_node(node: &Node) -> Self {
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(BaseCurrentBasedSynapseBody::notes(String::from_node(&child))),
-                "property" => body.push(BaseCurrentBasedSynapseBody::property(Property::from_node(&child))),
-                "annotation" => body.push(BaseCurrentBasedSynapseBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of BaseCurrentBasedSynapse.", t)
+                "notes" => body.push(BaseCurrentBasedSynapseBody::notes(String::from_node(&child)?)),
+                "property" => body.push(BaseCurrentBasedSynapseBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(BaseCurrentBasedSynapseBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "BaseCurrentBasedSynapse")),
             };
         }
-        BaseCurrentBasedSynapse {
+        Ok(BaseCurrentBasedSynapse {
             metaid,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -7585,21 +12054,32 @@ pub struct InputW {
 }
 
 impl XML for InputW {
-    fn from_node(node: &Node) -> Self {
-        let weight = node.attribute("weight").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let id = node.attribute("id").map(|s| s.parse::<i64>().unwrap()).unwrap();
-        let target = node.attribute("target").map(|s| s.to_string()).unwrap();
-        let destination = node.attribute("destination").map(|s| s.to_string()).unwrap();
-        let segmentId = node.attribute("segmentId").map(|s| s.parse::<i64>().unwrap());
-        let fractionAlong = node.attribute("fractionAlong").map(|s| s.parse::<f64>().unwrap());
-        InputW {
+    fn from_node(node: &Node) -> Result<Self> {
+        let weight = req_attr::<f64>(node, "weight")?;
+        let id = req_attr::<i64>(node, "id")?;
+        let target = req_attr::<String>(node, "target")?;
+        let destination = req_attr::<String>(node, "destination")?;
+        let segmentId = opt_attr::<i64>(node, "segmentId")?;
+        let fractionAlong = opt_attr::<f64>(node, "fractionAlong")?;
+        Ok(InputW {
             weight,
             id,
             target,
             destination,
             segmentId,
             fractionAlong,
-        }
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "weight", &self.weight);
+        write_attr(out, "id", &self.id);
+        write_attr(out, "target", &self.target);
+        write_attr(out, "destination", &self.destination);
+        if let Some(x) = &self.segmentId { write_attr(out, "segmentId", x); }
+        if let Some(x) = &self.fractionAlong { write_attr(out, "fractionAlong", x); }
+        out.push_str("/>\n");
     }
 }
 
@@ -7608,6 +12088,15 @@ pub enum InstanceBody {
     location(Location),
 }
 
+#[allow(dead_code)]
+impl InstanceBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            InstanceBody::location(x) => x.to_xml("location", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Instance {
     pub id: Option<i64>,
@@ -7618,27 +12107,44 @@ pub struct Instance {
 }
 
 impl XML for Instance {
-    fn from_node(node: &Node) -> Self {
-        let id = node.attribute("id").map(|s| s.parse::<i64>().unwrap());
-        let i = node.attribute("i").map(|s| s.parse::<i64>().unwrap());
-        let j = node.attribute("j").map(|s| s.parse::<i64>().unwrap());
-        let k = node.attribute("k").map(|s| s.parse::<i64>().unwrap());
+    fn from_node(node: &Node) -> Result<Self> {
+        let id = opt_attr::<i64>(node, "id")?;
+        let i = opt_attr::<i64>(node, "i")?;
+        let j = opt_attr::<i64>(node, "j")?;
+        let k = opt_attr::<i64>(node, "k")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "location" => body.push(InstanceBody::location(Location::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of Instance.", t)
+                "location" => body.push(InstanceBody::location(Location::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "Instance")),
             };
         }
-        Instance {
+        Ok(Instance {
             id,
             i,
             j,
             k,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        if let Some(x) = &self.id { write_attr(out, "id", x); }
+        if let Some(x) = &self.i { write_attr(out, "i", x); }
+        if let Some(x) = &self.j { write_attr(out, "j", x); }
+        if let Some(x) = &self.k { write_attr(out, "k", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -7651,37 +12157,65 @@ pub enum GateFractionalSubgateBody {
     timeCourse(HHTime),
 }
 
+#[allow(dead_code)]
+impl GateFractionalSubgateBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            GateFractionalSubgateBody::notes(x) => x.to_xml("notes", depth, out),
+            GateFractionalSubgateBody::q10Settings(x) => x.to_xml("q10Settings", depth, out),
+            GateFractionalSubgateBody::steadyState(x) => x.to_xml("steadyState", depth, out),
+            GateFractionalSubgateBody::timeCourse(x) => x.to_xml("timeCourse", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct GateFractionalSubgate {
-    pub fractionalConductance: String,
+    pub fractionalConductance: Nml2Quantity,
     pub id: String,
     pub neuroLexId: Option<String>,
     pub body: Vec<GateFractionalSubgateBody>
 }
 
 impl XML for GateFractionalSubgate {
-    fn from_node(node: &Node) -> Self {
-        let fractionalConductance = node.attribute("fractionalConductance").map(|s| s.to_string()).unwrap();
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let fractionalConductance = req_attr::<Nml2Quantity>(node, "fractionalConductance")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(GateFractionalSubgateBody::notes(String::from_node(&child))),
-                "q10Settings" => body.push(GateFractionalSubgateBody::q10Settings(Q10Settings::from_node(&child))),
-                "steadyState" => body.push(GateFractionalSubgateBody::steadyState(HHVariable::from_node(&child))),
-                "timeCourse" => body.push(GateFractionalSubgateBody::timeCourse(HHTime::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of GateFractionalSubgate.", t)
+                "notes" => body.push(GateFractionalSubgateBody::notes(String::from_node(&child)?)),
+                "q10Settings" => body.push(GateFractionalSubgateBody::q10Settings(Q10Settings::from_node(&child)?)),
+                "steadyState" => body.push(GateFractionalSubgateBody::steadyState(HHVariable::from_node(&child)?)),
+                "timeCourse" => body.push(GateFractionalSubgateBody::timeCourse(HHTime::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "GateFractionalSubgate")),
             };
         }
-        GateFractionalSubgate {
+        Ok(GateFractionalSubgate {
             fractionalConductance,
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "fractionalConductance", &self.fractionalConductance);
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -7693,6 +12227,17 @@ pub enum IF_curr_expBody {
     annotation(Annotation),
 }
 
+#[allow(dead_code)]
+impl IF_curr_expBody {
+    fn to_xml(&self, depth: usize, out: &mut String) {
+        match self {
+            IF_curr_expBody::notes(x) => x.to_xml("notes", depth, out),
+            IF_curr_expBody::property(x) => x.to_xml("property", depth, out),
+            IF_curr_expBody::annotation(x) => x.to_xml("annotation", depth, out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct IF_curr_exp {
     pub tau_m: f64,
@@ -7712,33 +12257,33 @@ pub struct IF_curr_exp {
 }
 
 impl XML for IF_curr_exp {
-    fn from_node(node: &Node) -> Self {
-        let tau_m = node.attribute("tau_m").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let tau_refrac = node.attribute("tau_refrac").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let v_reset = node.attribute("v_reset").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let v_rest = node.attribute("v_rest").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let v_thresh = node.attribute("v_thresh").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let cm = node.attribute("cm").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let i_offset = node.attribute("i_offset").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let tau_syn_E = node.attribute("tau_syn_E").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let tau_syn_I = node.attribute("tau_syn_I").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let v_init = node.attribute("v_init").map(|s| s.parse::<f64>().unwrap()).unwrap();
-        let metaid = node.attribute("metaid").map(|s| s.to_string());
-        let id = node.attribute("id").map(|s| s.to_string()).unwrap();
-        let neuroLexId = node.attribute("neuroLexId").map(|s| s.to_string());
+    fn from_node(node: &Node) -> Result<Self> {
+        let tau_m = req_attr::<f64>(node, "tau_m")?;
+        let tau_refrac = req_attr::<f64>(node, "tau_refrac")?;
+        let v_reset = req_attr::<f64>(node, "v_reset")?;
+        let v_rest = req_attr::<f64>(node, "v_rest")?;
+        let v_thresh = req_attr::<f64>(node, "v_thresh")?;
+        let cm = req_attr::<f64>(node, "cm")?;
+        let i_offset = req_attr::<f64>(node, "i_offset")?;
+        let tau_syn_E = req_attr::<f64>(node, "tau_syn_E")?;
+        let tau_syn_I = req_attr::<f64>(node, "tau_syn_I")?;
+        let v_init = req_attr::<f64>(node, "v_init")?;
+        let metaid = opt_attr::<String>(node, "metaid")?;
+        let id = req_attr::<String>(node, "id")?;
+        let neuroLexId = opt_attr::<String>(node, "neuroLexId")?;
         let mut body = Vec::new();
         for child in node.children() {
             if child.is_comment() || child.is_text() {
                 continue;
             }
             match child.tag_name().name() {
-                "notes" => body.push(IF_curr_expBody::notes(String::from_node(&child))),
-                "property" => body.push(IF_curr_expBody::property(Property::from_node(&child))),
-                "annotation" => body.push(IF_curr_expBody::annotation(Annotation::from_node(&child))),
-                t => panic!("Unexpected tag {} in body of IF_curr_exp.", t)
+                "notes" => body.push(IF_curr_expBody::notes(String::from_node(&child)?)),
+                "property" => body.push(IF_curr_expBody::property(Property::from_node(&child)?)),
+                "annotation" => body.push(IF_curr_expBody::annotation(Annotation::from_node(&child)?)),
+                _ => return Err(unexpected_tag(&child, "IF_curr_exp")),
             };
         }
-        IF_curr_exp {
+        Ok(IF_curr_exp {
             tau_m,
             tau_refrac,
             v_reset,
@@ -7753,6 +12298,32 @@ impl XML for IF_curr_exp {
             id,
             neuroLexId,
             body,
+        })
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        write_attr(out, "tau_m", &self.tau_m);
+        write_attr(out, "tau_refrac", &self.tau_refrac);
+        write_attr(out, "v_reset", &self.v_reset);
+        write_attr(out, "v_rest", &self.v_rest);
+        write_attr(out, "v_thresh", &self.v_thresh);
+        write_attr(out, "cm", &self.cm);
+        write_attr(out, "i_offset", &self.i_offset);
+        write_attr(out, "tau_syn_E", &self.tau_syn_E);
+        write_attr(out, "tau_syn_I", &self.tau_syn_I);
+        write_attr(out, "v_init", &self.v_init);
+        if let Some(x) = &self.metaid { write_attr(out, "metaid", x); }
+        write_attr(out, "id", &self.id);
+        if let Some(x) = &self.neuroLexId { write_attr(out, "neuroLexId", x); }
+        if self.body.is_empty() {
+            out.push_str("/>\n");
+        } else {
+            out.push_str(">\n");
+            for item in &self.body {
+                item.to_xml(depth + 1, out);
+            }
+            close_tag(out, depth, tag);
         }
     }
 }
@@ -7,9 +7,11 @@ use tracing::{info, trace};
 use crate::{
     error::Error,
     expr::{Expr, Quantity},
+    filter::Filter,
     instance::{Collapsed, Instance},
     lems::file::LemsFile,
     neuroml::process_files,
+    sweep::Overrides,
     variable::{VarKind, Variable},
     Result,
 };
@@ -29,13 +31,23 @@ fn automatic_variables(coll: &Collapsed) -> Vec<String> {
         res.push(format!("{}i", ion));
         res.push(format!("{}o", ion));
     }
-    let cai = String::from("cai");
-    if !res.contains(&cai) {
-        res.push(cai);
+    for xi in ["cai", "ca2i"] {
+        let xi = xi.to_string();
+        if !res.contains(&xi) {
+            res.push(xi);
+        }
     }
     res
 }
 
+/// Does `coll` need the second calcium pool? This is the case if anything
+/// besides the definition of `caConc2` refers to it or `ca2i`.
+fn uses_ca2(coll: &Collapsed) -> bool {
+    find_dependencies(&coll.variables)
+        .iter()
+        .any(|(k, ds)| k != "caConc2" && (ds.contains("caConc2") || ds.contains("ca2i")))
+}
+
 fn nmodl_init_block(coll: &Collapsed) -> Result<String> {
     let mut result = vec![String::from("INITIAL {")];
     let mut state = Vec::new();
@@ -321,6 +333,37 @@ fn nmodl_kinetic_block(coll: &Collapsed) -> Result<String> {
     Ok(result)
 }
 
+/// Comment relating the names of parameters, states, and derived variables to
+/// the NML2 paths they stem from. Variables introduced by the exporter, eg
+/// currents, have no such path and are omitted.
+fn nmodl_names_block(coll: &Collapsed) -> Result<String> {
+    let mut names = Vec::new();
+    for p in coll.parameters.keys() {
+        let path = coll
+            .names
+            .path(p)
+            .ok_or_else(|| nmodl_error(format!("Parameter {} has no known origin", p)))?;
+        names.push((p.as_str(), path));
+    }
+    for v in &coll.variables {
+        if let Some(path) = coll.names.path(&v.name) {
+            names.push((v.name.as_str(), path));
+        }
+    }
+    if names.is_empty() {
+        return Ok(String::new());
+    }
+    names.sort_unstable();
+    names.dedup();
+    let width = names.iter().map(|p| p.0.len()).max().unwrap_or_default();
+    let mut result = vec![String::from(": NML2 paths of parameters and variables")];
+    for (name, path) in names {
+        result.push(format!(":   {:width$}  {}", name, path, width = width));
+    }
+    result.push(String::from("\n"));
+    Ok(result.join("\n"))
+}
+
 fn nmodl_neuron_block(coll: &Collapsed) -> Result<String> {
     let suffix = coll.name.as_ref().unwrap().to_string();
     let mut result = vec![String::from("NEURON {\n"), format!("  SUFFIX {}\n", suffix)];
@@ -339,6 +382,11 @@ fn nmodl_neuron_block(coll: &Collapsed) -> Result<String> {
     if !ions.contains(&String::from("ca")) {
         result.push(String::from("  USEION ca READ cai\n"));
     }
+    // The second calcium pool is not known to Arbor by default, so only ask
+    // for it if needed.
+    if !ions.contains(&String::from("ca2")) && uses_ca2(coll) {
+        result.push(String::from("  USEION ca2 READ ca2i\n"));
+    }
     if !coll.parameters.is_empty() {
         let rs = coll.parameters.keys().cloned().collect::<Vec<_>>();
         result.push(format!("  RANGE {}\n", rs.join(", ")));
@@ -448,6 +496,19 @@ fn print_dependencies(roots: &[String], vars: &[Variable], known: &Set<String>)
 }
 
 pub fn to_nmodl(instance: &Instance, filter: &str) -> Result<String> {
+    mk_nmodl(&collapse(instance, filter)?)
+}
+
+/// Flatten `instance` like for NMODL export, see `fix_known_types`.
+pub fn collapse(instance: &Instance, filter: &str) -> Result<Collapsed> {
+    let (instance, filter) = fix_known_types(instance, filter)?;
+    Collapsed::from_instance(&instance)?.simplify(&filter)
+}
+
+/// Apply fixes for known types, eg supplying the ion current and
+/// concentrations to channels, and extend `filter` by the parameters these
+/// need to retain.
+pub fn fix_known_types(instance: &Instance, filter: &str) -> Result<(Instance, String)> {
     let mut filter = filter.to_string();
     let mut instance = instance.clone();
     // do fixes for known types
@@ -504,6 +565,15 @@ pub fn to_nmodl(instance: &Instance, filter: &str) -> Result<String> {
                 kind: VarKind::Derived(Vec::new(), Some(Expr::parse("cai")?)),
             });
 
+            if ion != "ca2" {
+                instance.component_type.variables.push(Variable {
+                    name: String::from("caConc2"),
+                    exposure: None,
+                    dimension: String::from("concentration"),
+                    kind: VarKind::Derived(Vec::new(), Some(Expr::parse("ca2i")?)),
+                });
+            }
+
             if ion.is_empty() {
                 instance
                     .parameters
@@ -522,11 +592,12 @@ pub fn to_nmodl(instance: &Instance, filter: &str) -> Result<String> {
         }
         _ => {}
     }
-    mk_nmodl(&Collapsed::from_instance(&instance)?.simplify(&filter))
+    Ok((instance, filter))
 }
 
 pub fn mk_nmodl(coll: &Collapsed) -> Result<String> {
     let result = vec![
+        nmodl_names_block(coll)?,
         nmodl_neuron_block(coll)?,
         nmodl_const_block(coll)?,
         nmodl_param_block(coll)?,
@@ -626,23 +697,29 @@ fn sorted_dependencies_of(
     Ok(result)
 }
 
+fn base_types<'a>(ty: &Option<&'a str>) -> Vec<&'a str> {
+    if let Some(ty) = ty {
+        vec![*ty]
+    } else {
+        vec!["baseIonChannel", "baseSynapse"]
+    }
+}
+
 pub fn export(
     lems: &LemsFile,
     nml: &[String],
     ty: &Option<&str>,
     filter: &str,
     cat: &str,
+    ovr: &Overrides,
 ) -> Result<()> {
-    let tys = if let Some(ty) = ty {
-        vec![*ty]
-    } else {
-        vec!["baseIonChannel", "baseSynapse"]
-    };
-    process_files(nml, |_, node| {
+    let tys = base_types(ty);
+    process_files(&lems.include_dir, nml, |_, node| {
         let tag = node.tag_name().name();
         for ty in &tys {
             if lems.derived_from(tag, ty) {
-                let instance = Instance::new(lems, node)?;
+                let mut instance = Instance::new(lems, node)?;
+                ovr.apply_instance(lems, &mut instance)?;
                 let mut path = PathBuf::from(&cat);
                 if !path.exists() {
                     trace!("Creating path to {:?}", &path);
@@ -658,9 +735,111 @@ pub fn export(
                     instance.id.as_deref().unwrap(),
                     &path
                 );
-                write(&path, to_nmodl(&instance, filter)?)?;
+                let filter = format!("{}{}", filter, ovr.selectors(file));
+                write(&path, to_nmodl(&instance, &filter)?)?;
             }
         }
         Ok(())
     })
 }
+
+/// Print for each mechanism whether its parameters are kept or inlined under
+/// `filter`, and which selector decided; writes no files.
+pub fn dry_run(
+    lems: &LemsFile,
+    nml: &[String],
+    ty: &Option<&str>,
+    filter: &str,
+    ovr: &Overrides,
+) -> Result<()> {
+    let tys = base_types(ty);
+    process_files(&lems.include_dir, nml, |_, node| {
+        let tag = node.tag_name().name();
+        for ty in &tys {
+            if lems.derived_from(tag, ty) {
+                let mut instance = Instance::new(lems, node)?;
+                ovr.apply_instance(lems, &mut instance)?;
+                let id = instance.id.clone().unwrap_or_default();
+                let filter = format!("{}{}", filter, ovr.selectors(&id));
+                let (instance, filter) = fix_known_types(&instance, &filter)?;
+                let coll = Collapsed::from_instance(&instance)?;
+                let filter = Filter::parse(&filter)?;
+                let rows = coll.select_parameters(&filter);
+                println!("{}", instance.id.as_deref().unwrap_or("Unknown"));
+                let rows = rows
+                    .iter()
+                    .map(|(name, keep, sel)| {
+                        let sel = if coll.parameters[name].is_none() {
+                            String::from("(no value)")
+                        } else {
+                            sel.map(|s| s.to_string())
+                                .unwrap_or_else(|| String::from("-"))
+                        };
+                        let keep = if *keep { "keep" } else { "inline" };
+                        (keep, name, coll.names.path(name).unwrap_or("-"), sel)
+                    })
+                    .collect::<Vec<_>>();
+                let wn = rows.iter().map(|r| r.1.len()).max().unwrap_or_default();
+                let wp = rows.iter().map(|r| r.2.len()).max().unwrap_or_default();
+                for (keep, name, path, sel) in rows {
+                    println!(
+                        "  {:6}  {:wn$}  {:wp$}  {}",
+                        keep,
+                        name,
+                        path,
+                        sel,
+                        wn = wn,
+                        wp = wp
+                    );
+                }
+            }
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn conc(name: &str, value: &str) -> Variable {
+        Variable {
+            name: name.to_string(),
+            exposure: None,
+            dimension: String::from("concentration"),
+            kind: VarKind::Derived(Vec::new(), Some(Expr::parse(value).unwrap())),
+        }
+    }
+
+    #[test]
+    fn test_uses_ca2() {
+        let mut coll = Collapsed::new(&Some(String::from("kca")));
+        coll.attributes
+            .insert(String::from("species"), Some(String::from("k")));
+        // Defining the pool alone does not need it
+        coll.variables.push(conc("caConc2", "ca2i"));
+        assert!(!uses_ca2(&coll));
+        let neuron = nmodl_neuron_block(&coll).unwrap();
+        assert!(neuron.contains("  USEION k READ ek, ki WRITE ik\n"));
+        assert!(neuron.contains("  USEION ca READ cai\n"));
+        assert!(!neuron.contains("USEION ca2"));
+
+        // Reading it, directly or via caConc2, does
+        for value in ["caConc2 / 2", "ca2i / 2"] {
+            let mut coll = coll.clone();
+            coll.variables.push(conc("x", value));
+            assert!(uses_ca2(&coll));
+            let neuron = nmodl_neuron_block(&coll).unwrap();
+            assert!(neuron.contains("  USEION ca2 READ ca2i\n"));
+        }
+
+        // Channels on the second pool read it as their ion
+        let mut coll = coll.clone();
+        coll.attributes
+            .insert(String::from("species"), Some(String::from("ca2")));
+        coll.variables.push(conc("x", "ca2i / 2"));
+        let neuron = nmodl_neuron_block(&coll).unwrap();
+        assert!(neuron.contains("  USEION ca2 READ eca2, ca2i WRITE ica2\n"));
+        assert!(!neuron.contains("  USEION ca2 READ ca2i\n"));
+    }
+}
@@ -0,0 +1,772 @@
+use std::collections::HashMap as Map;
+use std::fs::{create_dir_all, write};
+use std::path::PathBuf;
+use tracing::info;
+
+use crate::{
+    error::{Error, Result},
+    expr::Expr,
+    instance::{Collapsed, Instance},
+    lems::file::LemsFile,
+    neuroml::process_files,
+    nmodl::fix_known_types,
+    variable::VarKind,
+};
+
+fn eval_error<T: Into<String>>(what: T) -> Error {
+    Error::Eval { what: what.into() }
+}
+
+fn parse_error<T: Into<String>>(what: T) -> Error {
+    Error::Parse { what: what.into() }
+}
+
+/// Time stepping scheme
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    /// Explicit Euler for membrane potential and states
+    Euler,
+    /// Like Arbor: semi-implicit Euler for the membrane potential, exact
+    /// integration of ODEs linear in their state (`cnexp`), and implicit Euler
+    /// for kinetic schemes (`sparse`).
+    Cnexp,
+    /// Classic 4th order Runge-Kutta for membrane potential and states
+    Rk4,
+}
+
+impl std::str::FromStr for Method {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self> {
+        match input {
+            "euler" => Ok(Method::Euler),
+            "cnexp" => Ok(Method::Cnexp),
+            "rk4" => Ok(Method::Rk4),
+            _ => Err(parse_error(format!(
+                "Unknown method {:?}, expected one of euler, cnexp, rk4",
+                input
+            ))),
+        }
+    }
+}
+
+/// Current clamp injecting `amplitude` (nA) for `duration` (ms) from `delay`
+/// (ms) on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Clamp {
+    pub delay: f64,
+    pub duration: f64,
+    pub amplitude: f64,
+}
+
+impl Clamp {
+    fn current(&self, t: f64) -> f64 {
+        if t >= self.delay && t < self.delay + self.duration {
+            self.amplitude
+        } else {
+            0.0
+        }
+    }
+}
+
+impl std::str::FromStr for Clamp {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self> {
+        let err = || {
+            parse_error(format!(
+                "Expected delay:duration:amplitude, got {:?}",
+                input
+            ))
+        };
+        let xs = input
+            .split(':')
+            .map(|x| x.trim().parse::<f64>().map_err(|_| err()))
+            .collect::<Result<Vec<_>>>()?;
+        if let [delay, duration, amplitude] = xs[..] {
+            Ok(Clamp {
+                delay,
+                duration,
+                amplitude,
+            })
+        } else {
+            Err(err())
+        }
+    }
+}
+
+/// Binding `name=value`, eg a reversal potential `ena=50`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Binding {
+    pub name: String,
+    pub value: f64,
+}
+
+impl std::str::FromStr for Binding {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self> {
+        if let Some((name, value)) = input.split_once('=') {
+            if let Ok(value) = value.trim().parse::<f64>() {
+                return Ok(Binding {
+                    name: name.trim().to_string(),
+                    value,
+                });
+            }
+        }
+        Err(parse_error(format!("Expected name=value, got {:?}", input)))
+    }
+}
+
+/// A mechanism in the compartment; its currents are multiplied by `scale`,
+/// eg the number of channels.
+#[derive(Debug, Clone)]
+pub struct Mechanism {
+    pub name: String,
+    pub model: Collapsed,
+    pub scale: f64,
+}
+
+/// Single isopotential compartment. Units are those of the exported NMODL:
+/// mV, ms, nA, and uS, thus capacitance is given in nF.
+#[derive(Debug, Clone)]
+pub struct Compartment {
+    pub capacitance: f64,
+    pub mechanisms: Vec<Mechanism>,
+    pub clamp: Option<Clamp>,
+    /// Values visible to all mechanisms, eg reversal potentials `ena` or
+    /// concentrations `cai`.
+    pub env: Map<String, f64>,
+}
+
+impl Compartment {
+    pub fn new(capacitance: f64) -> Self {
+        let env = [
+            ("ena", 50.0),
+            ("ek", -77.0),
+            ("eca", 132.5),
+            ("cai", 5e-5),
+            ("ca2i", 5e-5),
+            ("temperature", 279.45),
+        ]
+        .iter()
+        .map(|(k, v)| (k.to_string(), *v))
+        .collect();
+        Compartment {
+            capacitance,
+            mechanisms: Vec::new(),
+            clamp: None,
+            env,
+        }
+    }
+}
+
+/// Simulation time, time step, initial membrane potential, and method
+#[derive(Debug, Clone, PartialEq)]
+pub struct Run {
+    pub t_final: f64,
+    pub dt: f64,
+    pub v0: f64,
+    pub method: Method,
+}
+
+/// Time, membrane potential, and states of all mechanisms, one row per step
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trace {
+    pub names: Vec<String>,
+    pub rows: Vec<Vec<f64>>,
+}
+
+impl Trace {
+    pub fn to_csv(&self) -> String {
+        let mut result = self.names.join(",");
+        result.push('\n');
+        for row in &self.rows {
+            let row = row.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+            result.push_str(&row.join(","));
+            result.push('\n');
+        }
+        result
+    }
+}
+
+/// Mechanism prepared for integration: states are addressed by index.
+struct Model<'a> {
+    mech: &'a Mechanism,
+    states: Vec<String>,
+    init: Vec<Option<Expr>>,
+    deriv: Vec<Option<Expr>>,
+    /// Kinetic transitions (from, to, forward rate, reverse rate)
+    kinetic: Vec<(usize, usize, String, String)>,
+    currents: Vec<String>,
+    base: Map<String, f64>,
+}
+
+impl<'a> Model<'a> {
+    fn new(mech: &'a Mechanism, env: &Map<String, f64>) -> Result<Self> {
+        let coll = &mech.model;
+        let mut states = Vec::new();
+        let mut init = Vec::new();
+        let mut deriv = Vec::new();
+        for var in &coll.variables {
+            match &var.kind {
+                VarKind::State(i, d) => {
+                    states.push(var.name.to_string());
+                    init.push(i.clone());
+                    deriv.push(d.clone());
+                }
+                VarKind::Derived(_, _) => {}
+                VarKind::Select(_, _) => {
+                    return Err(eval_error(format!(
+                        "Select variable {} post flattening stage",
+                        var.name
+                    )))
+                }
+            }
+        }
+        let index = |n: &str| {
+            states
+                .iter()
+                .position(|s| s == n)
+                .ok_or_else(|| eval_error(format!("Unknown kinetic state {}", n)))
+        };
+        let kinetic = coll
+            .transitions
+            .iter()
+            .map(|(f, t, rf, rr)| Ok((index(f)?, index(t)?, rf.to_string(), rr.to_string())))
+            .collect::<Result<Vec<_>>>()?;
+        let currents = coll
+            .attributes
+            .iter()
+            .filter(|(k, _)| k.ends_with("species"))
+            .map(|(_, v)| format!("i{}", v.as_deref().unwrap_or_default()))
+            .filter(|i| coll.variables.iter().any(|v| &v.name == i))
+            .collect();
+        let mut base = coll.bindings();
+        base.extend(env.iter().map(|(k, v)| (k.to_string(), *v)));
+        Ok(Model {
+            mech,
+            states,
+            init,
+            deriv,
+            kinetic,
+            currents,
+            base,
+        })
+    }
+
+    fn env(&self, v: f64, xs: &[f64]) -> Map<String, f64> {
+        let mut env = self.base.clone();
+        env.insert(String::from("v"), v);
+        for (s, x) in self.states.iter().zip(xs) {
+            env.insert(s.to_string(), *x);
+        }
+        env
+    }
+
+    /// Initial values: explicit ones where given, kinetic schemes in
+    /// equilibrium, zero otherwise.
+    fn initial(&self, v: f64) -> Result<Vec<f64>> {
+        let mut xs = vec![0.0; self.states.len()];
+        for (ix, i) in self.init.iter().enumerate() {
+            if let Some(i) = i {
+                xs[ix] = self.mech.model.eval(i, &mut self.env(v, &xs))?;
+            }
+        }
+        let a = self.kinetic_matrix(v, &xs)?;
+        // Each connected set of kinetic states sums to one; replace the first
+        // row of each with this constraint to fix the equilibrium.
+        let mut group = (0..xs.len()).collect::<Vec<_>>();
+        let root = |g: &[usize], mut ix: usize| {
+            while g[ix] != ix {
+                ix = g[ix];
+            }
+            ix
+        };
+        for (f, t, _, _) in &self.kinetic {
+            let (f, t) = (root(&group, *f), root(&group, *t));
+            group[f.max(t)] = f.min(t);
+        }
+        let kinetic = self
+            .kinetic
+            .iter()
+            .flat_map(|(f, t, _, _)| [*f, *t])
+            .collect::<std::collections::BTreeSet<_>>();
+        if kinetic.is_empty() {
+            return Ok(xs);
+        }
+        let mut a = a;
+        let mut b = vec![0.0; xs.len()];
+        for ix in 0..xs.len() {
+            if !kinetic.contains(&ix) {
+                a[ix] = vec![0.0; xs.len()];
+                a[ix][ix] = 1.0;
+                b[ix] = xs[ix];
+            } else if root(&group, ix) == ix {
+                for (jx, g) in a[ix].iter_mut().enumerate() {
+                    *g = if root(&group, jx) == ix { 1.0 } else { 0.0 };
+                }
+                b[ix] = 1.0;
+            }
+        }
+        solve(a, b)
+    }
+
+    /// Matrix `A` of the kinetic schemes, such that `x' = A x`
+    fn kinetic_matrix(&self, v: f64, xs: &[f64]) -> Result<Vec<Vec<f64>>> {
+        let n = xs.len();
+        let mut a = vec![vec![0.0; n]; n];
+        let mut env = self.env(v, xs);
+        for (f, t, rf, rr) in &self.kinetic {
+            let rf = self.mech.model.value(rf, &mut env)?;
+            let rr = self.mech.model.value(rr, &mut env)?;
+            a[*f][*f] -= rf;
+            a[*t][*f] += rf;
+            a[*f][*t] += rr;
+            a[*t][*t] -= rr;
+        }
+        Ok(a)
+    }
+
+    fn derivative(&self, v: f64, xs: &[f64]) -> Result<Vec<f64>> {
+        let mut env = self.env(v, xs);
+        let mut result = vec![0.0; xs.len()];
+        for (ix, d) in self.deriv.iter().enumerate() {
+            if let Some(d) = d {
+                result[ix] = self.mech.model.eval(d, &mut env)?;
+            }
+        }
+        for (f, t, rf, rr) in &self.kinetic {
+            let rf = self.mech.model.value(rf, &mut env)?;
+            let rr = self.mech.model.value(rr, &mut env)?;
+            let flux = rf * xs[*f] - rr * xs[*t];
+            result[*f] -= flux;
+            result[*t] += flux;
+        }
+        Ok(result)
+    }
+
+    fn current(&self, v: f64, xs: &[f64]) -> Result<f64> {
+        let mut env = self.env(v, xs);
+        let mut result = 0.0;
+        for i in &self.currents {
+            result += self.mech.model.value(i, &mut env)?;
+        }
+        Ok(self.mech.scale * result)
+    }
+
+    /// Advance states by `dt` at fixed `v`: ODEs linear in their own state
+    /// are integrated exactly, kinetic schemes by implicit Euler.
+    fn cnexp(&self, v: f64, xs: &[f64], dt: f64) -> Result<Vec<f64>> {
+        let mut result = xs.to_vec();
+        for (ix, d) in self.deriv.iter().enumerate() {
+            if let Some(d) = d {
+                // x' = a + b x, found by evaluating at x and x + 1
+                let mut ys = xs.to_vec();
+                let f0 = self.mech.model.eval(d, &mut self.env(v, &ys))?;
+                ys[ix] += 1.0;
+                let f1 = self.mech.model.eval(d, &mut self.env(v, &ys))?;
+                let b = f1 - f0;
+                result[ix] = if b.abs() > f64::EPSILON {
+                    let a = f0 - b * xs[ix];
+                    -a / b + (xs[ix] + a / b) * (b * dt).exp()
+                } else {
+                    xs[ix] + dt * f0
+                };
+            }
+        }
+        if !self.kinetic.is_empty() {
+            // (1 - dt A) x' = x
+            let mut a = self.kinetic_matrix(v, xs)?;
+            for (ix, row) in a.iter_mut().enumerate() {
+                for (jx, r) in row.iter_mut().enumerate() {
+                    *r = if ix == jx { 1.0 } else { 0.0 } - dt * *r;
+                }
+            }
+            let ys = solve(a, result.clone())?;
+            for (f, t, _, _) in &self.kinetic {
+                result[*f] = ys[*f];
+                result[*t] = ys[*t];
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// Solve `a x = b` by Gaussian elimination with partial pivoting.
+fn solve(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Result<Vec<f64>> {
+    let n = b.len();
+    for k in 0..n {
+        let p = (k..n)
+            .max_by(|i, j| a[*i][k].abs().total_cmp(&a[*j][k].abs()))
+            .unwrap_or(k);
+        if a[p][k].abs() < f64::EPSILON {
+            return Err(eval_error("Singular system in kinetic scheme"));
+        }
+        a.swap(k, p);
+        b.swap(k, p);
+        let (top, bottom) = a.split_at_mut(k + 1);
+        let pivot = &top[k];
+        for (i, row) in bottom.iter_mut().enumerate() {
+            let f = row[k] / pivot[k];
+            for (r, p) in row[k..].iter_mut().zip(&pivot[k..]) {
+                *r -= f * p;
+            }
+            b[k + 1 + i] -= f * b[k];
+        }
+    }
+    let mut x = vec![0.0; n];
+    for k in (0..n).rev() {
+        let s = (k + 1..n).map(|j| a[k][j] * x[j]).sum::<f64>();
+        x[k] = (b[k] - s) / a[k][k];
+    }
+    Ok(x)
+}
+
+/// State of the compartment: membrane potential and states per mechanism
+type State = (f64, Vec<Vec<f64>>);
+
+fn axpy(y: &State, h: f64, k: &State) -> State {
+    let xs =
+        y.1.iter()
+            .zip(&k.1)
+            .map(|(x, d)| x.iter().zip(d).map(|(x, d)| x + h * d).collect())
+            .collect();
+    (y.0 + h * k.0, xs)
+}
+
+/// Integrate `cmp` as given by `run` and record all states after each step.
+pub fn simulate(cmp: &Compartment, run: &Run) -> Result<Trace> {
+    let models = cmp
+        .mechanisms
+        .iter()
+        .map(|m| Model::new(m, &cmp.env))
+        .collect::<Result<Vec<_>>>()?;
+    let clamp = |t: f64| cmp.clamp.as_ref().map(|c| c.current(t)).unwrap_or(0.0);
+    let membrane = |v: f64, xs: &[Vec<f64>]| -> Result<f64> {
+        let mut i = 0.0;
+        for (m, x) in models.iter().zip(xs) {
+            i += m.current(v, x)?;
+        }
+        Ok(i)
+    };
+    let rhs = |t: f64, y: &State| -> Result<State> {
+        let (v, xs) = y;
+        let dv = (clamp(t) - membrane(*v, xs)?) / cmp.capacitance;
+        let dxs = models
+            .iter()
+            .zip(xs)
+            .map(|(m, x)| m.derivative(*v, x))
+            .collect::<Result<Vec<_>>>()?;
+        Ok((dv, dxs))
+    };
+
+    let mut names = vec![String::from("t"), String::from("v")];
+    for m in &models {
+        names.extend(m.states.iter().map(|s| format!("{}/{}", m.mech.name, s)));
+    }
+    let record = |t: f64, y: &State| {
+        let mut row = vec![t, y.0];
+        row.extend(y.1.iter().flatten());
+        row
+    };
+
+    let v0 = run.v0;
+    let xs = models
+        .iter()
+        .map(|m| m.initial(v0))
+        .collect::<Result<Vec<_>>>()?;
+    let mut y = (v0, xs);
+    let dt = run.dt;
+    let steps = (run.t_final / dt).round() as usize;
+    let mut rows = vec![record(0.0, &y)];
+    for step in 0..steps {
+        let t = step as f64 * dt;
+        y = match run.method {
+            Method::Euler => axpy(&y, dt, &rhs(t, &y)?),
+            Method::Rk4 => {
+                let k1 = rhs(t, &y)?;
+                let k2 = rhs(t + 0.5 * dt, &axpy(&y, 0.5 * dt, &k1))?;
+                let k3 = rhs(t + 0.5 * dt, &axpy(&y, 0.5 * dt, &k2))?;
+                let k4 = rhs(t + dt, &axpy(&y, dt, &k3))?;
+                let mut y = y;
+                for (k, h) in [
+                    (k1, dt / 6.0),
+                    (k2, dt / 3.0),
+                    (k3, dt / 3.0),
+                    (k4, dt / 6.0),
+                ] {
+                    y = axpy(&y, h, &k);
+                }
+                y
+            }
+            Method::Cnexp => {
+                // Linearise the membrane current around v to step implicitly
+                let (v, xs) = &y;
+                let i = membrane(*v, xs)?;
+                let g = (membrane(v + 1e-3, xs)? - i) / 1e-3;
+                let v = v + dt * (clamp(t) - i) / (cmp.capacitance + dt * g);
+                let xs = models
+                    .iter()
+                    .zip(xs)
+                    .map(|(m, x)| m.cnexp(v, x, dt))
+                    .collect::<Result<Vec<_>>>()?;
+                (v, xs)
+            }
+        };
+        let t = (step + 1) as f64 * dt;
+        if !y.0.is_finite() {
+            return Err(eval_error(format!(
+                "Membrane potential diverged at t={} ms",
+                t
+            )));
+        }
+        rows.push(record(t, &y));
+    }
+    Ok(Trace { names, rows })
+}
+
+/// Clamp the membrane potential of a compartment holding only `mech` to the
+/// potentials given by `schedule` as (duration, potential) pairs, starting with
+/// states in equilibrium at the first potential. Records time, potential,
+/// current, and conductance `g` after each step.
+pub fn clamp(
+    mech: &Mechanism,
+    env: &Map<String, f64>,
+    schedule: &[(f64, f64)],
+    dt: f64,
+    method: Method,
+) -> Result<Trace> {
+    let model = Model::new(mech, env)?;
+    let conductance = |v: f64, xs: &[f64]| -> Result<f64> {
+        let g = mech.model.value("g", &mut model.env(v, xs))?;
+        Ok(mech.scale * g)
+    };
+    let record = |t: f64, v: f64, xs: &[f64]| -> Result<Vec<f64>> {
+        Ok(vec![t, v, model.current(v, xs)?, conductance(v, xs)?])
+    };
+    let v0 = schedule
+        .first()
+        .map(|p| p.1)
+        .ok_or_else(|| eval_error("Empty clamp schedule"))?;
+    let mut xs = model.initial(v0)?;
+    let mut rows = vec![record(0.0, v0, &xs)?];
+    let mut step = 0;
+    for (duration, v) in schedule {
+        let steps = (duration / dt).round() as usize;
+        for _ in 0..steps {
+            xs = match method {
+                Method::Euler => {
+                    let d = model.derivative(*v, &xs)?;
+                    xs.iter().zip(d).map(|(x, d)| x + dt * d).collect()
+                }
+                Method::Rk4 => {
+                    let f = |ys: &[f64]| model.derivative(*v, ys);
+                    let add = |ys: &[f64], h: f64, ks: &[f64]| -> Vec<f64> {
+                        ys.iter().zip(ks).map(|(y, k)| y + h * k).collect()
+                    };
+                    let k1 = f(&xs)?;
+                    let k2 = f(&add(&xs, 0.5 * dt, &k1))?;
+                    let k3 = f(&add(&xs, 0.5 * dt, &k2))?;
+                    let k4 = f(&add(&xs, dt, &k3))?;
+                    (0..xs.len())
+                        .map(|ix| {
+                            xs[ix] + dt / 6.0 * (k1[ix] + 2.0 * k2[ix] + 2.0 * k3[ix] + k4[ix])
+                        })
+                        .collect()
+                }
+                Method::Cnexp => model.cnexp(*v, &xs, dt)?,
+            };
+            step += 1;
+            rows.push(record(step as f64 * dt, *v, &xs)?);
+        }
+    }
+    let names = ["t", "v", "i", "g"].iter().map(|s| s.to_string()).collect();
+    Ok(Trace { names, rows })
+}
+
+/// Flatten `instance` into a mechanism; if `filter` is given, simplify like
+/// NMODL export does, see `nmodl::collapse`.
+pub fn mechanism(instance: &Instance, filter: &Option<&str>, scale: f64) -> Result<Mechanism> {
+    let (inst, flt) = fix_known_types(instance, filter.unwrap_or_default())?;
+    let mut model = Collapsed::from_instance(&inst)?;
+    if filter.is_some() {
+        model = model.simplify(&flt)?;
+    }
+    Ok(Mechanism {
+        name: instance.id.as_deref().unwrap_or("Unknown").to_string(),
+        model,
+        scale,
+    })
+}
+
+/// Insert the `channels` found in `nml`, given as `id` or `id=count`, into
+/// `cmp` and write the traces of simulating `run` to `trace.csv` in `dir`.
+pub fn export(
+    lems: &LemsFile,
+    nml: &[String],
+    channels: &[String],
+    filter: &Option<&str>,
+    cmp: &Compartment,
+    run: &Run,
+    dir: &str,
+) -> Result<()> {
+    let mut counts = Map::new();
+    for ch in channels {
+        let (id, n) = match ch.split_once('=') {
+            Some((id, n)) => {
+                let n = n
+                    .parse::<f64>()
+                    .map_err(|_| parse_error(format!("Expected id=count, got {:?}", ch)))?;
+                (id.to_string(), n)
+            }
+            None => (ch.to_string(), 1.0),
+        };
+        counts.insert(id, n);
+    }
+    let mut cmp = cmp.clone();
+    process_files(&lems.include_dir, nml, |_, node| {
+        let ty = node
+            .attribute("type")
+            .unwrap_or_else(|| node.tag_name().name());
+        if !lems.derived_from(ty, "baseIonChannel") {
+            return Ok(());
+        }
+        let id = node.attribute("id").unwrap_or_default();
+        let scale = match counts.get(id) {
+            Some(n) => *n,
+            None if counts.is_empty() => 1.0,
+            None => return Ok(()),
+        };
+        let instance = Instance::new(lems, node)?;
+        cmp.mechanisms.push(mechanism(&instance, filter, scale)?);
+        Ok(())
+    })?;
+    for id in counts.keys() {
+        if !cmp.mechanisms.iter().any(|m| &m.name == id) {
+            return Err(Error::Nml {
+                what: format!("No such channel: {}", id),
+            });
+        }
+    }
+    let trace = simulate(&cmp, run)?;
+    create_dir_all(dir)?;
+    let mut path = PathBuf::from(dir);
+    path.push("trace.csv");
+    info!("Writing traces to {:?}", &path);
+    write(&path, trace.to_csv())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn channel(file: &str, id: &str, filter: &Option<&str>) -> Mechanism {
+        let lems = crate::fixture::core_types();
+        let xml = std::fs::read_to_string(file).unwrap();
+        let doc = roxmltree::Document::parse(&xml).unwrap();
+        let node = doc
+            .descendants()
+            .find(|n| n.attribute("id") == Some(id))
+            .unwrap();
+        let instance = Instance::new(&lems, &node).unwrap();
+        mechanism(&instance, filter, 1e4).unwrap()
+    }
+
+    fn run(file: &str, id: &str, filter: &Option<&str>, method: Method) -> Trace {
+        let mut cmp = Compartment::new(0.01);
+        cmp.mechanisms.push(channel(file, id, filter));
+        cmp.clamp = Some(Clamp {
+            delay: 1.0,
+            duration: 2.0,
+            amplitude: 0.05,
+        });
+        let run = Run {
+            t_final: 5.0,
+            dt: 0.01,
+            v0: -65.0,
+            method,
+        };
+        simulate(&cmp, &run).unwrap()
+    }
+
+    fn column(trace: &Trace, name: &str) -> Vec<f64> {
+        let ix = trace.names.iter().position(|n| n == name).unwrap();
+        trace.rows.iter().map(|r| r[ix]).collect()
+    }
+
+    fn max_diff(a: &[f64], b: &[f64]) -> f64 {
+        a.iter()
+            .zip(b)
+            .map(|(x, y)| (x - y).abs())
+            .fold(0.0, f64::max)
+    }
+
+    #[test]
+    #[cfg_attr(
+        not(core_types),
+        ignore = "needs the NML2 core types, run bootstrap.sh"
+    )]
+    fn test_simplify_preserves_dynamics() {
+        for (file, id) in [
+            ("example/nml-simple-ion-channels.xml", "NaConductance"),
+            ("example/nml-simple-ks.nml", "k_fwd_rev"),
+        ] {
+            for method in [Method::Euler, Method::Cnexp, Method::Rk4] {
+                let full = run(file, id, &None, method);
+                for filter in ["-*", "+*"] {
+                    let simple = run(file, id, &Some(filter), method);
+                    assert_eq!(full.names, simple.names);
+                    for name in &full.names {
+                        let d = max_diff(&column(&full, name), &column(&simple, name));
+                        assert!(
+                            d < 1e-9,
+                            "{} {} {:?}: {} differs by {}",
+                            id,
+                            filter,
+                            method,
+                            name,
+                            d
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[cfg_attr(
+        not(core_types),
+        ignore = "needs the NML2 core types, run bootstrap.sh"
+    )]
+    fn test_methods_agree() {
+        let file = "example/nml-simple-ion-channels.xml";
+        let rk4 = run(file, "NaConductance", &None, Method::Rk4);
+        for method in [Method::Euler, Method::Cnexp] {
+            let other = run(file, "NaConductance", &None, method);
+            let d = max_diff(&column(&rk4, "v"), &column(&other, "v"));
+            assert!(d < 0.5, "{:?} differs from rk4 by {} mV", method, d);
+        }
+    }
+
+    #[test]
+    #[cfg_attr(
+        not(core_types),
+        ignore = "needs the NML2 core types, run bootstrap.sh"
+    )]
+    fn test_kinetic_equilibrium() {
+        let file = "example/nml-simple-ks.nml";
+        let trace = run(file, "k_fwd_rev", &None, Method::Cnexp);
+        let row = &trace.rows[0];
+        let occ = trace
+            .names
+            .iter()
+            .zip(row)
+            .filter(|(n, _)| n.contains("gates_n_states"))
+            .map(|(_, x)| *x)
+            .collect::<Vec<_>>();
+        assert_eq!(occ.len(), 2);
+        assert!((occ.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+    }
+}
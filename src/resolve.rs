@@ -0,0 +1,94 @@
+use std::path::{Path, PathBuf};
+use tracing::trace;
+
+use crate::error::{Error, Result};
+
+/// Environment variable holding extra search paths, separated like `PATH`
+pub const NML2_PATH: &str = "NML2_PATH";
+
+/// Locates files referenced by `<include href>` (NML2) and `<Include file>`
+/// (LEMS). Names are tried relative to the including file first, then in the
+/// configured search paths, and finally in those given by `NML2_PATH`.
+#[derive(Debug, Clone, Default)]
+pub struct Resolver {
+    paths: Vec<PathBuf>,
+}
+
+impl Resolver {
+    pub fn new(dirs: &[String]) -> Self {
+        let mut paths = dirs.iter().map(PathBuf::from).collect::<Vec<_>>();
+        if let Some(env) = std::env::var_os(NML2_PATH) {
+            paths.extend(std::env::split_paths(&env));
+        }
+        Resolver { paths }
+    }
+
+    /// Candidate locations for `name` in order of precedence, `base` being
+    /// the directory of the including file, if any.
+    pub fn candidates(&self, name: &str, base: Option<&Path>) -> Vec<PathBuf> {
+        let name = Path::new(name);
+        if name.is_absolute() {
+            return vec![name.to_path_buf()];
+        }
+        let mut result: Vec<PathBuf> = Vec::new();
+        for dir in base
+            .iter()
+            .map(|p| p.to_path_buf())
+            .chain(self.paths.iter().cloned())
+        {
+            let path = dir.join(name);
+            if !result.contains(&path) {
+                result.push(path);
+            }
+        }
+        result
+    }
+
+    /// Find `name`, returning its canonical path
+    pub fn resolve(&self, name: &str, base: Option<&Path>) -> Result<PathBuf> {
+        let tried = self.candidates(name, base);
+        for path in &tried {
+            trace!("Trying {:?}", path);
+            if path.is_file() {
+                return Ok(path.canonicalize()?);
+            }
+        }
+        Err(not_found(name, &tried))
+    }
+}
+
+pub fn not_found(name: &str, tried: &[PathBuf]) -> Error {
+    let tried = tried
+        .iter()
+        .map(|p| format!("\n  - {}", p.display()))
+        .collect::<String>();
+    Error::Include {
+        what: format!("Could not find {}, tried:{}", name, tried),
+    }
+}
+
+/// Chain of files leading to an include, used to detect cycles
+#[derive(Debug, Clone, Default)]
+pub struct Trail(Vec<PathBuf>);
+
+impl Trail {
+    /// Extend by `path`; fails if `path` was already on the way here.
+    pub fn push(&self, path: &Path) -> Result<Self> {
+        if self.0.iter().any(|p| p == path) {
+            let cycle = self
+                .0
+                .iter()
+                .skip_while(|p| *p != path)
+                .chain(std::iter::once(&path.to_path_buf()))
+                .map(|p| p.display().to_string())
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            return Err(Error::Include {
+                what: format!("Include cycle {}", cycle),
+            });
+        }
+        let mut result = self.clone();
+        result.0.push(path.to_path_buf());
+        Ok(result)
+    }
+}
@@ -10,6 +10,8 @@ enum Kind {
     String,
     Integer,
     Float,
+    Quantity,
+    Enum(String),
     Class(String),
 }
 
@@ -34,7 +36,8 @@ impl Kind {
             Kind::Float => "f64".to_string(),
             Kind::Integer => "i64".to_string(),
             Kind::String => "String".to_string(),
-            Kind::Class(n) => n.to_string(),
+            Kind::Quantity => "Nml2Quantity".to_string(),
+            Kind::Enum(n) | Kind::Class(n) => n.to_string(),
         }
     }
 }
@@ -110,6 +113,7 @@ struct Schema {
     types: Map<String, Type>,
     alias: Map<String, Kind>,
     groups: Map<String, Body>,
+    enums: Map<String, Vec<String>>,
 }
 
 fn restriction(node: &Node) -> Result<Kind> {
@@ -118,7 +122,16 @@ fn restriction(node: &Node) -> Result<Kind> {
         .ok_or_else(|| String::from("Restriction requires attribute 'base'"))
 }
 
-fn simple_type(node: &Node) -> Result<(String, Kind)> {
+/// Values of a string restriction given as a list of `xs:enumeration`
+fn enumeration(node: &Node) -> Vec<String> {
+    node.children()
+        .filter(|c| c.tag_name().name() == "enumeration")
+        .filter_map(|c| c.attribute("value"))
+        .map(|v| v.to_string())
+        .collect()
+}
+
+fn simple_type(node: &Node) -> Result<(String, Kind, Vec<String>)> {
     let name = node
         .attribute("name")
         .ok_or_else(|| String::from("Unnamed simple type."))?;
@@ -127,7 +140,16 @@ fn simple_type(node: &Node) -> Result<(String, Kind)> {
             continue;
         }
         match child.tag_name().name() {
-            "restriction" => return Ok((name.to_string(), restriction(&child)?)),
+            "restriction" => {
+                let kind = restriction(&child)?;
+                let values = enumeration(&child);
+                let kind = match kind {
+                    Kind::String if name.starts_with("Nml2Quantity") => Kind::Quantity,
+                    Kind::String if !values.is_empty() => Kind::Enum(name.to_string()),
+                    k => k,
+                };
+                return Ok((name.to_string(), kind, values));
+            }
             "annotation" => {}
             t => return Err(format!("Invalid tag '{}' in simple type.", t)),
         }
@@ -296,7 +318,10 @@ fn schema(xml: &str) -> Result<Schema> {
                 state.types.insert(k, v);
             }
             "simpleType" => {
-                let (k, v) = simple_type(&child)?;
+                let (k, v, es) = simple_type(&child)?;
+                if let Kind::Enum(_) = v {
+                    state.enums.insert(k.clone(), es);
+                }
                 state.alias.insert(k, v);
             }
             "group" => {
@@ -386,7 +411,13 @@ fn dealias(tys: &Map<String, Type>, alias: &Map<String, Kind>) -> Map<String, Ty
             for e in es {
                 let x = match e {
                     Element::Element(n, Kind::Class(c)) if alias.contains_key(&c) => {
-                        Element::Element(n, alias.get(&c).unwrap().clone())
+                        // Elements are read from their text content, so only
+                        // plain values can be used here.
+                        let k = match alias.get(&c).unwrap() {
+                            Kind::Quantity | Kind::Enum(_) => Kind::String,
+                            k => k.clone(),
+                        };
+                        Element::Element(n, k)
                     }
                     a => a.clone(),
                 };
@@ -399,6 +430,62 @@ fn dealias(tys: &Map<String, Type>, alias: &Map<String, Kind>) -> Map<String, Ty
     result
 }
 
+/// Rust identifier for enumeration value `v`, eg 'Path Length from root'
+fn variant(v: &str) -> String {
+    v.chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+fn emit_enum(name: &str, values: &[String]) -> Vec<String> {
+    let mut result = vec![
+        String::from("#[derive(Debug, Clone, Copy, PartialEq, Eq)]"),
+        format!("pub enum {} {{", name),
+    ];
+    for v in values {
+        result.push(format!("    {},", variant(v)));
+    }
+    result.push(String::from("}"));
+    result.push(String::from(""));
+    result.push(format!("impl FromStr for {} {{", name));
+    result.push(String::from("    type Err = String;"));
+    result.push(String::from(""));
+    result.push(String::from(
+        "    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {",
+    ));
+    result.push(String::from("        match s {"));
+    for v in values {
+        result.push(format!(
+            "            {:?} => Ok({}::{}),",
+            v,
+            name,
+            variant(v)
+        ));
+    }
+    result.push(format!(
+        "            _ => Err(format!(\"Expected one of {}, got {{:?}}\", s)),",
+        values.join(", ").replace('"', "")
+    ));
+    result.push(String::from("        }"));
+    result.push(String::from("    }"));
+    result.push(String::from("}"));
+    result.push(String::from(""));
+    result.push(format!("impl fmt::Display for {} {{", name));
+    result.push(String::from(
+        "    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {",
+    ));
+    result.push(String::from("        let s = match self {"));
+    for v in values {
+        result.push(format!("            {}::{} => {:?},", name, variant(v), v));
+    }
+    result.push(String::from("        };"));
+    result.push(String::from("        write!(f, \"{}\", s)"));
+    result.push(String::from("    }"));
+    result.push(String::from("}"));
+    result.push(String::from(""));
+    result
+}
+
 fn emit_src(state: &Schema) -> Result<String> {
     // Squeeze input
     let data = flatten(&state.types, &state.groups);
@@ -413,21 +500,46 @@ fn emit_src(state: &Schema) -> Result<String> {
         String::from(""),
         String::from("use roxmltree::Node;"),
         String::from(""),
-        String::from("use crate::xml::XML;"),
-        String::from(""),
+        String::from("use crate::error::Result;"),
+        String::from("use crate::xml::{close_tag, def_attr, open_tag, opt_attr, req_attr, unexpected_tag, write_attr, XML};"),
     ];
+    let quantities = data
+        .values()
+        .any(|t| t.members.iter().any(|m| matches!(m.data, Kind::Quantity)));
+    if quantities {
+        lines.push(String::from("use crate::expr::Nml2Quantity;"));
+    }
+    if data.values().any(|t| matches!(t.body, Body::Any)) {
+        lines.push(String::from("use crate::xml::{inner_xml, write_inner};"));
+    }
+    if !state.enums.is_empty() {
+        lines.push(String::from("use std::fmt;"));
+        lines.push(String::from("use std::str::FromStr;"));
+    }
+    lines.push(String::from(""));
+    let mut enums = state.enums.iter().collect::<Vec<_>>();
+    enums.sort();
+    for (name, values) in enums {
+        lines.append(&mut emit_enum(name, values));
+    }
     for (_, t) in data {
         let mut ty = Vec::new();
         let mut nd = Vec::new();
         let mut attrs = Vec::new();
 
         nd.push(format!("impl XML for {} {{", t.name));
-        nd.push(String::from("    fn from_node(node: &Node) -> Self {"));
+        nd.push(String::from(
+            "    fn from_node(node: &Node) -> Result<Self> {",
+        ));
 
         ty.push("#[derive(Debug, Clone, PartialEq)]".to_string());
         ty.push(format!("pub struct {} {{", t.name));
 
         let mut bd = Vec::new();
+        let mut wr = vec![
+            String::from("    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {"),
+            String::from("        open_tag(out, depth, tag);"),
+        ];
 
         for m in &t.members {
             let name = match m.name.as_ref() {
@@ -438,32 +550,34 @@ fn emit_src(state: &Schema) -> Result<String> {
                 n => n.to_string(),
             };
             attrs.push(name.clone());
-            let convert = match m.data {
-                Kind::Float => "|s| s.parse::<f64>().unwrap()",
-                Kind::Integer => "|s| s.parse::<i64>().unwrap()",
-                Kind::String => "|s| s.to_string()",
-                _ => "",
-            };
-            let unwrap = if let Count::Opt(None) = &m.count {
-                ""
-            } else {
-                ".unwrap()"
-            };
-            let default = if let Count::Opt(Some(d)) = &m.count {
-                format!(".or(Some(\"{}\"))", d)
-            } else {
-                "".to_string()
-            };
             let kind = if let Count::Opt(None) = &m.count {
                 format!("Option<{}>", m.data.to_rs())
             } else {
                 m.data.to_rs()
             };
+            let getter = match &m.count {
+                Count::One => format!("req_attr::<{}>(node, \"{}\")", m.data.to_rs(), m.name),
+                Count::Opt(None) => format!("opt_attr::<{}>(node, \"{}\")", m.data.to_rs(), m.name),
+                Count::Opt(Some(d)) => format!(
+                    "def_attr::<{}>(node, \"{}\", \"{}\")",
+                    m.data.to_rs(),
+                    m.name,
+                    d
+                ),
+            };
             ty.push(format!("    pub {}: {},", name, kind));
-            nd.push(format!(
-                "        let {} = node.attribute(\"{}\"){}.map({}){};",
-                name, m.name, default, convert, unwrap
-            ));
+            nd.push(format!("        let {} = {}?;", name, getter));
+            if let Count::Opt(None) = &m.count {
+                wr.push(format!(
+                    "        if let Some(x) = &self.{} {{ write_attr(out, \"{}\", x); }}",
+                    name, m.name
+                ));
+            } else {
+                wr.push(format!(
+                    "        write_attr(out, \"{}\", &self.{});",
+                    m.name, name
+                ));
+            }
         }
         match t.body {
             Body::Alt(ref xs) => {
@@ -479,12 +593,25 @@ fn emit_src(state: &Schema) -> Result<String> {
                 bd.push("#[derive(Debug, Clone, PartialEq)]".to_string());
                 bd.push(format!("pub enum {}Body {{", t.name));
 
+                // Writers are only reachable through their parents', which
+                // may never be called
+                let mut bw = vec![
+                    String::from("#[allow(dead_code)]"),
+                    format!("impl {}Body {{", t.name),
+                    String::from("    fn to_xml(&self, depth: usize, out: &mut String) {"),
+                    String::from("        match self {"),
+                ];
+
                 for x in xs {
                     if let Element::Element(k, v) = x {
                         let v = v.to_rs();
                         bd.push(format!("    {}({}),", k, v));
+                        bw.push(format!(
+                            "            {}Body::{}(x) => x.to_xml(\"{}\", depth, out),",
+                            t.name, k, k
+                        ));
                         lp.push(format!(
-                            "                \"{}\" => body.push({}Body::{}({}::from_node(&child))),",
+                            "                \"{}\" => body.push({}Body::{}({}::from_node(&child)?)),",
                             k, t.name, k, v
                         ));
                     } else {
@@ -492,7 +619,7 @@ fn emit_src(state: &Schema) -> Result<String> {
                     }
                 }
                 lp.push(format!(
-                    "                t => panic!(\"Unexpected tag {{}} in body of {}.\", t)",
+                    "                _ => return Err(unexpected_tag(&child, \"{}\")),",
                     t.name
                 ));
                 lp.push(String::from("            };"));
@@ -501,18 +628,42 @@ fn emit_src(state: &Schema) -> Result<String> {
                 attrs.push(String::from("body"));
 
                 bd.push("}\n".to_string());
+                bw.push(String::from("        }"));
+                bw.push(String::from("    }"));
+                bw.push(String::from("}\n"));
+                bd.append(&mut bw);
+
+                wr.push(String::from("        if self.body.is_empty() {"));
+                wr.push(String::from("            out.push_str(\"/>\\n\");"));
+                wr.push(String::from("        } else {"));
+                wr.push(String::from("            out.push_str(\">\\n\");"));
+                wr.push(String::from("            for item in &self.body {"));
+                wr.push(String::from("                item.to_xml(depth + 1, out);"));
+                wr.push(String::from("            }"));
+                wr.push(String::from("            close_tag(out, depth, tag);"));
+                wr.push(String::from("        }"));
 
                 ty.push(format!("    pub body: Vec<{}Body>", t.name));
             }
-            Body::Any | Body::Empty => {}
+            Body::Any => {
+                // Arbitrary content, eg annotations, is kept verbatim
+                nd.push(String::from("        let body = inner_xml(node);"));
+                attrs.push(String::from("body"));
+                wr.push(String::from("        write_inner(out, tag, &self.body);"));
+                ty.push(String::from("    pub body: String,"));
+            }
+            Body::Empty => wr.push(String::from("        out.push_str(\"/>\\n\");")),
         }
+        wr.push(String::from("    }"));
 
-        nd.push(format!("        {} {{", t.name));
+        nd.push(format!("        Ok({} {{", t.name));
         for attr in &attrs {
             nd.push(format!("            {},", attr));
         }
-        nd.push(String::from("        }"));
+        nd.push(String::from("        })"));
         nd.push(String::from("    }"));
+        nd.push(String::from(""));
+        nd.append(&mut wr);
         nd.push(String::from("}"));
         nd.push(String::from(""));
 
@@ -0,0 +1,213 @@
+use roxmltree::Node;
+use tracing::{trace, warn};
+
+use crate::{
+    error::{Error, Result},
+    expr::Quantity,
+    neuroml::process_files,
+    xml::{opt_attr, req_attr},
+};
+
+/// Simulation setup as given by a LEMS run file (`LEMS_*.xml`), ie the
+/// `<Simulation>` selected by `<Target>`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Simulation {
+    pub id: String,
+    /// Id of the simulated network or cell
+    pub target: String,
+    pub length: Quantity,
+    pub step: Quantity,
+    /// `<OutputFile>`s
+    pub writers: Vec<DataWriter>,
+    /// `<Display>`s; recorded as well, but not written to disk by jnml
+    pub displays: Vec<DataWriter>,
+}
+
+/// Group of recordings sharing an output, either a file or a plot
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataWriter {
+    pub id: String,
+    /// File name for `<OutputFile>`, title for `<Display>`
+    pub name: String,
+    pub records: Vec<Record>,
+}
+
+/// A single recorded quantity, eg `hhpop[0]/v`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub id: String,
+    pub quantity: String,
+    pub scale: Option<Quantity>,
+}
+
+impl Simulation {
+    /// Scan the given files and their includes, found via `paths`, for a
+    /// LEMS `<Target>` and return the simulation it names, if any.
+    pub fn from_files(paths: &[String], nml: &[String]) -> Result<Option<Self>> {
+        let mut target = None;
+        let mut sims = Vec::new();
+        process_files(paths, nml, |_, node| {
+            // Only top-level elements of a LEMS file, `<Simulation>` also
+            // appears in `<ComponentType>` definitions.
+            if !node
+                .parent_element()
+                .map(|p| p.tag_name().name() == "Lems")
+                .unwrap_or(false)
+            {
+                return Ok(());
+            }
+            match node.tag_name().name() {
+                "Target" => {
+                    let id: String = req_attr(node, "component")?;
+                    if let Some(old) = target.replace(id) {
+                        warn!("Multiple <Target>s found, ignoring {}", old);
+                    }
+                }
+                "Simulation" => sims.push(Simulation::from_node(node)?),
+                _ => {}
+            }
+            Ok(())
+        })?;
+        if let Some(target) = target {
+            let sim = sims
+                .into_iter()
+                .find(|s| s.id == target)
+                .ok_or_else(|| Error::Lems {
+                    what: format!("Target {} is not a known Simulation", target),
+                })?;
+            trace!("Using simulation {:?}", sim);
+            Ok(Some(sim))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn from_node(node: &Node) -> Result<Self> {
+        let mut writers = Vec::new();
+        let mut displays = Vec::new();
+        for child in node.children().filter(|n| n.is_element()) {
+            match child.tag_name().name() {
+                "OutputFile" => writers.push(DataWriter::from_node(
+                    &child,
+                    req_attr(&child, "fileName")?,
+                    "OutputColumn",
+                )?),
+                "Display" => displays.push(DataWriter::from_node(
+                    &child,
+                    req_attr(&child, "title")?,
+                    "Line",
+                )?),
+                t => trace!("Ignoring <{}> in Simulation", t),
+            }
+        }
+        Ok(Simulation {
+            id: req_attr(node, "id")?,
+            target: req_attr(node, "target")?,
+            length: req_attr(node, "length")?,
+            step: req_attr(node, "step")?,
+            writers,
+            displays,
+        })
+    }
+}
+
+impl DataWriter {
+    fn from_node(node: &Node, name: String, tag: &str) -> Result<Self> {
+        let mut records = Vec::new();
+        for child in node.children().filter(|n| n.tag_name().name() == tag) {
+            records.push(Record {
+                id: req_attr(&child, "id")?,
+                quantity: req_attr(&child, "quantity")?,
+                scale: opt_attr(&child, "scale")?,
+            });
+        }
+        Ok(DataWriter {
+            id: req_attr(node, "id")?,
+            name,
+            records,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixture::Scratch;
+
+    const RUN: &str = r##"<Lems>
+  <Target component="sim1"/>
+  <Include file="sims.xml"/>
+  <ComponentType name="recorder">
+    <Simulation>
+      <Record quantity="v" scale="1" color="#000000"/>
+    </Simulation>
+  </ComponentType>
+</Lems>"##;
+
+    const SIMS: &str = r##"<Lems>
+  <Simulation id="sim0" length="10ms" step="0.1ms" target="other"/>
+  <Simulation id="sim1" length="300ms" step="0.025ms" target="net">
+    <Display id="d0" title="Voltages" timeScale="1ms" xmin="0" xmax="300" ymin="-90" ymax="50">
+      <Line id="v" quantity="pop[0]/v" scale="1mV" color="#000000" timeScale="1ms"/>
+    </Display>
+    <OutputFile id="of0" fileName="results/v.dat">
+      <OutputColumn id="v" quantity="pop[0]/v"/>
+      <OutputColumn id="m" quantity="pop/0/cell/biophys/membraneProperties/na/na/m/q"/>
+    </OutputFile>
+  </Simulation>
+</Lems>"##;
+
+    fn record(id: &str, quantity: &str, scale: Option<&str>) -> Record {
+        Record {
+            id: id.to_string(),
+            quantity: quantity.to_string(),
+            scale: scale.map(|s| Quantity::parse(s).unwrap()),
+        }
+    }
+
+    #[test]
+    fn test_from_files() {
+        let dir = Scratch::new("simulation");
+        dir.write("sims.xml", SIMS);
+        let run = dir.write("LEMS_run.xml", RUN);
+        let sim = Simulation::from_files(&[], &[run]).unwrap().unwrap();
+        assert_eq!(
+            sim,
+            Simulation {
+                id: String::from("sim1"),
+                target: String::from("net"),
+                length: Quantity::parse("300ms").unwrap(),
+                step: Quantity::parse("0.025ms").unwrap(),
+                writers: vec![DataWriter {
+                    id: String::from("of0"),
+                    name: String::from("results/v.dat"),
+                    records: vec![
+                        record("v", "pop[0]/v", None),
+                        record("m", "pop/0/cell/biophys/membraneProperties/na/na/m/q", None),
+                    ],
+                }],
+                displays: vec![DataWriter {
+                    id: String::from("d0"),
+                    name: String::from("Voltages"),
+                    records: vec![record("v", "pop[0]/v", Some("1mV"))],
+                }],
+            }
+        );
+    }
+
+    #[test]
+    fn test_from_files_errors() {
+        let dir = Scratch::new("simulation-errors");
+        dir.write("sims.xml", SIMS);
+        // No target, no simulation
+        let run = dir.write("none.xml", r#"<Lems><Include file="sims.xml"/></Lems>"#);
+        assert_eq!(Simulation::from_files(&[], &[run]).unwrap(), None);
+        // Target is not a simulation
+        let run = dir.write("net.xml", &RUN.replace("sim1", "net"));
+        assert!(Simulation::from_files(&[], &[run]).is_err());
+        // Simulation lacks required attributes
+        dir.write("sims.xml", &SIMS.replace(r#"length="300ms" "#, ""));
+        let run = dir.write("LEMS_run.xml", RUN);
+        assert!(Simulation::from_files(&[], &[run]).is_err());
+    }
+}
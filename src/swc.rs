@@ -0,0 +1,459 @@
+use std::collections::HashMap as Map;
+use std::fs::{create_dir_all, read_to_string, write};
+use std::path::{Path, PathBuf};
+
+use tracing::{info, warn};
+
+use crate::{
+    cell,
+    error::{Error, Result},
+    morphology::{Morphology, Point},
+    neuroml::raw::{
+        Member, Morphology as RawMorphology, MorphologyBody, NeuroMLDocument, NeuroMLDocumentBody,
+        Point3DWithDiam, Segment, SegmentBody, SegmentGroup, SegmentGroupBody, SegmentParent,
+    },
+    xml::to_nml2_string,
+};
+
+fn swc_error<T: Into<String>>(what: T) -> Error {
+    Error::Parse { what: what.into() }
+}
+
+/// SWC sample type to NML2 segment group, following the standard groups.
+fn group_of(ty: i64) -> String {
+    match ty {
+        1 => String::from("soma_group"),
+        2 => String::from("axon_group"),
+        3 | 4 => String::from("dendrite_group"),
+        t => format!("swc_type_{}", t),
+    }
+}
+
+/// One line of a SWC file
+#[derive(Debug, Clone, PartialEq)]
+struct Sample {
+    id: i64,
+    ty: i64,
+    point: Point,
+    parent: i64,
+}
+
+fn parse_sample(line: &str) -> std::result::Result<Sample, String> {
+    let cols = line.split_whitespace().collect::<Vec<_>>();
+    if cols.len() != 7 {
+        return Err(format!("Expected 7 columns, got {}", cols.len()));
+    }
+    let int = |s: &str| {
+        s.parse::<i64>()
+            .map_err(|_| format!("Expected an integer, got {}", s))
+    };
+    let float = |s: &str| {
+        s.parse::<f64>()
+            .map_err(|_| format!("Expected a number, got {}", s))
+    };
+    Ok(Sample {
+        id: int(cols[0])?,
+        ty: int(cols[1])?,
+        point: Point {
+            x: float(cols[2])?,
+            y: float(cols[3])?,
+            z: float(cols[4])?,
+            r: float(cols[5])?,
+        },
+        parent: int(cols[6])?,
+    })
+}
+
+fn point(p: &Point) -> Point3DWithDiam {
+    Point3DWithDiam {
+        x: p.x,
+        y: p.y,
+        z: p.z,
+        diameter: 2.0 * p.r,
+    }
+}
+
+/// Is `root` the centre of a three point soma as used by NeuroMorpho, ie with
+/// two further soma samples one radius away on opposite sides? If so, returns
+/// their indices.
+fn three_point_soma(samples: &[Sample], root: &Sample) -> Option<(usize, usize)> {
+    if root.parent != -1 || root.ty != 1 || samples.iter().filter(|s| s.ty == 1).count() != 3 {
+        return None;
+    }
+    let sides = samples
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| s.ty == 1 && s.parent == root.id)
+        .map(|(ix, _)| ix)
+        .collect::<Vec<_>>();
+    if let [a, b] = sides[..] {
+        let (pa, pb, pr) = (&samples[a].point, &samples[b].point, &root.point);
+        let eps = 1e-3 * (1.0 + pr.r);
+        let mid = pa.lerp(pb, 0.5);
+        if (pa.distance(pr) - pr.r).abs() < eps
+            && (pb.distance(pr) - pr.r).abs() < eps
+            && mid.distance(pr) < eps
+        {
+            return Some((a, b));
+        }
+    }
+    None
+}
+
+/// Read SWC into a NML2 morphology called `id`. Each sample with a parent
+/// becomes a segment from its parent to itself and is grouped by its type.
+/// Somata are treated specially
+/// - a root sample of type soma without further soma samples is a spherical
+///   soma and turned into a segment of zero length,
+/// - the three point soma of NeuroMorpho becomes a cylinder of the same
+///   radius and surface, split into two segments at the centre.
+///
+/// In both cases segments attached to the soma start with their own radius.
+/// Any other root sample is just the proximal point of its children; the
+/// first becomes the root segment, further ones attach to its proximal end.
+pub fn from_swc(id: &str, swc: &str) -> Result<RawMorphology> {
+    let mut samples = Vec::new();
+    for (ln, line) in swc.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let sample =
+            parse_sample(line).map_err(|e| swc_error(format!("Line {}: {}", ln + 1, e)))?;
+        samples.push(sample);
+    }
+    let mut index = Map::new();
+    for (ix, s) in samples.iter().enumerate() {
+        if index.insert(s.id, ix).is_some() {
+            return Err(swc_error(format!("Duplicate sample id {}", s.id)));
+        }
+    }
+
+    let roots = samples.iter().filter(|s| s.parent == -1).count();
+    if roots == 0 {
+        return Err(swc_error("No root sample"));
+    }
+    if roots > 1 {
+        warn!(
+            "SWC for {} has {} roots, the result is disconnected",
+            id, roots
+        );
+    }
+    let somas = samples.iter().filter(|s| s.ty == 1).count();
+    let sphere = |s: &Sample| s.parent == -1 && s.ty == 1 && somas == 1;
+
+    // Segment and fraction along it children of a sample attach to
+    let mut segment_of: Map<i64, (i64, f64)> = Map::new();
+    // Samples on the soma surface, whose children start with their own radius
+    let mut surface = Vec::new();
+    let mut segments = Vec::new();
+    let mut groups: Vec<(String, Vec<i64>)> = Vec::new();
+    let mut add = |sample: &Sample, parent: Option<(i64, f64)>, prox: &Point| {
+        let sid = segments.len() as i64;
+        let mut body = Vec::new();
+        if let Some((segment, fraction)) = parent {
+            body.push(SegmentBody::parent(SegmentParent {
+                segment,
+                fractionAlong: fraction,
+            }));
+        }
+        body.push(SegmentBody::proximal(point(prox)));
+        body.push(SegmentBody::distal(point(&sample.point)));
+        segments.push(Segment {
+            name: None,
+            id: sid,
+            neuroLexId: None,
+            body,
+        });
+        let group = group_of(sample.ty);
+        if let Some((_, ms)) = groups.iter_mut().find(|(g, _)| g == &group) {
+            ms.push(sid);
+        } else {
+            groups.push((group, vec![sid]));
+        }
+        sid
+    };
+
+    // Visit samples parents first; SWC files should be ordered that way, but
+    // we do not rely on it.
+    let mut children: Map<i64, Vec<usize>> = Map::new();
+    for (ix, s) in samples.iter().enumerate() {
+        if s.parent != -1 {
+            if !index.contains_key(&s.parent) {
+                return Err(swc_error(format!(
+                    "Sample {} has unknown parent {}",
+                    s.id, s.parent
+                )));
+            }
+            children.entry(s.parent).or_default().push(ix);
+        }
+    }
+    let mut todo = samples
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| s.parent == -1)
+        .map(|(ix, _)| ix)
+        .rev()
+        .collect::<Vec<_>>();
+    let mut seen = 0;
+    while let Some(ix) = todo.pop() {
+        seen += 1;
+        let sample = &samples[ix];
+        if sphere(sample) {
+            let sid = add(sample, None, &sample.point);
+            segment_of.insert(sample.id, (sid, 1.0));
+            surface.push(sample.id);
+        } else if let Some((a, b)) = three_point_soma(&samples, sample) {
+            let (a, b) = (&samples[a], &samples[b]);
+            let mut prox = a.point;
+            prox.r = sample.point.r;
+            let lo = add(sample, None, &prox);
+            let mut dist = b.clone();
+            dist.point.r = sample.point.r;
+            let hi = add(&dist, Some((lo, 1.0)), &sample.point);
+            segment_of.insert(a.id, (lo, 0.0));
+            segment_of.insert(sample.id, (lo, 1.0));
+            segment_of.insert(b.id, (hi, 1.0));
+            surface.extend([a.id, sample.id, b.id]);
+        } else if segment_of.contains_key(&sample.id) {
+            // Side of a three point soma, already done
+        } else if sample.parent != -1 {
+            let parent = &samples[index[&sample.parent]];
+            let mut prox = parent.point;
+            if surface.contains(&parent.id) {
+                prox.r = sample.point.r;
+            }
+            let attach = segment_of.get(&parent.id).copied();
+            let sid = add(sample, attach, &prox);
+            segment_of.insert(sample.id, (sid, 1.0));
+            if attach.is_none() {
+                // Root sample without a segment of its own
+                segment_of.insert(parent.id, (sid, 0.0));
+            }
+        }
+        if let Some(cs) = children.get(&sample.id) {
+            todo.extend(cs.iter().rev());
+        }
+    }
+    if seen != samples.len() {
+        return Err(swc_error("Samples contain a cycle"));
+    }
+
+    let mut body = segments
+        .into_iter()
+        .map(MorphologyBody::segment)
+        .collect::<Vec<_>>();
+    body.extend(groups.into_iter().map(|(g, ms)| {
+        MorphologyBody::segmentGroup(SegmentGroup {
+            id: g,
+            neuroLexId: None,
+            body: ms
+                .into_iter()
+                .map(|segment| SegmentGroupBody::member(Member { segment }))
+                .collect(),
+        })
+    }));
+    Ok(RawMorphology {
+        metaid: None,
+        id: id.to_string(),
+        neuroLexId: None,
+        body,
+    })
+}
+
+/// Write `mrf` as SWC. Each segment contributes its distal point; root
+/// segments and segments not starting at their parent's distal point add
+/// their proximal point, too. Sample types are taken from the segment tags,
+/// ie the standard groups.
+pub fn to_swc(mrf: &Morphology) -> String {
+    let mut result = format!(
+        "# Generated by nmlcc from morphology {}\n# id type x y z radius parent\n",
+        mrf.id
+    );
+    let mut n = 0;
+    let mut sample = |ty: i64, p: &Point, parent: i64| {
+        n += 1;
+        result += &format!("{} {} {} {} {} {} {}\n", n, ty, p.x, p.y, p.z, p.r, parent);
+        n
+    };
+    let mut distal = Vec::new();
+    for seg in &mrf.segments {
+        let parent = match seg.parent {
+            Some(p) if mrf.segments[p].distal == seg.proximal => distal[p],
+            Some(p) => sample(seg.tag, &seg.proximal, distal[p]),
+            None => sample(seg.tag, &seg.proximal, -1),
+        };
+        distal.push(sample(seg.tag, &seg.distal, parent));
+    }
+    result
+}
+
+/// NML2 document holding a top-level morphology, such that cells can refer to
+/// it by id.
+fn mk_nml(mrf: &RawMorphology) -> String {
+    to_nml2_string(&NeuroMLDocument {
+        metaid: None,
+        id: mrf.id.clone(),
+        neuroLexId: None,
+        body: vec![NeuroMLDocumentBody::morphology(mrf.clone())],
+    })
+}
+
+/// Write morphologies of all cells in `nml`, or just `cell`, as `<id>.swc`
+/// under `dir`.
+pub fn export(paths: &[String], nml: &[String], cell: &Option<&str>, dir: &str) -> Result<()> {
+    create_dir_all(dir)?;
+    for (id, raw) in cell::morphologies(paths, nml)? {
+        if let Some(cell) = cell {
+            if id != *cell {
+                continue;
+            }
+        }
+        if let Some(raw) = raw {
+            let mrf = Morphology::new(&raw)?;
+            let path = format!("{}/{}.swc", dir, id);
+            info!("Writing SWC to {:?}", &path);
+            write(&path, to_swc(&mrf))?;
+        } else {
+            warn!("Cell {} has no morphology, skipping", id);
+        }
+    }
+    Ok(())
+}
+
+/// Convert SWC files to NML2 morphologies; the file stem becomes the id.
+pub fn import(swcs: &[String], dir: &str) -> Result<()> {
+    create_dir_all(dir)?;
+    for swc in swcs {
+        let id = Path::new(swc)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .ok_or_else(|| swc_error(format!("Cannot derive an id from {}", swc)))?;
+        let text = read_to_string(swc)?;
+        let mrf = from_swc(id, &text).map_err(|e| e.in_file(Path::new(swc), &text))?;
+        let mut path = PathBuf::from(dir);
+        path.push(id);
+        path.set_extension("nml");
+        info!("Writing NML2 morphology to {:?}", &path);
+        write(&path, mk_nml(&mrf))?;
+    }
+    Ok(())
+}
+
+/// Is `file` a SWC file?
+pub fn is_swc(file: &str) -> bool {
+    Path::new(file)
+        .extension()
+        .map(|e| e.eq_ignore_ascii_case("swc"))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::xml::XML;
+
+    /// Convert `swc` to NML2 and read it back, returning (id, parent,
+    /// fraction) per segment and the members of the soma group
+    #[allow(clippy::type_complexity)]
+    fn round_trip(swc: &str) -> (Vec<(i64, Option<(i64, f64)>)>, Vec<i64>) {
+        let nml = mk_nml(&from_swc("test", swc).unwrap());
+        let doc = roxmltree::Document::parse(&nml).unwrap();
+        let node = doc
+            .descendants()
+            .find(|n| n.has_tag_name("morphology"))
+            .unwrap();
+        let raw: RawMorphology = XML::from_node(&node).unwrap();
+        assert!(Morphology::new(&raw).is_ok());
+        let mut segments = Vec::new();
+        let mut soma = Vec::new();
+        for item in &raw.body {
+            match item {
+                MorphologyBody::segment(s) => {
+                    let parent = s.body.iter().find_map(|b| match b {
+                        SegmentBody::parent(p) => Some((p.segment, p.fractionAlong)),
+                        _ => None,
+                    });
+                    segments.push((s.id, parent));
+                }
+                MorphologyBody::segmentGroup(g) if g.id == "soma_group" => {
+                    soma.extend(g.body.iter().filter_map(|b| match b {
+                        SegmentGroupBody::member(m) => Some(m.segment),
+                        _ => None,
+                    }))
+                }
+                _ => {}
+            }
+        }
+        (segments, soma)
+    }
+
+    #[test]
+    fn test_single_point_soma() {
+        let (segments, soma) = round_trip(
+            "# sphere
+1 1 0 0 0 5 -1
+2 3 0 5 0 1 1
+3 3 0 10 0 1 2
+4 3 0 -5 0 1 1",
+        );
+        assert_eq!(
+            segments,
+            vec![
+                (0, None),
+                (1, Some((0, 1.0))),
+                (2, Some((1, 1.0))),
+                (3, Some((0, 1.0))),
+            ]
+        );
+        assert_eq!(soma, vec![0]);
+    }
+
+    #[test]
+    fn test_three_point_soma() {
+        let (segments, soma) = round_trip(
+            "1 1 0 0 0 5 -1
+2 1 0 -5 0 5 1
+3 1 0 5 0 5 1
+4 3 -5 0 0 1 1
+5 3 -10 0 0 1 4",
+        );
+        assert_eq!(
+            segments,
+            vec![
+                (0, None),
+                (1, Some((0, 1.0))),
+                (2, Some((0, 1.0))),
+                (3, Some((2, 1.0))),
+            ]
+        );
+        assert_eq!(soma, vec![0, 1]);
+        let mrf = Morphology::new(
+            &from_swc("test", "1 1 0 0 0 5 -1\n2 1 0 -5 0 5 1\n3 1 0 5 0 5 1").unwrap(),
+        )
+        .unwrap();
+        let area = (0..2).map(|ix| mrf.measure(ix).1).sum::<f64>();
+        assert!((area - 100.0 * std::f64::consts::PI).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_multi_sample_soma() {
+        let (segments, soma) = round_trip(
+            "1 1 0 0 0 3 -1
+2 1 0 4 0 5 1
+3 1 0 8 0 3 2
+4 3 0 -5 0 1 1
+5 3 0 15 0 1 3",
+        );
+        assert_eq!(
+            segments,
+            vec![
+                (0, None),
+                (1, Some((0, 1.0))),
+                (2, Some((1, 1.0))),
+                (3, Some((0, 0.0))),
+            ]
+        );
+        assert_eq!(soma, vec![0, 1]);
+    }
+}
@@ -0,0 +1,643 @@
+use std::fs::{create_dir_all, write};
+
+use crate::{
+    acc::{Decor, Paintable},
+    cell,
+    error::{Error, Result},
+    expr::Quantity,
+    instance::{Collapsed, Instance},
+    lems::file::LemsFile,
+    neuroml::process_files,
+    neuroml::raw::{
+        BiophysicalProperties, BiophysicalPropertiesBody, ChannelDensity, ChannelDensityNernst,
+        MembranePropertiesBody,
+    },
+};
+
+fn parse_error<T: Into<String>>(what: T) -> Error {
+    Error::Parse { what: what.into() }
+}
+
+fn nml2_error<T: Into<String>>(what: T) -> Error {
+    Error::Nml { what: what.into() }
+}
+
+/// Split `path=value` and check that path names at least `<id>/<parameter>`
+fn split_assignment(input: &str) -> Result<(String, &str)> {
+    if let Some((path, value)) = input.split_once('=') {
+        let path = path.trim();
+        if path.split('/').count() > 1 && path.split('/').all(|s| !s.is_empty()) {
+            return Ok((path.to_string(), value));
+        }
+    }
+    Err(parse_error(format!(
+        "Expected <id>/<parameter>=<value>, got {:?}",
+        input
+    )))
+}
+
+/// Assignment `path=value`, eg `naChans/condDensity=100mS_per_cm2`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Assignment {
+    pub path: String,
+    pub value: Quantity,
+}
+
+impl std::str::FromStr for Assignment {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self> {
+        let (path, value) = split_assignment(input)?;
+        Ok(Assignment {
+            path,
+            value: value.parse()?,
+        })
+    }
+}
+
+/// Axis of a sweep `path=v0,v1,...`, eg `naChan/conductance=5pS,10pS`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Axis {
+    pub path: String,
+    pub values: Vec<Quantity>,
+}
+
+impl std::str::FromStr for Axis {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self> {
+        let (path, values) = split_assignment(input)?;
+        let values = values
+            .split(',')
+            .map(|v| v.parse())
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Axis { path, values })
+    }
+}
+
+/// What a path refers to
+#[derive(Debug, Clone, PartialEq)]
+enum Target {
+    /// Parameter of the component `id`, found by walking `segments` down the
+    /// instance tree; `name` is the flattened name in NMODL.
+    Parameter {
+        path: String,
+        id: String,
+        segments: Vec<String>,
+        name: String,
+    },
+    /// Attribute `attr` of the channel density `id` on `cell`
+    Density {
+        cell: String,
+        id: String,
+        attr: String,
+    },
+}
+
+/// Child of `inst` at `segments`, where collection members are given as
+/// `coll[id]` like in NML2 paths.
+fn descend<'a>(inst: &'a mut Instance, segments: &[String]) -> Option<&'a mut Instance> {
+    let mut inst = inst;
+    for seg in segments {
+        inst = if let Some((coll, id)) = seg.strip_suffix(']').and_then(|s| s.split_once('[')) {
+            inst.children
+                .get_mut(coll)?
+                .iter_mut()
+                .find(|c| c.id.as_deref() == Some(id))?
+        } else {
+            inst.child.get_mut(seg)?
+        };
+    }
+    Some(inst)
+}
+
+fn parameter(inst: &Instance, path: &str) -> Result<Target> {
+    let unknown = || nml2_error(format!("No parameter at {}", path));
+    let segments = path
+        .split('/')
+        .skip(1)
+        .map(String::from)
+        .collect::<Vec<_>>();
+    let (param, children) = segments.split_last().ok_or_else(unknown)?;
+    let mut inst = inst.clone();
+    let child = descend(&mut inst, children).ok_or_else(unknown)?;
+    if !child.component_type.parameters.contains(param) {
+        return Err(unknown());
+    }
+    let name = Collapsed::from_instance(&inst)?
+        .names
+        .iter()
+        .find(|(_, p)| p.as_str() == path)
+        .map(|(n, _)| n.clone())
+        .ok_or_else(unknown)?;
+    Ok(Target::Parameter {
+        path: path.to_string(),
+        id: inst.id.clone().unwrap_or_default(),
+        segments,
+        name,
+    })
+}
+
+/// Find what `paths` refer to: parameters of toplevel components take
+/// precedence over channel densities on cells. Density ids are only unique per
+/// cell, so `<density>/<attr>` must name a single one, else the cell must be
+/// given as `<cell>/<density>/<attr>`.
+fn resolve(lems: &LemsFile, nml: &[String], paths: &[&str]) -> Result<Vec<Target>> {
+    let root = |p: &str| p.split('/').next().unwrap_or_default().to_string();
+    let mut result = vec![None; paths.len()];
+    if paths.is_empty() {
+        return Ok(Vec::new());
+    }
+    process_files(&lems.include_dir, nml, |_, node| {
+        let top = node
+            .parent_element()
+            .map(|p| p.tag_name().name() == "neuroml")
+            .unwrap_or(false);
+        let id = match node.attribute("id") {
+            Some(id) if top && !cell::is_cell(node.tag_name().name()) => id,
+            _ => return Ok(()),
+        };
+        for (ix, path) in paths.iter().enumerate() {
+            if result[ix].is_none() && root(path) == id {
+                result[ix] = Some(parameter(&Instance::new(lems, node)?, path)?);
+            }
+        }
+        Ok(())
+    })?;
+    for cell in cell::cells(&lems.include_dir, nml)? {
+        let prop = if let Some(prop) = &cell.properties {
+            prop
+        } else {
+            continue;
+        };
+        for_densities(prop, |id, nernst| {
+            for (ix, path) in paths.iter().enumerate() {
+                let (attr, qualified) = match path.split('/').collect::<Vec<_>>()[..] {
+                    [d, a] if d == id => (a, false),
+                    [c, d, a] if c == cell.id && d == id => (a, true),
+                    _ => continue,
+                };
+                if attr != "condDensity" && (nernst || attr != "erev") {
+                    return Err(nml2_error(format!(
+                        "Cannot set {}, channel densities allow condDensity{}",
+                        path,
+                        if nernst { "" } else { " and erev" }
+                    )));
+                }
+                match &result[ix] {
+                    None => {
+                        result[ix] = Some(Target::Density {
+                            cell: cell.id.clone(),
+                            id: id.to_string(),
+                            attr: attr.to_string(),
+                        })
+                    }
+                    Some(Target::Density { cell: other, .. }) if !qualified && other != &cell.id => {
+                        return Err(nml2_error(format!(
+                            "Cannot set {}: channel density {} is on cells {} and {}, use <cell>/{}/{}",
+                            path, id, other, cell.id, id, attr
+                        )))
+                    }
+                    _ => {}
+                }
+            }
+            Ok(())
+        })?;
+    }
+    paths
+        .iter()
+        .zip(result)
+        .map(|(p, t)| {
+            t.ok_or_else(|| {
+                nml2_error(format!(
+                    "Cannot set {}: no such component parameter or channel density",
+                    p
+                ))
+            })
+        })
+        .collect()
+}
+
+/// Visit ids of channel densities in `prop`, flagging Nernst ones
+fn for_densities<F>(prop: &BiophysicalProperties, mut f: F) -> Result<()>
+where
+    F: FnMut(&str, bool) -> Result<()>,
+{
+    for item in &prop.body {
+        if let BiophysicalPropertiesBody::membraneProperties(m) = item {
+            for item in &m.body {
+                match item {
+                    MembranePropertiesBody::channelDensity(ChannelDensity { id, .. }) => {
+                        f(id, false)?
+                    }
+                    MembranePropertiesBody::channelDensityNernst(ChannelDensityNernst {
+                        id,
+                        ..
+                    }) => f(id, true)?,
+                    _ => {}
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Resolved assignments, applied in order. Entries without a value only ask
+/// for the parameter to be kept tweakable in NMODL, see `Sweep::retained`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Overrides(Vec<(Target, Option<Quantity>)>);
+
+impl Overrides {
+    pub fn new(lems: &LemsFile, nml: &[String], set: &[Assignment]) -> Result<Self> {
+        let paths = set.iter().map(|a| a.path.as_str()).collect::<Vec<_>>();
+        Ok(Overrides(
+            resolve(lems, nml, &paths)?
+                .into_iter()
+                .zip(set.iter().map(|a| Some(a.value.clone())))
+                .collect(),
+        ))
+    }
+
+    /// These followed by `other`, so `other` takes precedence
+    pub fn with(&self, other: &Overrides) -> Self {
+        let mut result = self.clone();
+        result.0.extend(other.0.iter().cloned());
+        result
+    }
+
+    /// Only the assignments to channel densities
+    pub fn densities(&self) -> Self {
+        Overrides(
+            self.0
+                .iter()
+                .filter(|(t, _)| matches!(t, Target::Density { .. }))
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// Set parameters of the component `inst`
+    pub fn apply_instance(&self, lems: &LemsFile, inst: &mut Instance) -> Result<()> {
+        for (target, value) in &self.0 {
+            if let (
+                Target::Parameter {
+                    path, id, segments, ..
+                },
+                Some(value),
+            ) = (target, value)
+            {
+                if inst.id.as_deref() != Some(id) {
+                    continue;
+                }
+                let unknown = || nml2_error(format!("No parameter at {}", path));
+                let (param, children) = segments.split_last().ok_or_else(unknown)?;
+                let child = descend(inst, children).ok_or_else(unknown)?;
+                child
+                    .parameters
+                    .insert(param.to_string(), lems.normalise_quantity(value)?);
+            }
+        }
+        Ok(())
+    }
+
+    /// Selectors keeping parameters of the mechanism `id` tweakable, to be
+    /// appended to a parameter filter.
+    pub fn selectors(&self, id: &str) -> String {
+        self.0
+            .iter()
+            .filter_map(|(t, v)| match t {
+                Target::Parameter { path, id: i, .. } if i == id && v.is_none() => {
+                    Some(format!(",+/{}", path))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Set attributes of the channel densities in `prop` of `cell`
+    pub fn apply_properties(&self, cell: &str, prop: &mut BiophysicalProperties) {
+        for (target, value) in &self.0 {
+            let (id, attr, value) = match (target, value) {
+                (Target::Density { cell: c, id, attr }, Some(v)) if c == cell => (id, attr, v),
+                _ => continue,
+            };
+            for item in prop.body.iter_mut() {
+                if let BiophysicalPropertiesBody::membraneProperties(m) = item {
+                    for item in m.body.iter_mut() {
+                        match item {
+                            MembranePropertiesBody::channelDensity(cd) if &cd.id == id => {
+                                if attr == "erev" {
+                                    cd.erev = value.clone();
+                                } else {
+                                    cd.condDensity = Some(value.clone());
+                                }
+                            }
+                            MembranePropertiesBody::channelDensityNernst(cd) if &cd.id == id => {
+                                cd.condDensity = Some(value.clone());
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Pass parameters of channels as mechanism parameters where they are
+    /// painted; the mechanisms must retain these parameters.
+    pub fn apply_decor(&self, lems: &LemsFile, decor: &mut [Decor]) -> Result<()> {
+        for (target, value) in &self.0 {
+            if let (Target::Parameter { id, name, .. }, Some(value)) = (target, value) {
+                for d in decor.iter_mut() {
+                    if let Decor::Paint(_, Paintable::Mech(m, ps)) = d {
+                        if m == id {
+                            ps.insert(name.clone(), lems.normalise_quantity(value)?);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Grid of assignments, spanned by the axes given
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Sweep(Vec<(String, Target, Vec<Quantity>)>);
+
+impl Sweep {
+    pub fn new(lems: &LemsFile, nml: &[String], axes: &[Axis]) -> Result<Self> {
+        let paths = axes.iter().map(|a| a.path.as_str()).collect::<Vec<_>>();
+        Ok(Sweep(
+            resolve(lems, nml, &paths)?
+                .into_iter()
+                .zip(axes.iter())
+                .map(|(t, a)| (a.path.clone(), t, a.values.clone()))
+                .collect(),
+        ))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Parameters swept, to be kept tweakable in a shared catalogue
+    pub fn retained(&self) -> Overrides {
+        Overrides(
+            self.0
+                .iter()
+                .filter(|(_, t, _)| matches!(t, Target::Parameter { .. }))
+                .map(|(_, t, _)| (t.clone(), None))
+                .collect(),
+        )
+    }
+
+    /// All points of the grid, the last axis varying fastest
+    pub fn points(&self) -> Vec<Overrides> {
+        let mut result = vec![Overrides::default()];
+        for (_, target, values) in &self.0 {
+            result = result
+                .iter()
+                .flat_map(|pt| {
+                    values.iter().map(move |v| {
+                        let mut pt = pt.clone();
+                        pt.0.push((target.clone(), Some(v.clone())));
+                        pt
+                    })
+                })
+                .collect();
+        }
+        result
+    }
+
+    /// Call `f` for each point with `base` followed by its assignments and
+    /// the directory `<dir>/sweep/<n>`; `<dir>/sweep/index.csv` lists the
+    /// values per point.
+    pub fn visit<F>(&self, base: &Overrides, dir: &str, mut f: F) -> Result<()>
+    where
+        F: FnMut(&Overrides, &str) -> Result<()>,
+    {
+        if self.is_empty() {
+            return Ok(());
+        }
+        let points = self.points();
+        let width = (points.len() - 1).to_string().len();
+        let mut index = String::from("point");
+        for (path, _, _) in &self.0 {
+            index.push(',');
+            index.push_str(path);
+        }
+        index.push('\n');
+        create_dir_all(format!("{}/sweep", dir))?;
+        for (ix, point) in points.iter().enumerate() {
+            let name = format!("{:0width$}", ix, width = width);
+            index.push_str(&name);
+            for (_, value) in &point.0 {
+                index.push_str(&format!(",{}", value.as_ref().unwrap()));
+            }
+            index.push('\n');
+            let pfx = format!("{}/sweep/{}", dir, name);
+            create_dir_all(&pfx)?;
+            f(&base.with(point), &pfx)?;
+        }
+        write(format!("{}/sweep/index.csv", dir), index)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sweep() {
+        let a: Assignment = "naChans/condDensity=120 mS_per_cm2".parse().unwrap();
+        assert_eq!(a.path, "naChans/condDensity");
+        assert_eq!(a.value, Quantity::parse("120mS_per_cm2").unwrap());
+        assert!("naChans=1".parse::<Assignment>().is_err());
+        assert!("naChans//g=1".parse::<Assignment>().is_err());
+        assert!("naChans/g=1,2".parse::<Assignment>().is_err());
+
+        let x: Axis = "a/g=1,2".parse().unwrap();
+        assert_eq!(x.values.len(), 2);
+        let density = |id: &str| Target::Density {
+            cell: String::from("cell"),
+            id: id.to_string(),
+            attr: String::from("condDensity"),
+        };
+        let sweep = Sweep(vec![
+            (x.path, density("a"), x.values),
+            (
+                String::from("b/g"),
+                density("b"),
+                vec![Quantity::parse("3").unwrap(); 3],
+            ),
+        ]);
+        let points = sweep.points();
+        assert_eq!(points.len(), 6);
+        assert_eq!(points[1].0[0].1, Some(Quantity::parse("1").unwrap()));
+        assert_eq!(points[3].0[0].1, Some(Quantity::parse("2").unwrap()));
+        assert_eq!(points[3].0[1].0, density("b"));
+        assert!(sweep.retained().0.is_empty());
+    }
+
+    const MODEL: &str = r#"<neuroml id="model">
+  <ionChannelHH id="naChan" conductance="10pS" species="na">
+    <gateHHrates id="m" instances="3">
+      <forwardRate type="HHExpLinearRate" rate="1per_ms" midpoint="-40mV" scale="10mV"/>
+      <reverseRate type="HHExpRate" rate="4per_ms" midpoint="-65mV" scale="-18mV"/>
+    </gateHHrates>
+  </ionChannelHH>
+  <morphology id="m">
+    <segment id="0"><proximal x="0" y="0" z="0" diameter="10"/><distal x="10" y="0" z="0" diameter="10"/></segment>
+  </morphology>
+  <cell id="a" morphology="m">
+    <biophysicalProperties id="pa">
+      <membraneProperties>
+        <channelDensity id="naChans" ionChannel="naChan" condDensity="120mS_per_cm2" erev="50mV" ion="na"/>
+      </membraneProperties>
+    </biophysicalProperties>
+  </cell>
+  <cell id="b" morphology="m">
+    <biophysicalProperties id="pb">
+      <membraneProperties>
+        <channelDensity id="naChans" ionChannel="naChan" condDensity="60mS_per_cm2" erev="50mV" ion="na"/>
+        <channelDensityNernst id="caChans" ionChannel="naChan" condDensity="1mS_per_cm2" ion="ca"/>
+      </membraneProperties>
+    </biophysicalProperties>
+  </cell>
+</neuroml>"#;
+
+    #[test]
+    #[cfg_attr(
+        not(core_types),
+        ignore = "needs the NML2 core types, run bootstrap.sh"
+    )]
+    fn test_resolve() {
+        let lems = crate::fixture::core_types();
+        let dir = crate::fixture::Scratch::new("sweep-resolve");
+        let nml = [dir.write("model.nml", MODEL)];
+        let resolve = |path: &str| resolve(&lems, &nml, &[path]).map(|mut ts| ts.remove(0));
+        let density = |cell: &str, id: &str, attr: &str| Target::Density {
+            cell: cell.to_string(),
+            id: id.to_string(),
+            attr: attr.to_string(),
+        };
+        assert_eq!(
+            resolve("naChan/gates[m]/forwardRate/midpoint").unwrap(),
+            Target::Parameter {
+                path: String::from("naChan/gates[m]/forwardRate/midpoint"),
+                id: String::from("naChan"),
+                segments: ["gates[m]", "forwardRate", "midpoint"]
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect(),
+                name: String::from("gates_m_forwardRate_midpoint"),
+            }
+        );
+        // Density ids are per cell
+        assert_eq!(
+            resolve("a/naChans/erev").unwrap(),
+            density("a", "naChans", "erev")
+        );
+        assert_eq!(
+            resolve("caChans/condDensity").unwrap(),
+            density("b", "caChans", "condDensity")
+        );
+        assert!(resolve("naChans/condDensity").is_err());
+        assert!(resolve("c/naChans/condDensity").is_err());
+        assert!(resolve("caChans/erev").is_err());
+        assert!(resolve("naChan/gates[h]/forwardRate/midpoint").is_err());
+        assert!(resolve("naChan/gates[m]/forwardRate/vHalf").is_err());
+
+        // Stale targets are reported, not a panic
+        let mut inst = None;
+        process_files(&[], &nml, |_, node| {
+            if node.tag_name().name() == "ionChannelHH" {
+                inst = Some(Instance::new(&lems, node)?);
+            }
+            Ok(())
+        })
+        .unwrap();
+        let mut inst = inst.unwrap();
+        let stale = |segments: &[&str]| {
+            Overrides(vec![(
+                Target::Parameter {
+                    path: String::from("naChan"),
+                    id: String::from("naChan"),
+                    segments: segments.iter().map(|s| s.to_string()).collect(),
+                    name: String::new(),
+                },
+                Some(Quantity::parse("1").unwrap()),
+            )])
+        };
+        assert!(stale(&[]).apply_instance(&lems, &mut inst).is_err());
+        assert!(stale(&["gates[h]", "rate"])
+            .apply_instance(&lems, &mut inst)
+            .is_err());
+    }
+
+    #[test]
+    #[cfg_attr(
+        not(core_types),
+        ignore = "needs the NML2 core types, run bootstrap.sh"
+    )]
+    fn test_export_sweep() {
+        let lems = crate::fixture::core_types();
+        let dir = crate::fixture::Scratch::new("sweep-export");
+        let nml = [dir.write("model.nml", MODEL)];
+        let set = ["b/naChans/condDensity=30mS_per_cm2".parse().unwrap()];
+        let set = Overrides::new(&lems, &nml, &set).unwrap();
+        let axes = ["naChan/gates[m]/forwardRate/midpoint=-30mV,-20mV"
+            .parse()
+            .unwrap()];
+        let sweep = Sweep::new(&lems, &nml, &axes).unwrap();
+        let out = dir.path().join("bundle");
+        let out = out.to_str().unwrap();
+        crate::bundle::export(&lems, &nml, out, false, &None, &set, &sweep).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(format!("{}/sweep/index.csv", out)).unwrap(),
+            "point,naChan/gates[m]/forwardRate/midpoint\n0,-30mV\n1,-20mV\n"
+        );
+        // Parameters of the painted densities per cell and point
+        let painted = |pfx: &str, cell: &str| {
+            std::fs::read_to_string(format!("{}/acc/{}.acc", pfx, cell))
+                .unwrap()
+                .lines()
+                .filter(|l| l.contains("(mechanism \"naChan\""))
+                .map(|l| {
+                    let mut ps = l
+                        .split("(\"")
+                        .skip(1)
+                        .map(|p| {
+                            let (name, value) = p.split_once("\" ").unwrap();
+                            let value = value.split(')').next().unwrap();
+                            (name.to_string(), value.to_string())
+                        })
+                        .collect::<Vec<_>>();
+                    ps.sort();
+                    ps
+                })
+                .collect::<Vec<_>>()
+        };
+        let ps = |g: &str, midpoint: Option<&str>| {
+            let mut ps = vec![(String::from("conductance"), g.to_string())];
+            if let Some(m) = midpoint {
+                ps.push((String::from("gates_m_forwardRate_midpoint"), m.to_string()));
+            }
+            ps
+        };
+        // Only cell b's naChans is set; swept values only appear per point
+        assert_eq!(painted(out, "a"), vec![ps("0.12", None)]);
+        assert_eq!(painted(out, "b"), vec![ps("0.03", None), ps("0.001", None)]);
+        for (point, m) in [("0", "-30"), ("1", "-20")] {
+            let pfx = format!("{}/sweep/{}", out, point);
+            assert_eq!(painted(&pfx, "a"), vec![ps("0.12", Some(m))]);
+            assert_eq!(
+                painted(&pfx, "b"),
+                vec![ps("0.03", Some(m)), ps("0.001", Some(m))]
+            );
+        }
+    }
+}
@@ -0,0 +1,388 @@
+use std::cmp::Ordering;
+use std::collections::HashMap as Map;
+use std::fs::{create_dir_all, write};
+use std::path::PathBuf;
+use tracing::{info, warn};
+
+use crate::{
+    error::{Error, Result},
+    gates::Range,
+    instance::Instance,
+    lems::file::LemsFile,
+    neuroml::process_files,
+    reference::{clamp, mechanism, Mechanism, Method, Trace},
+};
+
+/// Voltage clamp protocol: after holding, step to each potential in `steps`,
+/// then to the `test` potential. Potentials in mV, times in ms.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Protocol {
+    pub holding: f64,
+    pub hold: f64,
+    pub steps: Range,
+    pub duration: f64,
+    pub test: f64,
+    pub test_duration: f64,
+    pub dt: f64,
+    pub method: Method,
+}
+
+/// Measurements for a single step potential
+#[derive(Debug, Clone, PartialEq)]
+struct Step {
+    v: f64,
+    /// Current of largest magnitude during the step
+    i_peak: f64,
+    /// Current at the end of the step
+    i_ss: f64,
+    /// Largest conductance during the step
+    g_peak: f64,
+    /// Current of largest magnitude during the test pulse
+    i_test: f64,
+    /// Largest conductance during the test pulse
+    g_test: f64,
+}
+
+/// Rows of `trace` with `from < t <= to`
+fn window(trace: &Trace, from: f64, to: f64) -> impl Iterator<Item = &Vec<f64>> {
+    let eps = 1e-9;
+    trace
+        .rows
+        .iter()
+        .filter(move |r| r[0] > from + eps && r[0] <= to + eps)
+}
+
+/// Peak current and conductance in window
+fn peak<'a>(rows: impl Iterator<Item = &'a Vec<f64>>) -> (f64, f64) {
+    rows.fold((0.0, 0.0), |(i, g), r| {
+        (if r[2].abs() > i.abs() { r[2] } else { i }, g.max(r[3]))
+    })
+}
+
+/// Fit the Boltzmann function `y = 1/(1 + exp((v_half - v)/k))` to the
+/// samples, returning `(v_half, k)`; `k` is negative for decreasing curves,
+/// eg inactivation. The initial guess comes from a linear fit of the logit,
+/// which is refined by damped Gauss-Newton iterations.
+pub fn fit_boltzmann(vs: &[f64], ys: &[f64]) -> Option<(f64, f64)> {
+    let pts = vs
+        .iter()
+        .zip(ys)
+        .filter(|(_, y)| **y > 0.01 && **y < 0.99)
+        .map(|(v, y)| (*v, (y / (1.0 - y)).ln()))
+        .collect::<Vec<_>>();
+    if pts.len() < 2 {
+        return None;
+    }
+    let n = pts.len() as f64;
+    let (sx, sy) = pts.iter().fold((0.0, 0.0), |(a, b), (x, y)| (a + x, b + y));
+    let (mx, my) = (sx / n, sy / n);
+    let sxx = pts.iter().map(|(x, _)| (x - mx).powi(2)).sum::<f64>();
+    let sxy = pts.iter().map(|(x, y)| (x - mx) * (y - my)).sum::<f64>();
+    if sxx == 0.0 || sxy == 0.0 {
+        return None;
+    }
+    // logit(y) = (v - v_half)/k
+    let slope = sxy / sxx;
+    let mut k = 1.0 / slope;
+    let mut vh = mx - my / slope;
+
+    let f = |vh: f64, k: f64, v: f64| 1.0 / (1.0 + ((vh - v) / k).exp());
+    let sse = |vh: f64, k: f64| {
+        vs.iter()
+            .zip(ys)
+            .map(|(v, y)| (y - f(vh, k, *v)).powi(2))
+            .sum::<f64>()
+    };
+    for _ in 0..100 {
+        let (mut a, mut b, mut c, mut r0, mut r1) = (0.0, 0.0, 0.0, 0.0, 0.0);
+        for (v, y) in vs.iter().zip(ys) {
+            let y0 = f(vh, k, *v);
+            // d/dv_half and d/dk of the model
+            let d = y0 * (1.0 - y0);
+            let jv = -d / k;
+            let jk = d * (vh - v) / (k * k);
+            let r = y - y0;
+            a += jv * jv;
+            b += jv * jk;
+            c += jk * jk;
+            r0 += jv * r;
+            r1 += jk * r;
+        }
+        let det = a * c - b * b;
+        if det.abs() < f64::EPSILON {
+            break;
+        }
+        let dv = (c * r0 - b * r1) / det;
+        let dk = (a * r1 - b * r0) / det;
+        let old = sse(vh, k);
+        // Halve the step until it improves, this also rejects NaNs
+        let mut h = 1.0;
+        while h > 1e-6 && sse(vh + h * dv, k + h * dk).partial_cmp(&old) != Some(Ordering::Less) {
+            h *= 0.5;
+        }
+        if h <= 1e-6 {
+            break;
+        }
+        vh += h * dv;
+        k += h * dk;
+        if (h * dv).abs() < 1e-9 && (h * dk).abs() < 1e-9 {
+            break;
+        }
+    }
+    if vh.is_finite() && k.is_finite() && k != 0.0 {
+        Some((vh, k))
+    } else {
+        None
+    }
+}
+
+fn run(
+    mech: &Mechanism,
+    env: &Map<String, f64>,
+    protocol: &Protocol,
+) -> Result<(Vec<Step>, Trace)> {
+    let Protocol {
+        holding,
+        hold,
+        duration,
+        test,
+        test_duration,
+        dt,
+        method,
+        ..
+    } = protocol.clone();
+    let mut steps = Vec::new();
+    let mut names = vec![String::from("t")];
+    let mut traces: Vec<Vec<f64>> = Vec::new();
+    for v in protocol.steps.values() {
+        let schedule = [(hold, holding), (duration, v), (test_duration, test)];
+        let trace = clamp(mech, env, &schedule, dt, method)?;
+        let (i_peak, g_peak) = peak(window(&trace, hold, hold + duration));
+        let i_ss = window(&trace, hold, hold + duration)
+            .last()
+            .map(|r| r[2])
+            .unwrap_or_default();
+        let end = hold + duration + test_duration;
+        let (i_test, g_test) = peak(window(&trace, hold + duration, end));
+        steps.push(Step {
+            v,
+            i_peak,
+            i_ss,
+            g_peak,
+            i_test,
+            g_test,
+        });
+        names.push(format!("i@{}", v));
+        if traces.is_empty() {
+            traces = trace.rows.iter().map(|r| vec![r[0]]).collect();
+        }
+        for (row, r) in traces.iter_mut().zip(&trace.rows) {
+            row.push(r[2]);
+        }
+    }
+    Ok((
+        steps,
+        Trace {
+            names,
+            rows: traces,
+        },
+    ))
+}
+
+/// Write I-V curves, Boltzmann fits of activation and inactivation, and
+/// current traces of `mech` under `protocol` to `<id>.iv.csv`,
+/// `<id>.fit.csv`, and `<id>.traces.csv` in `dir`.
+fn report(mech: &Mechanism, env: &Map<String, f64>, protocol: &Protocol, dir: &str) -> Result<()> {
+    let (steps, traces) = run(mech, env, protocol)?;
+    let norm = |xs: Vec<f64>| {
+        let m = xs.iter().cloned().fold(0.0, f64::max);
+        xs.iter()
+            .map(|x| if m > 0.0 { x / m } else { 0.0 })
+            .collect::<Vec<_>>()
+    };
+    let vs = steps.iter().map(|s| s.v).collect::<Vec<_>>();
+    let act = norm(steps.iter().map(|s| s.g_peak).collect());
+    let inact = norm(steps.iter().map(|s| s.g_test).collect());
+    let act_fit = fit_boltzmann(&vs, &act);
+    let inact_fit = fit_boltzmann(&vs, &inact);
+    let boltzmann = |fit: &Option<(f64, f64)>, v: f64| {
+        fit.map(|(vh, k)| 1.0 / (1.0 + ((vh - v) / k).exp()))
+            .unwrap_or(f64::NAN)
+    };
+
+    let mut iv = String::from("v,i_peak,i_ss,g_peak,g_norm,g_fit,i_test,g_test,h_norm,h_fit\n");
+    for (ix, s) in steps.iter().enumerate() {
+        iv.push_str(&format!(
+            "{},{},{},{},{},{},{},{},{},{}\n",
+            s.v,
+            s.i_peak,
+            s.i_ss,
+            s.g_peak,
+            act[ix],
+            boltzmann(&act_fit, s.v),
+            s.i_test,
+            s.g_test,
+            inact[ix],
+            boltzmann(&inact_fit, s.v),
+        ));
+    }
+    let mut fit = String::from("curve,v_half,k\n");
+    for (curve, f) in [("activation", act_fit), ("inactivation", inact_fit)] {
+        if let Some((vh, k)) = f {
+            fit.push_str(&format!("{},{},{}\n", curve, vh, k));
+        } else {
+            warn!("Could not fit {} of {}", curve, mech.name);
+            fit.push_str(&format!("{},NaN,NaN\n", curve));
+        }
+    }
+    create_dir_all(dir)?;
+    for (ext, out) in [("iv", iv), ("fit", fit), ("traces", traces.to_csv())] {
+        let mut path = PathBuf::from(dir);
+        path.push(format!("{}.{}.csv", mech.name, ext));
+        info!("Writing voltage clamp results to {:?}", &path);
+        write(&path, out)?;
+    }
+    Ok(())
+}
+
+/// Run `protocol` on all channels found in `nml`, or just `channel`, and
+/// write the results to `dir`, see `report`.
+pub fn export(
+    lems: &LemsFile,
+    nml: &[String],
+    channel: &Option<&str>,
+    filter: &Option<&str>,
+    env: &Map<String, f64>,
+    protocol: &Protocol,
+    dir: &str,
+) -> Result<()> {
+    let mut found = false;
+    process_files(&lems.include_dir, nml, |_, node| {
+        let ty = node
+            .attribute("type")
+            .unwrap_or_else(|| node.tag_name().name());
+        if !lems.derived_from(ty, "baseIonChannel") {
+            return Ok(());
+        }
+        let instance = Instance::new(lems, node)?;
+        let id = instance.id.as_deref().ok_or(Error::Nml {
+            what: String::from("Channel must have an id"),
+        })?;
+        if let Some(channel) = channel {
+            if id != *channel {
+                return Ok(());
+            }
+        }
+        found = true;
+        report(&mechanism(&instance, filter, 1.0)?, env, protocol, dir)
+    })?;
+    match channel {
+        Some(id) if !found => Err(Error::Nml {
+            what: format!("No such channel: {}", id),
+        }),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_fit_boltzmann() {
+        for (vh, k) in [(-40.0, 5.0), (-65.0, -7.5)] {
+            let vs = (0..16)
+                .map(|i| -100.0 + 10.0 * i as f64)
+                .collect::<Vec<_>>();
+            let ys = vs
+                .iter()
+                .map(|v| 1.0 / (1.0 + ((vh - v) / k).exp()))
+                .collect::<Vec<_>>();
+            let (fvh, fk) = fit_boltzmann(&vs, &ys).unwrap();
+            assert!((fvh - vh).abs() < 1e-6 && (fk - k).abs() < 1e-6);
+        }
+        assert_eq!(fit_boltzmann(&[0.0, 1.0], &[0.0, 1.0]), None);
+    }
+
+    #[test]
+    #[cfg_attr(
+        not(core_types),
+        ignore = "needs the NML2 core types, run bootstrap.sh"
+    )]
+    fn test_export() {
+        let lems = crate::fixture::core_types();
+        let dir = crate::fixture::Scratch::new("vclamp-export");
+        let out = dir.path().to_str().unwrap();
+        let protocol = Protocol {
+            holding: -80.0,
+            hold: 5.0,
+            steps: Range {
+                from: -100.0,
+                to: 50.0,
+                step: 10.0,
+            },
+            duration: 30.0,
+            test: 0.0,
+            test_duration: 10.0,
+            dt: 0.05,
+            method: Method::Cnexp,
+        };
+        let env = crate::reference::Compartment::new(0.0).env;
+        export(
+            &lems,
+            &[String::from("example/nml-simple-ion-channels.xml")],
+            &Some("NaConductance"),
+            &None,
+            &env,
+            &protocol,
+            out,
+        )
+        .unwrap();
+        let read = |ext: &str| {
+            std::fs::read_to_string(format!("{}/NaConductance.{}.csv", out, ext))
+                .unwrap()
+                .lines()
+                .skip(1)
+                .map(|l| {
+                    l.split(',')
+                        .skip(if ext == "fit" { 1 } else { 0 })
+                        .map(|x| x.parse::<f64>().unwrap())
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // One row per step: v, i_peak, i_ss, g_peak, g_norm, g_fit, i_test,
+        // g_test, h_norm, h_fit
+        let iv = read("iv");
+        assert_eq!(iv.len(), 16);
+        assert_eq!(iv[0][0], -100.0);
+        assert_eq!(iv[15][0], 50.0);
+        // Inward sodium current below ena = 50mV, none at it
+        assert!(iv[8][1] < 0.0);
+        assert_eq!(iv[15][1], 0.0);
+        // Activation grows with the step, inactivation falls, both
+        // normalised to a maximum of one
+        let col = |ix: usize| iv.iter().map(|r| r[ix]).collect::<Vec<_>>();
+        for (ix, rising) in [(4, true), (8, false)] {
+            let ys = col(ix);
+            assert_eq!(ys.iter().cloned().fold(0.0, f64::max), 1.0);
+            assert!(ys
+                .windows(2)
+                .all(|w| if rising { w[0] <= w[1] } else { w[0] >= w[1] }));
+        }
+        assert_eq!(col(8)[0], 1.0);
+        assert!(col(8)[15] < 0.01);
+
+        // Fitted activation and inactivation
+        let fit = read("fit");
+        let (vh, k) = (fit[0][0], fit[0][1]);
+        assert!((-50.0..10.0).contains(&vh) && k > 0.0);
+        let (vh, k) = (fit[1][0], fit[1][1]);
+        assert!((-80.0..-40.0).contains(&vh) && k < 0.0);
+
+        // Time and one current per step
+        let traces = read("traces");
+        assert_eq!(traces[0].len(), 17);
+    }
+}
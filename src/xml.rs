@@ -1,15 +1,238 @@
 #![allow(clippy::upper_case_acronyms)]
 
 use roxmltree::Node;
+use std::fmt::Display;
+use std::str::FromStr;
 
-pub trait XML {
-    fn from_node(node: &Node) -> Self;
+use crate::error::{Error, Result};
+
+pub trait XML: Sized {
+    fn from_node(node: &Node) -> Result<Self>;
+    /// Serialise as element `tag` indented to level `depth`
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String);
 }
 
 impl XML for String {
-    fn from_node(node: &Node) -> Self {
+    fn from_node(node: &Node) -> Result<Self> {
         node.text()
-            .unwrap_or_else(|| panic!("Illegal conversion {:?} -> String", node))
-            .to_string()
+            .map(|s| s.to_string())
+            .ok_or_else(|| xml_error(node, "Expected text content"))
+    }
+
+    fn to_xml(&self, tag: &str, depth: usize, out: &mut String) {
+        open_tag(out, depth, tag);
+        out.push('>');
+        out.push_str(&escape(self));
+        out.push_str(&format!("</{}>\n", tag));
+    }
+}
+
+/// Render `item` as element `tag` at indentation level `depth`
+pub fn to_xml_string<T: XML>(item: &T, tag: &str, depth: usize) -> String {
+    let mut out = String::new();
+    item.to_xml(tag, depth, &mut out);
+    out
+}
+
+/// Namespace of NML2 documents
+pub const NML2_NAMESPACE: &str = "http://www.neuroml.org/schema/neuroml2";
+
+/// Schema the raw data model is generated from, see `schema.rs`
+pub const NML2_SCHEMA: &str =
+    "https://raw.githubusercontent.com/NeuroML/NeuroML2/master/Schemas/NeuroML2/NeuroML_v2.2.xsd";
+
+/// Render `doc` as a standalone NML2 document, ie with an XML declaration and
+/// the namespace and schema location on the root element.
+pub fn to_nml2_string<T: XML>(doc: &T) -> String {
+    let body = to_xml_string(doc, "neuroml", 0);
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    open_tag(&mut out, 0, "neuroml");
+    write_attr(&mut out, "xmlns", &NML2_NAMESPACE);
+    write_attr(
+        &mut out,
+        "xmlns:xsi",
+        &"http://www.w3.org/2001/XMLSchema-instance",
+    );
+    write_attr(
+        &mut out,
+        "xsi:schemaLocation",
+        &format!("{} {}", NML2_NAMESPACE, NML2_SCHEMA),
+    );
+    out.push_str(&body["<neuroml".len()..]);
+    out
+}
+
+/// Slash separated list of tags from the document root to `node`
+pub fn path(node: &Node) -> String {
+    let mut tags = node
+        .ancestors()
+        .filter(|n| n.is_element())
+        .map(|n| n.tag_name().name())
+        .collect::<Vec<_>>();
+    tags.reverse();
+    tags.join("/")
+}
+
+fn xml_error<T: Display>(node: &Node, what: T) -> Error {
+    Error::Parse {
+        what: format!("{} in <{}>", what, path(node)),
+    }
+    .at(node)
+}
+
+fn convert<T>(node: &Node, attr: &str, val: &str) -> Result<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    val.parse::<T>().map_err(|e| {
+        xml_error(
+            node,
+            format!("Could not convert attribute {}={:?}: {}", attr, val, e),
+        )
+    })
+}
+
+/// Optional attribute, converted if present
+pub fn opt_attr<T>(node: &Node, attr: &str) -> Result<Option<T>>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    node.attribute(attr)
+        .map(|s| convert(node, attr, s))
+        .transpose()
+}
+
+/// Required attribute
+pub fn req_attr<T>(node: &Node, attr: &str) -> Result<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    opt_attr(node, attr)?
+        .ok_or_else(|| xml_error(node, format!("Missing required attribute {}", attr)))
+}
+
+/// Attribute with default value `dflt` if absent
+pub fn def_attr<T>(node: &Node, attr: &str, dflt: &str) -> Result<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    convert(node, attr, node.attribute(attr).unwrap_or(dflt))
+}
+
+/// Element `node` is not allowed in the body of type `parent`
+pub fn unexpected_tag(node: &Node, parent: &str) -> Error {
+    xml_error(
+        node,
+        format!(
+            "Unexpected tag {} in body of {}",
+            node.tag_name().name(),
+            parent
+        ),
+    )
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Start element `tag`; must be followed by attributes (if any) and closed
+/// either directly or via `close_tag`.
+pub fn open_tag(out: &mut String, depth: usize, tag: &str) {
+    out.push_str(&"    ".repeat(depth));
+    out.push('<');
+    out.push_str(tag);
+}
+
+pub fn write_attr<T: Display>(out: &mut String, key: &str, val: &T) {
+    out.push_str(&format!(" {}=\"{}\"", key, escape(&val.to_string())));
+}
+
+pub fn close_tag(out: &mut String, depth: usize, tag: &str) {
+    out.push_str(&"    ".repeat(depth));
+    out.push_str(&format!("</{}>\n", tag));
+}
+
+/// Source text of all children of `node`, for elements with arbitrary content
+pub fn inner_xml(node: &Node) -> String {
+    match (node.first_child(), node.last_child()) {
+        (Some(first), Some(last)) => {
+            node.document().input_text()[first.range().start..last.range().end].to_string()
+        }
+        _ => String::new(),
+    }
+}
+
+/// Close element `tag` after writing `inner` verbatim, see `inner_xml`
+pub fn write_inner(out: &mut String, tag: &str, inner: &str) {
+    if inner.trim().is_empty() {
+        out.push_str("/>\n");
+    } else {
+        out.push('>');
+        out.push_str(inner);
+        out.push_str(&format!("</{}>\n", tag));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::neuroml::raw::NeuroMLDocument;
+
+    #[test]
+    fn test_round_trip() {
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/example");
+        let mut files = Vec::new();
+        for dir in [dir.to_string(), format!("{}/io", dir)] {
+            for entry in std::fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_file() {
+                    files.push(path);
+                }
+            }
+        }
+        assert!(!files.is_empty());
+        let elements =
+            |tree: &roxmltree::Document| tree.descendants().filter(|n| n.is_element()).count();
+        for file in files {
+            let xml = std::fs::read_to_string(&file).unwrap();
+            let tree = roxmltree::Document::parse(&xml).unwrap();
+            let read = NeuroMLDocument::from_node(&tree.root_element()).unwrap();
+            let written = to_nml2_string(&read);
+            let again = roxmltree::Document::parse(&written).unwrap();
+            // Valid NML2 needs the namespace on the root element
+            let root = again.root_element();
+            assert_eq!(root.tag_name().namespace(), Some(NML2_NAMESPACE));
+            assert_eq!(
+                root.attribute((
+                    "http://www.w3.org/2001/XMLSchema-instance",
+                    "schemaLocation"
+                )),
+                Some(format!("{} {}", NML2_NAMESPACE, NML2_SCHEMA).as_str())
+            );
+            // Elements keep their namespace, eg RDF in annotations
+            let namespaces = |tree: &roxmltree::Document| {
+                tree.descendants()
+                    .filter(|n| n.is_element())
+                    .map(|n| n.tag_name().namespace().map(|s| s.to_string()))
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(
+                namespaces(&tree),
+                namespaces(&again),
+                "Namespaces of {:?}",
+                file
+            );
+            // Nothing is dropped, including the contents of annotations
+            assert_eq!(elements(&tree), elements(&again), "Elements of {:?}", file);
+            let again = NeuroMLDocument::from_node(&again.root_element()).unwrap();
+            assert_eq!(read, again, "Round trip of {:?}", file);
+        }
     }
 }